@@ -1,10 +1,20 @@
+pub mod branch_picker;
 pub mod command_palette;
 pub mod editor;
+pub mod git_panel;
+pub mod history_panel;
 pub mod menu;
+pub mod mock_server;
 pub mod pane;
 pub mod project_panel;
+pub mod project_search;
 pub mod projects;
+pub mod request_diff;
+pub mod request_editor;
+pub mod request_finder;
 pub mod response_panel;
+pub mod search;
+pub mod secrets;
 pub mod text;
 pub mod theme;
 pub mod updater;
@@ -0,0 +1,7 @@
+gpui::actions!(
+    branch_picker,
+    [
+        /// Toggle the picker for checking out a branch.
+        Toggle,
+    ]
+);
@@ -123,6 +123,10 @@ gpui::actions!(
         Delete,
         /// Delete from the cursor to the end of the line.
         DeleteToEndOfLine,
+        /// Fold the syntax node at the cursor.
+        Fold,
+        /// Fold every foldable syntax node in the editor.
+        FoldAll,
         /// Insert a new line and move the cursor to it.
         Newline,
         /// Move the cursor left.
@@ -179,5 +183,9 @@ gpui::actions!(
         Undo,
         /// Undo the last selection change.
         UndoSelection,
+        /// Unfold the folds on the cursor's line.
+        Unfold,
+        /// Unfold every fold in the editor.
+        UnfoldAll,
     ]
 );
@@ -0,0 +1,7 @@
+gpui::actions!(
+    git_panel,
+    [
+        /// Toggle focus on the git panel.
+        ToggleFocus
+    ]
+);
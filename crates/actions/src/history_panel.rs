@@ -0,0 +1,9 @@
+gpui::actions!(
+    history_panel,
+    [
+        /// Clear the history of sent requests.
+        ClearHistory,
+        /// Toggle focus on the history panel.
+        ToggleFocus
+    ]
+);
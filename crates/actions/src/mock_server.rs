@@ -0,0 +1,13 @@
+gpui::actions!(
+    mock_server,
+    [
+        /// Start serving the project's saved examples on a local port.
+        Start,
+        /// Stop the mock server.
+        Stop,
+        /// Clear the mock server's request log.
+        ClearLog,
+        /// Toggle focus on the mock server panel.
+        ToggleFocus
+    ]
+);
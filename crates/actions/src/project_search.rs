@@ -0,0 +1,7 @@
+gpui::actions!(
+    project_search,
+    [
+        /// Toggle focus on the project search panel.
+        ToggleFocus
+    ]
+);
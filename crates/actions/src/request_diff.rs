@@ -0,0 +1,9 @@
+gpui::actions!(
+    request_diff,
+    [
+        /// Compare the active request with its version in the HEAD commit.
+        CompareWithHead,
+        /// Show the commits that changed the active request.
+        ShowFileHistory,
+    ]
+);
@@ -0,0 +1,9 @@
+gpui::actions!(
+    request_editor,
+    [
+        /// Pretty print the request body.
+        FormatBody,
+        /// Minify the request body.
+        MinifyBody
+    ]
+);
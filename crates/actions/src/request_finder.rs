@@ -0,0 +1,7 @@
+gpui::actions!(
+    request_finder,
+    [
+        /// Toggle the finder for going to a request.
+        Toggle,
+    ]
+);
@@ -0,0 +1,25 @@
+gpui::actions!(
+    search,
+    [
+        /// Open the find bar for the focused editor.
+        Deploy,
+        /// Open the find bar with the replace field shown.
+        DeployReplace,
+        /// Close the find bar.
+        Dismiss,
+        /// Select the next search match.
+        SelectNextMatch,
+        /// Select the previous search match.
+        SelectPreviousMatch,
+        /// Toggle case-sensitive matching.
+        ToggleCaseSensitive,
+        /// Toggle whole-word matching.
+        ToggleWholeWord,
+        /// Toggle regular expression matching.
+        ToggleRegex,
+        /// Replace the active search match.
+        ReplaceNext,
+        /// Replace all search matches.
+        ReplaceAll,
+    ]
+);
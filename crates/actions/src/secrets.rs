@@ -0,0 +1,7 @@
+gpui::actions!(
+    secrets,
+    [
+        /// Manage the secrets of the current project.
+        ManageSecrets
+    ]
+);
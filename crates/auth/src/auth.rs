@@ -0,0 +1,16 @@
+mod aws_sigv4;
+mod oauth2;
+mod redirect_listener;
+#[cfg(test)]
+mod stand_in_server;
+mod token_store;
+
+use gpui::App;
+
+pub use aws_sigv4::sign_aws_sigv4;
+pub use oauth2::OAuth2Token;
+pub use token_store::OAuth2Tokens;
+
+pub fn init(cx: &mut App) {
+    token_store::init(cx);
+}
@@ -0,0 +1,508 @@
+use anyhow::{Context as _, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use futures::io::AsyncReadExt as _;
+use jiff::{SignedDuration, Timestamp};
+use serde::Deserialize;
+use sha2::{Digest as _, Sha256};
+use std::{sync::Arc, time::Duration};
+
+use http_client::{AsyncBody, HttpClient, Method, Request, StatusCode, Url, http};
+use project::{OAuth2GrantType, RequestFileOAuth2};
+
+use crate::redirect_listener::RedirectListener;
+
+/// How long before it expires a token is replaced, so it doesn't expire while a request is in
+/// flight.
+const REFRESH_MARGIN: SignedDuration = SignedDuration::from_secs(60);
+
+/// How long to wait for the user to sign in before giving up on an authorization code grant.
+const SIGN_IN_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// An access token, along with what the authorization server said about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    /// The granted scopes. When the server leaves them out, the requested ones were granted.
+    pub scopes: Vec<String>,
+    pub obtained_at: Timestamp,
+    /// When the token expires, or `None` if the server didn't say.
+    pub expires_at: Option<Timestamp>,
+}
+
+impl OAuth2Token {
+    /// The value of the `Authorization` header the token is sent in.
+    pub fn authorization(&self) -> String {
+        // Servers often answer with a lowercase `bearer`, which not every resource server accepts.
+        if self.token_type.eq_ignore_ascii_case("bearer") {
+            format!("Bearer {}", self.access_token)
+        } else {
+            format!("{} {}", self.token_type, self.access_token)
+        }
+    }
+
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Whether the token expires soon enough that a new one should be used instead.
+    pub fn needs_refresh(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| {
+            now.checked_add(REFRESH_MARGIN)
+                .is_ok_and(|refresh_at| expires_at <= refresh_at)
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    token_type: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    /// Seconds until the token expires. Some servers send it as a string.
+    #[serde(default)]
+    expires_in: Option<serde_json::Value>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// Reads the token endpoint's response to a token request made with `requested_scope`.
+fn parse_token_response(
+    status: StatusCode,
+    body: &[u8],
+    requested_scope: &str,
+    now: Timestamp,
+) -> anyhow::Result<OAuth2Token> {
+    if !status.is_success() {
+        if let Ok(error) = serde_json::from_slice::<ErrorResponse>(body) {
+            match error.error_description {
+                Some(description) => bail!("{}: {description}", error.error),
+                None => bail!("{}", error.error),
+            }
+        }
+        let body = String::from_utf8_lossy(body);
+        bail!(
+            "token endpoint responded with {status}: {}",
+            body.chars().take(200).collect::<String>()
+        );
+    }
+
+    let response = serde_json::from_slice::<TokenResponse>(body)
+        .context("token endpoint responded with an invalid token")?;
+    let expires_in = response.expires_in.and_then(|expires_in| match expires_in {
+        serde_json::Value::Number(number) => number.as_i64(),
+        serde_json::Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    });
+    let scope = response.scope.as_deref().unwrap_or(requested_scope);
+    Ok(OAuth2Token {
+        access_token: response.access_token,
+        token_type: if response.token_type.is_empty() {
+            "Bearer".to_owned()
+        } else {
+            response.token_type
+        },
+        refresh_token: response.refresh_token.filter(|token| !token.is_empty()),
+        scopes: scope.split_whitespace().map(str::to_owned).collect(),
+        obtained_at: now,
+        expires_at: expires_in
+            .and_then(|seconds| now.checked_add(SignedDuration::from_secs(seconds)).ok()),
+    })
+}
+
+/// Posts a token request with `params` and the client's credentials to the token endpoint.
+async fn request_token(
+    http_client: &dyn HttpClient,
+    config: &RequestFileOAuth2,
+    params: &[(&str, &str)],
+) -> anyhow::Result<OAuth2Token> {
+    let token_url = Url::parse(config.token_url.trim()).context("invalid token URL")?;
+    let mut form = url::form_urlencoded::Serializer::new(String::new());
+    form.extend_pairs(params);
+    for (name, value) in [
+        ("client_id", config.client_id.as_str()),
+        ("client_secret", config.client_secret.as_str()),
+    ] {
+        if !value.is_empty() {
+            form.append_pair(name, value);
+        }
+    }
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(token_url.as_str())
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .header(http::header::ACCEPT, "application/json")
+        .body(AsyncBody::from(form.finish()))?;
+
+    let mut response = http_client
+        .send(request)
+        .await
+        .context("failed to reach the token endpoint")?;
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .context("failed to read the token endpoint's response")?;
+    let requested_scope = params
+        .iter()
+        .find(|(name, _)| *name == "scope")
+        .map_or(config.scope.as_str(), |(_, scope)| scope);
+    parse_token_response(response.status(), &body, requested_scope, Timestamp::now())
+}
+
+/// Obtains a token with the client credentials or password grant. The authorization code grant
+/// needs the user to sign in first, with an [`AuthorizationCodeFlow`].
+pub(crate) async fn request_token_without_sign_in(
+    http_client: &dyn HttpClient,
+    config: &RequestFileOAuth2,
+) -> anyhow::Result<OAuth2Token> {
+    let mut params = match config.grant_type {
+        OAuth2GrantType::ClientCredentials => vec![("grant_type", "client_credentials")],
+        OAuth2GrantType::Password => vec![
+            ("grant_type", "password"),
+            ("username", config.username.as_str()),
+            ("password", config.password.as_str()),
+        ],
+        OAuth2GrantType::AuthorizationCode => {
+            bail!("the authorization code grant needs the user to sign in")
+        }
+    };
+    if !config.scope.trim().is_empty() {
+        params.push(("scope", config.scope.trim()));
+    }
+    request_token(http_client, config, &params).await
+}
+
+/// Exchanges the refresh token of `token` for a new token. The refresh token is kept when the
+/// server doesn't issue a new one.
+pub(crate) async fn refresh_token(
+    http_client: &dyn HttpClient,
+    config: &RequestFileOAuth2,
+    token: &OAuth2Token,
+) -> anyhow::Result<OAuth2Token> {
+    let refresh_token = token
+        .refresh_token
+        .as_deref()
+        .context("the token has no refresh token")?;
+    let scope = token.scopes.join(" ");
+    let mut params = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+    ];
+    if !scope.is_empty() {
+        params.push(("scope", scope.as_str()));
+    }
+
+    let mut refreshed_token = request_token(http_client, config, &params).await?;
+    if refreshed_token.refresh_token.is_none() {
+        refreshed_token.refresh_token = Some(refresh_token.to_owned());
+    }
+    Ok(refreshed_token)
+}
+
+/// A code verifier for Proof Key for Code Exchange, and its S256 challenge (RFC 7636).
+struct Pkce {
+    verifier: String,
+    challenge: String,
+}
+
+impl Pkce {
+    fn new() -> Self {
+        Self::from_verifier(URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>()))
+    }
+
+    fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// An authorization code grant with PKCE. The user signs in at [`Self::authorization_url`] in
+/// the browser, which the authorization server then redirects to a listener on the loopback
+/// interface with the code.
+pub(crate) struct AuthorizationCodeFlow {
+    config: RequestFileOAuth2,
+    listener: RedirectListener,
+    redirect_uri: String,
+    authorization_url: Url,
+    state: String,
+    pkce: Pkce,
+}
+
+impl AuthorizationCodeFlow {
+    /// Starts listening for the redirect.
+    pub(crate) fn start(config: RequestFileOAuth2) -> anyhow::Result<Self> {
+        let mut authorization_url =
+            Url::parse(config.authorization_url.trim()).context("invalid authorization URL")?;
+        let listener = RedirectListener::bind(config.redirect_port)?;
+        let redirect_uri = listener.redirect_uri();
+        let state = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 16]>());
+        let pkce = Pkce::new();
+
+        {
+            let mut query = authorization_url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &config.client_id)
+                .append_pair("redirect_uri", &redirect_uri);
+            if !config.scope.trim().is_empty() {
+                query.append_pair("scope", config.scope.trim());
+            }
+            query
+                .append_pair("state", &state)
+                .append_pair("code_challenge", &pkce.challenge)
+                .append_pair("code_challenge_method", "S256");
+        }
+
+        Ok(Self {
+            config,
+            listener,
+            redirect_uri,
+            authorization_url,
+            state,
+            pkce,
+        })
+    }
+
+    /// Where the user signs in.
+    pub(crate) fn authorization_url(&self) -> &Url {
+        &self.authorization_url
+    }
+
+    /// Waits for the user to sign in, then exchanges the code the server redirects with for a
+    /// token.
+    pub(crate) async fn finish(
+        self,
+        http_client: Arc<dyn HttpClient>,
+    ) -> anyhow::Result<OAuth2Token> {
+        let code = smol::future::or(self.listener.receive_code(&self.state), async {
+            smol::Timer::after(SIGN_IN_TIMEOUT).await;
+            Err(anyhow!("timed out waiting for the sign-in to complete"))
+        })
+        .await?;
+        request_token(
+            http_client.as_ref(),
+            &self.config,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("code_verifier", self.pkce.verifier.as_str()),
+            ],
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::stand_in_server::{
+        StandInAuthorizationServer, config, http_client, open_in_browser,
+    };
+
+    #[test]
+    fn test_pkce_challenge() {
+        // The example in appendix B of RFC 7636.
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_owned());
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pkce = Pkce::new();
+        assert_eq!(pkce.verifier.len(), 43);
+        assert_ne!(pkce.verifier, Pkce::new().verifier);
+    }
+
+    #[test]
+    fn test_parse_token_response() {
+        let now = Timestamp::from_second(1_700_000_000).unwrap();
+        let token = parse_token_response(
+            StatusCode::OK,
+            br#"{"access_token":"abc","token_type":"bearer","expires_in":"3600","scope":"read"}"#,
+            "read write",
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            token,
+            OAuth2Token {
+                access_token: "abc".to_owned(),
+                token_type: "bearer".to_owned(),
+                refresh_token: None,
+                scopes: vec!["read".to_owned()],
+                obtained_at: now,
+                expires_at: Some(Timestamp::from_second(1_700_003_600).unwrap()),
+            }
+        );
+        assert_eq!(token.authorization(), "Bearer abc");
+        assert!(!token.needs_refresh(now));
+        assert!(token.needs_refresh(Timestamp::from_second(1_700_003_550).unwrap()));
+        assert!(!token.is_expired(Timestamp::from_second(1_700_003_550).unwrap()));
+        assert!(token.is_expired(Timestamp::from_second(1_700_003_600).unwrap()));
+
+        let token = parse_token_response(
+            StatusCode::OK,
+            br#"{"access_token":"abc","refresh_token":""}"#,
+            "read write",
+            now,
+        )
+        .unwrap();
+        assert_eq!(token.token_type, "Bearer");
+        assert_eq!(token.refresh_token, None);
+        assert_eq!(token.scopes, vec!["read", "write"]);
+        assert_eq!(token.expires_at, None);
+        assert!(!token.needs_refresh(now));
+
+        let error = parse_token_response(
+            StatusCode::BAD_REQUEST,
+            br#"{"error":"invalid_grant","error_description":"The code has expired."}"#,
+            "",
+            now,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "invalid_grant: The code has expired.");
+        let error =
+            parse_token_response(StatusCode::BAD_GATEWAY, b"upstream down", "", now).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "token endpoint responded with 502 Bad Gateway: upstream down"
+        );
+    }
+
+    #[test]
+    fn test_request_token_without_sign_in() {
+        smol::block_on(async {
+            let server = StandInAuthorizationServer::new(3600);
+            let http_client = http_client(server.clone());
+
+            let token = request_token_without_sign_in(
+                http_client.as_ref(),
+                &config(OAuth2GrantType::ClientCredentials),
+            )
+            .await
+            .unwrap();
+            assert_eq!(token.access_token, "token-1");
+            assert_eq!(token.scopes, vec!["read", "write"]);
+            assert_eq!(token.refresh_token.as_deref(), Some("refresh-1"));
+
+            let token = request_token_without_sign_in(
+                http_client.as_ref(),
+                &config(OAuth2GrantType::Password),
+            )
+            .await
+            .unwrap();
+            assert_eq!(token.access_token, "token-2");
+
+            let refreshed_token = refresh_token(
+                http_client.as_ref(),
+                &config(OAuth2GrantType::Password),
+                &token,
+            )
+            .await
+            .unwrap();
+            assert_eq!(refreshed_token.access_token, "token-3");
+            assert_eq!(refreshed_token.refresh_token.as_deref(), Some("refresh-3"));
+
+            let error = request_token_without_sign_in(
+                http_client.as_ref(),
+                &RequestFileOAuth2 {
+                    password: "wrong".to_owned(),
+                    ..config(OAuth2GrantType::Password)
+                },
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(error.to_string(), "invalid_grant");
+
+            let error = request_token_without_sign_in(
+                http_client.as_ref(),
+                &RequestFileOAuth2 {
+                    client_secret: String::new(),
+                    ..config(OAuth2GrantType::ClientCredentials)
+                },
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(error.to_string(), "invalid_client");
+        });
+    }
+
+    #[test]
+    fn test_authorization_code_flow() {
+        smol::block_on(async {
+            let server = StandInAuthorizationServer::new(3600);
+            let http_client = http_client(server.clone());
+
+            let flow =
+                AuthorizationCodeFlow::start(config(OAuth2GrantType::AuthorizationCode)).unwrap();
+            let authorization_url = flow.authorization_url().clone();
+            assert_eq!(authorization_url.host_str(), Some("auth.zaku.dev"));
+            assert_eq!(
+                authorization_url.query_pairs().next().unwrap().1,
+                "api",
+                "the authorization URL's own query should be kept"
+            );
+            let redirect_url = server.lock().authorize(&authorization_url);
+            assert!(redirect_url.starts_with("http://127.0.0.1:"));
+
+            let (token, page) = futures::join!(
+                flow.finish(http_client.clone()),
+                open_in_browser(&redirect_url)
+            );
+            let token = token.unwrap();
+            assert_eq!(token.access_token, "token-1");
+            assert_eq!(token.scopes, vec!["read", "write"]);
+            assert!(page.starts_with("HTTP/1.1 200 OK\r\n"), "{page}");
+
+            // A redirect with another state is rejected.
+            let flow =
+                AuthorizationCodeFlow::start(config(OAuth2GrantType::AuthorizationCode)).unwrap();
+            let redirect_url = server.lock().authorize(flow.authorization_url());
+            let forged_url = format!("{redirect_url}x");
+            let (token, page) = futures::join!(
+                flow.finish(http_client.clone()),
+                open_in_browser(&forged_url)
+            );
+            assert_eq!(
+                token.unwrap_err().to_string(),
+                "the sign-in redirect didn't match the request"
+            );
+            assert!(page.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{page}");
+
+            // As is one the authorization server sends with an error.
+            let flow =
+                AuthorizationCodeFlow::start(config(OAuth2GrantType::AuthorizationCode)).unwrap();
+            let denied_url = format!(
+                "{}?error=access_denied&error_description=Nope&state={}",
+                flow.redirect_uri, flow.state
+            );
+            let (token, _) = futures::join!(flow.finish(http_client), open_in_browser(&denied_url));
+            assert_eq!(
+                token.unwrap_err().to_string(),
+                "sign-in failed: access_denied: Nope"
+            );
+        });
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{Context as _, bail};
+use smol::{
+    io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use std::net::Ipv4Addr;
+
+use http_client::{StatusCode, Url};
+
+const MAX_HEAD_SIZE: u64 = 64 * 1024;
+const REDIRECT_PATH: &str = "/callback";
+
+/// A listener on the loopback interface for the authorization server's redirect back from the
+/// sign-in page (RFC 8252). It stops listening once it is dropped.
+pub(crate) struct RedirectListener {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl RedirectListener {
+    /// Binds to `port`, or to any free port if `None`.
+    pub(crate) fn bind(port: Option<u16>) -> anyhow::Result<Self> {
+        let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port.unwrap_or(0)))
+            .with_context(|| match port {
+                Some(port) => format!("failed to listen for the sign-in redirect on port {port}"),
+                None => "failed to listen for the sign-in redirect".to_owned(),
+            })?;
+        let port = listener.local_addr()?.port();
+        Ok(Self {
+            listener: TcpListener::try_from(listener)?,
+            port,
+        })
+    }
+
+    pub(crate) fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}{REDIRECT_PATH}", self.port)
+    }
+
+    /// Answers requests until the redirect arrives, returning its authorization code if its
+    /// state is `state`.
+    pub(crate) async fn receive_code(&self, state: &str) -> anyhow::Result<String> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            match handle_connection(stream, state).await {
+                Ok(Some(code)) => return Ok(code),
+                Ok(None) => {}
+                Err(error) if error.is::<RedirectError>() => return Err(error),
+                Err(error) => log::debug!("Failed to read sign-in redirect: {error:#}"),
+            }
+        }
+    }
+}
+
+/// Why the redirect didn't carry a code. The sign-in can't complete after one of these.
+#[derive(Debug)]
+struct RedirectError(String);
+
+impl std::fmt::Display for RedirectError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RedirectError {}
+
+/// Reads one request, returning the code if it was the redirect. Other requests, like the
+/// browser asking for a favicon, are answered with a 404.
+async fn handle_connection(mut stream: TcpStream, state: &str) -> anyhow::Result<Option<String>> {
+    let mut reader = BufReader::new(stream.clone());
+    let target = read_request_target(&mut reader).await?;
+    let url = Url::parse(&format!("http://127.0.0.1{target}"))
+        .with_context(|| format!("invalid request target {target:?}"))?;
+    if url.path() != REDIRECT_PATH {
+        write_page(&mut stream, StatusCode::NOT_FOUND, "Not found.").await?;
+        return Ok(None);
+    }
+
+    let mut code = None;
+    let mut redirect_state = None;
+    let mut error = None;
+    let mut error_description = None;
+    for (name, value) in url.query_pairs() {
+        match name.as_ref() {
+            "code" => code = Some(value.into_owned()),
+            "state" => redirect_state = Some(value.into_owned()),
+            "error" => error = Some(value.into_owned()),
+            "error_description" => error_description = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    let result = if redirect_state.as_deref() != Some(state) {
+        Err(RedirectError(
+            "the sign-in redirect didn't match the request".to_owned(),
+        ))
+    } else if let Some(error) = error {
+        Err(RedirectError(match error_description {
+            Some(description) => format!("sign-in failed: {error}: {description}"),
+            None => format!("sign-in failed: {error}"),
+        }))
+    } else {
+        code.ok_or_else(|| RedirectError("the sign-in redirect has no code".to_owned()))
+    };
+    match result {
+        Ok(code) => {
+            write_page(
+                &mut stream,
+                StatusCode::OK,
+                "Signed in. You can close this tab and return to Zaku.",
+            )
+            .await?;
+            Ok(Some(code))
+        }
+        Err(error) => {
+            write_page(
+                &mut stream,
+                StatusCode::BAD_REQUEST,
+                &format!("Sign-in failed: {error}."),
+            )
+            .await?;
+            Err(error.into())
+        }
+    }
+}
+
+/// Reads an HTTP/1.x request head, returning the request target.
+async fn read_request_target(reader: &mut BufReader<TcpStream>) -> anyhow::Result<String> {
+    let mut target = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.take(MAX_HEAD_SIZE).read_line(&mut line).await?;
+        if read == 0 || !line.ends_with('\n') {
+            bail!("incomplete request head");
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        if target.is_none() {
+            let mut parts = line.split_whitespace();
+            let (Some(_method), Some(request_target), Some(_version)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                bail!("invalid request line {line:?}");
+            };
+            target = Some(request_target.to_owned());
+        }
+    }
+    target.context("empty request")
+}
+
+async fn write_page(
+    stream: &mut TcpStream,
+    status: StatusCode,
+    message: &str,
+) -> anyhow::Result<()> {
+    let message = message
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let body = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><title>Zaku</title></head>\
+         <body style=\"font-family: sans-serif; margin: 4rem; text-align: center\">\
+         <p>{message}</p></body></html>"
+    );
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default(),
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use parking_lot::Mutex;
+use sha2::{Digest as _, Sha256};
+use smol::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::TcpStream,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use http_client::{AsyncBody, FakeHttpClient, HttpClient, Request, Response, Url};
+use project::{OAuth2GrantType, RequestFileOAuth2};
+
+/// An authorization server that issues tokens for one client and one user, and checks the
+/// PKCE verifier of the codes it issued.
+#[derive(Default)]
+pub(crate) struct StandInAuthorizationServer {
+    /// The code challenges and scopes of sign-ins, by the code they were issued with.
+    codes: HashMap<String, (String, String)>,
+    refresh_tokens: Vec<String>,
+    issued_tokens: u32,
+    expires_in: i64,
+}
+
+impl StandInAuthorizationServer {
+    pub(crate) fn new(expires_in: i64) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self {
+            expires_in,
+            ..Self::default()
+        }))
+    }
+
+    /// Signs the user in, as the browser would after being sent to `authorization_url`,
+    /// returning the redirect URL.
+    pub(crate) fn authorize(&mut self, authorization_url: &Url) -> String {
+        let query = authorization_url
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<_, _>>();
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], "zaku");
+        assert_eq!(query["code_challenge_method"], "S256");
+
+        let code = format!("code-{}", self.codes.len());
+        let scope = query.get("scope").cloned().unwrap_or_default();
+        self.codes
+            .insert(code.clone(), (query["code_challenge"].clone(), scope));
+        format!(
+            "{}?code={code}&state={}",
+            query["redirect_uri"], query["state"]
+        )
+    }
+
+    pub(crate) fn issued_tokens(&self) -> u32 {
+        self.issued_tokens
+    }
+
+    fn token(&mut self, params: &HashMap<String, String>) -> (u16, serde_json::Value) {
+        if params.get("client_id").map(String::as_str) != Some("zaku")
+            || params.get("client_secret").map(String::as_str) != Some("shh")
+        {
+            return (401, serde_json::json!({ "error": "invalid_client" }));
+        }
+        let requested_scope = params.get("scope").cloned().unwrap_or_default();
+        let granted_scope = match params["grant_type"].as_str() {
+            "client_credentials" => Some(requested_scope),
+            "password" => (params["username"] == "ada" && params["password"] == "hunter2")
+                .then_some(requested_scope),
+            "authorization_code" => self
+                .codes
+                .remove(&params["code"])
+                .filter(|(challenge, _)| {
+                    URL_SAFE_NO_PAD.encode(Sha256::digest(params["code_verifier"].as_bytes()))
+                        == *challenge
+                })
+                .map(|(_, scope)| scope),
+            "refresh_token" => self
+                .refresh_tokens
+                .contains(&params["refresh_token"])
+                .then_some(requested_scope),
+            grant_type => {
+                return (
+                    400,
+                    serde_json::json!({ "error": "unsupported_grant_type", "error_description": grant_type }),
+                );
+            }
+        };
+        let Some(granted_scope) = granted_scope else {
+            return (400, serde_json::json!({ "error": "invalid_grant" }));
+        };
+
+        self.issued_tokens += 1;
+        let refresh_token = format!("refresh-{}", self.issued_tokens);
+        self.refresh_tokens.push(refresh_token.clone());
+        (
+            200,
+            serde_json::json!({
+                "access_token": format!("token-{}", self.issued_tokens),
+                "token_type": "bearer",
+                "expires_in": self.expires_in,
+                "refresh_token": refresh_token,
+                "scope": granted_scope,
+            }),
+        )
+    }
+}
+
+pub(crate) fn http_client(server: Arc<Mutex<StandInAuthorizationServer>>) -> Arc<dyn HttpClient> {
+    FakeHttpClient::create(move |mut request: Request<AsyncBody>| {
+        let server = server.clone();
+        async move {
+            assert_eq!(request.uri(), "https://auth.zaku.dev/token");
+            let mut body = Vec::new();
+            request.body_mut().read_to_end(&mut body).await?;
+            let params = url::form_urlencoded::parse(&body)
+                .into_owned()
+                .collect::<HashMap<_, _>>();
+            let (status, body) = server.lock().token(&params);
+            Ok(Response::builder()
+                .status(status)
+                .body(AsyncBody::from(body.to_string()))?)
+        }
+    })
+}
+
+pub(crate) fn config(grant_type: OAuth2GrantType) -> RequestFileOAuth2 {
+    RequestFileOAuth2 {
+        grant_type,
+        token_url: "https://auth.zaku.dev/token".to_owned(),
+        authorization_url: "https://auth.zaku.dev/authorize?audience=api".to_owned(),
+        client_id: "zaku".to_owned(),
+        client_secret: "shh".to_owned(),
+        scope: "read write".to_owned(),
+        username: "ada".to_owned(),
+        password: "hunter2".to_owned(),
+        redirect_port: None,
+    }
+}
+
+/// Requests `url` as the browser would, returning the raw response.
+pub(crate) async fn open_in_browser(url: &str) -> String {
+    let url = Url::parse(url).unwrap();
+    let mut stream = TcpStream::connect((url.host_str().unwrap(), url.port().unwrap()))
+        .await
+        .unwrap();
+    let target = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+    stream
+        .write_all(format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
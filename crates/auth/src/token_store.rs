@@ -0,0 +1,260 @@
+use anyhow::anyhow;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, Global, Task};
+use jiff::Timestamp;
+use std::sync::Arc;
+
+use collections::{HashMap, hash_map};
+use http_client::HttpClient;
+use project::{OAuth2GrantType, RequestFileOAuth2};
+
+use crate::oauth2::{
+    AuthorizationCodeFlow, OAuth2Token, refresh_token, request_token_without_sign_in,
+};
+
+struct GlobalOAuth2Tokens(Entity<OAuth2Tokens>);
+
+impl Global for GlobalOAuth2Tokens {}
+
+pub(crate) fn init(cx: &mut App) {
+    let tokens = cx.new(|_| OAuth2Tokens::default());
+    cx.set_global(GlobalOAuth2Tokens(tokens));
+}
+
+type TokenTask = Shared<Task<Result<OAuth2Token, Arc<anyhow::Error>>>>;
+
+/// The OAuth 2.0 tokens obtained in this session, by the configuration they were obtained with.
+/// They are only kept in memory.
+#[derive(Default)]
+pub struct OAuth2Tokens {
+    tokens: HashMap<RequestFileOAuth2, OAuth2Token>,
+    /// Tokens being obtained, so requests sent meanwhile wait for the same token instead of
+    /// signing in again.
+    pending_tokens: HashMap<RequestFileOAuth2, TokenTask>,
+    /// Why the last attempt to obtain a token for a configuration failed.
+    errors: HashMap<RequestFileOAuth2, String>,
+}
+
+impl OAuth2Tokens {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalOAuth2Tokens>().0.clone()
+    }
+
+    pub fn try_global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalOAuth2Tokens>()
+            .map(|tokens| tokens.0.clone())
+    }
+
+    pub fn cached_token(&self, config: &RequestFileOAuth2) -> Option<&OAuth2Token> {
+        self.tokens.get(config)
+    }
+
+    pub fn is_fetching(&self, config: &RequestFileOAuth2) -> bool {
+        self.pending_tokens.contains_key(config)
+    }
+
+    pub fn error(&self, config: &RequestFileOAuth2) -> Option<&str> {
+        self.errors.get(config).map(String::as_str)
+    }
+
+    pub fn clear_token(&mut self, config: &RequestFileOAuth2, cx: &mut Context<Self>) {
+        let removed_token = self.tokens.remove(config).is_some();
+        let removed_error = self.errors.remove(config).is_some();
+        if removed_token || removed_error {
+            cx.notify();
+        }
+    }
+
+    /// A token for `config` that isn't about to expire. The cached token is used while it is
+    /// fresh. After that it is refreshed, and if that fails a new token is obtained.
+    pub fn token(
+        &mut self,
+        config: RequestFileOAuth2,
+        http_client: Arc<dyn HttpClient>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<OAuth2Token>> {
+        let cached_token = self.tokens.get(&config).cloned();
+        if let Some(token) = &cached_token
+            && !token.needs_refresh(Timestamp::now())
+        {
+            return Task::ready(Ok(token.clone()));
+        }
+        self.obtain_token(config, cached_token, http_client, cx)
+    }
+
+    /// Obtains a new token for `config` even if the cached one is still fresh.
+    pub fn fetch_new_token(
+        &mut self,
+        config: RequestFileOAuth2,
+        http_client: Arc<dyn HttpClient>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<OAuth2Token>> {
+        self.obtain_token(config, None, http_client, cx)
+    }
+
+    fn obtain_token(
+        &mut self,
+        config: RequestFileOAuth2,
+        cached_token: Option<OAuth2Token>,
+        http_client: Arc<dyn HttpClient>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<OAuth2Token>> {
+        let token_task = match self.pending_tokens.entry(config.clone()) {
+            hash_map::Entry::Occupied(entry) => entry.get().clone(),
+            hash_map::Entry::Vacant(entry) => {
+                let refreshable_token = cached_token.filter(|token| token.refresh_token.is_some());
+                entry
+                    .insert(
+                        cx.spawn(async move |this, cx| {
+                            let result =
+                                obtain_token(config.clone(), refreshable_token, http_client, cx)
+                                    .await;
+                            this.update(cx, |this, cx| {
+                                this.pending_tokens.remove(&config);
+                                match &result {
+                                    Ok(token) => {
+                                        this.tokens.insert(config.clone(), token.clone());
+                                        this.errors.remove(&config);
+                                    }
+                                    Err(error) => {
+                                        this.errors.insert(config, format!("{error:#}"));
+                                    }
+                                }
+                                cx.notify();
+                            })?;
+                            result.map_err(Arc::new)
+                        })
+                        .shared(),
+                    )
+                    .clone()
+            }
+        };
+        cx.notify();
+
+        cx.background_spawn(async move { token_task.await.map_err(|error| anyhow!("{error:#}")) })
+    }
+}
+
+/// Refreshes `refreshable_token`, or obtains a new token if there is none or the refresh fails.
+async fn obtain_token(
+    config: RequestFileOAuth2,
+    refreshable_token: Option<OAuth2Token>,
+    http_client: Arc<dyn HttpClient>,
+    cx: &mut AsyncApp,
+) -> anyhow::Result<OAuth2Token> {
+    if let Some(token) = refreshable_token {
+        match refresh_token(http_client.as_ref(), &config, &token).await {
+            Ok(token) => return Ok(token),
+            Err(error) => log::info!("Failed to refresh OAuth 2.0 token: {error:#}"),
+        }
+    }
+
+    if config.grant_type == OAuth2GrantType::AuthorizationCode {
+        let flow = AuthorizationCodeFlow::start(config)?;
+        cx.update(|cx| cx.open_url(flow.authorization_url().as_str()));
+        flow.finish(http_client).await
+    } else {
+        request_token_without_sign_in(http_client.as_ref(), &config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+
+    use crate::stand_in_server::{StandInAuthorizationServer, config, http_client};
+
+    #[gpui::test]
+    async fn test_tokens_are_cached_and_refreshed(cx: &mut TestAppContext) {
+        cx.update(init);
+        let tokens = cx.update(|cx| OAuth2Tokens::global(cx));
+        let config = config(OAuth2GrantType::ClientCredentials);
+
+        let server = StandInAuthorizationServer::new(3600);
+        let fresh_client = http_client(server.clone());
+        let token = tokens
+            .update(cx, |tokens, cx| {
+                tokens.token(config.clone(), fresh_client.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-1");
+        let token = tokens
+            .update(cx, |tokens, cx| {
+                tokens.token(config.clone(), fresh_client.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-1");
+        assert_eq!(server.lock().issued_tokens(), 1);
+
+        let token = tokens
+            .update(cx, |tokens, cx| {
+                tokens.fetch_new_token(config.clone(), fresh_client.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-2");
+
+        // Tokens about to expire are refreshed before they are used.
+        let expiring_config = RequestFileOAuth2 {
+            scope: "read".to_owned(),
+            ..config.clone()
+        };
+        let server = StandInAuthorizationServer::new(30);
+        let expiring_client = http_client(server.clone());
+        let token = tokens
+            .update(cx, |tokens, cx| {
+                tokens.token(expiring_config.clone(), expiring_client.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh-1"));
+        let token = tokens
+            .update(cx, |tokens, cx| {
+                tokens.token(expiring_config.clone(), expiring_client.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-2");
+        assert_eq!(token.scopes, vec!["read"]);
+
+        tokens.update(cx, |tokens, cx| {
+            assert_eq!(
+                tokens
+                    .cached_token(&expiring_config)
+                    .map(|token| token.access_token.as_str()),
+                Some("token-2")
+            );
+            assert_eq!(
+                tokens
+                    .cached_token(&config)
+                    .map(|token| token.access_token.as_str()),
+                Some("token-2")
+            );
+            tokens.clear_token(&config, cx);
+            assert_eq!(tokens.cached_token(&config), None);
+        });
+
+        let failing_config = RequestFileOAuth2 {
+            client_secret: "wrong".to_owned(),
+            ..config
+        };
+        let error = tokens
+            .update(cx, |tokens, cx| {
+                let task = tokens.token(failing_config.clone(), fresh_client.clone(), cx);
+                assert!(tokens.is_fetching(&failing_config));
+                task
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "invalid_client");
+        tokens.read_with(cx, |tokens, _| {
+            assert!(!tokens.is_fetching(&failing_config));
+            assert_eq!(tokens.error(&failing_config), Some("invalid_client"));
+        });
+    }
+}
@@ -0,0 +1,117 @@
+use gpui::{App, Context, Hsla, SharedString, Window};
+use std::ops::Range;
+
+use multi_buffer::{Anchor, MultiBufferOffset, ToOffset};
+use text::{Bias, SelectionGoal};
+use theme::ActiveTheme;
+
+use crate::{Editor, SelectionEffects, scroll::Autoscroll};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// A message attached to a range of the buffer, drawn as a squiggly underline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub range: Range<MultiBufferOffset>,
+    pub severity: DiagnosticSeverity,
+    pub message: SharedString,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DiagnosticEntry {
+    range: Range<Anchor>,
+    severity: DiagnosticSeverity,
+    message: SharedString,
+}
+
+impl Editor {
+    /// Replaces the editor's diagnostics. Ranges are anchored to the buffer,
+    /// so they follow edits until the diagnostics are replaced again.
+    pub fn set_diagnostics(
+        &mut self,
+        diagnostics: impl IntoIterator<Item = Diagnostic>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer_snapshot(cx);
+        self.diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| DiagnosticEntry {
+                range: snapshot.anchor_before(&diagnostic.range.start)
+                    ..snapshot.anchor_after(&diagnostic.range.end),
+                severity: diagnostic.severity,
+                message: diagnostic.message,
+            })
+            .collect();
+        cx.notify();
+    }
+
+    pub fn clear_diagnostics(&mut self, cx: &mut Context<Self>) {
+        if !self.diagnostics.is_empty() {
+            self.diagnostics.clear();
+            cx.notify();
+        }
+    }
+
+    /// Returns the diagnostics with their ranges resolved against the current
+    /// buffer contents.
+    pub fn diagnostics(&self, cx: &App) -> Vec<Diagnostic> {
+        let snapshot = self.buffer_snapshot(cx);
+        self.diagnostics
+            .iter()
+            .map(|entry| Diagnostic {
+                range: entry.range.start.to_offset(&snapshot)..entry.range.end.to_offset(&snapshot),
+                severity: entry.severity,
+                message: entry.message.clone(),
+            })
+            .collect()
+    }
+
+    /// Selects the range of the diagnostic at `index`, unfolding it if needed,
+    /// and scrolls it into view.
+    pub fn select_diagnostic(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.diagnostics.get(index) else {
+            return;
+        };
+
+        let snapshot = self.buffer_snapshot(cx);
+        let range = entry.range.start.to_offset(&snapshot)..entry.range.end.to_offset(&snapshot);
+        self.display_map.update(cx, |display_map, cx| {
+            display_map.unfold_intersecting([range.clone()], cx);
+        });
+        self.selection_goal = SelectionGoal::None;
+        self.change_selections(
+            SelectionEffects::scroll(Autoscroll::newest()),
+            cx,
+            |selections| selections.select_ranges([range]),
+        );
+        window.focus(&self.focus_handle, cx);
+    }
+
+    pub(crate) fn diagnostic_highlight_ranges(&self, cx: &App) -> Vec<(Range<usize>, Hsla)> {
+        let snapshot = self.buffer_snapshot(cx);
+        let status_colors = cx.theme().status();
+        self.diagnostics
+            .iter()
+            .map(|entry| {
+                let start = entry.range.start.to_offset(&snapshot);
+                let mut end = entry.range.end.to_offset(&snapshot);
+                if start == end {
+                    // Empty ranges still get a visible squiggle under the next character.
+                    end = snapshot.clip_offset(
+                        MultiBufferOffset((end.0 + 1).min(snapshot.len().0)),
+                        Bias::Right,
+                    );
+                }
+                let color = match entry.severity {
+                    DiagnosticSeverity::Error => status_colors.error,
+                    DiagnosticSeverity::Warning => status_colors.warning,
+                };
+                (start.0..end.0, color)
+            })
+            .collect()
+    }
+}
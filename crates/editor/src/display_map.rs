@@ -1,6 +1,8 @@
+mod fold_map;
 mod raw_chunks;
 mod tab_map;
 
+pub use fold_map::{FoldMap, FoldPoint, FoldSnapshot};
 pub use tab_map::{TabMap, TabPoint, TabSnapshot};
 
 #[cfg(test)]
@@ -22,7 +24,7 @@ use language::Buffer;
 use language::LanguageAwareStyling;
 use multi_buffer::{
     Anchor, MultiBuffer, MultiBufferOffset, MultiBufferPoint, MultiBufferRow, MultiBufferSnapshot,
-    RowInfo, ToPoint,
+    RowInfo, ToOffset, ToPoint,
 };
 
 #[cfg(test)]
@@ -37,6 +39,8 @@ pub trait ToDisplayPoint {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HighlightKey {
     InputComposition,
+    SearchMatch,
+    ActiveSearchMatch,
 }
 
 pub struct HighlightedChunk<'a> {
@@ -48,6 +52,7 @@ pub struct DisplayMap {
     buffer: Entity<MultiBuffer>,
     buffer_subscription: BufferSubscription<MultiBufferOffset>,
     tab_map: TabMap,
+    fold_map: FoldMap,
     tab_size: NonZeroU32,
     text_highlights: HashMap<HighlightKey, (HighlightStyle, Vec<Range<Anchor>>)>,
 }
@@ -56,28 +61,62 @@ impl DisplayMap {
     pub fn new(buffer: Entity<MultiBuffer>, tab_size: NonZeroU32, cx: &mut Context<Self>) -> Self {
         let buffer_subscription = buffer.update(cx, |buffer, _| buffer.subscribe());
         let buffer_snapshot = buffer.read(cx).snapshot(cx);
-        let (tab_map, _) = TabMap::new(buffer_snapshot, tab_size);
+        let (tab_map, tab_snapshot) = TabMap::new(buffer_snapshot, tab_size);
+        let (fold_map, _) = FoldMap::new(tab_snapshot);
 
         Self {
             buffer,
             buffer_subscription,
             tab_map,
+            fold_map,
             tab_size,
             text_highlights: HashMap::default(),
         }
     }
 
-    fn sync_through_tab(&mut self, cx: &mut Context<Self>) -> TabSnapshot {
+    fn sync_through_fold(&mut self, cx: &mut Context<Self>) -> FoldSnapshot {
         let buffer_snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
-        let (snapshot, _tab_edits) = self.tab_map.sync(buffer_snapshot, edits, self.tab_size);
-        snapshot
+        let (tab_snapshot, _tab_edits) =
+            self.tab_map
+                .sync(buffer_snapshot, edits.clone(), self.tab_size);
+        self.fold_map.sync(tab_snapshot, &edits)
     }
 
     pub fn snapshot(&mut self, cx: &mut Context<Self>) -> DisplaySnapshot {
-        let tab_snapshot = self.sync_through_tab(cx);
+        let fold_snapshot = self.sync_through_fold(cx);
 
-        DisplaySnapshot { tab_snapshot }
+        DisplaySnapshot { fold_snapshot }
+    }
+
+    pub fn fold<T: ToOffset>(
+        &mut self,
+        ranges: impl IntoIterator<Item = Range<T>>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.sync_through_fold(cx);
+        let buffer_snapshot = snapshot.buffer_snapshot();
+        self.fold_map.fold(ranges.into_iter().map(|range| {
+            range.start.to_offset(buffer_snapshot)..range.end.to_offset(buffer_snapshot)
+        }));
+    }
+
+    pub fn unfold_intersecting<T: ToOffset>(
+        &mut self,
+        ranges: impl IntoIterator<Item = Range<T>>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.sync_through_fold(cx);
+        let buffer_snapshot = snapshot.buffer_snapshot();
+        self.fold_map
+            .unfold_intersecting(ranges.into_iter().map(|range| {
+                range.start.to_offset(buffer_snapshot)..range.end.to_offset(buffer_snapshot)
+            }));
+    }
+
+    pub fn unfold_all(&mut self, cx: &mut Context<Self>) {
+        self.sync_through_fold(cx);
+        self.fold_map.unfold_all();
     }
 
     pub fn highlight_text(
@@ -119,25 +158,30 @@ impl DisplayMap {
 
 #[derive(Clone)]
 pub struct DisplaySnapshot {
-    tab_snapshot: TabSnapshot,
+    fold_snapshot: FoldSnapshot,
 }
 
 impl DisplaySnapshot {
+    pub fn fold_snapshot(&self) -> &FoldSnapshot {
+        &self.fold_snapshot
+    }
+
     pub fn tab_snapshot(&self) -> &TabSnapshot {
-        &self.tab_snapshot
+        self.fold_snapshot.tab_snapshot()
     }
 
     pub fn buffer_snapshot(&self) -> &MultiBufferSnapshot {
-        self.tab_snapshot.buffer_snapshot()
+        self.fold_snapshot.buffer_snapshot()
     }
 
     pub fn point_to_display_point(&self, point: MultiBufferPoint, bias: Bias) -> DisplayPoint {
-        DisplayPoint::from_tab_point(self.tab_snapshot.point_to_tab_point(point, bias))
+        let tab_point = self.tab_snapshot().point_to_tab_point(point, bias);
+        DisplayPoint::from_fold_point(self.fold_snapshot.to_fold_point(tab_point, bias))
     }
 
     pub fn display_point_to_point(&self, point: DisplayPoint, bias: Bias) -> MultiBufferPoint {
-        self.tab_snapshot
-            .tab_point_to_point(point.to_tab_point(), bias)
+        let tab_point = self.fold_snapshot.to_tab_point(point.to_fold_point(), bias);
+        self.tab_snapshot().tab_point_to_point(tab_point, bias)
     }
 
     pub fn display_point_to_anchor(&self, point: DisplayPoint, bias: Bias) -> Anchor {
@@ -149,18 +193,18 @@ impl DisplaySnapshot {
     }
 
     pub fn max_point(&self) -> DisplayPoint {
-        DisplayPoint::from_tab_point(self.tab_snapshot.max_point())
+        DisplayPoint::from_fold_point(self.fold_snapshot.max_point())
     }
 
     pub fn text_chunks(&self, display_row: DisplayRow) -> impl Iterator<Item = &str> {
-        let max_point = self.max_point().to_tab_point();
+        let max_point = self.max_point().to_fold_point();
         let start = if display_row.0 > max_point.row() {
             max_point
         } else {
-            TabPoint::new(display_row.0, 0)
+            FoldPoint::new(display_row.0, 0)
         };
 
-        self.tab_snapshot
+        self.fold_snapshot
             .chunks(
                 start..max_point,
                 LanguageAwareStyling {
@@ -173,16 +217,26 @@ impl DisplaySnapshot {
 
     pub fn highlighted_chunks<'a>(
         &'a self,
-        range: Range<TabPoint>,
+        range: Range<DisplayPoint>,
         language_aware: LanguageAwareStyling,
         editor_style: &'a EditorStyle,
     ) -> impl Iterator<Item = HighlightedChunk<'a>> {
-        self.tab_snapshot
-            .chunks(range, language_aware)
+        self.fold_snapshot
+            .chunks(
+                range.start.to_fold_point()..range.end.to_fold_point(),
+                language_aware,
+            )
             .map(move |chunk| {
-                let style = chunk
-                    .syntax_highlight_id
-                    .and_then(|id| editor_style.syntax.get(id).copied());
+                let style = if chunk.is_placeholder {
+                    Some(HighlightStyle {
+                        fade_out: Some(0.5),
+                        ..HighlightStyle::default()
+                    })
+                } else {
+                    chunk
+                        .syntax_highlight_id
+                        .and_then(|id| editor_style.syntax.get(id).copied())
+                };
 
                 HighlightedChunk {
                     text: chunk.text,
@@ -206,15 +260,63 @@ impl DisplaySnapshot {
     }
 
     pub fn clip_point(&self, point: DisplayPoint, bias: Bias) -> DisplayPoint {
-        DisplayPoint::from_tab_point(self.tab_snapshot.clip_point(point.to_tab_point(), bias))
+        DisplayPoint::from_fold_point(self.fold_snapshot.clip_point(point.to_fold_point(), bias))
     }
 
     pub fn line_len(&self, row: DisplayRow) -> u32 {
-        self.tab_snapshot.line_len(row.0)
+        self.fold_snapshot.line_len(row.0)
     }
 
     pub fn longest_row(&self) -> DisplayRow {
-        DisplayRow(self.buffer_snapshot().text_summary().longest_row)
+        let longest_row = self.buffer_snapshot().text_summary().longest_row;
+        self.point_to_display_point(MultiBufferPoint::new(longest_row, 0), Bias::Left)
+            .row()
+    }
+
+    /// Returns the buffer ranges hidden by the placeholders displayed on
+    /// `display_row`.
+    pub fn folds_on_row(
+        &self,
+        display_row: DisplayRow,
+    ) -> impl Iterator<Item = Range<MultiBufferPoint>> + '_ {
+        self.fold_snapshot
+            .folds_on_row(display_row.0)
+            .map(|(_, buffer_range)| buffer_range)
+    }
+
+    /// Returns the buffer ranges hidden by every displayed fold, in order.
+    pub fn folded_ranges(&self) -> impl Iterator<Item = Range<MultiBufferPoint>> + '_ {
+        self.fold_snapshot.folded_ranges()
+    }
+
+    /// Returns the outermost multi-line bracket range whose opening bracket ends
+    /// on `buffer_row`. The range spans the text between the brackets.
+    pub fn foldable_range_for_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<Range<MultiBufferPoint>> {
+        let buffer_snapshot = self.buffer_snapshot();
+        let row_start = MultiBufferPoint::new(buffer_row.0, 0);
+        let row_end = MultiBufferPoint::new(buffer_row.0, buffer_snapshot.line_len(buffer_row));
+        self.foldable_ranges(row_start..row_end)
+            .find(|range| range.start.row == buffer_row.0)
+    }
+
+    /// Returns the multi-line bracket ranges intersecting `range`, outer ranges
+    /// first. Each range spans the text between the brackets.
+    pub fn foldable_ranges(
+        &self,
+        range: Range<MultiBufferPoint>,
+    ) -> impl Iterator<Item = Range<MultiBufferPoint>> + '_ {
+        let buffer_snapshot = self.buffer_snapshot();
+        buffer_snapshot
+            .bracket_ranges(range)
+            .into_iter()
+            .filter_map(|(open_range, close_range)| {
+                let start = buffer_snapshot.offset_to_point(open_range.end);
+                let end = buffer_snapshot.offset_to_point(close_range.start);
+                (end.row > start.row).then_some(start..end)
+            })
     }
 
     pub fn widest_line_number(&self) -> u32 {
@@ -315,11 +417,11 @@ impl DisplayPoint {
             .point_to_offset(map.display_point_to_point(self, bias))
     }
 
-    fn to_tab_point(self) -> TabPoint {
-        TabPoint(self.0)
+    fn to_fold_point(self) -> FoldPoint {
+        FoldPoint(self.0)
     }
 
-    fn from_tab_point(point: TabPoint) -> Self {
+    fn from_fold_point(point: FoldPoint) -> Self {
         Self(point.0)
     }
 }
@@ -0,0 +1,563 @@
+use std::{cmp, ops::Range, sync::Arc};
+use text::{Bias, Point};
+
+use language::{HighlightId, LanguageAwareStyling};
+use multi_buffer::{Anchor, MultiBufferOffset, MultiBufferSnapshot, ToOffset, ToPoint};
+
+use super::tab_map::{TabChunks, TabPoint, TabSnapshot};
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct FoldChunk<'a> {
+    pub text: &'a str,
+    pub syntax_highlight_id: Option<HighlightId>,
+    pub is_placeholder: bool,
+}
+
+/// Hides multi-line ranges of a buffer behind a single-line placeholder.
+///
+/// Folds are stored as anchor ranges so they follow the buffer through edits. A
+/// fold is dropped as soon as an edit touches the text it hides, and only the
+/// outermost of several nested folds is displayed.
+pub struct FoldMap {
+    folds: Vec<Range<Anchor>>,
+    snapshot: FoldSnapshot,
+}
+
+impl FoldMap {
+    pub fn new(tab_snapshot: TabSnapshot) -> (Self, FoldSnapshot) {
+        let snapshot = FoldSnapshot {
+            tab_snapshot,
+            folds: Arc::default(),
+        };
+        (
+            Self {
+                folds: Vec::new(),
+                snapshot: snapshot.clone(),
+            },
+            snapshot,
+        )
+    }
+
+    pub(super) fn sync(
+        &mut self,
+        tab_snapshot: TabSnapshot,
+        buffer_edits: &[text::Edit<MultiBufferOffset>],
+    ) -> FoldSnapshot {
+        if !self.folds.is_empty() {
+            let buffer = tab_snapshot.buffer_snapshot();
+            self.folds.retain(|fold| {
+                let start = fold.start.to_offset(buffer);
+                let end = fold.end.to_offset(buffer);
+                start < end
+                    && !buffer_edits
+                        .iter()
+                        .any(|edit| edit.new.start < end && edit.new.end > start)
+            });
+        }
+
+        self.snapshot = FoldSnapshot::build(tab_snapshot, &self.folds);
+        self.snapshot.clone()
+    }
+
+    pub(super) fn fold(&mut self, ranges: impl IntoIterator<Item = Range<MultiBufferOffset>>) {
+        let buffer = self.snapshot.tab_snapshot.buffer_snapshot();
+        for range in ranges {
+            if range.start >= range.end {
+                continue;
+            }
+            let fold = buffer.anchor_after(&range.start)..buffer.anchor_before(&range.end);
+            let is_duplicate = self.folds.iter().any(|existing| {
+                existing.start.cmp(&fold.start, buffer).is_eq()
+                    && existing.end.cmp(&fold.end, buffer).is_eq()
+            });
+            if !is_duplicate {
+                self.folds.push(fold);
+            }
+        }
+        self.folds.sort_by(|left, right| {
+            left.start
+                .cmp(&right.start, buffer)
+                .then_with(|| right.end.cmp(&left.end, buffer))
+        });
+        self.rebuild();
+    }
+
+    /// Removes every fold that intersects one of `ranges`, including folds that
+    /// merely touch a range's boundaries.
+    pub(super) fn unfold_intersecting(
+        &mut self,
+        ranges: impl IntoIterator<Item = Range<MultiBufferOffset>>,
+    ) {
+        let buffer = self.snapshot.tab_snapshot.buffer_snapshot();
+        let ranges = ranges.into_iter().collect::<Vec<_>>();
+        self.folds.retain(|fold| {
+            let start = fold.start.to_offset(buffer);
+            let end = fold.end.to_offset(buffer);
+            !ranges
+                .iter()
+                .any(|range| range.start <= end && range.end >= start)
+        });
+        self.rebuild();
+    }
+
+    pub(super) fn unfold_all(&mut self) {
+        self.folds.clear();
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.snapshot = FoldSnapshot::build(self.snapshot.tab_snapshot.clone(), &self.folds);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Fold {
+    /// The hidden text, in tab coordinates.
+    range: Range<TabPoint>,
+    /// The placeholder shown instead of the hidden text, in fold coordinates.
+    display_range: Range<FoldPoint>,
+    /// How many rows are hidden by this fold and every fold before it.
+    hidden_rows: u32,
+    placeholder: String,
+}
+
+#[derive(Clone)]
+pub struct FoldSnapshot {
+    tab_snapshot: TabSnapshot,
+    folds: Arc<[Fold]>,
+}
+
+impl FoldSnapshot {
+    fn build(tab_snapshot: TabSnapshot, fold_ranges: &[Range<Anchor>]) -> Self {
+        let buffer = tab_snapshot.buffer_snapshot();
+        let mut folds = Vec::<Fold>::new();
+        let mut hidden_rows = 0;
+        for fold_range in fold_ranges {
+            let start = fold_range.start.to_point(buffer);
+            let end = fold_range.end.to_point(buffer);
+            if end.row <= start.row {
+                continue;
+            }
+
+            let start = tab_snapshot.point_to_tab_point(start, Bias::Left);
+            let end = tab_snapshot.point_to_tab_point(end, Bias::Right);
+            let display_start = match folds.last() {
+                Some(previous) if start < previous.range.end => continue,
+                Some(previous) if start.row() == previous.range.end.row() => FoldPoint::new(
+                    previous.display_range.end.row(),
+                    previous.display_range.end.column() + start.column()
+                        - previous.range.end.column(),
+                ),
+                _ => FoldPoint::new(start.row() - hidden_rows, start.column()),
+            };
+
+            let placeholder = placeholder_text(end.row() - start.row());
+            let placeholder_len =
+                u32::try_from(placeholder.len()).expect("placeholder length should fit in u32");
+            hidden_rows += end.row() - start.row();
+            folds.push(Fold {
+                range: start..end,
+                display_range: display_start
+                    ..FoldPoint::new(
+                        display_start.row(),
+                        display_start.column() + placeholder_len,
+                    ),
+                hidden_rows,
+                placeholder,
+            });
+        }
+
+        Self {
+            tab_snapshot,
+            folds: folds.into(),
+        }
+    }
+
+    pub fn tab_snapshot(&self) -> &TabSnapshot {
+        &self.tab_snapshot
+    }
+
+    pub fn buffer_snapshot(&self) -> &MultiBufferSnapshot {
+        self.tab_snapshot.buffer_snapshot()
+    }
+
+    pub fn has_folds(&self) -> bool {
+        !self.folds.is_empty()
+    }
+
+    /// Returns the buffer ranges hidden by the displayed folds, in order.
+    pub fn folded_ranges(&self) -> impl Iterator<Item = Range<Point>> + '_ {
+        self.folds.iter().map(|fold| {
+            self.tab_snapshot
+                .tab_point_to_point(fold.range.start, Bias::Left)
+                ..self
+                    .tab_snapshot
+                    .tab_point_to_point(fold.range.end, Bias::Right)
+        })
+    }
+
+    pub fn max_point(&self) -> FoldPoint {
+        self.to_fold_point(self.tab_snapshot.max_point(), Bias::Right)
+    }
+
+    pub fn line_len(&self, row: u32) -> u32 {
+        let last_fold_on_row = self
+            .folds
+            .partition_point(|fold| fold.display_range.start.row() <= row)
+            .checked_sub(1)
+            .and_then(|index| self.folds.get(index))
+            .filter(|fold| fold.display_range.end.row() == row);
+        match last_fold_on_row {
+            Some(fold) => {
+                fold.display_range.end.column()
+                    + self
+                        .tab_snapshot
+                        .line_len(fold.range.end.row())
+                        .saturating_sub(fold.range.end.column())
+            }
+            None => {
+                let tab_row = self.to_tab_point(FoldPoint::new(row, 0), Bias::Left).row();
+                self.tab_snapshot.line_len(tab_row)
+            }
+        }
+    }
+
+    pub fn clip_point(&self, point: FoldPoint, bias: Bias) -> FoldPoint {
+        let max_point = self.max_point();
+        let row = point.row().min(max_point.row());
+        let point = FoldPoint::new(row, point.column().min(self.line_len(row)));
+        let tab_point = self
+            .tab_snapshot
+            .clip_point(self.to_tab_point(point, bias), bias);
+        self.to_fold_point(tab_point, bias)
+    }
+
+    /// Converts a tab point to a fold point. Points hidden by a fold are moved to
+    /// the start or the end of its placeholder, depending on `bias`.
+    pub fn to_fold_point(&self, point: TabPoint, bias: Bias) -> FoldPoint {
+        let preceding_fold = self
+            .folds
+            .partition_point(|fold| fold.range.start < point)
+            .checked_sub(1)
+            .and_then(|index| self.folds.get(index));
+        let Some(fold) = preceding_fold else {
+            return FoldPoint(point.0);
+        };
+
+        if point < fold.range.end {
+            match bias {
+                Bias::Left => fold.display_range.start,
+                Bias::Right => fold.display_range.end,
+            }
+        } else if point.row() == fold.range.end.row() {
+            FoldPoint::new(
+                fold.display_range.end.row(),
+                fold.display_range.end.column() + point.column() - fold.range.end.column(),
+            )
+        } else {
+            FoldPoint::new(point.row() - fold.hidden_rows, point.column())
+        }
+    }
+
+    /// Converts a fold point to a tab point. Points inside a placeholder are
+    /// moved to the start or the end of the text it hides, depending on `bias`.
+    pub fn to_tab_point(&self, point: FoldPoint, bias: Bias) -> TabPoint {
+        let preceding_fold = self
+            .folds
+            .partition_point(|fold| fold.display_range.start < point)
+            .checked_sub(1)
+            .and_then(|index| self.folds.get(index));
+        let Some(fold) = preceding_fold else {
+            return TabPoint(point.0);
+        };
+
+        if point < fold.display_range.end {
+            match bias {
+                Bias::Left => fold.range.start,
+                Bias::Right => fold.range.end,
+            }
+        } else if point.row() == fold.display_range.end.row() {
+            TabPoint::new(
+                fold.range.end.row(),
+                fold.range.end.column() + point.column() - fold.display_range.end.column(),
+            )
+        } else {
+            TabPoint::new(point.row() + fold.hidden_rows, point.column())
+        }
+    }
+
+    /// Returns the placeholders displayed on `row`, along with the buffer
+    /// ranges they hide.
+    pub fn folds_on_row(
+        &self,
+        row: u32,
+    ) -> impl Iterator<Item = (Range<FoldPoint>, Range<Point>)> + '_ {
+        let first_fold = self
+            .folds
+            .partition_point(|fold| fold.display_range.start.row() < row);
+        self.folds
+            .get(first_fold..)
+            .unwrap_or_default()
+            .iter()
+            .take_while(move |fold| fold.display_range.start.row() == row)
+            .map(|fold| {
+                (
+                    fold.display_range.clone(),
+                    self.tab_snapshot
+                        .tab_point_to_point(fold.range.start, Bias::Left)
+                        ..self
+                            .tab_snapshot
+                            .tab_point_to_point(fold.range.end, Bias::Right),
+                )
+            })
+    }
+
+    pub(super) fn chunks(
+        &self,
+        range: Range<FoldPoint>,
+        language_aware: LanguageAwareStyling,
+    ) -> FoldChunks<'_> {
+        let first_fold = self
+            .folds
+            .partition_point(|fold| fold.display_range.end <= range.start);
+        FoldChunks {
+            snapshot: self,
+            folds: self.folds.get(first_fold..).unwrap_or_default(),
+            tab_chunks: None,
+            position: range.start,
+            max_position: range.end,
+            language_aware,
+        }
+    }
+}
+
+fn placeholder_text(hidden_rows: u32) -> String {
+    match hidden_rows.saturating_sub(1).max(1) {
+        1 => "… 1 line".to_string(),
+        lines => format!("… {lines} lines"),
+    }
+}
+
+pub(super) struct FoldChunks<'a> {
+    snapshot: &'a FoldSnapshot,
+    folds: &'a [Fold],
+    tab_chunks: Option<TabChunks<'a>>,
+    position: FoldPoint,
+    max_position: FoldPoint,
+    language_aware: LanguageAwareStyling,
+}
+
+impl<'a> Iterator for FoldChunks<'a> {
+    type Item = FoldChunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(tab_chunks) = &mut self.tab_chunks {
+                if let Some(chunk) = tab_chunks.next() {
+                    return Some(FoldChunk {
+                        text: chunk.text,
+                        syntax_highlight_id: chunk.syntax_highlight_id,
+                        is_placeholder: false,
+                    });
+                }
+                self.tab_chunks = None;
+            }
+
+            if self.position >= self.max_position {
+                return None;
+            }
+
+            match self.folds.split_first() {
+                Some((fold, remaining_folds)) if fold.display_range.start <= self.position => {
+                    let end = cmp::min(fold.display_range.end, self.max_position);
+                    let start_column = self.position.column() - fold.display_range.start.column();
+                    let end_column = end.column() - fold.display_range.start.column();
+                    let text = usize::try_from(start_column)
+                        .ok()
+                        .zip(usize::try_from(end_column).ok())
+                        .and_then(|(start, end)| fold.placeholder.get(start..end))
+                        .unwrap_or_default();
+                    self.folds = remaining_folds;
+                    self.position = end;
+                    return Some(FoldChunk {
+                        text,
+                        syntax_highlight_id: None,
+                        is_placeholder: true,
+                    });
+                }
+                next_fold => {
+                    let segment_end = next_fold.map_or(self.max_position, |(fold, _)| {
+                        cmp::min(fold.display_range.start, self.max_position)
+                    });
+                    let tab_start = self.snapshot.to_tab_point(self.position, Bias::Right);
+                    let tab_end = self.snapshot.to_tab_point(segment_end, Bias::Left);
+                    self.tab_chunks = Some(
+                        self.snapshot
+                            .tab_snapshot
+                            .chunks(tab_start..tab_end, self.language_aware),
+                    );
+                    self.position = segment_end;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FoldPoint(pub Point);
+
+impl FoldPoint {
+    pub fn new(row: u32, column: u32) -> Self {
+        Self(Point::new(row, column))
+    }
+
+    pub fn row(self) -> u32 {
+        self.0.row
+    }
+
+    pub fn column(self) -> u32 {
+        self.0.column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use gpui::{App, AppContext};
+
+    use language::Buffer;
+    use multi_buffer::MultiBuffer;
+
+    use crate::{DEFAULT_TAB_SIZE, display_map::TabMap};
+
+    fn fold_map_for_text(text: &str, cx: &mut App) -> (FoldMap, MultiBufferSnapshot) {
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let buffer_snapshot = multi_buffer.read(cx).snapshot(cx);
+        let (_, tab_snapshot) = TabMap::new(buffer_snapshot.clone(), DEFAULT_TAB_SIZE);
+        let (fold_map, _) = FoldMap::new(tab_snapshot);
+        (fold_map, buffer_snapshot)
+    }
+
+    fn text(snapshot: &FoldSnapshot) -> String {
+        snapshot
+            .chunks(
+                FoldPoint::default()..snapshot.max_point(),
+                LanguageAwareStyling {
+                    tree_sitter: false,
+                    diagnostics: false,
+                },
+            )
+            .map(|chunk| chunk.text)
+            .collect()
+    }
+
+    fn offset(buffer: &MultiBufferSnapshot, row: u32, column: u32) -> MultiBufferOffset {
+        buffer.point_to_offset(Point::new(row, column))
+    }
+
+    #[gpui::test]
+    fn test_folded_text(cx: &mut App) {
+        let (mut fold_map, buffer) = fold_map_for_text(
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": 3\n}\nnext",
+            cx,
+        );
+
+        fold_map.fold([offset(&buffer, 1, 8)..offset(&buffer, 4, 2)]);
+        assert_eq!(
+            text(&fold_map.snapshot),
+            "{\n  \"a\": [… 2 lines],\n  \"b\": 3\n}\nnext"
+        );
+
+        fold_map.fold([offset(&buffer, 0, 1)..offset(&buffer, 6, 0)]);
+        assert_eq!(text(&fold_map.snapshot), "{… 5 lines}\nnext");
+        assert_eq!(fold_map.snapshot.max_point(), FoldPoint::new(1, 4));
+        assert_eq!(fold_map.snapshot.line_len(0), 13);
+
+        fold_map.unfold_intersecting([offset(&buffer, 0, 0)..offset(&buffer, 0, 1)]);
+        assert_eq!(
+            text(&fold_map.snapshot),
+            "{\n  \"a\": [… 2 lines],\n  \"b\": 3\n}\nnext"
+        );
+
+        fold_map.unfold_all();
+        assert_eq!(text(&fold_map.snapshot), buffer.text());
+    }
+
+    #[gpui::test]
+    fn test_adjacent_folds(cx: &mut App) {
+        let (mut fold_map, buffer) = fold_map_for_text("[{\n  1\n}, {\n  2\n}]\nend", cx);
+
+        fold_map.fold([
+            offset(&buffer, 0, 2)..offset(&buffer, 2, 0),
+            offset(&buffer, 2, 4)..offset(&buffer, 4, 0),
+        ]);
+        let snapshot = fold_map.snapshot.clone();
+        assert_eq!(text(&snapshot), "[{… 1 line}, {… 1 line}]\nend");
+        assert_eq!(snapshot.line_len(0), 28);
+
+        let after_second_fold = TabPoint::new(4, 1);
+        let fold_point = snapshot.to_fold_point(after_second_fold, Bias::Left);
+        assert_eq!(fold_point, FoldPoint::new(0, 27));
+        assert_eq!(
+            snapshot.to_tab_point(fold_point, Bias::Left),
+            after_second_fold
+        );
+
+        let hidden = TabPoint::new(1, 2);
+        assert_eq!(
+            snapshot.to_fold_point(hidden, Bias::Left),
+            FoldPoint::new(0, 2)
+        );
+        assert_eq!(
+            snapshot.to_fold_point(hidden, Bias::Right),
+            FoldPoint::new(0, 12)
+        );
+        assert_eq!(
+            snapshot.clip_point(FoldPoint::new(0, 5), Bias::Left),
+            FoldPoint::new(0, 2)
+        );
+        assert_eq!(
+            snapshot.clip_point(FoldPoint::new(0, 5), Bias::Right),
+            FoldPoint::new(0, 12)
+        );
+        assert_eq!(
+            snapshot.to_tab_point(FoldPoint::new(1, 2), Bias::Left),
+            TabPoint::new(5, 2)
+        );
+    }
+
+    #[gpui::test]
+    fn test_edits_inside_folds_unfold(cx: &mut App) {
+        let text = "{\n  \"a\": 1\n}";
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let buffer_snapshot = multi_buffer.read(cx).snapshot(cx);
+        let (mut tab_map, tab_snapshot) = TabMap::new(buffer_snapshot.clone(), DEFAULT_TAB_SIZE);
+        let (mut fold_map, _) = FoldMap::new(tab_snapshot);
+        let mut subscription = multi_buffer.update(cx, |multi_buffer, _| multi_buffer.subscribe());
+
+        fold_map.fold([offset(&buffer_snapshot, 0, 1)..offset(&buffer_snapshot, 2, 0)]);
+        assert!(fold_map.snapshot.has_folds());
+
+        multi_buffer.update(cx, |multi_buffer, cx| {
+            multi_buffer.edit([(Point::new(2, 1)..Point::new(2, 1), "\n")], cx);
+        });
+        let edits = subscription.consume().into_inner();
+        let buffer_snapshot = multi_buffer.read(cx).snapshot(cx);
+        let (tab_snapshot, _) = tab_map.sync(buffer_snapshot, edits.clone(), DEFAULT_TAB_SIZE);
+        let snapshot = fold_map.sync(tab_snapshot, &edits);
+        assert_eq!(text(&snapshot), "{… 1 line}\n");
+
+        multi_buffer.update(cx, |multi_buffer, cx| {
+            multi_buffer.edit([(Point::new(1, 2)..Point::new(1, 2), " ")], cx);
+        });
+        let edits = subscription.consume().into_inner();
+        let buffer_snapshot = multi_buffer.read(cx).snapshot(cx);
+        let (tab_snapshot, _) = tab_map.sync(buffer_snapshot, edits.clone(), DEFAULT_TAB_SIZE);
+        let snapshot = fold_map.sync(tab_snapshot, &edits);
+        assert!(!snapshot.has_folds());
+        assert_eq!(text(&snapshot), "{\n   \"a\": 1\n}\n");
+    }
+}
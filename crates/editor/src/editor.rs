@@ -1,16 +1,21 @@
 mod config;
+mod diagnostics;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod folding;
 pub mod items;
 mod movement;
 mod persistence;
 mod scroll;
+mod search;
 mod selections_collection;
 
+pub use diagnostics::{Diagnostic, DiagnosticSeverity};
 pub use editor_settings::{CurrentLineHighlight, EditorSettings, Gutter};
 pub use element::EditorElement;
 pub use multi_buffer::{MultiBufferOffset, MultiBufferOffsetUtf16};
+pub use search::SearchQuery;
 
 use gpui::{
     AnyElement, App, Axis, Bounds, ClipboardEntry, ClipboardItem, Context, Entity,
@@ -40,7 +45,7 @@ use theme::{ActiveTheme, SyntaxTheme, ThemeSettings};
 use util::ResultExt;
 
 use crate::{
-    display_map::{DisplayMap, DisplayPoint, DisplayRow, DisplaySnapshot, HighlightKey},
+    display_map::{DisplayMap, DisplayPoint, DisplaySnapshot, HighlightKey, ToDisplayPoint},
     element::PositionMap,
     scroll::ScrollOffset,
     selections_collection::{MutableSelectionsCollection, SelectionsCollection},
@@ -204,11 +209,17 @@ impl EditorSnapshot {
             } else {
                 gpui::px(0.0)
             };
+            let fold_area_width = if gutter_settings.folds {
+                ch_width * 2.0
+            } else {
+                gpui::px(0.0)
+            };
 
             GutterDimensions {
                 left_padding,
                 right_padding,
-                width: line_gutter_width + left_padding + right_padding,
+                fold_area_width,
+                width: line_gutter_width + left_padding + right_padding + fold_area_width,
                 margin: GutterDimensions::default_gutter_margin(font_id, font_size, cx),
             }
         } else {
@@ -229,6 +240,7 @@ impl Deref for EditorSnapshot {
 pub struct GutterDimensions {
     pub left_padding: Pixels,
     pub right_padding: Pixels,
+    pub fold_area_width: Pixels,
     pub width: Pixels,
     pub margin: Pixels,
 }
@@ -417,6 +429,8 @@ pub struct Editor {
     masked: bool,
     muted: bool,
     current_line_highlight: Option<CurrentLineHighlight>,
+    search: Option<search::SearchBar>,
+    diagnostics: Vec<diagnostics::DiagnosticEntry>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -492,6 +506,14 @@ impl Editor {
                 cx,
                 move |_, event: &BufferEvent, _, cx| {
                     let events = match event {
+                        BufferEvent::Edited => {
+                            if let Err(error) = editor.update(cx, |editor, cx| {
+                                editor.refresh_search_matches(false, cx);
+                            }) {
+                                log::debug!("Failed to update editor search matches: {error:?}");
+                            }
+                            return;
+                        }
                         BufferEvent::DirtyChanged => [Some(EditorEvent::DirtyChanged), None],
                         BufferEvent::FileHandleChanged => [
                             Some(EditorEvent::TitleChanged),
@@ -542,6 +564,8 @@ impl Editor {
             masked: false,
             muted: false,
             current_line_highlight: None,
+            search: None,
+            diagnostics: Vec::new(),
             _subscriptions: subscriptions,
         };
 
@@ -1046,10 +1070,11 @@ impl Editor {
             MultiBufferOffset(self.cursor_offset(cx).min(buffer_snapshot.len().0)),
             Bias::Left,
         );
-        let cursor_point = buffer_snapshot.offset_to_point(cursor);
-        let row = DisplayRow(cursor_point.row);
-        let line_start = buffer_snapshot.point_to_offset(text::Point::new(cursor_point.row, 0));
-        let line = buffer_line_text(display_snapshot.buffer_snapshot(), row.0);
+        let line_start_point =
+            DisplayPoint::new(cursor.to_display_point(&display_snapshot).row(), 0)
+                .to_point(&display_snapshot);
+        let line_start = buffer_snapshot.point_to_offset(line_start_point);
+        let line = buffer_line_text(display_snapshot.buffer_snapshot(), line_start_point.row);
 
         for (index, character) in line.char_indices() {
             if !character.is_whitespace() {
@@ -1066,8 +1091,8 @@ impl Editor {
             MultiBufferOffset(self.cursor_offset(cx).min(buffer_snapshot.len().0)),
             Bias::Left,
         );
-        let cursor_point = buffer_snapshot.offset_to_point(cursor);
-        let line_start = buffer_snapshot.point_to_offset(text::Point::new(cursor_point.row, 0));
+        let line_start = DisplayPoint::new(cursor.to_display_point(&display_snapshot).row(), 0)
+            .to_offset(&display_snapshot, Bias::Left);
 
         if !stop_at_indent {
             return line_start.0;
@@ -1088,10 +1113,10 @@ impl Editor {
             MultiBufferOffset(self.cursor_offset(cx).min(buffer_snapshot.len().0)),
             Bias::Left,
         );
-        let cursor_point = buffer_snapshot.offset_to_point(cursor);
-        let line_start = buffer_snapshot.point_to_offset(text::Point::new(cursor_point.row, 0));
-        let line_len = buffer_snapshot.line_len(MultiBufferRow(cursor_point.row)) as usize;
-        (line_start + line_len).0
+        let row = cursor.to_display_point(&display_snapshot).row();
+        DisplayPoint::new(row, display_snapshot.line_len(row))
+            .to_offset(&display_snapshot, Bias::Right)
+            .0
     }
 
     fn replace_range(&mut self, range: Range<usize>, new_text: &str, cx: &mut Context<Self>) {
@@ -1239,6 +1264,34 @@ impl Editor {
         cx.notify();
     }
 
+    /// Replaces the buffer contents with `text` in a single undoable transaction.
+    ///
+    /// Only the changed ranges are edited, so selections in untouched text keep their position.
+    pub fn apply_text(&mut self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let edits = language::text_diff(&self.text(cx), text);
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(window, cx, |editor, _, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit(
+                    edits.into_iter().map(|(range, new_text)| {
+                        (
+                            MultiBufferOffset(range.start)..MultiBufferOffset(range.end),
+                            new_text,
+                        )
+                    }),
+                    cx,
+                );
+            });
+            editor.selection_goal = SelectionGoal::None;
+        });
+        self.refresh_search_matches(false, cx);
+        cx.emit(EditorEvent::BufferEdited);
+        cx.notify();
+    }
+
     pub fn text(&self, cx: &App) -> String {
         self.buffer.read(cx).read(cx).text()
     }
@@ -2777,7 +2830,9 @@ impl EntityInputHandler for Editor {
                 range.end.min(display_snapshot.buffer_snapshot().len().0),
             ));
 
-        let row = start_point.row;
+        let start_display_point = display_snapshot.point_to_display_point(start_point, Bias::Left);
+        let end_display_point = display_snapshot.point_to_display_point(end_point, Bias::Right);
+        let row = start_display_point.row().0;
         let scroll_row = position_map
             .scroll_position
             .y
@@ -2791,16 +2846,11 @@ impl EntityInputHandler for Editor {
             return None;
         }
 
-        let start_display_column = display_snapshot
-            .point_to_display_point(start_point, Bias::Left)
-            .column() as usize;
-        let end_display_column = if start_point.row == end_point.row {
-            display_snapshot
-                .point_to_display_point(end_point, Bias::Right)
-                .column() as usize
+        let start_display_column = start_display_point.column() as usize;
+        let end_display_column = if start_display_point.row() == end_display_point.row() {
+            end_display_point.column() as usize
         } else {
-            let row = DisplayRow(row);
-            display_snapshot.line_len(row) as usize
+            display_snapshot.line_len(start_display_point.row()) as usize
         };
         let line_display_column_start = line.line_display_column_start;
         let start_display_column = start_display_column
@@ -2878,8 +2928,18 @@ impl EntityInputHandler for Editor {
 }
 
 impl Render for Editor {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        EditorElement::new(&cx.entity(), self.create_style(cx))
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let element = EditorElement::new(&cx.entity(), self.create_style(cx));
+        match self.render_search_bar(window, cx) {
+            Some(search_bar) => gpui::div()
+                .size_full()
+                .flex()
+                .flex_col()
+                .child(search_bar)
+                .child(gpui::div().flex_1().min_h_0().child(element))
+                .into_any_element(),
+            None => element.into_any_element(),
+        }
     }
 }
 
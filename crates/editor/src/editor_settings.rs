@@ -12,6 +12,7 @@ pub struct EditorSettings {
 pub struct Gutter {
     pub min_line_number_digits: usize,
     pub line_numbers: bool,
+    pub folds: bool,
 }
 
 impl Settings for EditorSettings {
@@ -31,6 +32,9 @@ impl Settings for EditorSettings {
                 line_numbers: gutter
                     .line_numbers
                     .expect("editor gutter line numbers should be defaulted"),
+                folds: gutter
+                    .folds
+                    .expect("editor gutter folds should be defaulted"),
             },
         }
     }
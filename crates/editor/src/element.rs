@@ -12,7 +12,7 @@ use std::{
     any::TypeId,
     borrow::Cow,
     cmp::{self, Ordering},
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Write,
     ops::Range,
     panic,
@@ -29,7 +29,7 @@ use util::ResultExt;
 use crate::{
     CurrentLineHighlight, Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle,
     GutterDimensions, MAX_LINE_LEN, ScrollbarDrag, SizingBehavior,
-    display_map::{DisplayPoint, DisplayRow, DisplaySnapshot, TabPoint},
+    display_map::{DisplayPoint, DisplayRow, DisplaySnapshot},
     scroll::ScrollOffset,
 };
 
@@ -183,9 +183,12 @@ pub struct EditorLayout {
     hitbox: Hitbox,
     gutter_hitbox: Hitbox,
     line_numbers: Arc<HashMap<MultiBufferRow, LineNumberLayout>>,
+    fold_indicators: Vec<FoldIndicator>,
     active_line_background: Option<PaintQuad>,
     cursor: Option<PaintQuad>,
     selection_ranges: Vec<Range<DisplayPoint>>,
+    search_highlights: Vec<(Range<DisplayPoint>, Hsla)>,
+    diagnostic_underlines: Vec<(Range<DisplayPoint>, Hsla)>,
     vertical_scrollbar: Option<ScrollbarPrepaint>,
     horizontal_scrollbar: Option<ScrollbarPrepaint>,
     em_width: Pixels,
@@ -216,6 +219,12 @@ struct LineNumberLayout {
     segments: SmallVec<[LineNumberSegment; 1]>,
 }
 
+#[derive(Debug, Clone, Copy)]
+struct FoldIndicator {
+    bounds: Bounds<Pixels>,
+    folded: bool,
+}
+
 struct Gutter<'a> {
     line_height: Pixels,
     range: Range<DisplayRow>,
@@ -298,12 +307,26 @@ impl EditorElement {
         register_action(editor, window, Editor::undo_selection);
         register_action(editor, window, Editor::redo_selection);
         register_action(editor, window, Editor::toggle_line_numbers);
+        register_action(editor, window, Editor::fold);
+        register_action(editor, window, Editor::unfold);
+        register_action(editor, window, Editor::fold_all);
+        register_action(editor, window, Editor::unfold_all);
         register_action(editor, window, Editor::move_to_beginning_of_line);
         register_action(editor, window, Editor::move_to_end_of_line);
         register_action(editor, window, Editor::select_to_beginning_of_line);
         register_action(editor, window, Editor::select_to_end_of_line);
         register_action(editor, window, Editor::delete_to_beginning_of_line);
         register_action(editor, window, Editor::delete_to_end_of_line);
+        register_action(editor, window, Editor::deploy_search);
+        register_action(editor, window, Editor::deploy_replace);
+        register_action(editor, window, Editor::dismiss_search);
+        register_action(editor, window, Editor::select_next_match);
+        register_action(editor, window, Editor::select_previous_match);
+        register_action(editor, window, Editor::toggle_case_sensitive);
+        register_action(editor, window, Editor::toggle_whole_word);
+        register_action(editor, window, Editor::toggle_regex);
+        register_action(editor, window, Editor::replace_next);
+        register_action(editor, window, Editor::replace_all);
         register_action(
             editor,
             window,
@@ -372,6 +395,18 @@ impl EditorElement {
         cx: &mut App,
     ) {
         let corner_radius = 0.15 * layout.position_map.line_height;
+        for (range, color) in &layout.search_highlights {
+            Self::paint_highlighted_range(
+                range.clone(),
+                true,
+                *color,
+                corner_radius,
+                corner_radius * 2.0,
+                layout,
+                text_bounds,
+                window,
+            );
+        }
         for range in &layout.selection_ranges {
             Self::paint_highlighted_range(
                 range.clone(),
@@ -386,6 +421,55 @@ impl EditorElement {
         }
     }
 
+    fn paint_diagnostic_underlines(layout: &EditorLayout, window: &mut Window) {
+        let Some(first_line) = layout.position_map.line_layouts.first() else {
+            return;
+        };
+        let start_row = first_line.row;
+        let line_height = layout.position_map.line_height;
+        for (range, color) in &layout.diagnostic_underlines {
+            for row in range.start.row().0..=range.end.row().0 {
+                let Some(line_layout) = row.checked_sub(start_row.0).and_then(|line_index| {
+                    layout.position_map.line_layouts.get(line_index as usize)
+                }) else {
+                    continue;
+                };
+
+                let start_column = if row == range.start.row().0 {
+                    range.start.column() as usize
+                } else {
+                    0
+                };
+                let end_column = if row == range.end.row().0 {
+                    range.end.column() as usize
+                } else {
+                    usize::MAX
+                };
+                let start_index = start_column
+                    .saturating_sub(line_layout.line_display_column_start)
+                    .min(line_layout.len);
+                let end_index = end_column
+                    .saturating_sub(line_layout.line_display_column_start)
+                    .min(line_layout.len);
+                if start_index >= end_index {
+                    continue;
+                }
+
+                let start_x = line_layout.origin.x + line_layout.x_for_index(start_index);
+                let end_x = line_layout.origin.x + line_layout.x_for_index(end_index);
+                window.paint_underline(
+                    gpui::point(start_x, line_layout.origin.y + line_height * 0.85),
+                    end_x - start_x,
+                    &UnderlineStyle {
+                        color: Some(*color),
+                        thickness: gpui::px(1.0),
+                        wavy: true,
+                    },
+                );
+            }
+        }
+    }
+
     fn layout_line_numbers(
         &self,
         gutter: &Gutter<'_>,
@@ -430,7 +514,8 @@ impl EditorElement {
                     + gpui::point(
                         gutter.hitbox.size.width
                             - shaped_line.width
-                            - gutter.dimensions.right_padding,
+                            - gutter.dimensions.right_padding
+                            - gutter.dimensions.fold_area_width,
                         Pixels::from(f64::from(row_offset) * f64::from(gutter.line_height))
                             - Pixels::from(scroll_top % f64::from(gutter.line_height)),
                     );
@@ -473,6 +558,92 @@ impl EditorElement {
         Arc::new(line_numbers)
     }
 
+    fn layout_fold_indicators(gutter: &Gutter<'_>) -> Vec<FoldIndicator> {
+        let fold_area_width = gutter.dimensions.fold_area_width;
+        if fold_area_width <= Pixels::ZERO {
+            return Vec::new();
+        }
+        let Some(first_buffer_row) = gutter.row_infos.first().and_then(|info| info.buffer_row)
+        else {
+            return Vec::new();
+        };
+
+        let snapshot = gutter.snapshot;
+        let last_display_row = DisplayRow(gutter.range.end.0.saturating_sub(1));
+        let last_visible_point =
+            DisplayPoint::new(last_display_row, snapshot.line_len(last_display_row))
+                .to_point(snapshot);
+        let foldable_rows = snapshot
+            .foldable_ranges(text::Point::new(first_buffer_row, 0)..last_visible_point)
+            .map(|range| range.start.row)
+            .collect::<HashSet<_>>();
+
+        let scroll_top = gutter.scroll_position.y * f64::from(gutter.line_height);
+        gutter
+            .row_infos
+            .iter()
+            .enumerate()
+            .filter_map(|(index, row_info)| {
+                let row_offset = u32::try_from(index).ok()?;
+                let display_row = DisplayRow(gutter.range.start.0.checked_add(row_offset)?);
+                let folded = snapshot.folds_on_row(display_row).next().is_some();
+                if !folded && !foldable_rows.contains(&row_info.buffer_row?) {
+                    return None;
+                }
+
+                let origin = gutter.hitbox.origin
+                    + gpui::point(
+                        gutter.hitbox.size.width - fold_area_width,
+                        Pixels::from(f64::from(row_offset) * f64::from(gutter.line_height))
+                            - Pixels::from(scroll_top % f64::from(gutter.line_height)),
+                    );
+                Some(FoldIndicator {
+                    bounds: Bounds::new(origin, gpui::size(fold_area_width, gutter.line_height)),
+                    folded,
+                })
+            })
+            .collect()
+    }
+
+    fn paint_fold_indicators(layout: &EditorLayout, window: &mut Window, cx: &mut App) {
+        let color = cx.theme().colors().editor_line_number;
+        for indicator in &layout.fold_indicators {
+            let center = indicator.bounds.center();
+            let half_extent = indicator
+                .bounds
+                .size
+                .width
+                .min(indicator.bounds.size.height)
+                * 0.2;
+            let mut builder = PathBuilder::stroke(gpui::px(1.5));
+            if indicator.folded {
+                builder.move_to(gpui::point(
+                    center.x - half_extent / 2.0,
+                    center.y - half_extent,
+                ));
+                builder.line_to(gpui::point(center.x + half_extent / 2.0, center.y));
+                builder.line_to(gpui::point(
+                    center.x - half_extent / 2.0,
+                    center.y + half_extent,
+                ));
+            } else {
+                builder.move_to(gpui::point(
+                    center.x - half_extent,
+                    center.y - half_extent / 2.0,
+                ));
+                builder.line_to(gpui::point(center.x, center.y + half_extent / 2.0));
+                builder.line_to(gpui::point(
+                    center.x + half_extent,
+                    center.y - half_extent / 2.0,
+                ));
+            }
+
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, color);
+            }
+        }
+    }
+
     fn paint_line_numbers(layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
         let line_height = layout.position_map.line_height;
         window.set_cursor_style(CursorStyle::Arrow, &layout.gutter_hitbox);
@@ -523,6 +694,17 @@ impl EditorElement {
         let mut click_count = event.click_count;
 
         if gutter_hitbox.is_hovered(window) {
+            let fold_area_width = editor.gutter_dimensions.fold_area_width;
+            let clicked_row = point_for_position.exact_unclipped.row();
+            if fold_area_width > Pixels::ZERO
+                && event.position.x >= gutter_hitbox.bounds.right() - fold_area_width
+            {
+                if clicked_row <= position_map.snapshot.max_point().row() {
+                    editor.toggle_fold_at_display_row(clicked_row, cx);
+                }
+                cx.stop_propagation();
+                return;
+            }
             click_count = 3;
         } else if !text_hitbox.is_hovered(window) {
             return;
@@ -1162,6 +1344,8 @@ impl Element for EditorElement {
                 selection_range,
                 marked_range,
                 cursor_offset,
+                search_highlight_ranges,
+                diagnostic_highlight_ranges,
             ) = {
                 let editor = self.editor.read(cx);
                 (
@@ -1175,6 +1359,8 @@ impl Element for EditorElement {
                     editor.selected_range(cx),
                     editor.marked_range(cx),
                     editor.cursor_offset(cx),
+                    editor.search_highlight_ranges(cx),
+                    editor.diagnostic_highlight_ranges(cx),
                 )
             };
             let placeholder_color = if muted {
@@ -1192,7 +1378,7 @@ impl Element for EditorElement {
             let current_line_highlight = snapshot.current_line_highlight;
             let display_snapshot = snapshot.display_snapshot.clone();
             let height_in_lines = f64::from(bounds.size.height / line_height);
-            let max_row = f64::from(display_snapshot.buffer_snapshot().max_point().row);
+            let max_row = f64::from(display_snapshot.max_point().row().0);
             let max_scroll_y = if matches!(
                 mode,
                 EditorMode::SingleLine
@@ -1259,7 +1445,7 @@ impl Element for EditorElement {
                 });
 
             let show_horizontal_scrollbar = show_scrollbars.horizontal && max_scroll_x > 0.0;
-            let max_display_row = display_snapshot.buffer_snapshot().max_point().row;
+            let max_display_row = display_snapshot.max_point().row().0;
 
             let cursor_point =
                 display_snapshot
@@ -1267,7 +1453,6 @@ impl Element for EditorElement {
                     .offset_to_point(MultiBufferOffset(
                         cursor_offset.min(display_snapshot.buffer_snapshot().len().0),
                     ));
-            let cursor_row = cursor_point.row;
             let cursor_display_point =
                 display_snapshot.point_to_display_point(cursor_point, text::Bias::Left);
             let cursor_display_row = cursor_display_point.row();
@@ -1430,6 +1615,22 @@ impl Element for EditorElement {
                 }
             }
 
+            let search_highlights = search_highlight_ranges
+                .into_iter()
+                .filter_map(|(range, color)| {
+                    let range = display_range_for_offsets(&display_snapshot, range);
+                    (range.start < range.end).then_some((range, color))
+                })
+                .collect();
+
+            let diagnostic_underlines = diagnostic_highlight_ranges
+                .into_iter()
+                .filter_map(|(range, color)| {
+                    let range = display_range_for_offsets(&display_snapshot, range);
+                    (range.start < range.end).then_some((range, color))
+                })
+                .collect();
+
             let mut active_line_background = None;
             let mut cursor = None;
             for line in &lines {
@@ -1460,7 +1661,7 @@ impl Element for EditorElement {
                     }
                 }
 
-                if cursor.is_none() && cursor_row == line.row.0 {
+                if cursor.is_none() && cursor_display_row == line.row {
                     let cursor_column =
                         u32::try_from(cursor_offset.saturating_sub(line.line_start_offset))
                             .expect("cursor column should fit in u32");
@@ -1470,12 +1671,7 @@ impl Element for EditorElement {
                             line_text.get(..cursor_column).unwrap_or("").chars().count();
                         line.origin.x + line.x_for_index(display_column.min(line.len))
                     } else {
-                        let cursor_display_column = display_snapshot
-                            .point_to_display_point(
-                                text::Point::new(line.row.0, cursor_column),
-                                text::Bias::Left,
-                            )
-                            .column() as usize;
+                        let cursor_display_column = cursor_display_point.column() as usize;
                         let line_display_column_end =
                             line_display_column_start.saturating_add(line.len);
                         if cursor_display_column < line_display_column_start {
@@ -1536,6 +1732,7 @@ impl Element for EditorElement {
                 window,
                 cx,
             );
+            let fold_indicators = Self::layout_fold_indicators(&gutter);
             window.set_focus_handle(&focus_handle, cx);
 
             let (vertical_scrollbar, horizontal_scrollbar) = Self::layout_scrollbars(
@@ -1577,9 +1774,12 @@ impl Element for EditorElement {
                 hitbox,
                 gutter_hitbox,
                 line_numbers,
+                fold_indicators,
                 active_line_background,
                 cursor,
                 selection_ranges,
+                search_highlights,
+                diagnostic_underlines,
                 vertical_scrollbar,
                 horizontal_scrollbar,
                 em_width,
@@ -1640,6 +1840,7 @@ impl Element for EditorElement {
                     }),
                     |window| {
                         Self::paint_line_numbers(layout, window, cx);
+                        Self::paint_fold_indicators(layout, window, cx);
                     },
                 );
 
@@ -1655,6 +1856,7 @@ impl Element for EditorElement {
                                 .paint(line.origin, line_height, TextAlign::Left, None, window, cx)
                                 .log_err();
                         }
+                        Self::paint_diagnostic_underlines(layout, window);
 
                         if focus_handle.is_focused(window)
                             && let Some(cursor) = layout.cursor.take()
@@ -1680,6 +1882,27 @@ impl Element for EditorElement {
     }
 }
 
+fn display_range_for_offsets(
+    display_snapshot: &DisplaySnapshot,
+    range: Range<usize>,
+) -> Range<DisplayPoint> {
+    let buffer_snapshot = display_snapshot.buffer_snapshot();
+    let max_offset = buffer_snapshot.len().0;
+    let start = buffer_snapshot.clip_offset(
+        MultiBufferOffset(range.start.min(max_offset)),
+        text::Bias::Left,
+    );
+    let end = buffer_snapshot.clip_offset(
+        MultiBufferOffset(range.end.min(max_offset)),
+        text::Bias::Right,
+    );
+    let start = display_snapshot
+        .point_to_display_point(buffer_snapshot.offset_to_point(start), text::Bias::Left);
+    let end = display_snapshot
+        .point_to_display_point(buffer_snapshot.offset_to_point(end), text::Bias::Right);
+    start..end
+}
+
 fn gutter_bounds(
     editor_bounds: Bounds<Pixels>,
     gutter_dimensions: GutterDimensions,
@@ -1720,13 +1943,10 @@ fn build_visible_lines(
         }
 
         let row = DisplayRow(row);
-        let line_start_offset = display_snapshot
-            .buffer_snapshot()
-            .point_to_offset(text::Point::new(row.0, 0));
-        let line_len = display_snapshot
-            .buffer_snapshot()
-            .line_len(MultiBufferRow(row.0)) as usize;
-        let line_end_offset = line_start_offset + line_len;
+        let line_start_offset =
+            DisplayPoint::new(row, 0).to_offset(display_snapshot, text::Bias::Left);
+        let line_end_offset = DisplayPoint::new(row, display_snapshot.line_len(row))
+            .to_offset(display_snapshot, text::Bias::Right);
         let mut line_text = String::new();
         let mut runs = Vec::new();
         let line_display_column_start = if masked {
@@ -1799,13 +2019,13 @@ fn build_visible_lines(
                 .column() as usize;
 
             if line_display_column_start < line_display_column_end {
-                let chunk_start = TabPoint::new(
-                    row.0,
+                let chunk_start = DisplayPoint::new(
+                    row,
                     u32::try_from(line_display_column_start)
                         .expect("display column should fit in u32"),
                 );
-                let chunk_end = TabPoint::new(
-                    row.0,
+                let chunk_end = DisplayPoint::new(
+                    row,
                     u32::try_from(line_display_column_end)
                         .expect("display column should fit in u32"),
                 );
@@ -1924,13 +2144,17 @@ fn build_visible_lines(
                     (
                         display_snapshot
                             .point_to_display_point(
-                                text::Point::new(row.0, start_column),
+                                display_snapshot
+                                    .buffer_snapshot()
+                                    .offset_to_point(MultiBufferOffset(marked_start)),
                                 text::Bias::Left,
                             )
                             .column() as usize,
                         display_snapshot
                             .point_to_display_point(
-                                text::Point::new(row.0, end_column),
+                                display_snapshot
+                                    .buffer_snapshot()
+                                    .offset_to_point(MultiBufferOffset(marked_end)),
                                 text::Bias::Right,
                             )
                             .column() as usize,
@@ -0,0 +1,124 @@
+use gpui::{Context, Window};
+use std::ops::Range;
+
+use actions::editor::{Fold, FoldAll, Unfold, UnfoldAll};
+use multi_buffer::{MultiBufferOffset, MultiBufferRow, ToPoint};
+use text::Point;
+
+use crate::{
+    Editor, SelectionEffects,
+    display_map::{DisplayPoint, DisplayRow, ToDisplayPoint},
+};
+
+impl Editor {
+    pub fn fold(&mut self, _: &Fold, _: &mut Window, cx: &mut Context<Self>) {
+        let display_snapshot = self.display_snapshot(cx);
+        let buffer_snapshot = display_snapshot.buffer_snapshot();
+        let cursor = MultiBufferOffset(self.cursor_offset(cx)).to_point(buffer_snapshot);
+        let folded_ranges = display_snapshot.folded_ranges().collect::<Vec<_>>();
+
+        let range = display_snapshot
+            .foldable_range_for_row(MultiBufferRow(cursor.row))
+            .filter(|range| !folded_ranges.contains(range))
+            .or_else(|| {
+                let line_end = Point::new(
+                    cursor.row,
+                    buffer_snapshot.line_len(MultiBufferRow(cursor.row)),
+                );
+                display_snapshot
+                    .foldable_ranges(Point::new(cursor.row, 0)..line_end)
+                    .filter(|range| range.start <= cursor && cursor <= range.end)
+                    .last()
+            });
+        if let Some(range) = range {
+            self.fold_ranges([range], cx);
+        }
+    }
+
+    pub fn unfold(&mut self, _: &Unfold, _: &mut Window, cx: &mut Context<Self>) {
+        let display_snapshot = self.display_snapshot(cx);
+        let cursor_row = MultiBufferOffset(self.cursor_offset(cx))
+            .to_display_point(&display_snapshot)
+            .row();
+        self.unfold_display_row(cursor_row, cx);
+    }
+
+    pub fn fold_all(&mut self, _: &FoldAll, _: &mut Window, cx: &mut Context<Self>) {
+        let display_snapshot = self.display_snapshot(cx);
+        let max_point = display_snapshot.buffer_snapshot().max_point();
+        let ranges = display_snapshot
+            .foldable_ranges(Point::zero()..max_point)
+            .collect::<Vec<_>>();
+        self.fold_ranges(ranges, cx);
+    }
+
+    pub fn unfold_all(&mut self, _: &UnfoldAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.display_map
+            .update(cx, |display_map, cx| display_map.unfold_all(cx));
+        cx.notify();
+    }
+
+    /// Unfolds the folds on `display_row` if there are any, otherwise folds the
+    /// syntax node that starts on it.
+    pub(crate) fn toggle_fold_at_display_row(
+        &mut self,
+        display_row: DisplayRow,
+        cx: &mut Context<Self>,
+    ) {
+        let display_snapshot = self.display_snapshot(cx);
+        if display_snapshot.folds_on_row(display_row).next().is_some() {
+            self.unfold_display_row(display_row, cx);
+            return;
+        }
+
+        let buffer_row = DisplayPoint::new(display_row, 0)
+            .to_point(&display_snapshot)
+            .row;
+        if let Some(range) = display_snapshot.foldable_range_for_row(MultiBufferRow(buffer_row)) {
+            self.fold_ranges([range], cx);
+        }
+    }
+
+    /// Folds `ranges` and moves the selection out of any text that became
+    /// hidden, so that typing never edits folded text.
+    pub fn fold_ranges(
+        &mut self,
+        ranges: impl IntoIterator<Item = Range<Point>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.display_map
+            .update(cx, |display_map, cx| display_map.fold(ranges, cx));
+
+        let display_snapshot = self.display_snapshot(cx);
+        let buffer_snapshot = display_snapshot.buffer_snapshot();
+        let selection = self.selection(cx);
+        let start = selection.start.to_point(buffer_snapshot);
+        let end = selection.end.to_point(buffer_snapshot);
+        let hidden_by = display_snapshot.folded_ranges().find(|folded_range| {
+            (folded_range.start < start && start < folded_range.end)
+                || (folded_range.start < end && end < folded_range.end)
+        });
+        if let Some(folded_range) = hidden_by {
+            self.change_selections(SelectionEffects::default(), cx, |selections| {
+                selections.select_ranges([folded_range.start..folded_range.start]);
+            });
+        }
+        cx.notify();
+    }
+
+    fn unfold_display_row(&mut self, display_row: DisplayRow, cx: &mut Context<Self>) {
+        let display_snapshot = self.display_snapshot(cx);
+        let fold_starts = display_snapshot
+            .folds_on_row(display_row)
+            .map(|range| range.start..range.start)
+            .collect::<Vec<_>>();
+        if fold_starts.is_empty() {
+            return;
+        }
+
+        self.display_map.update(cx, |display_map, cx| {
+            display_map.unfold_intersecting(fold_starts, cx);
+        });
+        cx.notify();
+    }
+}
@@ -35,7 +35,7 @@ pub(crate) fn left(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoi
 pub(crate) fn right(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoint {
     if point.column() < map.line_len(point.row()) {
         *point.column_mut() += 1;
-    } else if point.row().0 < map.buffer_snapshot().max_point().row {
+    } else if point.row() < map.max_point().row() {
         *point.row_mut() += 1;
         *point.column_mut() = 0;
     }
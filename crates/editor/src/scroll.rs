@@ -146,7 +146,7 @@ impl ScrollManager {
     }
 
     fn set_scroll_position(&mut self, snapshot: &DisplaySnapshot, position: Point<ScrollOffset>) {
-        let max_row = snapshot.buffer_snapshot().max_point().row;
+        let max_row = snapshot.max_point().row().0;
         let scroll_top = position.y.max(0.0);
         let row = DisplayRow(
             scroll_top
@@ -6,7 +6,7 @@ use multi_buffer::MultiBufferOffset;
 
 use crate::{
     Editor, EditorMode,
-    display_map::{DisplayPoint, DisplayRow},
+    display_map::{DisplayPoint, DisplayRow, ToDisplayPoint},
     element::LineWithInvisibles,
     scroll::{ScrollOffset, WasScrolled},
 };
@@ -71,9 +71,10 @@ impl Editor {
 
         let snapshot = display_snapshot.buffer_snapshot();
         let cursor_offset = self.cursor_offset(cx).min(snapshot.len().0);
-        let cursor_row = snapshot
-            .offset_to_point(MultiBufferOffset(cursor_offset))
-            .row;
+        let cursor_row = MultiBufferOffset(cursor_offset)
+            .to_display_point(&display_snapshot)
+            .row()
+            .0;
         let target_top = f64::from(cursor_row);
         let target_bottom = target_top + 1.0;
 
@@ -0,0 +1,849 @@
+use gpui::{
+    AnyElement, App, Context, Entity, Focusable, HighlightStyle, Hsla, KeyContext, SharedString,
+    Subscription, Window, prelude::*,
+};
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+use actions::search::{
+    Deploy, DeployReplace, Dismiss, ReplaceAll, ReplaceNext, SelectNextMatch, SelectPreviousMatch,
+    ToggleCaseSensitive, ToggleRegex, ToggleWholeWord,
+};
+use input::{ErasedEditorEvent, InputField};
+use multi_buffer::{Anchor, MultiBufferOffset, ToOffset};
+use text::SelectionGoal;
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, Disableable, IconAsset,
+    IconButton, IconButtonShape, IconSize, Text, TextCommon, TextSize, Toggleable, Tooltip,
+};
+
+use crate::{Editor, EditorEvent, SelectionEffects, display_map::HighlightKey, scroll::Autoscroll};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+impl SearchQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn find_matches(&self, haystack: &str) -> anyhow::Result<Vec<Range<usize>>> {
+        if self.text.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let regex = self.build_regex()?;
+        Ok(regex
+            .find_iter(haystack)
+            .filter(|found| !found.is_empty())
+            .map(|found| found.range())
+            .collect())
+    }
+
+    /// Returns every match paired with its replacement text. In regex mode,
+    /// `$1` and `${name}` in the replacement expand to the captured groups.
+    pub fn replacements(
+        &self,
+        haystack: &str,
+        replacement: &str,
+    ) -> anyhow::Result<Vec<(Range<usize>, String)>> {
+        if self.text.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let regex = self.build_regex()?;
+        Ok(regex
+            .captures_iter(haystack)
+            .filter_map(|captures| {
+                let found = captures.get(0).filter(|found| !found.is_empty())?;
+                let new_text = if self.regex {
+                    let mut new_text = String::new();
+                    captures.expand(replacement, &mut new_text);
+                    new_text
+                } else {
+                    replacement.to_string()
+                };
+                Some((found.range(), new_text))
+            })
+            .collect())
+    }
+
+    fn build_regex(&self) -> anyhow::Result<Regex> {
+        let pattern = if self.regex {
+            self.text.clone()
+        } else {
+            regex::escape(&self.text)
+        };
+        let pattern = if self.whole_word {
+            format!(r"\b(?:{pattern})\b")
+        } else {
+            pattern
+        };
+
+        Ok(RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .multi_line(true)
+            .build()?)
+    }
+}
+
+pub(crate) struct SearchBar {
+    query_field: Entity<InputField>,
+    replacement_field: Entity<InputField>,
+    case_sensitive: bool,
+    whole_word: bool,
+    regex: bool,
+    replace_enabled: bool,
+    matches: Vec<Range<Anchor>>,
+    active_match_index: Option<usize>,
+    error: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SearchBar {
+    fn new(window: &mut Window, cx: &mut Context<Editor>) -> Self {
+        let query_field = cx.new(|cx| InputField::new(window, cx, "Find"));
+        let replacement_field = cx.new(|cx| InputField::new(window, cx, "Replace"));
+
+        let editor = cx.weak_entity();
+        let query_editor = query_field.read(cx).editor().clone();
+        let query_subscription = query_editor.subscribe(
+            Box::new(move |event, _window, cx| {
+                if event == ErasedEditorEvent::BufferEdited
+                    && let Err(error) = editor.update(cx, |editor, cx| {
+                        editor.refresh_search_matches(true, cx);
+                    })
+                {
+                    log::debug!("Failed to update search matches: {error:?}");
+                }
+            }),
+            window,
+            cx,
+        );
+
+        Self {
+            query_field,
+            replacement_field,
+            case_sensitive: false,
+            whole_word: false,
+            regex: false,
+            replace_enabled: false,
+            matches: Vec::new(),
+            active_match_index: None,
+            error: None,
+            _subscriptions: vec![query_subscription],
+        }
+    }
+
+    fn query(&self, cx: &App) -> SearchQuery {
+        SearchQuery {
+            text: self.query_field.read(cx).value(cx),
+            case_sensitive: self.case_sensitive,
+            whole_word: self.whole_word,
+            regex: self.regex,
+        }
+    }
+}
+
+impl Editor {
+    pub fn is_search_visible(&self) -> bool {
+        self.search.is_some()
+    }
+
+    pub fn search_match_ranges(&self, cx: &App) -> Vec<Range<usize>> {
+        let Some(search) = &self.search else {
+            return Vec::new();
+        };
+
+        let snapshot = self.buffer_snapshot(cx);
+        search
+            .matches
+            .iter()
+            .map(|range| range.start.to_offset(&snapshot).0..range.end.to_offset(&snapshot).0)
+            .collect()
+    }
+
+    pub fn active_search_match_index(&self) -> Option<usize> {
+        self.search.as_ref()?.active_match_index
+    }
+
+    pub fn search_error(&self) -> Option<SharedString> {
+        self.search.as_ref()?.error.clone()
+    }
+
+    pub fn set_search_query(
+        &mut self,
+        query: &SearchQuery,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.search.is_none() {
+            self.show_search(false, window, cx);
+        }
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        search.case_sensitive = query.case_sensitive;
+        search.whole_word = query.whole_word;
+        search.regex = query.regex;
+        let query_field = search.query_field.clone();
+        query_field.update(cx, |field, cx| field.set_value(&query.text, window, cx));
+        self.refresh_search_matches(true, cx);
+    }
+
+    pub fn set_search_replacement(
+        &mut self,
+        replacement: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(search) = &self.search {
+            let replacement_field = search.replacement_field.clone();
+            replacement_field.update(cx, |field, cx| field.set_value(replacement, window, cx));
+        }
+    }
+
+    pub(crate) fn deploy_search(
+        &mut self,
+        _: &Deploy,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_search(false, window, cx);
+    }
+
+    pub(crate) fn deploy_replace(
+        &mut self,
+        _: &DeployReplace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_search(true, window, cx);
+    }
+
+    fn show_search(&mut self, replace_enabled: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.mode.is_full() {
+            cx.propagate();
+            return;
+        }
+
+        let read_only = self.read_only(cx);
+        let selected_text = self.selected_single_line_text(cx);
+        if self.search.is_none() {
+            self.search = Some(SearchBar::new(window, cx));
+        }
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        search.replace_enabled = replace_enabled && !read_only;
+        let query_field = search.query_field.clone();
+        if let Some(selected_text) = selected_text {
+            query_field.update(cx, |field, cx| field.set_value(&selected_text, window, cx));
+        }
+        let query_editor = query_field.read(cx).editor().clone();
+        query_editor.move_selection_to_end(window, cx);
+        window.focus(&query_field.focus_handle(cx), cx);
+
+        self.refresh_search_matches(true, cx);
+        cx.notify();
+    }
+
+    fn selected_single_line_text(&self, cx: &App) -> Option<String> {
+        let selected_range = self.selected_range(cx);
+        if selected_range.is_empty() {
+            return None;
+        }
+
+        let snapshot = self.buffer_snapshot(cx);
+        let text = snapshot
+            .text_for_range(
+                MultiBufferOffset(selected_range.start)..MultiBufferOffset(selected_range.end),
+            )
+            .collect::<String>();
+        (!text.contains('\n')).then_some(text)
+    }
+
+    pub(crate) fn dismiss_search(
+        &mut self,
+        _: &Dismiss,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.search.take().is_none() {
+            cx.propagate();
+            return;
+        }
+
+        self.clear_highlights(HighlightKey::SearchMatch, cx);
+        self.clear_highlights(HighlightKey::ActiveSearchMatch, cx);
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    pub(crate) fn select_next_match(
+        &mut self,
+        _: &SelectNextMatch,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(search) = &self.search else {
+            cx.propagate();
+            return;
+        };
+
+        let match_count = search.matches.len();
+        if match_count == 0 {
+            return;
+        }
+        let index = search
+            .active_match_index
+            .map_or(0, |index| (index + 1) % match_count);
+        self.activate_search_match(index, cx);
+    }
+
+    pub(crate) fn select_previous_match(
+        &mut self,
+        _: &SelectPreviousMatch,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(search) = &self.search else {
+            cx.propagate();
+            return;
+        };
+
+        let match_count = search.matches.len();
+        if match_count == 0 {
+            return;
+        }
+        let index = search.active_match_index.map_or(match_count - 1, |index| {
+            (index + match_count - 1) % match_count
+        });
+        self.activate_search_match(index, cx);
+    }
+
+    pub(crate) fn toggle_case_sensitive(
+        &mut self,
+        _: &ToggleCaseSensitive,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(|search| &mut search.case_sensitive, cx);
+    }
+
+    pub(crate) fn toggle_whole_word(
+        &mut self,
+        _: &ToggleWholeWord,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(|search| &mut search.whole_word, cx);
+    }
+
+    pub(crate) fn toggle_regex(&mut self, _: &ToggleRegex, _: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_search_option(|search| &mut search.regex, cx);
+    }
+
+    fn toggle_search_option(
+        &mut self,
+        option: impl FnOnce(&mut SearchBar) -> &mut bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(search) = &mut self.search else {
+            cx.propagate();
+            return;
+        };
+
+        let option = option(search);
+        *option = !*option;
+        self.refresh_search_matches(true, cx);
+        cx.notify();
+    }
+
+    pub(crate) fn replace_next(&mut self, _: &ReplaceNext, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(search) = &self.search else {
+            cx.propagate();
+            return;
+        };
+        if self.read_only(cx) {
+            return;
+        }
+        let Some(active_match) = search
+            .active_match_index
+            .and_then(|index| search.matches.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        let query = search.query(cx);
+        let replacement = search.replacement_field.read(cx).value(cx);
+        let snapshot = self.buffer_snapshot(cx);
+        let active_range =
+            active_match.start.to_offset(&snapshot).0..active_match.end.to_offset(&snapshot).0;
+        let replacements = match query.replacements(&snapshot.text(), &replacement) {
+            Ok(replacements) => replacements,
+            Err(error) => {
+                log::debug!("Failed to build search replacements: {error:?}");
+                return;
+            }
+        };
+        let Some((range, new_text)) = replacements
+            .into_iter()
+            .find(|(range, _)| *range == active_range)
+        else {
+            return;
+        };
+
+        self.replace_range(range, &new_text, cx);
+        self.refresh_search_matches(true, cx);
+    }
+
+    pub(crate) fn replace_all(
+        &mut self,
+        _: &ReplaceAll,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(search) = &self.search else {
+            cx.propagate();
+            return;
+        };
+        if self.read_only(cx) {
+            return;
+        }
+
+        let query = search.query(cx);
+        let replacement = search.replacement_field.read(cx).value(cx);
+        let text = self.buffer_snapshot(cx).text();
+        let replacements = match query.replacements(&text, &replacement) {
+            Ok(replacements) => replacements,
+            Err(error) => {
+                log::debug!("Failed to build search replacements: {error:?}");
+                return;
+            }
+        };
+        if replacements.is_empty() {
+            return;
+        }
+
+        self.transact(window, cx, |editor, _, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit(
+                    replacements.into_iter().map(|(range, new_text)| {
+                        (
+                            MultiBufferOffset(range.start)..MultiBufferOffset(range.end),
+                            new_text,
+                        )
+                    }),
+                    cx,
+                );
+            });
+            editor.selection_goal = SelectionGoal::None;
+        });
+        cx.emit(EditorEvent::BufferEdited);
+        self.refresh_search_matches(false, cx);
+        cx.notify();
+    }
+
+    fn activate_search_match(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let Some(range) = search.matches.get(index).cloned() else {
+            return;
+        };
+
+        search.active_match_index = Some(index);
+        let snapshot = self.buffer_snapshot(cx);
+        let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+        self.selection_goal = SelectionGoal::None;
+        self.change_selections(
+            SelectionEffects::scroll(Autoscroll::newest()),
+            cx,
+            |selections| selections.select_ranges([range]),
+        );
+        self.update_search_highlights(cx);
+    }
+
+    /// Recomputes matches against the current buffer contents. When
+    /// `select_active` is set, the first match at or after the cursor becomes
+    /// active and is selected, otherwise the active match index is kept.
+    pub(crate) fn refresh_search_matches(&mut self, select_active: bool, cx: &mut Context<Self>) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        let query = search.query(cx);
+        let snapshot = self.buffer_snapshot(cx);
+        let (ranges, error) = match query.find_matches(&snapshot.text()) {
+            Ok(ranges) => (ranges, None),
+            Err(error) => {
+                let message = error.to_string();
+                let message = message
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .trim_start_matches("error: ")
+                    .to_string();
+                (Vec::new(), Some(SharedString::from(message)))
+            }
+        };
+
+        let cursor = self.selected_range(cx).start;
+        let active_match_index = if ranges.is_empty() {
+            None
+        } else if select_active {
+            Some(
+                ranges
+                    .iter()
+                    .position(|range| range.start >= cursor)
+                    .unwrap_or(0),
+            )
+        } else {
+            search
+                .active_match_index
+                .map(|index| index.min(ranges.len() - 1))
+        };
+        let matches = ranges
+            .iter()
+            .map(|range| {
+                snapshot.anchor_after(&MultiBufferOffset(range.start))
+                    ..snapshot.anchor_before(&MultiBufferOffset(range.end))
+            })
+            .collect();
+
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.matches = matches;
+        search.active_match_index = active_match_index;
+        search.error = error;
+
+        match active_match_index {
+            Some(index) if select_active => self.activate_search_match(index, cx),
+            _ => self.update_search_highlights(cx),
+        }
+        cx.notify();
+    }
+
+    fn update_search_highlights(&mut self, cx: &mut Context<Self>) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        let status_colors = cx.theme().status();
+        let match_style = HighlightStyle {
+            background_color: Some(status_colors.warning_background),
+            ..HighlightStyle::default()
+        };
+        let active_match_style = HighlightStyle {
+            background_color: Some(status_colors.warning.opacity(0.4)),
+            ..HighlightStyle::default()
+        };
+        let matches = search.matches.clone();
+        let active_match = search
+            .active_match_index
+            .and_then(|index| search.matches.get(index))
+            .cloned();
+
+        if matches.is_empty() {
+            self.clear_highlights(HighlightKey::SearchMatch, cx);
+        } else {
+            self.highlight_text(HighlightKey::SearchMatch, matches, match_style, cx);
+        }
+        if let Some(active_match) = active_match {
+            self.highlight_text(
+                HighlightKey::ActiveSearchMatch,
+                vec![active_match],
+                active_match_style,
+                cx,
+            );
+        } else {
+            self.clear_highlights(HighlightKey::ActiveSearchMatch, cx);
+        }
+    }
+
+    pub(crate) fn search_highlight_ranges(&self, cx: &App) -> Vec<(Range<usize>, Hsla)> {
+        let snapshot = self.buffer_snapshot(cx);
+        let mut highlight_ranges = Vec::new();
+        for key in [HighlightKey::SearchMatch, HighlightKey::ActiveSearchMatch] {
+            let Some((style, ranges)) = self.text_highlights(key, cx) else {
+                continue;
+            };
+            let Some(color) = style.background_color else {
+                continue;
+            };
+            highlight_ranges.extend(ranges.iter().map(|range| {
+                (
+                    range.start.to_offset(&snapshot).0..range.end.to_offset(&snapshot).0,
+                    color,
+                )
+            }));
+        }
+        highlight_ranges
+    }
+
+    pub(crate) fn render_search_bar(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let search = self.search.as_ref()?;
+        let theme_colors = cx.theme().colors();
+        let query_is_empty = search.query_field.read(cx).value(cx).is_empty();
+        let match_count = search.matches.len();
+        let replace_enabled = search.replace_enabled && !self.read_only(cx);
+
+        let mut key_context = KeyContext::new_with_defaults();
+        key_context.add("BufferSearchBar");
+        if search
+            .replacement_field
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+        {
+            key_context.add("in_replace");
+        }
+
+        let (status, status_color) = match (&search.error, search.active_match_index) {
+            (Some(error), _) => (error.clone(), Color::Error),
+            (None, _) if query_is_empty => (SharedString::default(), Color::Muted),
+            (None, _) if match_count == 0 => (SharedString::new_static("No results"), Color::Muted),
+            (None, Some(index)) => (
+                SharedString::from(format!("{} of {match_count}", index + 1)),
+                Color::Muted,
+            ),
+            (None, None) => (
+                SharedString::from(format!("{match_count} matches")),
+                Color::Muted,
+            ),
+        };
+
+        let option_button = |id: &'static str, label: &'static str, tooltip: &'static str| {
+            Button::new(id, label)
+                .variant(ButtonVariant::Ghost)
+                .size(ButtonSize::Compact)
+                .tooltip(Tooltip::text(tooltip))
+        };
+
+        let search_row = gpui::div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(search.query_field.clone()),
+            )
+            .child(
+                option_button("search-case-sensitive", "Aa", "Match Case")
+                    .toggle_state(search.case_sensitive)
+                    .on_click(cx.listener(|editor, _, window, cx| {
+                        editor.toggle_case_sensitive(&ToggleCaseSensitive, window, cx);
+                    })),
+            )
+            .child(
+                option_button("search-whole-word", "W", "Match Whole Word")
+                    .toggle_state(search.whole_word)
+                    .on_click(cx.listener(|editor, _, window, cx| {
+                        editor.toggle_whole_word(&ToggleWholeWord, window, cx);
+                    })),
+            )
+            .child(
+                option_button("search-regex", ".*", "Use Regular Expression")
+                    .toggle_state(search.regex)
+                    .on_click(cx.listener(|editor, _, window, cx| {
+                        editor.toggle_regex(&ToggleRegex, window, cx);
+                    })),
+            )
+            .child(
+                gpui::div().min_w_16().px_1().child(
+                    Text::new(status)
+                        .size(TextSize::Small)
+                        .color(status_color)
+                        .single_line()
+                        .truncate(),
+                ),
+            )
+            .child(
+                Button::new("search-previous-match", "Previous")
+                    .variant(ButtonVariant::OutlinedGhost)
+                    .size(ButtonSize::Compact)
+                    .disabled(match_count == 0)
+                    .on_click(cx.listener(|editor, _, window, cx| {
+                        editor.select_previous_match(&SelectPreviousMatch, window, cx);
+                    })),
+            )
+            .child(
+                Button::new("search-next-match", "Next")
+                    .variant(ButtonVariant::OutlinedGhost)
+                    .size(ButtonSize::Compact)
+                    .disabled(match_count == 0)
+                    .on_click(cx.listener(|editor, _, window, cx| {
+                        editor.select_next_match(&SelectNextMatch, window, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("search-dismiss", IconAsset::Close)
+                    .shape(IconButtonShape::Square)
+                    .icon_size(IconSize::Small)
+                    .icon_color(Color::Muted)
+                    .tooltip(Tooltip::text("Close"))
+                    .on_click(cx.listener(|editor, _, window, cx| {
+                        editor.dismiss_search(&Dismiss, window, cx);
+                    })),
+            );
+
+        let replace_row = replace_enabled.then(|| {
+            gpui::div()
+                .flex()
+                .items_center()
+                .gap_1()
+                .child(
+                    gpui::div()
+                        .flex_1()
+                        .min_w_0()
+                        .child(search.replacement_field.clone()),
+                )
+                .child(
+                    Button::new("search-replace-next", "Replace")
+                        .variant(ButtonVariant::OutlinedGhost)
+                        .size(ButtonSize::Compact)
+                        .disabled(search.active_match_index.is_none())
+                        .on_click(cx.listener(|editor, _, window, cx| {
+                            editor.replace_next(&ReplaceNext, window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("search-replace-all", "Replace All")
+                        .variant(ButtonVariant::OutlinedGhost)
+                        .size(ButtonSize::Compact)
+                        .disabled(match_count == 0)
+                        .on_click(cx.listener(|editor, _, window, cx| {
+                            editor.replace_all(&ReplaceAll, window, cx);
+                        })),
+                )
+        });
+
+        Some(
+            gpui::div()
+                .key_context(key_context)
+                .on_action(cx.listener(Self::select_next_match))
+                .on_action(cx.listener(Self::select_previous_match))
+                .on_action(cx.listener(Self::toggle_case_sensitive))
+                .on_action(cx.listener(Self::toggle_whole_word))
+                .on_action(cx.listener(Self::toggle_regex))
+                .on_action(cx.listener(Self::replace_next))
+                .on_action(cx.listener(Self::replace_all))
+                .on_action(cx.listener(Self::dismiss_search))
+                .flex()
+                .flex_col()
+                .gap_1()
+                .px_2()
+                .py_1()
+                .border_b_1()
+                .border_color(theme_colors.border)
+                .bg(theme_colors.panel_background)
+                .child(search_row)
+                .children(replace_row)
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_find_matches() {
+        let text = "Alpha alpha alphabet (alpha)";
+
+        assert_eq!(
+            SearchQuery::new("alpha").find_matches(text).unwrap(),
+            vec![0..5, 6..11, 12..17, 22..27]
+        );
+        assert_eq!(
+            SearchQuery {
+                case_sensitive: true,
+                ..SearchQuery::new("alpha")
+            }
+            .find_matches(text)
+            .unwrap(),
+            vec![6..11, 12..17, 22..27]
+        );
+        assert_eq!(
+            SearchQuery {
+                whole_word: true,
+                ..SearchQuery::new("alpha")
+            }
+            .find_matches(text)
+            .unwrap(),
+            vec![0..5, 6..11, 22..27]
+        );
+        assert_eq!(
+            SearchQuery::new("(alpha)").find_matches(text).unwrap(),
+            vec![21..28]
+        );
+        assert_eq!(
+            SearchQuery {
+                regex: true,
+                ..SearchQuery::new(r"^\w+")
+            }
+            .find_matches("one\ntwo")
+            .unwrap(),
+            vec![0..3, 4..7]
+        );
+        assert!(SearchQuery::new("").find_matches(text).unwrap().is_empty());
+        assert!(
+            SearchQuery {
+                regex: true,
+                ..SearchQuery::new("a*")
+            }
+            .find_matches("bbb")
+            .unwrap()
+            .is_empty()
+        );
+        assert!(
+            SearchQuery {
+                regex: true,
+                ..SearchQuery::new("(")
+            }
+            .find_matches(text)
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_replacements() {
+        let text = "user_id=1&user_name=zaku";
+
+        assert_eq!(
+            SearchQuery::new("user_").replacements(text, "$1").unwrap(),
+            vec![(0..5, "$1".to_string()), (10..15, "$1".to_string())]
+        );
+        assert_eq!(
+            SearchQuery {
+                regex: true,
+                ..SearchQuery::new(r"user_(\w+)=")
+            }
+            .replacements(text, "${1}:")
+            .unwrap(),
+            vec![(0..8, "id:".to_string()), (10..20, "name:".to_string())]
+        );
+    }
+}
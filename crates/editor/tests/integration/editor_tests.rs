@@ -20,7 +20,8 @@ use text::SelectionGoal;
 use util::test::{generate_marked_text, marked_text_ranges};
 
 use editor::{
-    DEFAULT_TAB_SIZE, Editor, EditorMode, SelectionEffects, SelectionHistory,
+    DEFAULT_TAB_SIZE, Diagnostic, DiagnosticSeverity, Editor, EditorMode, SearchQuery,
+    SelectionEffects, SelectionHistory,
     display_map::{DisplayMap, DisplayPoint, DisplayRow, HighlightKey},
 };
 
@@ -1904,3 +1905,301 @@ fn test_move_cursor_different_line_lengths(cx: &mut TestAppContext) {
         ⓐⓑⓒⓓⓔ\
     "});
 }
+
+#[gpui::test]
+fn test_search_select_next_and_previous_match(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    cx.set_state("ˇone two One three one");
+    cx.update_editor(|editor, window, cx| {
+        editor.set_search_query(&SearchQuery::new("one"), window, cx);
+    });
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert!(editor.is_search_visible());
+        assert_eq!(editor.search_match_ranges(cx), vec![0..3, 8..11, 18..21]);
+        assert_eq!(editor.active_search_match_index(), Some(0));
+    });
+    cx.assert_state("«oneˇ» two One three one");
+
+    cx.dispatch_action(actions::search::SelectNextMatch);
+    cx.assert_state("one two «Oneˇ» three one");
+
+    cx.dispatch_action(actions::search::SelectNextMatch);
+    cx.assert_state("one two One three «oneˇ»");
+
+    cx.dispatch_action(actions::search::SelectNextMatch);
+    cx.assert_state("«oneˇ» two One three one");
+
+    cx.dispatch_action(actions::search::SelectPreviousMatch);
+    cx.assert_state("one two One three «oneˇ»");
+
+    cx.dispatch_action(actions::search::Dismiss);
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert!(!editor.is_search_visible());
+        assert!(editor.search_match_ranges(cx).is_empty());
+    });
+}
+
+#[gpui::test]
+fn test_search_options(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    cx.set_state("ˇtoken Token tokens token_id");
+    cx.update_editor(|editor, window, cx| {
+        editor.set_search_query(
+            &SearchQuery {
+                case_sensitive: true,
+                ..SearchQuery::new("token")
+            },
+            window,
+            cx,
+        );
+    });
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.search_match_ranges(cx), vec![0..5, 13..18, 20..25]);
+    });
+
+    cx.dispatch_action(actions::search::ToggleWholeWord);
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.search_match_ranges(cx), vec![0..5]);
+    });
+
+    cx.update_editor(|editor, window, cx| {
+        editor.set_search_query(
+            &SearchQuery {
+                regex: true,
+                ..SearchQuery::new(r"t\w+s")
+            },
+            window,
+            cx,
+        );
+    });
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.search_match_ranges(cx), vec![13..19]);
+        assert_eq!(editor.search_error(), None);
+    });
+
+    cx.update_editor(|editor, window, cx| {
+        editor.set_search_query(
+            &SearchQuery {
+                regex: true,
+                ..SearchQuery::new("(token")
+            },
+            window,
+            cx,
+        );
+    });
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert!(editor.search_match_ranges(cx).is_empty());
+        assert_eq!(editor.active_search_match_index(), None);
+        assert!(editor.search_error().is_some());
+    });
+}
+
+#[gpui::test]
+fn test_search_replace(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    cx.set_state("ˇid: 1, id: 2, id: 3");
+    cx.update_editor(|editor, window, cx| {
+        editor.set_search_query(
+            &SearchQuery {
+                regex: true,
+                ..SearchQuery::new(r"id: (\d)")
+            },
+            window,
+            cx,
+        );
+        editor.set_search_replacement("key: $1", window, cx);
+    });
+
+    cx.dispatch_action(actions::search::ReplaceNext);
+    cx.assert_state("key: 1, «id: 2ˇ», id: 3");
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.search_match_ranges(cx), vec![8..13, 15..20]);
+        assert_eq!(editor.active_search_match_index(), Some(0));
+    });
+
+    cx.set_state("ˇid: 1, id: 2, id: 3");
+    cx.update_editor(|editor, window, cx| {
+        editor.set_search_query(
+            &SearchQuery {
+                regex: true,
+                ..SearchQuery::new(r"id: (\d)")
+            },
+            window,
+            cx,
+        );
+    });
+    cx.dispatch_action(actions::search::ReplaceAll);
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "key: 1, key: 2, key: 3");
+        assert!(editor.search_match_ranges(cx).is_empty());
+    });
+
+    cx.dispatch_action(actions::editor::Undo);
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "id: 1, id: 2, id: 3");
+    });
+}
+
+#[gpui::test]
+fn test_search_replace_in_read_only_editor(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    cx.set_state("ˇone one");
+    cx.update_editor(|editor, window, cx| {
+        editor.set_read_only(true);
+        editor.set_search_query(&SearchQuery::new("one"), window, cx);
+        editor.set_search_replacement("two", window, cx);
+    });
+
+    cx.dispatch_action(actions::search::ReplaceNext);
+    cx.dispatch_action(actions::search::ReplaceAll);
+    cx.assert_state("«oneˇ» one");
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.search_match_ranges(cx), vec![0..3, 4..7]);
+    });
+}
+
+#[gpui::test]
+fn test_apply_text_is_single_undo_step(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    cx.set_state("{\"a\":1,\"b\":[2,ˇ3]}");
+    cx.update_editor(|editor, window, cx| {
+        editor.apply_text(
+            "{\n  \"a\": 1,\n  \"b\": [\n    2,\n    3\n  ]\n}\n",
+            window,
+            cx,
+        );
+    });
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(
+            editor.text(cx),
+            "{\n  \"a\": 1,\n  \"b\": [\n    2,\n    3\n  ]\n}\n"
+        );
+    });
+
+    cx.dispatch_action(actions::editor::Undo);
+    cx.editor.read_with(&cx.cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "{\"a\":1,\"b\":[2,3]}");
+    });
+}
+
+#[gpui::test]
+fn test_folding_in_read_only_editor(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    let text = "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": 3\n}";
+    cx.set_state("{\n  \"a\":ˇ [\n    1,\n    2\n  ],\n  \"b\": 3\n}");
+    cx.update_editor(|editor, _, cx| {
+        editor.set_read_only(true);
+        let buffer = editor
+            .buffer
+            .read(cx)
+            .as_singleton()
+            .expect("editor should have a singleton buffer");
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_language(Some(language::json_lang()), cx);
+        });
+    });
+    let display_text = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, _, cx| {
+            editor
+                .display_snapshot(cx)
+                .text_chunks(DisplayRow(0))
+                .collect::<String>()
+        })
+    };
+
+    cx.dispatch_action(actions::editor::Fold);
+    assert_eq!(
+        display_text(&mut cx),
+        "{\n  \"a\": [… 2 lines],\n  \"b\": 3\n}"
+    );
+    cx.assert_state("{\n  \"a\":ˇ [\n    1,\n    2\n  ],\n  \"b\": 3\n}");
+
+    cx.dispatch_action(actions::editor::Unfold);
+    assert_eq!(display_text(&mut cx), text);
+
+    cx.dispatch_action(actions::editor::FoldAll);
+    assert_eq!(display_text(&mut cx), "{… 5 lines}");
+    cx.assert_state("{ˇ\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": 3\n}");
+
+    cx.dispatch_action(actions::editor::Unfold);
+    assert_eq!(
+        display_text(&mut cx),
+        "{\n  \"a\": [… 2 lines],\n  \"b\": 3\n}"
+    );
+
+    cx.dispatch_action(actions::editor::UnfoldAll);
+    assert_eq!(display_text(&mut cx), text);
+    cx.assert_state("{ˇ\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": 3\n}");
+}
+
+#[gpui::test]
+fn test_diagnostics_follow_edits(cx: &mut TestAppContext) {
+    init_test(cx);
+    let mut cx = EditorTestContext::new(cx);
+
+    cx.set_state("ˇ{\"id\": 1, \"name\": 2}");
+    let diagnostics = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, _, cx| {
+            editor
+                .diagnostics(cx)
+                .into_iter()
+                .map(|diagnostic| (diagnostic.range, diagnostic.message.to_string()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    cx.update_editor(|editor, _, cx| {
+        editor.set_diagnostics(
+            [
+                Diagnostic {
+                    range: MultiBufferOffset(1)..MultiBufferOffset(5),
+                    severity: DiagnosticSeverity::Error,
+                    message: "Expected string".into(),
+                },
+                Diagnostic {
+                    range: MultiBufferOffset(10)..MultiBufferOffset(16),
+                    severity: DiagnosticSeverity::Warning,
+                    message: "Deprecated".into(),
+                },
+            ],
+            cx,
+        );
+    });
+
+    cx.update_editor(|editor, window, cx| {
+        editor.replace_text_in_range(Some(0..0), "  ", window, cx);
+    });
+    assert_eq!(
+        diagnostics(&mut cx),
+        vec![
+            (
+                MultiBufferOffset(3)..MultiBufferOffset(7),
+                "Expected string".to_string()
+            ),
+            (
+                MultiBufferOffset(12)..MultiBufferOffset(18),
+                "Deprecated".to_string()
+            ),
+        ]
+    );
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_diagnostic(1, window, cx);
+    });
+    cx.assert_state("  {\"id\": 1, «\"name\"ˇ»: 2}");
+
+    cx.update_editor(|editor, _, cx| editor.clear_diagnostics(cx));
+    assert_eq!(diagnostics(&mut cx), Vec::new());
+}
@@ -196,10 +196,37 @@ pub struct CommitDetails {
     pub author_name: SharedString,
 }
 
+/// A commit that changed a file, as listed by [`GitRepository::file_history`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHistoryEntry {
+    pub commit: CommitSummary,
+    /// The path of the file in the commit, which differs from its current path if it was renamed
+    /// since.
+    pub path: RepoPath,
+}
+
 pub trait GitRepository: Send + Sync {
     fn branches(&self) -> BoxFuture<'_, anyhow::Result<BranchesScanResult>>;
     fn show(&self, commit: String) -> BoxFuture<'_, anyhow::Result<CommitDetails>>;
+    /// The text of the file at `path` in the HEAD commit, or `None` if HEAD doesn't have it.
+    fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, anyhow::Result<Option<String>>>;
+    /// The text of the file at `path` in `commit`, or `None` if the commit doesn't have it.
+    fn load_text_at_commit(
+        &self,
+        commit: SharedString,
+        path: RepoPath,
+    ) -> BoxFuture<'_, anyhow::Result<Option<String>>>;
+    /// The commits that changed the file at `path`, newest first, following it across renames.
+    fn file_history(&self, path: RepoPath) -> BoxFuture<'_, anyhow::Result<Vec<FileHistoryEntry>>>;
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<anyhow::Result<GitStatus>>;
+    /// Adds the changes to `paths`, including deletions, to the index.
+    fn stage_paths(&self, paths: Vec<RepoPath>) -> BoxFuture<'_, anyhow::Result<()>>;
+    /// Resets `paths` in the index to HEAD, keeping their changes in the working tree.
+    fn unstage_paths(&self, paths: Vec<RepoPath>) -> BoxFuture<'_, anyhow::Result<()>>;
+    fn commit(&self, message: SharedString) -> BoxFuture<'_, anyhow::Result<()>>;
+    /// Switches to `branch`. A remote branch is checked out as the local branch tracking it,
+    /// which is created if it doesn't exist yet.
+    fn checkout_branch(&self, branch: Branch) -> BoxFuture<'_, anyhow::Result<()>>;
 }
 
 impl fmt::Debug for dyn GitRepository {
@@ -412,6 +439,44 @@ impl GitRepository for SystemGitRepository {
             .boxed()
     }
 
+    fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, anyhow::Result<Option<String>>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move { git.load_text("HEAD", &path).await })
+            .boxed()
+    }
+
+    fn load_text_at_commit(
+        &self,
+        commit: SharedString,
+        path: RepoPath,
+    ) -> BoxFuture<'_, anyhow::Result<Option<String>>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move { git.load_text(&commit, &path).await })
+            .boxed()
+    }
+
+    fn file_history(&self, path: RepoPath) -> BoxFuture<'_, anyhow::Result<Vec<FileHistoryEntry>>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let output = git
+                    .run(&[
+                        OsStr::new("log"),
+                        OsStr::new("--follow"),
+                        OsStr::new("--name-only"),
+                        OsStr::new("-z"),
+                        OsStr::new("--format=%x1e%H%x00%P%x00%ct%x00%an%x00%s"),
+                        OsStr::new("--"),
+                        path.as_std_path().as_os_str(),
+                    ])
+                    .await?;
+                parse_file_history(&output)
+            })
+            .boxed()
+    }
+
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<anyhow::Result<GitStatus>> {
         let git = self.git_binary_in_worktree();
         let args = git_status_args(path_prefixes);
@@ -428,6 +493,104 @@ impl GitRepository for SystemGitRepository {
             }
         })
     }
+
+    fn stage_paths(&self, paths: Vec<RepoPath>) -> BoxFuture<'_, anyhow::Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let mut args = vec![
+                    OsString::from("add"),
+                    OsString::from("--all"),
+                    OsString::from("--"),
+                ];
+                args.extend(paths.iter().map(|path| path.as_std_path().into()));
+                git.run(&args).await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn unstage_paths(&self, paths: Vec<RepoPath>) -> BoxFuture<'_, anyhow::Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let has_head = git
+                    .build_command(&["rev-parse", "--verify", "--quiet", "HEAD"])
+                    .output()
+                    .await?
+                    .status
+                    .success();
+                // Before the first commit there is no HEAD to reset to, so the paths are removed
+                // from the index instead.
+                let mut args = if has_head {
+                    vec![
+                        OsString::from("reset"),
+                        OsString::from("--quiet"),
+                        OsString::from("HEAD"),
+                    ]
+                } else {
+                    vec![
+                        OsString::from("rm"),
+                        OsString::from("--cached"),
+                        OsString::from("--quiet"),
+                        OsString::from("-r"),
+                    ]
+                };
+                args.push(OsString::from("--"));
+                args.extend(paths.iter().map(|path| path.as_std_path().into()));
+                git.run(&args).await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn commit(&self, message: SharedString) -> BoxFuture<'_, anyhow::Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                git.run(&[
+                    "commit",
+                    "--quiet",
+                    "--cleanup=strip",
+                    "-m",
+                    message.as_ref(),
+                ])
+                .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn checkout_branch(&self, branch: Branch) -> BoxFuture<'_, anyhow::Result<()>> {
+        let git = self.git_binary_in_worktree();
+        self.executor
+            .spawn(async move {
+                let local_name = match branch.remote_name() {
+                    Some(remote_name) if branch.is_remote() => branch
+                        .name()
+                        .strip_prefix(remote_name)
+                        .and_then(|name| name.strip_prefix('/'))
+                        .with_context(|| format!("invalid remote branch {}", branch.ref_name))?,
+                    _ => branch.name(),
+                };
+                let local_ref = format!("refs/heads/{local_name}");
+                let has_local_branch = git
+                    .build_command(&["rev-parse", "--verify", "--quiet", &local_ref])
+                    .output()
+                    .await?
+                    .status
+                    .success();
+
+                if branch.is_remote() && !has_local_branch {
+                    git.run(&["switch", "--quiet", "--track", branch.name()])
+                        .await?;
+                } else {
+                    git.run(&["switch", "--quiet", local_name]).await?;
+                }
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 fn parse_branch_input(input: &str) -> Vec<Branch> {
@@ -492,6 +655,47 @@ fn parse_branch_input(input: &str) -> Vec<Branch> {
     branches
 }
 
+/// Parses the output of `git log -z --name-only` with each commit formatted as
+/// `%x1e%H%x00%P%x00%ct%x00%an%x00%s`. With `-z`, the path follows the format after a newline and
+/// isn't quoted.
+fn parse_file_history(output: &str) -> anyhow::Result<Vec<FileHistoryEntry>> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let mut fields = record.split('\x00');
+            let (
+                Some(sha),
+                Some(parents),
+                Some(commit_timestamp),
+                Some(author_name),
+                Some(subject),
+                Some(path),
+            ) = (
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+            )
+            else {
+                anyhow::bail!("Unexpected git-log output: {record:?}");
+            };
+            Ok(FileHistoryEntry {
+                commit: CommitSummary {
+                    sha: sha.to_string().into(),
+                    subject: subject.to_string().into(),
+                    commit_timestamp: commit_timestamp.parse()?,
+                    author_name: author_name.to_string().into(),
+                    has_parent: !parents.is_empty(),
+                },
+                path: RepoPath::new(path.trim_start_matches('\n'))?,
+            })
+        })
+        .collect()
+}
+
 fn format_branch_scan_error(output: &Output) -> String {
     let stderr = String::from_utf8_lossy(&output.stderr)
         .trim()
@@ -559,6 +763,46 @@ impl GitBinary {
 
         command
     }
+
+    /// The text of the file at `path` in `revision`, or `None` if the revision doesn't have it.
+    async fn load_text(&self, revision: &str, path: &RepoPath) -> anyhow::Result<Option<String>> {
+        let object = format!("{revision}:{}", path.as_unix_str());
+        let output = self
+            .build_command(&["rev-parse", "--verify", "--quiet", &object])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let blob_sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let output = self
+            .build_command(&["cat-file", "blob", &blob_sha])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Git cat-file failed for {object}: {stderr}");
+        }
+        Ok(Some(String::from_utf8(output.stdout)?))
+    }
+
+    /// Runs Git with `args`, returning its output or failing with what it printed to stderr.
+    async fn run<S>(&self, args: &[S]) -> anyhow::Result<String>
+    where
+        S: AsRef<OsStr>,
+    {
+        let output = self.build_command(args).output().await?;
+        if !output.status.success() {
+            let command = args
+                .first()
+                .map(|command| command.as_ref().to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Git {command} failed: {}", stderr.trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
 }
 
 fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
@@ -590,6 +834,8 @@ mod tests {
     use fs::TempFs;
     use util_macros::path;
 
+    use crate::status::StageStatus;
+
     async fn git_command<I, S>(working_directory: &Path, arguments: I) -> std::process::Output
     where
         I: IntoIterator<Item = S>,
@@ -763,6 +1009,274 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_load_committed_text(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        temp_fs.insert_tree(path!("repo"), json!({}));
+        let repository_dir = temp_fs.path().join(path!("repo"));
+        git_command(&repository_dir, ["init", "-b", "main"]).await;
+        let repository = SystemGitRepository::new(
+            &repository_dir.join(path!(".git")),
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let request_path = RepoPath::new("orders/create.toml").unwrap();
+
+        assert_eq!(
+            repository
+                .load_committed_text(request_path.clone())
+                .await
+                .unwrap(),
+            None
+        );
+
+        temp_fs.insert_tree(
+            path!("repo"),
+            json!({ "orders": { "create.toml": "method = \"POST\"\n" } }),
+        );
+        git_command(&repository_dir, ["add", "."]).await;
+        git_command(&repository_dir, ["commit", "-m", "Add orders"]).await;
+        temp_fs.insert_tree(
+            path!("repo"),
+            json!({
+                "orders": {
+                    "create.toml": "method = \"PUT\"\n",
+                    "list.toml": "method = \"GET\"\n",
+                }
+            }),
+        );
+
+        assert_eq!(
+            repository
+                .load_committed_text(request_path)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("method = \"POST\"\n")
+        );
+        assert_eq!(
+            repository
+                .load_committed_text(RepoPath::new("orders/list.toml").unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[gpui::test]
+    async fn test_file_history(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        temp_fs.insert_tree(
+            path!("repo"),
+            json!({ "orders": { "create.toml": "method = \"POST\"\n" } }),
+        );
+        let repository_dir = temp_fs.path().join(path!("repo"));
+        git_command(&repository_dir, ["init", "-b", "main"]).await;
+        git_command(&repository_dir, ["add", "."]).await;
+        git_command(&repository_dir, ["commit", "-m", "Add orders"]).await;
+        temp_fs.insert_tree(
+            path!("repo"),
+            json!({ "orders": { "create.toml": "method = \"PUT\"\n" } }),
+        );
+        git_command(&repository_dir, ["commit", "-am", "Use PUT"]).await;
+        git_command(
+            &repository_dir,
+            ["mv", "orders/create.toml", "orders/update.toml"],
+        )
+        .await;
+        git_command(&repository_dir, ["commit", "-m", "Rename create"]).await;
+        temp_fs.insert_tree(path!("repo"), json!({ "README.md": "# Orders" }));
+        git_command(&repository_dir, ["add", "."]).await;
+        git_command(&repository_dir, ["commit", "-m", "Add README"]).await;
+        let repository = SystemGitRepository::new(
+            &repository_dir.join(path!(".git")),
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        let history = repository
+            .file_history(RepoPath::new("orders/update.toml").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|entry| (
+                    entry.commit.subject.as_ref(),
+                    entry.path.as_unix_str(),
+                    entry.commit.author_name.as_ref(),
+                    entry.commit.has_parent,
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("Rename create", "orders/update.toml", "test", true),
+                ("Use PUT", "orders/create.toml", "test", true),
+                ("Add orders", "orders/create.toml", "test", false),
+            ]
+        );
+
+        let [rename, use_put, add] = history.as_slice() else {
+            panic!("expected three commits, got {history:?}");
+        };
+        assert_eq!(
+            repository
+                .load_text_at_commit(use_put.commit.sha.clone(), use_put.path.clone())
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("method = \"PUT\"\n")
+        );
+        assert_eq!(
+            repository
+                .load_text_at_commit(add.commit.sha.clone(), add.path.clone())
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("method = \"POST\"\n")
+        );
+        assert_eq!(
+            repository
+                .load_text_at_commit(add.commit.sha.clone(), rename.path.clone())
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    async fn stage_statuses(repository: &SystemGitRepository) -> Vec<(String, StageStatus)> {
+        repository
+            .status(&[RepoPath::new("").unwrap()])
+            .await
+            .unwrap()
+            .entries
+            .iter()
+            .map(|(path, status)| (path.as_unix_str().to_string(), status.staging()))
+            .collect()
+    }
+
+    async fn head_ref(repository_dir: &Path) -> String {
+        let output = git_command(repository_dir, ["symbolic-ref", "HEAD"]).await;
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    #[gpui::test]
+    async fn test_stage_commit_and_checkout_branch(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        temp_fs.insert_tree(
+            path!("repo"),
+            json!({
+                "orders": {
+                    "create.toml": "method = \"POST\"\n",
+                    "list.toml": "method = \"GET\"\n",
+                }
+            }),
+        );
+        let repository_dir = temp_fs.path().join(path!("repo"));
+        git_command(&repository_dir, ["init", "-b", "main"]).await;
+        git_command(&repository_dir, ["config", "user.name", "test"]).await;
+        git_command(&repository_dir, ["config", "user.email", "test@zaku.dev"]).await;
+        let repository = SystemGitRepository::new(
+            &repository_dir.join(path!(".git")),
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let create_path = RepoPath::new("orders/create.toml").unwrap();
+        let list_path = RepoPath::new("orders/list.toml").unwrap();
+
+        repository
+            .stage_paths(vec![create_path.clone(), list_path.clone()])
+            .await
+            .unwrap();
+        repository
+            .unstage_paths(vec![list_path.clone()])
+            .await
+            .unwrap();
+        assert_eq!(
+            stage_statuses(&repository).await,
+            vec![
+                ("orders/create.toml".to_string(), StageStatus::Staged),
+                ("orders/list.toml".to_string(), StageStatus::Unstaged),
+            ]
+        );
+
+        repository.commit("Add orders".into()).await.unwrap();
+        assert_eq!(
+            repository
+                .show("HEAD".to_string())
+                .await
+                .unwrap()
+                .message
+                .trim(),
+            "Add orders"
+        );
+        assert_eq!(
+            stage_statuses(&repository).await,
+            vec![("orders/list.toml".to_string(), StageStatus::Unstaged)]
+        );
+
+        temp_fs.insert_tree(
+            path!("repo"),
+            json!({ "orders": { "create.toml": "method = \"PUT\"\n" } }),
+        );
+        repository
+            .stage_paths(vec![create_path.clone()])
+            .await
+            .unwrap();
+        repository.unstage_paths(vec![create_path]).await.unwrap();
+        assert_eq!(
+            stage_statuses(&repository).await,
+            vec![
+                ("orders/create.toml".to_string(), StageStatus::Unstaged),
+                ("orders/list.toml".to_string(), StageStatus::Unstaged),
+            ]
+        );
+
+        git_command(&repository_dir, ["branch", "feature"]).await;
+        git_command(&repository_dir, ["remote", "add", "origin", "../origin"]).await;
+        git_command(
+            &repository_dir,
+            ["update-ref", "refs/remotes/origin/review", "HEAD"],
+        )
+        .await;
+        let branch = |ref_name: &'static str| Branch {
+            is_head: false,
+            ref_name: ref_name.into(),
+            upstream: None,
+            most_recent_commit: None,
+        };
+
+        repository
+            .checkout_branch(branch("refs/heads/feature"))
+            .await
+            .unwrap();
+        assert_eq!(head_ref(&repository_dir).await, "refs/heads/feature");
+
+        repository
+            .checkout_branch(branch("refs/remotes/origin/review"))
+            .await
+            .unwrap();
+        assert_eq!(head_ref(&repository_dir).await, "refs/heads/review");
+
+        repository
+            .checkout_branch(branch("refs/heads/main"))
+            .await
+            .unwrap();
+        repository
+            .checkout_branch(branch("refs/remotes/origin/review"))
+            .await
+            .unwrap();
+        assert_eq!(head_ref(&repository_dir).await, "refs/heads/review");
+    }
+
     #[gpui::test]
     async fn test_system_git_repository_new_rejects_bare_repositories(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -0,0 +1,472 @@
+use gpui::{
+    Action, AnyElement, App, Context, Entity, FocusHandle, Focusable, Pixels, Render, SharedString,
+    Subscription, Task, WeakEntity, Window, prelude::*,
+};
+use std::ops::Range;
+
+use git::{repository::RepoPath, status::FileStatus};
+use input::{ErasedEditorEvent, InputField};
+use project::{
+    Project,
+    git_store::{GitStoreEvent, Repository, RepositoryEvent, StatusEntry},
+};
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, Disableable, IconAsset,
+    Text, TextCommon, TextSize, ToggleState,
+};
+use workspace::{Panel, Toast, Workspace, notifications::NotificationId};
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace.register_action(
+                |workspace, _: &actions::git_panel::ToggleFocus, window, cx| {
+                    workspace.toggle_panel_focus::<GitPanel>(window, cx);
+                },
+            );
+        },
+    )
+    .detach();
+}
+
+/// Lists the changed files of the active repository, stages and unstages them, and commits what
+/// is staged.
+pub struct GitPanel {
+    focus_handle: FocusHandle,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    commit_message_field: Entity<InputField>,
+    /// The changed files of the active repository, ordered by path.
+    entries: Vec<StatusEntry>,
+    branch_name: Option<SharedString>,
+    committing: bool,
+    stage_task: Task<()>,
+    commit_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitPanel {
+    const PANEL_KEY: &str = "GitPanel";
+    const DEFAULT_SIZE: Pixels = gpui::px(300.0);
+
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        cx.new(|cx| {
+            let focus_handle = cx.focus_handle();
+            let commit_message_field = cx.new(|cx| InputField::new(window, cx, "Commit message"));
+            let panel = cx.weak_entity();
+            let commit_message_subscription =
+                commit_message_field.read(cx).editor().clone().subscribe(
+                    Box::new(move |event, _window, cx| {
+                        if event == ErasedEditorEvent::BufferEdited
+                            && let Err(error) = panel.update(cx, |_, cx| cx.notify())
+                        {
+                            log::debug!("Failed to update git panel: {error:?}");
+                        }
+                    }),
+                    window,
+                    cx,
+                );
+            let git_store_subscription = cx.subscribe(
+                project.read(cx).git_store(),
+                |this, _, event: &GitStoreEvent, cx| match event {
+                    GitStoreEvent::ActiveRepositoryChanged(_)
+                    | GitStoreEvent::RepositoryUpdated(
+                        _,
+                        RepositoryEvent::StatusesChanged | RepositoryEvent::HeadChanged,
+                        true,
+                    ) => this.update_entries(cx),
+                    _ => {}
+                },
+            );
+            let subscriptions = vec![
+                cx.on_focus(&focus_handle, window, |this, window, cx| {
+                    window.focus(&this.commit_message_field.focus_handle(cx), cx);
+                }),
+                commit_message_subscription,
+                git_store_subscription,
+            ];
+
+            let mut this = Self {
+                focus_handle,
+                project,
+                workspace: workspace_handle,
+                commit_message_field,
+                entries: Vec::new(),
+                branch_name: None,
+                committing: false,
+                stage_task: Task::ready(()),
+                commit_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            this.update_entries(cx);
+            this
+        })
+    }
+
+    fn active_repository(&self, cx: &App) -> Option<Entity<Repository>> {
+        self.project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .active_repository()
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        match self.active_repository(cx) {
+            Some(repository) => {
+                let snapshot = repository.read(cx).snapshot();
+                self.entries = snapshot
+                    .status()
+                    .filter(|entry| entry.status.has_changes())
+                    .collect();
+                self.branch_name = snapshot
+                    .branch
+                    .as_ref()
+                    .map(|branch| SharedString::from(branch.name().to_string()));
+            }
+            None => {
+                self.entries.clear();
+                self.branch_name = None;
+            }
+        }
+        cx.notify();
+    }
+
+    fn has_staged_entries(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.status.staging().has_staged())
+    }
+
+    fn show_message(&self, message: impl Into<SharedString>, cx: &mut App) {
+        if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<Self>(), message).autohide(),
+                cx,
+            );
+        }) {
+            log::debug!("Failed to show git panel message: {error:?}");
+        }
+    }
+
+    fn set_staged(&mut self, paths: Vec<RepoPath>, staged: bool, cx: &mut Context<Self>) {
+        let Some(repository) = self.active_repository(cx) else {
+            return;
+        };
+        if paths.is_empty() {
+            return;
+        }
+
+        let task = repository.update(cx, |repository, cx| {
+            if staged {
+                repository.stage_paths(paths, cx)
+            } else {
+                repository.unstage_paths(paths, cx)
+            }
+        });
+        self.stage_task = cx.spawn(async move |this, cx| {
+            if let Err(error) = task.await
+                && let Err(error) = this.update(cx, |this, cx| {
+                    let action = if staged { "stage" } else { "unstage" };
+                    this.show_message(format!("Failed to {action} changes: {error:#}"), cx);
+                })
+            {
+                log::debug!("Failed to report git panel error: {error:?}");
+            }
+        });
+    }
+
+    fn set_all_staged(&mut self, staged: bool, cx: &mut Context<Self>) {
+        let paths = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                let staging = entry.status.staging();
+                if staged {
+                    staging.has_unstaged()
+                } else {
+                    staging.has_staged()
+                }
+            })
+            .map(|entry| entry.repo_path.clone())
+            .collect();
+        self.set_staged(paths, staged, cx);
+    }
+
+    /// Commits the staged changes with the message in the field, clearing it once that worked.
+    fn commit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let message = self.commit_message_field.read(cx).value(cx);
+        let message = message.trim();
+        if self.committing || message.is_empty() || !self.has_staged_entries() {
+            return;
+        }
+        let Some(repository) = self.active_repository(cx) else {
+            return;
+        };
+
+        let message = SharedString::from(message.to_string());
+        let task = repository.update(cx, |repository, cx| repository.commit(message, cx));
+        self.committing = true;
+        cx.notify();
+        self.commit_task = cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            if let Err(error) = this.update_in(cx, |this, window, cx| {
+                this.committing = false;
+                match result {
+                    Ok(()) => this
+                        .commit_message_field
+                        .update(cx, |field, cx| field.clear(window, cx)),
+                    Err(error) => this.show_message(format!("Failed to commit: {error:#}"), cx),
+                }
+                cx.notify();
+            }) {
+                log::debug!("Failed to update git panel after committing: {error:?}");
+            }
+        });
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let has_unstaged = self
+            .entries
+            .iter()
+            .any(|entry| entry.status.staging().has_unstaged());
+        let has_staged = self.has_staged_entries();
+        let message = self.commit_message_field.read(cx).value(cx);
+        let can_commit = has_staged && !self.committing && !message.trim().is_empty();
+        let status = match &self.branch_name {
+            Some(branch_name) => format!("On {branch_name}"),
+            None => String::new(),
+        };
+
+        gpui::div()
+            .flex()
+            .flex_none()
+            .flex_col()
+            .gap_1()
+            .px_3()
+            .py_1()
+            .border_b_1()
+            .border_color(colors.border)
+            .bg(colors.panel_tab_bar_background)
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        gpui::div()
+                            .flex_1()
+                            .min_w_0()
+                            .child(self.commit_message_field.clone()),
+                    )
+                    .child(
+                        Button::new("git-panel-commit", "Commit")
+                            .variant(ButtonVariant::OutlinedGhost)
+                            .size(ButtonSize::Compact)
+                            .disabled(!can_commit)
+                            .on_click(cx.listener(|this, _, window, cx| this.commit(window, cx))),
+                    ),
+            )
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_1()
+                    .child(
+                        Text::new(status)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line()
+                            .truncate(),
+                    )
+                    .child(
+                        gpui::div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .child(
+                                Button::new("git-panel-stage-all", "Stage All")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Compact)
+                                    .disabled(!has_unstaged)
+                                    .on_click(
+                                        cx.listener(|this, _, _, cx| this.set_all_staged(true, cx)),
+                                    ),
+                            )
+                            .child(
+                                Button::new("git-panel-unstage-all", "Unstage All")
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::Compact)
+                                    .disabled(!has_staged)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.set_all_staged(false, cx);
+                                    })),
+                            ),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn render_entry(&self, index: usize, cx: &mut Context<Self>) -> AnyElement {
+        let Some(entry) = self.entries.get(index) else {
+            return gpui::div().into_any_element();
+        };
+        let colors = cx.theme().colors();
+        let path = entry
+            .repo_path
+            .display(self.project.read(cx).path_style(cx))
+            .into_owned();
+        let (indicator, color) = status_indicator(entry.status);
+        let repo_path = entry.repo_path.clone();
+
+        gpui::div()
+            .id(("git-panel-entry", index))
+            .flex()
+            .items_center()
+            .gap_2()
+            .w_full()
+            .px_3()
+            .py_0p5()
+            .hover(|this| this.bg(colors.ghost_element_hover))
+            .child(
+                ui::checkbox(
+                    ("git-panel-entry-staged", index),
+                    ToggleState::from(entry.status.staging().as_bool()),
+                )
+                .on_click(cx.listener(move |this, state: &ToggleState, _, cx| {
+                    this.set_staged(vec![repo_path.clone()], state.selected(), cx);
+                })),
+            )
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(Text::new(path).size(TextSize::Small).truncate()),
+            )
+            .child(
+                Text::new(indicator)
+                    .size(TextSize::Small)
+                    .color(color)
+                    .single_line(),
+            )
+            .into_any_element()
+    }
+
+    fn render_entries(&self, cx: &mut Context<Self>) -> AnyElement {
+        if self.entries.is_empty() {
+            let message = if self.active_repository(cx).is_some() {
+                "No changes to commit."
+            } else {
+                "This project isn't in a Git repository."
+            };
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .px_3()
+                .child(Text::new(message).size(TextSize::Small).color(Color::Muted))
+                .into_any_element();
+        }
+
+        gpui::div()
+            .flex_1()
+            .min_h_0()
+            .py_1()
+            .child(
+                gpui::uniform_list(
+                    "git-panel-entries",
+                    self.entries.len(),
+                    cx.processor(|this, range: Range<usize>, _window, cx| {
+                        range.map(|index| this.render_entry(index, cx)).collect()
+                    }),
+                )
+                .size_full(),
+            )
+            .into_any_element()
+    }
+}
+
+/// The letter shown next to a changed file and its color.
+fn status_indicator(status: FileStatus) -> (&'static str, Color) {
+    if status.is_conflicted() {
+        ("!", Color::Conflict)
+    } else if status.is_untracked() {
+        ("U", Color::Created)
+    } else if status.is_deleted() {
+        ("D", Color::Deleted)
+    } else if status.is_created() {
+        ("A", Color::Created)
+    } else {
+        ("M", Color::Modified)
+    }
+}
+
+impl Focusable for GitPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn panel_key() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn default_size(&self, _window: &Window, _: &App) -> Pixels {
+        Self::DEFAULT_SIZE
+    }
+
+    fn icon(&self, _window: &Window, _: &App) -> Option<IconAsset> {
+        Some(IconAsset::GitBranch)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Git")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        actions::git_panel::ToggleFocus.boxed_clone()
+    }
+
+    fn set_active(&mut self, active: bool, _window: &mut Window, cx: &mut Context<Self>) {
+        if active {
+            self.update_entries(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        6
+    }
+}
+
+impl Render for GitPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = self.render_header(cx);
+        let entries = self.render_entries(cx);
+
+        gpui::div()
+            .track_focus(&self.focus_handle)
+            .key_context(Self::PANEL_KEY)
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(header)
+            .child(entries)
+    }
+}
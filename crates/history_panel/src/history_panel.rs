@@ -0,0 +1,669 @@
+mod persistence;
+
+use anyhow::Context as _;
+use gpui::{
+    Action, AnyElement, App, Context, Entity, FocusHandle, Focusable, Global, Pixels, Render,
+    SharedString, Subscription, Task, WeakEntity, Window, prelude::*,
+};
+use jiff::tz::TimeZone;
+use std::{ops::Range, path::PathBuf};
+
+use http_client::StatusCode;
+use input::{ErasedEditorEvent, InputField};
+use path::RelPath;
+use project::{Project, ProjectPath, RequestFile, request_method_short_name, url_path};
+use request_editor::{RequestEditor, RequestSent};
+use response_panel::ResponseHistoryEntry;
+use settings::{RegisterSetting, Settings, SettingsContent};
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, IconAsset, Text, TextCommon,
+    TextSize, VisibleOnHover,
+};
+use workspace::{Panel, Toast, Workspace, notifications::NotificationId};
+
+use persistence::{RequestHistoryDb, RequestHistorySummary, SerializedRequestHistoryEntry};
+
+pub fn init(cx: &mut App) {
+    let history = cx.new(RequestHistory::new);
+    cx.set_global(GlobalRequestHistory(history));
+
+    cx.observe_new(
+        |_: &mut RequestEditor, _window, cx: &mut Context<RequestEditor>| {
+            let request_editor = cx.entity();
+            RequestHistory::global(cx).update(cx, |_, cx| {
+                cx.subscribe(&request_editor, |history, _, event: &RequestSent, cx| {
+                    history.record(event.clone(), cx);
+                })
+                .detach();
+            });
+        },
+    )
+    .detach();
+
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace
+                .register_action(
+                    |workspace, _: &actions::history_panel::ToggleFocus, window, cx| {
+                        workspace.toggle_panel_focus::<HistoryPanel>(window, cx);
+                    },
+                )
+                .register_action(|_, _: &actions::history_panel::ClearHistory, _, cx| {
+                    RequestHistory::global(cx).update(cx, |history, cx| history.clear(cx));
+                });
+        },
+    )
+    .detach();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RegisterSetting)]
+struct RequestHistorySettings {
+    enabled: bool,
+    max_entries: u32,
+}
+
+impl Settings for RequestHistorySettings {
+    fn from_settings(content: &SettingsContent) -> Self {
+        let request_history = content.request_history.as_ref();
+
+        Self {
+            enabled: request_history
+                .and_then(|request_history| request_history.enabled)
+                .expect("request history enabled should be defaulted"),
+            max_entries: request_history
+                .and_then(|request_history| request_history.max_entries)
+                .expect("request history max entries should be defaulted"),
+        }
+    }
+}
+
+struct GlobalRequestHistory(Entity<RequestHistory>);
+
+impl Global for GlobalRequestHistory {}
+
+/// The requests sent from every request editor, across projects.
+struct RequestHistory {
+    /// The recorded requests, newest first.
+    entries: Vec<RequestHistorySummary>,
+    reload_task: Task<()>,
+}
+
+impl RequestHistory {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let mut history = Self {
+            entries: Vec::new(),
+            reload_task: Task::ready(()),
+        };
+        history.reload(cx);
+        history
+    }
+
+    fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalRequestHistory>().0.clone()
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let db = RequestHistoryDb::global(cx);
+        let load_history = cx.background_spawn(async move { db.request_history() });
+        self.reload_task = cx.spawn(async move |history, cx| {
+            let entries = match load_history.await {
+                Ok(entries) => entries,
+                Err(error) => {
+                    log::error!("Failed to load request history: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = history.update(cx, |history, cx| {
+                history.entries = entries;
+                cx.notify();
+            }) {
+                log::debug!("Failed to update request history: {error:?}");
+            }
+        });
+    }
+
+    fn record(&mut self, request_sent: RequestSent, cx: &mut Context<Self>) {
+        let settings = *RequestHistorySettings::get_global(cx);
+        if !settings.enabled || settings.max_entries == 0 {
+            return;
+        }
+
+        let db = RequestHistoryDb::global(cx);
+        let serialize_entry = cx.background_spawn(async move {
+            SerializedRequestHistoryEntry::new(
+                request_sent.path,
+                &request_sent.request_file,
+                &request_sent.response,
+            )
+        });
+        cx.spawn(async move |history, cx| {
+            let entry = match serialize_entry.await {
+                Ok(entry) => entry,
+                Err(error) => {
+                    log::error!("Failed to serialize request history entry: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = db
+                .save_request_history_entry(entry, settings.max_entries)
+                .await
+            {
+                log::error!("Failed to save request history entry: {error:?}");
+                return;
+            }
+            if let Err(error) = history.update(cx, |history, cx| history.reload(cx)) {
+                log::debug!("Failed to reload request history: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        let db = RequestHistoryDb::global(cx);
+        self.entries.clear();
+        cx.notify();
+        cx.spawn(async move |history, cx| {
+            if let Err(error) = db.clear_request_history().await {
+                log::error!("Failed to clear request history: {error:?}");
+            }
+            if let Err(error) = history.update(cx, |history, cx| history.reload(cx)) {
+                log::debug!("Failed to reload request history: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    /// Loads the request and response of the entry with `id`, along with the absolute path of
+    /// the request file it was sent from.
+    fn load_entry(
+        &self,
+        id: i64,
+        cx: &App,
+    ) -> Task<anyhow::Result<(Option<PathBuf>, RequestFile, ResponseHistoryEntry)>> {
+        let db = RequestHistoryDb::global(cx);
+        cx.background_spawn(async move {
+            db.request_history_entry(id)?
+                .context("request history entry not found")?
+                .into_entry()
+        })
+    }
+}
+
+/// Lists the requests sent from every request editor and reopens them with the response they
+/// got.
+pub struct HistoryPanel {
+    focus_handle: FocusHandle,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    history: Entity<RequestHistory>,
+    search_field: Entity<InputField>,
+    /// Indices into the history's entries that match the search, newest first.
+    matches: Vec<usize>,
+    selected_entry: Option<i64>,
+    open_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HistoryPanel {
+    const PANEL_KEY: &str = "HistoryPanel";
+    const DEFAULT_SIZE: Pixels = gpui::px(320.0);
+
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        let history = RequestHistory::global(cx);
+        cx.new(|cx| {
+            let search_field = cx.new(|cx| {
+                InputField::new(window, cx, "Search history").start_icon(IconAsset::ListSearch)
+            });
+            let panel = cx.weak_entity();
+            let search_subscription = search_field.read(cx).editor().clone().subscribe(
+                Box::new(move |event, _window, cx| {
+                    if event == ErasedEditorEvent::BufferEdited
+                        && let Err(error) = panel.update(cx, |panel, cx| panel.update_matches(cx))
+                    {
+                        log::debug!("Failed to update history search: {error:?}");
+                    }
+                }),
+                window,
+                cx,
+            );
+            let subscriptions = vec![
+                cx.observe(&history, |this, _, cx| this.update_matches(cx)),
+                search_subscription,
+            ];
+
+            let mut panel = Self {
+                focus_handle: cx.focus_handle(),
+                project,
+                workspace: workspace_handle,
+                history,
+                search_field,
+                matches: Vec::new(),
+                selected_entry: None,
+                open_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            panel.update_matches(cx);
+            panel
+        })
+    }
+
+    fn update_matches(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_field.read(cx).value(cx).trim().to_lowercase();
+        self.matches = self
+            .history
+            .read(cx)
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| query.is_empty() || entry_matches(entry, &query))
+            .map(|(index, _)| index)
+            .collect();
+        cx.notify();
+    }
+
+    fn show_error(&self, message: impl Into<SharedString>, cx: &mut App) {
+        if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<Self>(), message).autohide(),
+                cx,
+            );
+        }) {
+            log::debug!("Failed to show request history error: {error:?}");
+        }
+    }
+
+    /// Opens the request file the entry was sent from with the request as it was sent, and shows
+    /// the response it got.
+    fn open_entry(&mut self, id: i64, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_entry = Some(id);
+        cx.notify();
+
+        let load_entry = self.history.read(cx).load_entry(id, cx);
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        self.open_task = cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let (path, request_file, response) = load_entry.await?;
+                let project_path = path
+                    .and_then(|path| {
+                        project.read_with(cx, |project, cx| {
+                            project.project_path_for_absolute_path(&path, cx)
+                        })
+                    })
+                    .context(
+                        "This request isn't in the project. Save it as a request to open it.",
+                    )?;
+                let request_editor = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_path(project_path, None, true, window, cx)
+                    })?
+                    .await?
+                    .downcast::<RequestEditor>()
+                    .context("The request file couldn't be opened as a request.")?;
+                request_editor.update_in(cx, |request_editor, window, cx| {
+                    request_editor.restore_sent_request(request_file, response, window, cx);
+                })?;
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result
+                && let Err(error) =
+                    this.update(cx, |this, cx| this.show_error(error.to_string(), cx))
+            {
+                log::debug!("Failed to report request history error: {error:?}");
+            }
+        });
+    }
+
+    /// Writes the entry's request to a new request file at the root of the project and opens it.
+    fn save_as_request(&mut self, id: i64, window: &mut Window, cx: &mut Context<Self>) {
+        let load_entry = self.history.read(cx).load_entry(id, cx);
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        self.open_task = cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let (_, request_file, _) = load_entry.await?;
+                let (project_path, create_request_file) = project.update(cx, |project, cx| {
+                    let worktree = project
+                        .root_worktree(cx)
+                        .context("Open a project to save the request in.")?;
+                    let worktree = worktree.read(cx);
+                    let file_name = request_file_name(
+                        &request_file.http.method,
+                        &request_file.http.url,
+                        |file_name| {
+                            RelPath::unix(file_name)
+                                .is_ok_and(|path| worktree.entry_for_path(path).is_some())
+                        },
+                    );
+                    let project_path = ProjectPath {
+                        worktree_id: worktree.id(),
+                        path: RelPath::unix(&file_name)?.into_arc(),
+                    };
+                    let create_request_file =
+                        project.create_request_file(project_path.clone(), &request_file, cx);
+                    anyhow::Ok((project_path, create_request_file))
+                })?;
+                create_request_file.await?;
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_path(project_path, None, true, window, cx)
+                    })?
+                    .await?;
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result
+                && let Err(error) = this.update(cx, |this, cx| {
+                    this.show_error(format!("Failed to save the request: {error}"), cx);
+                })
+            {
+                log::debug!("Failed to report request history error: {error:?}");
+            }
+        });
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let clear_button = Button::new("history-clear", "Clear")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::Compact)
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.history.update(cx, |history, cx| history.clear(cx));
+            }));
+
+        gpui::div()
+            .flex()
+            .flex_none()
+            .items_center()
+            .gap_2()
+            .px_3()
+            .py_1()
+            .border_b_1()
+            .border_color(colors.border)
+            .bg(colors.panel_tab_bar_background)
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(self.search_field.clone()),
+            )
+            .child(clear_button)
+            .into_any_element()
+    }
+
+    fn render_entry(
+        &self,
+        entry: &RequestHistorySummary,
+        time_zone: &TimeZone,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let id = entry.id;
+        let sent_at = entry
+            .sent_at
+            .to_zoned(time_zone.clone())
+            .strftime("%b %d %H:%M:%S")
+            .to_string();
+        let (status, status_color) = match entry
+            .status_code
+            .map(StatusCode::from_u16)
+            .and_then(Result::ok)
+        {
+            Some(status_code) => (
+                SharedString::from(status_code.as_u16().to_string()),
+                response_panel::status_color(status_code),
+            ),
+            None => ("Error".into(), Color::Error),
+        };
+        let is_selected = self.selected_entry == Some(id);
+        let element_index = usize::try_from(id).unwrap_or_default();
+
+        gpui::div()
+            .id(("history-entry", element_index))
+            .group("history-entry")
+            .flex()
+            .items_center()
+            .gap_3()
+            .w_full()
+            .px_3()
+            .py_0p5()
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(colors.ghost_element_selected))
+            .hover(|this| this.bg(colors.ghost_element_hover))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.open_entry(id, window, cx);
+            }))
+            .child(
+                Text::new(sent_at)
+                    .size(TextSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .child(
+                gpui::div().w(gpui::rems(2.5)).child(
+                    Text::new(request_method_short_name(&entry.method))
+                        .size(TextSize::Small)
+                        .single_line(),
+                ),
+            )
+            .child(
+                gpui::div().flex_1().min_w_0().child(
+                    Text::new(entry.url.clone())
+                        .size(TextSize::Small)
+                        .truncate(),
+                ),
+            )
+            .child(
+                Text::new(status)
+                    .size(TextSize::Small)
+                    .color(status_color)
+                    .single_line(),
+            )
+            .child(
+                Text::new(format!("{} ms", entry.elapsed_duration.as_millis()))
+                    .size(TextSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .child(
+                gpui::div().visible_on_hover("history-entry").child(
+                    Button::new(
+                        ("history-save-as-request", element_index),
+                        "Save as Request",
+                    )
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::Compact)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        this.save_as_request(id, window, cx);
+                    })),
+                ),
+            )
+            .into_any_element()
+    }
+
+    fn render_entries(&self, cx: &mut Context<Self>) -> AnyElement {
+        if self.matches.is_empty() {
+            let message = if self.history.read(cx).entries.is_empty() {
+                "Requests you send will show up here."
+            } else {
+                "No requests match your search."
+            };
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(Text::new(message).size(TextSize::Small).color(Color::Muted))
+                .into_any_element();
+        }
+
+        gpui::div()
+            .flex_1()
+            .min_h_0()
+            .py_1()
+            .child(
+                gpui::uniform_list(
+                    "history-entries",
+                    self.matches.len(),
+                    cx.processor(|this, range: Range<usize>, _window, cx| {
+                        let time_zone = TimeZone::system();
+                        let history = this.history.clone();
+                        range
+                            .filter_map(|index| {
+                                let entry_index = *this.matches.get(index)?;
+                                let entry = history.read(cx).entries.get(entry_index)?.clone();
+                                Some(this.render_entry(&entry, &time_zone, cx))
+                            })
+                            .collect()
+                    }),
+                )
+                .size_full(),
+            )
+            .into_any_element()
+    }
+}
+
+/// Whether `entry` matches a lowercase search `query` by its method, URL, status or file.
+fn entry_matches(entry: &RequestHistorySummary, query: &str) -> bool {
+    entry.method.to_lowercase().contains(query)
+        || entry.url.to_lowercase().contains(query)
+        || entry
+            .status_code
+            .is_some_and(|status_code| status_code.to_string().contains(query))
+        || entry
+            .path
+            .as_ref()
+            .is_some_and(|path| path.to_string_lossy().to_lowercase().contains(query))
+}
+
+/// A file name for a request saved from history, named after its method and the last segment of
+/// its URL path, such as `get-users.toml`, that `exists` reports as free.
+fn request_file_name(method: &str, url: &str, exists: impl Fn(&str) -> bool) -> String {
+    let mut base_name = method.trim().to_lowercase();
+    let segment = url_path(url)
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default();
+    let mut slug = String::new();
+    for character in segment.chars() {
+        if character.is_ascii_alphanumeric() {
+            slug.push(character.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if !slug.is_empty() {
+        if !base_name.is_empty() {
+            base_name.push('-');
+        }
+        base_name.push_str(slug);
+    }
+    if base_name.is_empty() {
+        base_name.push_str("request");
+    }
+
+    let mut file_name = format!("{base_name}.toml");
+    let mut suffix = 2;
+    while exists(&file_name) {
+        file_name = format!("{base_name}-{suffix}.toml");
+        suffix += 1;
+    }
+    file_name
+}
+
+impl Focusable for HistoryPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for HistoryPanel {
+    fn persistent_name() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn panel_key() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn default_size(&self, _window: &Window, _: &App) -> Pixels {
+        Self::DEFAULT_SIZE
+    }
+
+    fn icon(&self, _window: &Window, _: &App) -> Option<IconAsset> {
+        Some(IconAsset::History)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("History")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        actions::history_panel::ToggleFocus.boxed_clone()
+    }
+
+    fn activation_priority(&self) -> u32 {
+        4
+    }
+}
+
+impl Render for HistoryPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = self.render_header(cx);
+        let entries = self.render_entries(cx);
+
+        gpui::div()
+            .track_focus(&self.focus_handle)
+            .key_context(Self::PANEL_KEY)
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(header)
+            .child(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_file_name() {
+        let no_files = |_: &str| false;
+        assert_eq!(
+            request_file_name("GET", "https://example.com/api/users?page=2", no_files),
+            "get-users.toml"
+        );
+        assert_eq!(
+            request_file_name("POST", "{{base_url}}/orders/{id}/Line Items/", no_files),
+            "post-line-items.toml"
+        );
+        assert_eq!(
+            request_file_name("GET", "https://example.com", no_files),
+            "get.toml"
+        );
+        assert_eq!(request_file_name("", "", no_files), "request.toml");
+
+        let existing = ["get-users.toml", "get-users-2.toml"];
+        assert_eq!(
+            request_file_name("GET", "https://example.com/users", |file_name| {
+                existing.contains(&file_name)
+            }),
+            "get-users-3.toml"
+        );
+    }
+}
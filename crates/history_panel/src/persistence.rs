@@ -0,0 +1,348 @@
+use jiff::Timestamp;
+use std::{path::PathBuf, time::Duration};
+
+use db::{
+    Bind, Column, Row, Statement, StaticColumnCount, ThreadSafeConnection, query,
+    sql::domain::Domain, sql_macros::sql,
+};
+use project::RequestFile;
+use response_panel::{ResponseHistoryEntry, SentRequest, SerializedResponseHistoryEntry};
+
+/// What the history panel lists for a sent request, without its response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestHistorySummary {
+    pub(crate) id: i64,
+    /// The absolute path of the request file the request was sent from, if it had one.
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) sent_at: Timestamp,
+    pub(crate) method: String,
+    pub(crate) url: String,
+    pub(crate) status_code: Option<u16>,
+    pub(crate) elapsed_duration: Duration,
+}
+
+impl Column for RequestHistorySummary {
+    fn column(row: &mut Row<'_, '_>, start_index: i32) -> anyhow::Result<(Self, i32)> {
+        let (id, next_index) = i64::column(row, start_index)?;
+        let (path, next_index) = Option::<PathBuf>::column(row, next_index)?;
+        let (sent_at, next_index) = i64::column(row, next_index)?;
+        let (request, next_index) = String::column(row, next_index)?;
+        let (status_code, next_index) = Option::<u16>::column(row, next_index)?;
+        let (elapsed_millis, next_index) = u64::column(row, next_index)?;
+        let request: SentRequest = serde_json::from_str(&request)?;
+
+        Ok((
+            Self {
+                id,
+                path,
+                sent_at: Timestamp::from_second(sent_at)?,
+                method: request.method,
+                url: request.url,
+                status_code,
+                elapsed_duration: Duration::from_millis(elapsed_millis),
+            },
+            next_index,
+        ))
+    }
+}
+
+/// A sent request as it is stored in the database: the request file as it was in the editor,
+/// and the response it got.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SerializedRequestHistoryEntry {
+    path: Option<PathBuf>,
+    request_file: String,
+    response: SerializedResponseHistoryEntry,
+}
+
+impl SerializedRequestHistoryEntry {
+    pub(crate) fn new(
+        path: Option<PathBuf>,
+        request_file: &RequestFile,
+        response: &ResponseHistoryEntry,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            path,
+            request_file: serde_json::to_string(request_file)?,
+            response: SerializedResponseHistoryEntry::from_entry(response)?,
+        })
+    }
+
+    pub(crate) fn into_entry(
+        self,
+    ) -> anyhow::Result<(Option<PathBuf>, RequestFile, ResponseHistoryEntry)> {
+        Ok((
+            self.path,
+            serde_json::from_str(&self.request_file)?,
+            self.response.into_entry()?,
+        ))
+    }
+}
+
+impl StaticColumnCount for SerializedRequestHistoryEntry {
+    fn column_count() -> usize {
+        2 + SerializedResponseHistoryEntry::column_count()
+    }
+}
+
+impl Bind for SerializedRequestHistoryEntry {
+    fn bind(&self, statement: &Statement<'_>, start_index: i32) -> anyhow::Result<i32> {
+        let next_index = statement.bind(&self.path, start_index)?;
+        let next_index = statement.bind(&self.request_file, next_index)?;
+        statement.bind(&self.response, next_index)
+    }
+}
+
+impl Column for SerializedRequestHistoryEntry {
+    fn column(row: &mut Row<'_, '_>, start_index: i32) -> anyhow::Result<(Self, i32)> {
+        let (path, next_index) = Option::<PathBuf>::column(row, start_index)?;
+        let (request_file, next_index) = String::column(row, next_index)?;
+        let (response, next_index) = SerializedResponseHistoryEntry::column(row, next_index)?;
+
+        Ok((
+            Self {
+                path,
+                request_file,
+                response,
+            },
+            next_index,
+        ))
+    }
+}
+
+pub(crate) struct RequestHistoryDb(ThreadSafeConnection);
+
+impl RequestHistoryDb {
+    query! {
+        pub(crate) fn request_history() -> anyhow::Result<Vec<RequestHistorySummary>> {
+            SELECT id, path, sent_at, request, status_code, elapsed_millis
+            FROM request_history
+            ORDER BY id DESC
+        }
+    }
+
+    query! {
+        pub(crate) fn request_history_entry(
+            id: i64,
+        ) -> anyhow::Result<Option<SerializedRequestHistoryEntry>> {
+            SELECT
+                path,
+                request_file,
+                id,
+                sent_at,
+                request,
+                status_code,
+                bytes_received,
+                elapsed_millis,
+                headers,
+                cookies,
+                body,
+                language_name
+            FROM request_history
+            WHERE id = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_request_history_entry(
+            entry: SerializedRequestHistoryEntry,
+            max_entries: u32,
+        ) -> anyhow::Result<()> {
+            INSERT INTO request_history(
+                path,
+                request_file,
+                sent_at,
+                request,
+                status_code,
+                bytes_received,
+                elapsed_millis,
+                headers,
+                cookies,
+                body,
+                language_name
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11);
+            DELETE FROM request_history
+            WHERE id NOT IN (
+                SELECT id
+                FROM request_history
+                ORDER BY id DESC
+                LIMIT ?12
+            )
+        }
+    }
+
+    query! {
+        pub(crate) async fn clear_request_history() -> anyhow::Result<()> {
+            DELETE FROM request_history
+        }
+    }
+}
+
+impl Domain for RequestHistoryDb {
+    const NAME: &str = stringify!(RequestHistoryDb);
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE IF NOT EXISTS request_history(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path BLOB,
+            request_file TEXT NOT NULL,
+            sent_at INTEGER NOT NULL,
+            request TEXT NOT NULL,
+            status_code INTEGER,
+            bytes_received INTEGER NOT NULL,
+            elapsed_millis INTEGER NOT NULL,
+            headers TEXT NOT NULL,
+            cookies TEXT NOT NULL,
+            body BLOB NOT NULL,
+            language_name TEXT
+        ) STRICT;
+    )];
+}
+
+db::static_connection!(RequestHistoryDb, []);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_client::StatusCode;
+    use pretty_assertions::assert_eq;
+
+    use project::RequestFileHttp;
+
+    fn request_file(method: &str, url: &str) -> RequestFile {
+        RequestFile {
+            http: RequestFileHttp {
+                method: method.to_string(),
+                url: url.to_string(),
+                ..RequestFileHttp::default()
+            },
+            ..RequestFile::default()
+        }
+    }
+
+    fn response(method: &str, url: &str, status_code: u16, body: &str) -> ResponseHistoryEntry {
+        ResponseHistoryEntry::test(
+            SentRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                headers: Vec::new(),
+                body: None,
+                variables: Vec::new(),
+            },
+            Some(StatusCode::from_u16(status_code).unwrap()),
+            Duration::from_millis(42),
+            body,
+        )
+    }
+
+    #[gpui::test]
+    async fn test_request_history_is_recorded() {
+        let db = RequestHistoryDb::test_open("test_request_history_is_recorded").await;
+        let path = PathBuf::from("/project/users.toml");
+
+        assert!(db.request_history().unwrap().is_empty());
+
+        let users = request_file("GET", "https://example.com/users");
+        db.save_request_history_entry(
+            SerializedRequestHistoryEntry::new(
+                Some(path.clone()),
+                &users,
+                &response("GET", "https://example.com/users", 200, "[]"),
+            )
+            .unwrap(),
+            10,
+        )
+        .await
+        .unwrap();
+        db.save_request_history_entry(
+            SerializedRequestHistoryEntry::new(
+                None,
+                &request_file("POST", "https://example.com/orders"),
+                &response("POST", "https://example.com/orders", 500, "oops"),
+            )
+            .unwrap(),
+            10,
+        )
+        .await
+        .unwrap();
+
+        let history = db.request_history().unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|summary| (
+                    summary.path.clone(),
+                    summary.method.as_str(),
+                    summary.url.as_str(),
+                    summary.status_code,
+                    summary.elapsed_duration,
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    None,
+                    "POST",
+                    "https://example.com/orders",
+                    Some(500),
+                    Duration::from_millis(42)
+                ),
+                (
+                    Some(path.clone()),
+                    "GET",
+                    "https://example.com/users",
+                    Some(200),
+                    Duration::from_millis(42)
+                ),
+            ]
+        );
+
+        let oldest_id = history.last().unwrap().id;
+        let (entry_path, entry_request_file, entry_response) = db
+            .request_history_entry(oldest_id)
+            .unwrap()
+            .unwrap()
+            .into_entry()
+            .unwrap();
+        assert_eq!(entry_path, Some(path));
+        assert_eq!(entry_request_file, users);
+        assert_eq!(entry_response.status_code(), Some(StatusCode::OK));
+        assert_eq!(entry_response.body(), "[]");
+    }
+
+    #[gpui::test]
+    async fn test_request_history_retention() {
+        let db = RequestHistoryDb::test_open("test_request_history_retention").await;
+
+        for index in 0..5 {
+            let url = format!("https://example.com/{index}");
+            db.save_request_history_entry(
+                SerializedRequestHistoryEntry::new(
+                    None,
+                    &request_file("GET", &url),
+                    &response("GET", &url, 200, ""),
+                )
+                .unwrap(),
+                3,
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            db.request_history()
+                .unwrap()
+                .iter()
+                .map(|summary| summary.url.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "https://example.com/4",
+                "https://example.com/3",
+                "https://example.com/2"
+            ]
+        );
+
+        db.clear_request_history().await.unwrap();
+        assert!(db.request_history().unwrap().is_empty());
+    }
+}
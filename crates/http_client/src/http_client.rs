@@ -2,12 +2,16 @@ mod async_body;
 
 use futures::future::BoxFuture;
 use http::HeaderValue;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
 
 #[cfg(any(test, feature = "test"))]
 use parking_lot::Mutex;
 
 #[cfg(any(test, feature = "test"))]
-use std::{any::type_name, fmt, sync::Arc};
+use std::{any::type_name, sync::Arc};
 
 pub use http::{self, Method, Request, Response, StatusCode, Uri, request::Builder};
 pub use url::Url;
@@ -22,6 +26,55 @@ pub enum RedirectPolicy {
     FollowAll,
 }
 
+/// Why a request could not be sent, for telling transient failures apart from the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SendErrorKind {
+    Connect,
+    Timeout,
+    Other,
+}
+
+impl SendErrorKind {
+    /// The kind of an error returned by [`HttpClient::send`]. Errors that were not wrapped in a
+    /// [`SendError`] are [`SendErrorKind::Other`].
+    pub fn of(error: &anyhow::Error) -> Self {
+        error
+            .downcast_ref::<SendError>()
+            .map_or(Self::Other, SendError::kind)
+    }
+}
+
+#[derive(Debug)]
+pub struct SendError {
+    kind: SendErrorKind,
+    error: anyhow::Error,
+}
+
+impl SendError {
+    pub fn new(kind: SendErrorKind, error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            kind,
+            error: error.into(),
+        }
+    }
+
+    pub fn kind(&self) -> SendErrorKind {
+        self.kind
+    }
+}
+
+impl Display for SendError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, formatter)
+    }
+}
+
+impl Error for SendError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
 pub trait HttpRequestExt {
     fn when(self, condition: bool, then: impl FnOnce(Self) -> Self) -> Self
     where
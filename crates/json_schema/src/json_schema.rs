@@ -0,0 +1,670 @@
+mod source;
+
+use anyhow::{Context as _, bail};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+pub use source::{SourceError, locate_pointer, locate_pointers, validate_source};
+
+/// Nesting limit for subschemas, which stops `$ref` cycles from recursing forever.
+const MAX_DEPTH: usize = 64;
+
+/// A parsed JSON Schema.
+///
+/// Validation covers the draft 2020-12 keywords that describe the shape of a payload: types,
+/// enums, numeric and length bounds, patterns, object properties, array items, the `allOf`,
+/// `anyOf`, `oneOf`, `not` and `if` combinators, and `$ref`s pointing into the same document.
+/// Annotations such as `format` and `description` are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonSchema {
+    root: Value,
+}
+
+/// A value that failed validation, identified by its JSON pointer in the validated document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl JsonSchema {
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let root = serde_json::from_str(text).context("Invalid JSON Schema")?;
+        Self::from_value(root)
+    }
+
+    pub fn from_value(root: Value) -> anyhow::Result<Self> {
+        if !root.is_object() && !root.is_boolean() {
+            bail!("A JSON Schema must be an object or a boolean");
+        }
+        Ok(Self { root })
+    }
+
+    /// Returns every validation error in `instance`, in document order.
+    pub fn validate(&self, instance: &Value) -> Vec<ValidationError> {
+        let mut validator = Validator {
+            root: &self.root,
+            patterns: HashMap::new(),
+        };
+        validator.validate(&self.root, instance, "", 0)
+    }
+}
+
+/// Escapes a property name for use as a JSON pointer reference token.
+pub fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    patterns: HashMap<&'a str, Option<Regex>>,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(
+        &mut self,
+        schema: &'a Value,
+        instance: &Value,
+        pointer: &str,
+        depth: usize,
+    ) -> Vec<ValidationError> {
+        let error = |message: String| ValidationError {
+            pointer: pointer.to_string(),
+            message,
+        };
+        let schema = match schema {
+            Value::Bool(true) => return Vec::new(),
+            Value::Bool(false) => return vec![error("No value is allowed here".to_string())],
+            Value::Object(schema) => schema,
+            _ => return vec![error("Schema must be an object or a boolean".to_string())],
+        };
+        if depth > MAX_DEPTH {
+            return vec![error(
+                "Schema is nested too deeply, check for a recursive $ref".to_string(),
+            )];
+        }
+
+        let mut errors = Vec::new();
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(target) => errors.extend(self.validate(target, instance, pointer, depth + 1)),
+                None => errors.push(error(format!("Cannot resolve $ref `{reference}`"))),
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            let expected = match types {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !expected.is_empty() && !expected.iter().any(|name| has_type(instance, name)) {
+                errors.push(error(format!(
+                    "Expected {}, got {}",
+                    expected.join(" or "),
+                    type_name(instance)
+                )));
+            }
+        }
+        if let Some(Value::Array(allowed)) = schema.get("enum")
+            && !allowed.contains(instance)
+        {
+            let allowed = allowed
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            errors.push(error(format!("Value must be one of {allowed}")));
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != instance
+        {
+            errors.push(error(format!("Value must be {expected}")));
+        }
+
+        match instance {
+            Value::Number(number) => {
+                if let Some(value) = number.as_f64() {
+                    Self::validate_number(schema, value, pointer, &mut errors);
+                }
+            }
+            Value::String(value) => self.validate_string(schema, value, pointer, &mut errors),
+            Value::Array(items) => {
+                self.validate_array(schema, items, pointer, depth, &mut errors);
+            }
+            Value::Object(properties) => {
+                self.validate_object(schema, properties, pointer, depth, &mut errors);
+            }
+            Value::Null | Value::Bool(_) => {}
+        }
+
+        if let Some(Value::Array(subschemas)) = schema.get("allOf") {
+            for subschema in subschemas {
+                errors.extend(self.validate(subschema, instance, pointer, depth + 1));
+            }
+        }
+        if let Some(Value::Array(subschemas)) = schema.get("anyOf")
+            && !subschemas
+                .iter()
+                .any(|subschema| self.is_valid(subschema, instance, pointer, depth))
+        {
+            errors.push(error(
+                "Must match at least one of the schemas in anyOf".to_string(),
+            ));
+        }
+        if let Some(Value::Array(subschemas)) = schema.get("oneOf") {
+            let matched = subschemas
+                .iter()
+                .filter(|subschema| self.is_valid(subschema, instance, pointer, depth))
+                .count();
+            if matched != 1 {
+                errors.push(error(format!(
+                    "Must match exactly one of the schemas in oneOf, but matched {matched}"
+                )));
+            }
+        }
+        if let Some(subschema) = schema.get("not")
+            && self.is_valid(subschema, instance, pointer, depth)
+        {
+            errors.push(error("Must not match the schema in not".to_string()));
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.is_valid(condition, instance, pointer, depth) {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                errors.extend(self.validate(branch, instance, pointer, depth + 1));
+            }
+        }
+
+        errors
+    }
+
+    fn is_valid(
+        &mut self,
+        schema: &'a Value,
+        instance: &Value,
+        pointer: &str,
+        depth: usize,
+    ) -> bool {
+        self.validate(schema, instance, pointer, depth + 1)
+            .is_empty()
+    }
+
+    fn validate_number(
+        schema: &Map<String, Value>,
+        value: f64,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let bound = |keyword: &str| {
+            schema
+                .get(keyword)
+                .and_then(|bound| bound.as_f64().map(|limit| (bound, limit)))
+        };
+        let mut push = |message: String| {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                message,
+            });
+        };
+
+        if let Some((bound, limit)) = bound("minimum")
+            && value < limit
+        {
+            push(format!("Must be at least {bound}"));
+        }
+        if let Some((bound, limit)) = bound("maximum")
+            && value > limit
+        {
+            push(format!("Must be at most {bound}"));
+        }
+        if let Some((bound, limit)) = bound("exclusiveMinimum")
+            && value <= limit
+        {
+            push(format!("Must be greater than {bound}"));
+        }
+        if let Some((bound, limit)) = bound("exclusiveMaximum")
+            && value >= limit
+        {
+            push(format!("Must be less than {bound}"));
+        }
+        if let Some((bound, divisor)) = bound("multipleOf")
+            && divisor > 0.0
+        {
+            let quotient = value / divisor;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                push(format!("Must be a multiple of {bound}"));
+            }
+        }
+    }
+
+    fn validate_string(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        value: &str,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let length = value.chars().count();
+        if let Some(min_length) = count_keyword(schema, "minLength")
+            && length < min_length
+        {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                message: format!("Must be at least {min_length} characters long"),
+            });
+        }
+        if let Some(max_length) = count_keyword(schema, "maxLength")
+            && length > max_length
+        {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                message: format!("Must be at most {max_length} characters long"),
+            });
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            match self.pattern(pattern) {
+                Some(regex) if regex.is_match(value) => {}
+                Some(_) => errors.push(ValidationError {
+                    pointer: pointer.to_string(),
+                    message: format!("Must match pattern `{pattern}`"),
+                }),
+                None => errors.push(ValidationError {
+                    pointer: pointer.to_string(),
+                    message: format!("Schema pattern `{pattern}` is not a valid regex"),
+                }),
+            }
+        }
+    }
+
+    fn validate_array(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        items: &[Value],
+        pointer: &str,
+        depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        // Draft 2020-12 spells tuples as `prefixItems` and `items`, older drafts as an `items`
+        // array followed by `additionalItems`.
+        let (prefix_items, rest_items) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix_items)), rest_items) => (prefix_items.as_slice(), rest_items),
+            (_, Some(Value::Array(prefix_items))) => {
+                (prefix_items.as_slice(), schema.get("additionalItems"))
+            }
+            (_, rest_items) => (&[][..], rest_items),
+        };
+        for (index, item) in items.iter().enumerate() {
+            let item_schema = prefix_items.get(index).or(rest_items);
+            if let Some(item_schema) = item_schema {
+                let item_pointer = format!("{pointer}/{index}");
+                errors.extend(self.validate(item_schema, item, &item_pointer, depth + 1));
+            }
+        }
+
+        let mut push = |message: String| {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                message,
+            });
+        };
+        if let Some(min_items) = count_keyword(schema, "minItems")
+            && items.len() < min_items
+        {
+            push(format!("Must have at least {min_items} items"));
+        }
+        if let Some(max_items) = count_keyword(schema, "maxItems")
+            && items.len() > max_items
+        {
+            push(format!("Must have at most {max_items} items"));
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = items.iter().enumerate().find_map(|(index, item)| {
+                items
+                    .get(..index)
+                    .and_then(|previous| previous.iter().position(|other| other == item))
+                    .map(|first| (first, index))
+            });
+            if let Some((first, index)) = duplicate {
+                push(format!(
+                    "Items must be unique, item {index} repeats item {first}"
+                ));
+            }
+        }
+        if let Some(contains) = schema.get("contains") {
+            let matched = items
+                .iter()
+                .enumerate()
+                .filter(|(index, item)| {
+                    self.is_valid(contains, item, &format!("{pointer}/{index}"), depth)
+                })
+                .count();
+            let min_contains = count_keyword(schema, "minContains").unwrap_or(1);
+            if matched < min_contains {
+                errors.push(ValidationError {
+                    pointer: pointer.to_string(),
+                    message: format!(
+                        "Must contain at least {min_contains} items matching contains"
+                    ),
+                });
+            }
+            if let Some(max_contains) = count_keyword(schema, "maxContains")
+                && matched > max_contains
+            {
+                errors.push(ValidationError {
+                    pointer: pointer.to_string(),
+                    message: format!("Must contain at most {max_contains} items matching contains"),
+                });
+            }
+        }
+    }
+
+    fn validate_object(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        properties: &Map<String, Value>,
+        pointer: &str,
+        depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let declared = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+
+        for (name, value) in properties {
+            let property_pointer = format!("{pointer}/{}", escape_pointer_token(name));
+            let mut evaluated = false;
+            if let Some(property_schema) = declared.and_then(|declared| declared.get(name)) {
+                evaluated = true;
+                errors.extend(self.validate(property_schema, value, &property_pointer, depth + 1));
+            }
+            for (pattern, property_schema) in pattern_properties.into_iter().flatten() {
+                if self
+                    .pattern(pattern)
+                    .is_some_and(|regex| regex.is_match(name))
+                {
+                    evaluated = true;
+                    errors.extend(self.validate(
+                        property_schema,
+                        value,
+                        &property_pointer,
+                        depth + 1,
+                    ));
+                }
+            }
+            match additional {
+                Some(Value::Bool(false)) if !evaluated => errors.push(ValidationError {
+                    pointer: property_pointer.clone(),
+                    message: format!("Property `{name}` is not allowed"),
+                }),
+                Some(additional) if !evaluated => {
+                    errors.extend(self.validate(additional, value, &property_pointer, depth + 1));
+                }
+                _ => {}
+            }
+            if let Some(name_schema) = schema.get("propertyNames") {
+                let name_errors = self.validate(
+                    name_schema,
+                    &Value::String(name.clone()),
+                    &property_pointer,
+                    depth + 1,
+                );
+                errors.extend(name_errors.into_iter().map(|error| ValidationError {
+                    message: format!("Property name `{name}`: {}", error.message),
+                    ..error
+                }));
+            }
+        }
+
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !properties.contains_key(name) {
+                    errors.push(ValidationError {
+                        pointer: pointer.to_string(),
+                        message: format!("Missing required property `{name}`"),
+                    });
+                }
+            }
+        }
+        if let Some(Value::Object(dependent_required)) = schema.get("dependentRequired") {
+            for (name, dependencies) in dependent_required {
+                if !properties.contains_key(name) {
+                    continue;
+                }
+                for dependency in dependencies.as_array().into_iter().flatten() {
+                    if let Some(dependency) = dependency.as_str()
+                        && !properties.contains_key(dependency)
+                    {
+                        errors.push(ValidationError {
+                            pointer: pointer.to_string(),
+                            message: format!(
+                                "Property `{dependency}` is required when `{name}` is present"
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+        if let Some(min_properties) = count_keyword(schema, "minProperties")
+            && properties.len() < min_properties
+        {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                message: format!("Must have at least {min_properties} properties"),
+            });
+        }
+        if let Some(max_properties) = count_keyword(schema, "maxProperties")
+            && properties.len() > max_properties
+        {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                message: format!("Must have at most {max_properties} properties"),
+            });
+        }
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+
+    fn pattern(&mut self, pattern: &'a str) -> Option<&Regex> {
+        self.patterns
+            .entry(pattern)
+            .or_insert_with(|| Regex::new(pattern).ok())
+            .as_ref()
+    }
+}
+
+fn count_keyword(schema: &Map<String, Value>, keyword: &str) -> Option<usize> {
+    let count = schema.get(keyword)?.as_u64()?;
+    Some(usize::try_from(count).unwrap_or(usize::MAX))
+}
+
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|value| value.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn errors(schema: Value, instance: &Value) -> Vec<(String, String)> {
+        JsonSchema::from_value(schema)
+            .unwrap()
+            .validate(instance)
+            .into_iter()
+            .map(|error| (error.pointer, error.message))
+            .collect()
+    }
+
+    fn error(pointer: &str, message: &str) -> (String, String) {
+        (pointer.to_string(), message.to_string())
+    }
+
+    #[test]
+    fn test_validate_object() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "name": { "type": "string", "minLength": 2 },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] }, "uniqueItems": true }
+            }
+        });
+
+        assert_eq!(
+            errors(
+                schema.clone(),
+                &json!({ "id": 7, "name": "zaku", "tags": ["a"] })
+            ),
+            Vec::new()
+        );
+        assert_eq!(
+            errors(
+                schema,
+                &json!({ "id": 0, "name": "z", "tags": ["a", "c", "a"], "extra/key": true })
+            ),
+            vec![
+                error("/id", "Must be at least 1"),
+                error("/name", "Must be at least 2 characters long"),
+                error("/tags/1", "Value must be one of \"a\", \"b\""),
+                error("/tags", "Items must be unique, item 2 repeats item 0"),
+                error("/extra~1key", "Property `extra/key` is not allowed"),
+            ]
+        );
+        assert_eq!(
+            errors(json!({ "required": ["id"] }), &json!({})),
+            vec![error("", "Missing required property `id`")]
+        );
+    }
+
+    #[test]
+    fn test_validate_types() {
+        assert_eq!(
+            errors(json!({ "type": ["string", "null"] }), &json!(1)),
+            vec![error("", "Expected string or null, got number")]
+        );
+        assert_eq!(
+            errors(json!({ "type": "integer" }), &json!(2.0)),
+            Vec::new()
+        );
+        assert_eq!(
+            errors(json!({ "type": "integer" }), &json!(2.5)),
+            vec![error("", "Expected integer, got number")]
+        );
+        assert_eq!(
+            errors(json!(false), &json!(null)),
+            vec![error("", "No value is allowed here")]
+        );
+        assert_eq!(
+            errors(
+                json!({ "exclusiveMaximum": 10, "multipleOf": 0.5 }),
+                &json!(10.25)
+            ),
+            vec![
+                error("", "Must be less than 10"),
+                error("", "Must be a multiple of 0.5"),
+            ]
+        );
+        assert_eq!(
+            errors(json!({ "pattern": "^[a-z]+$" }), &json!("Zaku")),
+            vec![error("", "Must match pattern `^[a-z]+$`")]
+        );
+    }
+
+    #[test]
+    fn test_validate_combinators_and_refs() {
+        let schema = json!({
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "number" },
+                        "children": { "type": "array", "items": { "$ref": "#/$defs/node" } }
+                    }
+                }
+            },
+            "$ref": "#/$defs/node"
+        });
+        assert_eq!(
+            errors(
+                schema,
+                &json!({ "value": 1, "children": [{ "value": 2 }, { "value": "3" }] })
+            ),
+            vec![error("/children/1/value", "Expected number, got string")]
+        );
+
+        assert_eq!(
+            errors(
+                json!({ "oneOf": [{ "type": "number" }, { "minimum": 0 }] }),
+                &json!(5)
+            ),
+            vec![error(
+                "",
+                "Must match exactly one of the schemas in oneOf, but matched 2"
+            )]
+        );
+        assert_eq!(
+            errors(
+                json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] }),
+                &json!(5)
+            ),
+            vec![error("", "Must match at least one of the schemas in anyOf")]
+        );
+        assert_eq!(
+            errors(
+                json!({
+                    "if": { "properties": { "kind": { "const": "user" } } },
+                    "then": { "required": ["email"] }
+                }),
+                &json!({ "kind": "user" })
+            ),
+            vec![error("", "Missing required property `email`")]
+        );
+        assert_eq!(
+            errors(json!({ "$ref": "other.json#/user" }), &json!({})),
+            vec![error("", "Cannot resolve $ref `other.json#/user`")]
+        );
+        assert_eq!(
+            errors(json!({ "$ref": "#" }), &json!({})),
+            vec![error(
+                "",
+                "Schema is nested too deeply, check for a recursive $ref"
+            )]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use serde_json::Value;
+use std::{collections::HashMap, ops::Range};
+
+use crate::{JsonSchema, escape_pointer_token};
+
+/// A validation or syntax error located in the JSON text it was found in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceError {
+    pub pointer: String,
+    pub message: String,
+    /// The bytes to highlight: the member name for object properties, the opening bracket for
+    /// other objects and arrays, and the whole value otherwise.
+    pub range: Range<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct Span {
+    name: Option<Range<usize>>,
+    value: Range<usize>,
+}
+
+/// Parses `text` as JSON and validates it against `schema`. Text that fails to parse yields a
+/// single error at the position where parsing stopped.
+pub fn validate_source(schema: &JsonSchema, text: &str) -> Vec<SourceError> {
+    let instance = match serde_json::from_str::<Value>(text) {
+        Ok(instance) => instance,
+        Err(error) => {
+            let offset = offset_for_position(text, error.line(), error.column());
+            return vec![SourceError {
+                pointer: String::new(),
+                message: format!("Invalid JSON: {error}"),
+                range: offset..offset,
+            }];
+        }
+    };
+
+    let spans = spans(text);
+    schema
+        .validate(&instance)
+        .into_iter()
+        .map(|error| SourceError {
+            range: highlight_range(&spans, text, &error.pointer),
+            pointer: error.pointer,
+            message: error.message,
+        })
+        .collect()
+}
+
+/// Returns the range to highlight for the value at `pointer` in the JSON `text`, falling back to
+/// the closest ancestor that exists.
+pub fn locate_pointer(text: &str, pointer: &str) -> Option<Range<usize>> {
+    locate_pointers(text, [pointer])?.pop()
+}
+
+/// Like [`locate_pointer`], but scans `text` once for all of `pointers`.
+pub fn locate_pointers<'a>(
+    text: &str,
+    pointers: impl IntoIterator<Item = &'a str>,
+) -> Option<Vec<Range<usize>>> {
+    let spans = spans(text);
+    if spans.is_empty() {
+        return None;
+    }
+    Some(
+        pointers
+            .into_iter()
+            .map(|pointer| highlight_range(&spans, text, pointer))
+            .collect(),
+    )
+}
+
+fn highlight_range(spans: &HashMap<String, Span>, text: &str, pointer: &str) -> Range<usize> {
+    let mut pointer = pointer;
+    loop {
+        if let Some(span) = spans.get(pointer) {
+            if let Some(name) = &span.name {
+                return name.clone();
+            }
+            let opens_container = text
+                .get(span.value.clone())
+                .is_some_and(|value| value.starts_with(['{', '[']));
+            return if opens_container {
+                span.value.start..span.value.start + 1
+            } else {
+                span.value.clone()
+            };
+        }
+
+        match pointer.rfind('/') {
+            Some(index) => pointer = pointer.get(..index).unwrap_or_default(),
+            None => return 0..0,
+        }
+    }
+}
+
+fn offset_for_position(text: &str, line: usize, column: usize) -> usize {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let mut offset = (line_start + column.saturating_sub(1)).min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Maps the JSON pointer of every value in `text` to where it was written.
+fn spans(text: &str) -> HashMap<String, Span> {
+    let mut scanner = Scanner {
+        text,
+        position: 0,
+        spans: HashMap::new(),
+    };
+    scanner.scan_value(String::new(), None);
+    scanner.spans
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    position: usize,
+    spans: HashMap<String, Span>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn scan_value(&mut self, pointer: String, name: Option<Range<usize>>) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.position;
+        match self.peek()? {
+            b'{' => {
+                self.position += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b'}' => break,
+                        b',' => self.position += 1,
+                        b'"' => {
+                            let name_range = self.scan_string()?;
+                            let name =
+                                serde_json::from_str::<String>(self.text.get(name_range.clone())?)
+                                    .ok()?;
+                            self.skip_whitespace();
+                            if self.peek()? != b':' {
+                                return None;
+                            }
+                            self.position += 1;
+                            let member_pointer =
+                                format!("{pointer}/{}", escape_pointer_token(&name));
+                            self.scan_value(member_pointer, Some(name_range))?;
+                        }
+                        _ => return None,
+                    }
+                }
+                self.position += 1;
+            }
+            b'[' => {
+                self.position += 1;
+                let mut index = 0_usize;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b']' => break,
+                        b',' => self.position += 1,
+                        _ => {
+                            self.scan_value(format!("{pointer}/{index}"), None)?;
+                            index += 1;
+                        }
+                    }
+                }
+                self.position += 1;
+            }
+            b'"' => {
+                self.scan_string()?;
+            }
+            _ => {
+                while self.peek().is_some_and(|byte| {
+                    !matches!(byte, b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')
+                }) {
+                    self.position += 1;
+                }
+            }
+        }
+
+        self.spans.insert(
+            pointer,
+            Span {
+                name,
+                value: start..self.position,
+            },
+        );
+        Some(())
+    }
+
+    fn scan_string(&mut self) -> Option<Range<usize>> {
+        let start = self.position;
+        self.position += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.position += 2,
+                b'"' => {
+                    self.position += 1;
+                    return Some(start..self.position);
+                }
+                _ => self.position += 1,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_source() {
+        let schema = JsonSchema::from_value(json!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "user": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } }
+                },
+                "items": { "type": "array", "items": { "type": "integer" } }
+            }
+        }))
+        .unwrap();
+        let text = "{\n  \"user\": { \"name\": 42 },\n  \"items\": [1, \"two\"]\n}";
+
+        let errors = validate_source(&schema, text);
+        let located = errors
+            .iter()
+            .map(|error| {
+                (
+                    error.pointer.as_str(),
+                    text.get(error.range.clone()).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            located,
+            vec![
+                ("/user/name", "\"name\""),
+                ("/items/1", "\"two\""),
+                ("", "{")
+            ]
+        );
+
+        let text = "{\n  \"id\": 1,\n  oops\n}";
+        let errors = validate_source(&schema, text);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pointer, "");
+        assert!(errors[0].message.starts_with("Invalid JSON: "));
+        assert!(errors[0].range.is_empty());
+        assert!(
+            text.get(errors[0].range.start..)
+                .unwrap()
+                .starts_with("oops")
+        );
+    }
+
+    #[test]
+    fn test_locate_pointer() {
+        let text = r#"{"a/b": [{"c": null}, true], "d": "é"}"#;
+
+        assert_eq!(locate_pointer(text, "/a~1b/0/c"), Some(10..13));
+        assert_eq!(locate_pointer(text, "/a~1b/1"), Some(22..26));
+        assert_eq!(locate_pointer(text, "/a~1b/0"), Some(9..10));
+        assert_eq!(locate_pointer(text, "/d/missing"), Some(29..32));
+        assert_eq!(locate_pointer(text, ""), Some(0..1));
+        assert_eq!(locate_pointer("", "/a"), None);
+        assert_eq!(
+            locate_pointers(text, ["/d", "/a~1b/1"]),
+            Some(vec![29..32, 22..26])
+        );
+    }
+}
@@ -4,7 +4,8 @@ use parking_lot::Mutex;
 use std::{
     any::Any,
     cell::Cell,
-    cmp, mem,
+    cmp::{self, Reverse},
+    mem,
     ops::{Deref, Range},
     path::PathBuf,
     sync::Arc,
@@ -15,10 +16,13 @@ use text::{LineEnding, ReplicaId, ToOffset, Transaction, TransactionId};
 use fs::MTime;
 use path::{PathStyle, RelPath};
 use settings::WorktreeId;
+use streaming_iterator::StreamingIterator;
 
 use crate::{
     HighlightId, HighlightMap, Language, PLAIN_TEXT, Rope, SyntaxMap, SyntaxMapCapture,
-    SyntaxMapCaptures, SyntaxSnapshot, text_diff::text_diff,
+    SyntaxMapCaptures, SyntaxSnapshot,
+    syntax_map::{QueryCursorHandle, TextProvider},
+    text_diff::text_diff,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -105,6 +109,53 @@ impl BufferSnapshot {
         }
         BufferChunks::new(self.text.as_rope(), range, syntax)
     }
+
+    /// Returns the open and close ranges of every bracket pair described by the
+    /// grammars' `brackets.scm` queries that intersects `range`, ordered by the
+    /// start of the open bracket with outer pairs first.
+    pub fn bracket_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Vec<(Range<usize>, Range<usize>)> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut brackets = Vec::new();
+        for layer in self.syntax.layers_for_range(range.clone(), &self.text) {
+            let Some(config) = layer
+                .language
+                .grammar()
+                .and_then(|grammar| grammar.brackets_config.as_ref())
+            else {
+                continue;
+            };
+
+            let mut cursor = QueryCursorHandle::new();
+            cursor.set_byte_range(range.clone());
+            let mut matches = cursor.matches(
+                &config.query,
+                layer.node(),
+                TextProvider(self.text.as_rope()),
+            );
+            while let Some(query_match) = matches.next() {
+                let mut open_range = None;
+                let mut close_range = None;
+                for capture in query_match.captures {
+                    if capture.index == config.open_capture_ix {
+                        open_range = Some(capture.node.byte_range());
+                    } else if capture.index == config.close_capture_ix {
+                        close_range = Some(capture.node.byte_range());
+                    }
+                }
+                if let Some(pair) = open_range.zip(close_range) {
+                    brackets.push(pair);
+                }
+            }
+        }
+
+        brackets
+            .sort_by_key(|(open_range, close_range)| (open_range.start, Reverse(close_range.end)));
+        brackets.dedup();
+        brackets
+    }
 }
 
 impl Clone for BufferSnapshot {
@@ -1016,6 +1067,44 @@ mod tests {
         assert_eq!(get_tree_sexp(&buffer, cx), "(document (object))");
     }
 
+    #[gpui::test]
+    fn test_bracket_ranges(cx: &mut App) {
+        let text = "{\n  \"a\": [1, 2],\n  \"b\": {}\n}";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(json_lang(), cx));
+        let snapshot = buffer.read(cx).snapshot();
+
+        let brackets = snapshot
+            .bracket_ranges(0..snapshot.len())
+            .into_iter()
+            .map(|(open_range, close_range)| {
+                (
+                    &text[open_range.start..close_range.end],
+                    open_range.len(),
+                    close_range.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            brackets,
+            vec![
+                (text, 1, 1),
+                ("\"a\"", 1, 1),
+                ("[1, 2]", 1, 1),
+                ("\"b\"", 1, 1),
+                ("{}", 1, 1),
+            ]
+        );
+
+        let line_start = text.find("\"b\"").unwrap();
+        let brackets = snapshot.bracket_ranges(line_start..line_start + 3);
+        assert!(
+            brackets.iter().all(
+                |(open_range, close_range)| open_range.start <= line_start + 3
+                    && close_range.end >= line_start
+            )
+        );
+    }
+
     #[gpui::test]
     fn test_formatted_chunks(cx: &mut App) {
         let buffer = cx.new(|cx| {
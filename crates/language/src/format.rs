@@ -0,0 +1,490 @@
+use anyhow::{Context as _, bail};
+
+const INDENT: &str = "  ";
+
+const HTML_VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+const HTML_RAW_TEXT_ELEMENTS: &[&str] = &["pre", "script", "style", "textarea"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextFormat {
+    Pretty,
+    Minified,
+}
+
+/// Returns whether [`format_text`] supports the language with the given name.
+pub fn supports_text_format(language_name: &str) -> bool {
+    matches!(language_name, "JSON" | "XML" | "HTML")
+}
+
+/// Pretty prints or minifies JSON, XML or HTML text.
+///
+/// Formatting works on the source tokens rather than a parsed value, so numbers, key order and
+/// string escapes are kept exactly as written.
+pub fn format_text(language_name: &str, text: &str, format: TextFormat) -> anyhow::Result<String> {
+    match language_name {
+        "JSON" => format_json(text, format),
+        "XML" => format_markup(text, format, MarkupKind::Xml),
+        "HTML" => format_markup(text, format, MarkupKind::Html),
+        _ => bail!("Formatting {language_name} is not supported"),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum JsonToken<'a> {
+    Open(char),
+    Close(char),
+    Comma,
+    Colon,
+    Value(&'a str),
+}
+
+fn tokenize_json(text: &str) -> anyhow::Result<Vec<JsonToken<'_>>> {
+    let mut tokens = Vec::new();
+    let mut characters = text.char_indices().peekable();
+
+    while let Some((start, character)) = characters.next() {
+        match character {
+            '{' | '[' => tokens.push(JsonToken::Open(character)),
+            '}' | ']' => tokens.push(JsonToken::Close(character)),
+            ',' => tokens.push(JsonToken::Comma),
+            ':' => tokens.push(JsonToken::Colon),
+            '"' => {
+                let mut escaped = false;
+                let mut end = None;
+                for (index, character) in characters.by_ref() {
+                    if escaped {
+                        escaped = false;
+                    } else if character == '\\' {
+                        escaped = true;
+                    } else if character == '"' {
+                        end = Some(index + 1);
+                        break;
+                    }
+                }
+                let end = end.context("Unterminated string")?;
+                tokens.push(JsonToken::Value(
+                    text.get(start..end).context("Invalid string")?,
+                ));
+            }
+            character if character.is_whitespace() => {}
+            character if character.is_ascii_alphanumeric() || matches!(character, '-' | '+') => {
+                let mut end = start + character.len_utf8();
+                while let Some((index, character)) = characters.peek().copied() {
+                    if !(character.is_ascii_alphanumeric() || matches!(character, '-' | '+' | '.'))
+                    {
+                        break;
+                    }
+                    end = index + character.len_utf8();
+                    characters.next();
+                }
+                let value = text.get(start..end).context("Invalid value")?;
+                let is_number = value
+                    .chars()
+                    .next()
+                    .is_some_and(|character| character == '-' || character.is_ascii_digit());
+                if !(is_number || matches!(value, "true" | "false" | "null")) {
+                    bail!("Unexpected '{value}'");
+                }
+                tokens.push(JsonToken::Value(value));
+            }
+            character => bail!("Unexpected '{character}'"),
+        }
+    }
+
+    let mut stack = Vec::new();
+    for token in &tokens {
+        match token {
+            JsonToken::Open(character) => stack.push(*character),
+            JsonToken::Close(character) => {
+                let expected = match stack.pop() {
+                    Some('{') => '}',
+                    Some(_) => ']',
+                    None => bail!("Unexpected '{character}'"),
+                };
+                if *character != expected {
+                    bail!("Expected '{expected}' but found '{character}'");
+                }
+            }
+            _ => {}
+        }
+    }
+    if !stack.is_empty() {
+        bail!("Unexpected end of JSON");
+    }
+
+    Ok(tokens)
+}
+
+fn format_json(text: &str, format: TextFormat) -> anyhow::Result<String> {
+    let tokens = tokenize_json(text)?;
+    let mut output = String::with_capacity(text.len());
+    let mut depth = 0_usize;
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match (token, format) {
+            (JsonToken::Open(character), TextFormat::Pretty) => {
+                output.push(character);
+                if matches!(tokens.peek(), Some(JsonToken::Close(_))) {
+                    if let Some(JsonToken::Close(close)) = tokens.next() {
+                        output.push(close);
+                    }
+                } else {
+                    depth += 1;
+                    push_newline(&mut output, depth);
+                }
+            }
+            (JsonToken::Close(character), TextFormat::Pretty) => {
+                depth = depth.saturating_sub(1);
+                push_newline(&mut output, depth);
+                output.push(character);
+            }
+            (JsonToken::Comma, TextFormat::Pretty) => {
+                output.push(',');
+                push_newline(&mut output, depth);
+            }
+            (JsonToken::Colon, TextFormat::Pretty) => output.push_str(": "),
+            (JsonToken::Open(character) | JsonToken::Close(character), TextFormat::Minified) => {
+                output.push(character);
+            }
+            (JsonToken::Comma, TextFormat::Minified) => output.push(','),
+            (JsonToken::Colon, TextFormat::Minified) => output.push(':'),
+            (JsonToken::Value(value), _) => output.push_str(value),
+        }
+    }
+
+    if format == TextFormat::Pretty && !output.is_empty() {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn push_newline(output: &mut String, depth: usize) {
+    output.push('\n');
+    for _ in 0..depth {
+        output.push_str(INDENT);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MarkupKind {
+    Xml,
+    Html,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MarkupToken<'a> {
+    Open { name: String, source: &'a str },
+    Close { name: String, source: &'a str },
+    SelfClosing(&'a str),
+    Other(&'a str),
+    Text(&'a str),
+    RawText(&'a str),
+}
+
+fn tokenize_markup(text: &str, kind: MarkupKind) -> anyhow::Result<Vec<MarkupToken<'_>>> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while let Some(rest) = text.get(position..).filter(|rest| !rest.is_empty()) {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(MarkupToken::Text(rest.get(..end).context("Invalid text")?));
+            position += end;
+            continue;
+        }
+
+        let terminator = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<![CDATA[") {
+            Some("]]>")
+        } else if rest.starts_with("<?") {
+            Some("?>")
+        } else if rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+
+        let end = match terminator {
+            Some(terminator) => {
+                rest.find(terminator)
+                    .with_context(|| format!("Expected '{terminator}'"))?
+                    + terminator.len()
+            }
+            None => find_tag_end(rest).context("Unterminated tag")?,
+        };
+        let source = rest.get(..end).context("Invalid tag")?;
+        position += end;
+
+        if terminator.is_some() {
+            tokens.push(MarkupToken::Other(source));
+            continue;
+        }
+
+        let inner = source
+            .get(1..source.len().saturating_sub(1))
+            .context("Invalid tag")?;
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(MarkupToken::Close {
+                name: normalize_tag_name(tag_name(name), kind),
+                source,
+            });
+            continue;
+        }
+
+        let name = normalize_tag_name(tag_name(inner), kind);
+        if name.is_empty() {
+            bail!("Expected a tag name");
+        }
+        if inner.trim_end().ends_with('/')
+            || (kind == MarkupKind::Html && HTML_VOID_ELEMENTS.contains(&name.as_str()))
+        {
+            tokens.push(MarkupToken::SelfClosing(source));
+            continue;
+        }
+
+        if kind == MarkupKind::Html && HTML_RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let rest = text.get(position..).unwrap_or_default();
+            let closing_tag = format!("</{name}");
+            let raw_end = rest
+                .to_ascii_lowercase()
+                .find(&closing_tag)
+                .with_context(|| format!("Expected '</{name}>'"))?;
+            tokens.push(MarkupToken::Open { name, source });
+            if raw_end > 0 {
+                tokens.push(MarkupToken::RawText(
+                    rest.get(..raw_end).context("Invalid text")?,
+                ));
+            }
+            position += raw_end;
+            continue;
+        }
+
+        tokens.push(MarkupToken::Open { name, source });
+    }
+
+    Ok(tokens)
+}
+
+fn find_tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, character) in tag.char_indices() {
+        match (quote, character) {
+            (Some(quote_character), character) if character == quote_character => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(character),
+            (None, '>') => return Some(index + 1),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+fn tag_name(tag: &str) -> &str {
+    tag.split(|character: char| character.is_whitespace() || character == '/')
+        .next()
+        .unwrap_or_default()
+}
+
+fn normalize_tag_name(name: &str, kind: MarkupKind) -> String {
+    match kind {
+        MarkupKind::Xml => name.to_string(),
+        MarkupKind::Html => name.to_ascii_lowercase(),
+    }
+}
+
+fn format_markup(text: &str, format: TextFormat, kind: MarkupKind) -> anyhow::Result<String> {
+    let tokens = tokenize_markup(text, kind)?;
+    let mut output = String::with_capacity(text.len());
+
+    if format == TextFormat::Minified {
+        for token in &tokens {
+            match token {
+                MarkupToken::Text(text) => {
+                    if !text.trim().is_empty() {
+                        output.push_str(&collapse_whitespace(text));
+                    }
+                }
+                MarkupToken::Open { source, .. }
+                | MarkupToken::Close { source, .. }
+                | MarkupToken::SelfClosing(source)
+                | MarkupToken::Other(source)
+                | MarkupToken::RawText(source) => output.push_str(source),
+            }
+        }
+        return Ok(output);
+    }
+
+    let mut depth = 0_usize;
+    let mut index = 0;
+    while let Some(token) = tokens.get(index) {
+        index += 1;
+        match token {
+            MarkupToken::Open { name, source } => {
+                let next = tokens.get(index);
+                let after_next = tokens.get(index + 1);
+                let closes = |token: Option<&MarkupToken<'_>>| matches!(token, Some(MarkupToken::Close { name: close_name, .. }) if close_name == name);
+
+                push_line(&mut output, depth, source);
+                if closes(next) {
+                    if let Some(MarkupToken::Close { source, .. }) = next {
+                        output.push_str(source);
+                    }
+                    index += 1;
+                } else if let Some(MarkupToken::Text(text) | MarkupToken::RawText(text)) = next
+                    && closes(after_next)
+                    && (matches!(next, Some(MarkupToken::RawText(_))) || !text.contains('\n'))
+                {
+                    if matches!(next, Some(MarkupToken::RawText(_))) {
+                        output.push_str(text);
+                    } else {
+                        output.push_str(text.trim());
+                    }
+                    if let Some(MarkupToken::Close { source, .. }) = after_next {
+                        output.push_str(source);
+                    }
+                    index += 2;
+                } else {
+                    depth += 1;
+                }
+            }
+            MarkupToken::Close { source, .. } => {
+                depth = depth.saturating_sub(1);
+                push_line(&mut output, depth, source);
+            }
+            MarkupToken::SelfClosing(source) | MarkupToken::Other(source) => {
+                push_line(&mut output, depth, source);
+            }
+            MarkupToken::Text(text) => {
+                let text = text.trim();
+                if !text.is_empty() {
+                    push_line(&mut output, depth, text);
+                }
+            }
+            MarkupToken::RawText(text) => output.push_str(text),
+        }
+    }
+
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn push_line(output: &mut String, depth: usize, text: &str) {
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    for _ in 0..depth {
+        output.push_str(INDENT);
+    }
+    output.push_str(text);
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut previous_whitespace = false;
+    for character in text.chars() {
+        if character.is_whitespace() {
+            if !previous_whitespace {
+                collapsed.push(' ');
+            }
+            previous_whitespace = true;
+        } else {
+            collapsed.push(character);
+            previous_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_json() {
+        let minified =
+            r#"{"b":1.000000000000000000001,"a":[1,2,{}],"c":{"d":"x\"y","e":[]},"f":null}"#;
+        let pretty = r#"{
+  "b": 1.000000000000000000001,
+  "a": [
+    1,
+    2,
+    {}
+  ],
+  "c": {
+    "d": "x\"y",
+    "e": []
+  },
+  "f": null
+}
+"#;
+
+        assert_eq!(
+            format_text("JSON", minified, TextFormat::Pretty).unwrap(),
+            pretty
+        );
+        assert_eq!(
+            format_text("JSON", pretty, TextFormat::Minified).unwrap(),
+            minified
+        );
+        format_text("JSON", r#"{"a": [1}"#, TextFormat::Pretty).unwrap_err();
+        format_text("JSON", r#"{"a": "b"#, TextFormat::Pretty).unwrap_err();
+        format_text("JSON", r#"{"a": nope}"#, TextFormat::Pretty).unwrap_err();
+    }
+
+    #[test]
+    fn test_format_xml() {
+        let minified = r#"<?xml version="1.0"?><root><item id="1">One</item><item id="2"/><!-- note --><empty></empty></root>"#;
+        let pretty = r#"<?xml version="1.0"?>
+<root>
+  <item id="1">One</item>
+  <item id="2"/>
+  <!-- note -->
+  <empty></empty>
+</root>
+"#;
+
+        assert_eq!(
+            format_text("XML", minified, TextFormat::Pretty).unwrap(),
+            pretty
+        );
+        assert_eq!(
+            format_text("XML", pretty, TextFormat::Minified).unwrap(),
+            minified
+        );
+        format_text("XML", "<root><item", TextFormat::Pretty).unwrap_err();
+    }
+
+    #[test]
+    fn test_format_html() {
+        let minified = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><script>if (a < b) {}</script></head><body><p>Hello <b>world</b></p><br></body></html>";
+        let pretty = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <script>if (a < b) {}</script>
+  </head>
+  <body>
+    <p>
+      Hello
+      <b>world</b>
+    </p>
+    <br>
+  </body>
+</html>
+"#;
+
+        assert_eq!(
+            format_text("HTML", minified, TextFormat::Pretty).unwrap(),
+            pretty
+        );
+        format_text("Plain Text", minified, TextFormat::Pretty).unwrap_err();
+    }
+}
@@ -1,9 +1,11 @@
 mod buffer;
+mod format;
 mod language_registry;
 mod syntax_map;
 mod text_diff;
 
 pub use buffer::*;
+pub use format::{TextFormat, format_text, supports_text_format};
 pub use language_core::{
     BlockCommentConfig, BracketPair, BracketPairConfig, BracketPairContent, BracketsConfig,
     BracketsPatternConfig, DecreaseIndentConfig, Grammar, GrammarId, HighlightId, HighlightMap,
@@ -25,6 +27,7 @@ pub use text::{
     ReplicaId, Rope, Selection, SelectionGoal, TextDimension, TextSummary, ToOffset, ToOffsetUtf16,
     ToPoint, ToPointUtf16, Transaction, TransactionId, Unclipped,
 };
+pub use text_diff::text_diff;
 
 use anyhow::anyhow;
 use parking_lot::Mutex;
@@ -1,7 +1,7 @@
 use imara_diff::{Algorithm, Diff as ImaraDiff, InternedInput, Token};
 use std::{ops::Range, sync::Arc};
 
-pub(crate) fn text_diff(old_text: &str, new_text: &str) -> Vec<(Range<usize>, Arc<str>)> {
+pub fn text_diff(old_text: &str, new_text: &str) -> Vec<(Range<usize>, Arc<str>)> {
     let empty: Arc<str> = Arc::default();
     let mut edits = Vec::new();
     let input = InternedInput::new(old_text, new_text);
@@ -0,0 +1,554 @@
+mod router;
+mod server;
+
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    Action, AnyElement, App, Context, Entity, FocusHandle, Focusable, Pixels, Render, SharedString,
+    Subscription, Task, WeakEntity, Window, prelude::*,
+};
+use jiff::tz::TimeZone;
+use parking_lot::RwLock;
+use smol::net::TcpListener;
+use std::{collections::VecDeque, net::Ipv4Addr, sync::Arc, time::Duration};
+
+use http_client::StatusCode;
+use project::{Project, ProjectEvent, ProjectPath};
+use settings::{RegisterSetting, Settings, SettingsContent, SettingsStore};
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, IconAsset, Indicator, Text,
+    TextCommon, TextSize,
+};
+use workspace::{Panel, Workspace};
+
+use router::Router;
+use server::{MockHit, Mocks};
+
+/// How many requests the panel keeps in its log before dropping the oldest ones.
+const MAX_HITS: usize = 500;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace
+                .register_action(
+                    |workspace, _: &actions::mock_server::ToggleFocus, window, cx| {
+                        workspace.toggle_panel_focus::<MockServerPanel>(window, cx);
+                    },
+                )
+                .register_action(|workspace, _: &actions::mock_server::Start, window, cx| {
+                    if let Some(mock_server_panel) = workspace.panel::<MockServerPanel>(cx) {
+                        mock_server_panel.update(cx, |mock_server_panel, cx| {
+                            mock_server_panel.start(cx);
+                        });
+                        workspace.open_panel::<MockServerPanel>(window, cx);
+                    }
+                })
+                .register_action(|workspace, _: &actions::mock_server::Stop, _, cx| {
+                    if let Some(mock_server_panel) = workspace.panel::<MockServerPanel>(cx) {
+                        mock_server_panel.update(cx, |mock_server_panel, cx| {
+                            mock_server_panel.stop(cx);
+                        });
+                    }
+                })
+                .register_action(|workspace, _: &actions::mock_server::ClearLog, _, cx| {
+                    if let Some(mock_server_panel) = workspace.panel::<MockServerPanel>(cx) {
+                        mock_server_panel.update(cx, |mock_server_panel, cx| {
+                            mock_server_panel.clear_log(cx);
+                        });
+                    }
+                });
+        },
+    )
+    .detach();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RegisterSetting)]
+struct MockServerSettings {
+    port: u16,
+    delay: Duration,
+    cors: bool,
+}
+
+impl Settings for MockServerSettings {
+    fn from_settings(content: &SettingsContent) -> Self {
+        let mock_server = content.mock_server.as_ref();
+
+        Self {
+            port: mock_server
+                .and_then(|mock_server| mock_server.port)
+                .expect("mock server port should be defaulted"),
+            delay: Duration::from_millis(
+                mock_server
+                    .and_then(|mock_server| mock_server.delay_ms)
+                    .expect("mock server delay should be defaulted"),
+            ),
+            cors: mock_server
+                .and_then(|mock_server| mock_server.cors)
+                .expect("mock server cors should be defaulted"),
+        }
+    }
+}
+
+enum MockServerState {
+    Stopped,
+    Starting {
+        port: u16,
+        _task: Task<()>,
+    },
+    Running {
+        port: u16,
+        _server: Task<()>,
+        _hits: Task<()>,
+    },
+    Failed(SharedString),
+}
+
+/// Serves the examples saved in the project's request files on a local port and logs the
+/// requests it answers.
+pub struct MockServerPanel {
+    focus_handle: FocusHandle,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    state: MockServerState,
+    mocks: Arc<RwLock<Mocks>>,
+    /// The answered requests, oldest first.
+    hits: VecDeque<MockHit>,
+    reload_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MockServerPanel {
+    const PANEL_KEY: &str = "MockServerPanel";
+    const DEFAULT_SIZE: Pixels = gpui::px(320.0);
+
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        cx.new(|cx| {
+            let mut settings = *MockServerSettings::get_global(cx);
+            let subscriptions = vec![
+                cx.subscribe_in(
+                    &project,
+                    window,
+                    |this: &mut Self, _, event: &ProjectEvent, _, cx| {
+                        if let ProjectEvent::WorktreeUpdatedEntries(..)
+                        | ProjectEvent::WorktreeAdded(_)
+                        | ProjectEvent::WorktreeRemoved(_) = event
+                            && this.is_running()
+                        {
+                            this.reload_mocks(cx);
+                        }
+                    },
+                ),
+                cx.observe_global_in::<SettingsStore>(window, move |this, _, cx| {
+                    let new_settings = *MockServerSettings::get_global(cx);
+                    if settings == new_settings {
+                        return;
+                    }
+                    let port_changed = settings.port != new_settings.port;
+                    settings = new_settings;
+                    if port_changed && this.is_running() {
+                        this.stop(cx);
+                        this.start(cx);
+                    } else {
+                        let mut mocks = this.mocks.write();
+                        mocks.delay = new_settings.delay;
+                        mocks.cors = new_settings.cors;
+                    }
+                }),
+            ];
+
+            Self {
+                focus_handle: cx.focus_handle(),
+                project,
+                workspace: workspace_handle,
+                state: MockServerState::Stopped,
+                mocks: Arc::default(),
+                hits: VecDeque::new(),
+                reload_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(
+            self.state,
+            MockServerState::Starting { .. } | MockServerState::Running { .. }
+        )
+    }
+
+    /// Starts listening on the configured port, on localhost only.
+    pub fn start(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() {
+            return;
+        }
+
+        let port = MockServerSettings::get_global(cx).port;
+        self.reload_mocks(cx);
+        let task = cx.spawn(async move |this, cx| {
+            let listener = cx
+                .background_spawn(
+                    async move { TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await },
+                )
+                .await;
+            let result = this.update(cx, |this, cx| {
+                match listener {
+                    Ok(listener) => this.serve(listener, cx),
+                    Err(error) => {
+                        this.state = MockServerState::Failed(
+                            format!("Couldn't listen on port {port}: {error}").into(),
+                        );
+                    }
+                }
+                cx.notify();
+            });
+            if let Err(error) = result {
+                log::debug!("Mock server panel was dropped before the server started: {error}");
+            }
+        });
+        self.state = MockServerState::Starting { port, _task: task };
+        cx.notify();
+    }
+
+    fn serve(&mut self, listener: TcpListener, cx: &mut Context<Self>) {
+        let port = match listener.local_addr() {
+            Ok(address) => address.port(),
+            Err(error) => {
+                self.state = MockServerState::Failed(
+                    format!("Couldn't read the mock server address: {error}").into(),
+                );
+                return;
+            }
+        };
+        let (sender, mut receiver) = mpsc::unbounded();
+        let server = cx.background_spawn(server::serve(listener, self.mocks.clone(), sender));
+        let hits = cx.spawn(async move |this, cx| {
+            while let Some(hit) = receiver.next().await {
+                if this.update(cx, |this, cx| this.push_hit(hit, cx)).is_err() {
+                    break;
+                }
+            }
+        });
+        self.state = MockServerState::Running {
+            port,
+            _server: server,
+            _hits: hits,
+        };
+    }
+
+    /// Stops the server, which closes its port.
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() {
+            self.state = MockServerState::Stopped;
+            cx.notify();
+        }
+    }
+
+    pub fn clear_log(&mut self, cx: &mut Context<Self>) {
+        self.hits.clear();
+        cx.notify();
+    }
+
+    fn push_hit(&mut self, hit: MockHit, cx: &mut Context<Self>) {
+        if self.hits.len() >= MAX_HITS {
+            self.hits.pop_front();
+        }
+        self.hits.push_back(hit);
+        cx.notify();
+    }
+
+    /// Rebuilds the routes from the request files on disk.
+    fn reload_mocks(&mut self, cx: &mut Context<Self>) {
+        let request_files = self.project.read(cx).load_request_files(cx);
+        let MockServerSettings { delay, cors, .. } = *MockServerSettings::get_global(cx);
+        self.reload_task = cx.spawn(async move |this, cx| {
+            let request_files = request_files.await;
+            let router = cx
+                .background_spawn(async move {
+                    Router::new(
+                        request_files
+                            .into_iter()
+                            .map(|(project_path, request_file)| (project_path.path, request_file)),
+                    )
+                })
+                .await;
+            let result = this.update(cx, |this, cx| {
+                *this.mocks.write() = Mocks {
+                    router,
+                    delay,
+                    cors,
+                };
+                cx.notify();
+            });
+            if let Err(error) = result {
+                log::debug!("Mock server panel was dropped before its routes loaded: {error}");
+            }
+        });
+    }
+
+    fn open_request(&self, hit_index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((request_path, _)) = self
+            .hits
+            .get(hit_index)
+            .and_then(|hit| hit.example.as_ref())
+        else {
+            return;
+        };
+        let Some(worktree) = self.project.read(cx).root_worktree(cx) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: request_path.clone(),
+        };
+        let result = self.workspace.update(cx, |workspace, cx| {
+            workspace
+                .open_path(project_path, None, true, window, cx)
+                .detach_and_log_err(cx);
+        });
+        if let Err(error) = result {
+            log::debug!("Failed to open the mocked request: {error}");
+        }
+    }
+
+    fn status_text(&self) -> (SharedString, Color) {
+        match &self.state {
+            MockServerState::Stopped => ("Stopped".into(), Color::Muted),
+            MockServerState::Starting { port, .. } => {
+                (format!("Starting on port {port}…").into(), Color::Muted)
+            }
+            MockServerState::Running { port, .. } => {
+                let route_count = self.mocks.read().router.route_count();
+                (
+                    format!(
+                        "Listening on http://localhost:{port} · {route_count} {}",
+                        if route_count == 1 { "route" } else { "routes" }
+                    )
+                    .into(),
+                    Color::Success,
+                )
+            }
+            MockServerState::Failed(error) => (error.clone(), Color::Error),
+        }
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let (status, status_color) = self.status_text();
+        let toggle_button = if self.is_running() {
+            Button::new("mock-server-stop", "Stop")
+                .variant(ButtonVariant::OutlinedGhost)
+                .size(ButtonSize::Compact)
+                .on_click(cx.listener(|this, _, _, cx| this.stop(cx)))
+        } else {
+            Button::new("mock-server-start", "Start")
+                .variant(ButtonVariant::OutlinedGhost)
+                .size(ButtonSize::Compact)
+                .on_click(cx.listener(|this, _, _, cx| this.start(cx)))
+        };
+        let clear_button = Button::new("mock-server-clear", "Clear")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::Compact)
+            .on_click(cx.listener(|this, _, _, cx| this.clear_log(cx)));
+
+        gpui::div()
+            .flex()
+            .flex_none()
+            .items_center()
+            .gap_2()
+            .px_3()
+            .py_1()
+            .border_b_1()
+            .border_color(colors.border)
+            .bg(colors.panel_tab_bar_background)
+            .child(Indicator::dot().color(status_color))
+            .child(
+                gpui::div().flex_1().min_w_0().child(
+                    Text::new(status)
+                        .size(TextSize::Small)
+                        .color(status_color)
+                        .truncate(),
+                ),
+            )
+            .child(toggle_button)
+            .child(clear_button)
+            .into_any_element()
+    }
+
+    fn render_hits(&self, cx: &mut Context<Self>) -> AnyElement {
+        if self.hits.is_empty() {
+            let message = if self.is_running() {
+                "No requests yet."
+            } else {
+                "Start the mock server to serve the project's saved examples."
+            };
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(Text::new(message).size(TextSize::Small).color(Color::Muted))
+                .into_any_element();
+        }
+
+        let colors = cx.theme().colors();
+        let time_zone = TimeZone::system();
+        let rows = self.hits.iter().enumerate().rev().map(|(index, hit)| {
+            let received_at = hit
+                .received_at
+                .to_zoned(time_zone.clone())
+                .strftime("%H:%M:%S")
+                .to_string();
+            let status_color = StatusCode::from_u16(hit.status).map_or(Color::Muted, status_color);
+            let (example, example_color) = match &hit.example {
+                Some((request_path, example)) => (
+                    format!("{} › {example}", request_path.as_unix_str()),
+                    Color::Default,
+                ),
+                None => ("No matching example".to_string(), Color::Muted),
+            };
+            let is_matched = hit.example.is_some();
+
+            gpui::div()
+                .id(("mock-server-hit", index))
+                .flex()
+                .items_center()
+                .gap_3()
+                .w_full()
+                .px_3()
+                .py_0p5()
+                .when(is_matched, |this| {
+                    this.cursor_pointer()
+                        .hover(|this| this.bg(colors.ghost_element_hover))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_request(index, window, cx);
+                        }))
+                })
+                .child(
+                    Text::new(received_at)
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                )
+                .child(
+                    Text::new(hit.method.clone())
+                        .size(TextSize::Small)
+                        .single_line(),
+                )
+                .child(
+                    gpui::div().flex_1().min_w_0().child(
+                        Text::new(hit.target.clone())
+                            .size(TextSize::Small)
+                            .truncate(),
+                    ),
+                )
+                .child(
+                    Text::new(hit.status.to_string())
+                        .size(TextSize::Small)
+                        .color(status_color)
+                        .single_line(),
+                )
+                .child(
+                    gpui::div().w(gpui::rems(14.0)).min_w_0().child(
+                        Text::new(example)
+                            .size(TextSize::Small)
+                            .color(example_color)
+                            .truncate(),
+                    ),
+                )
+                .when(!hit.delay.is_zero(), |this| {
+                    this.child(
+                        Text::new(format!("+{} ms", hit.delay.as_millis()))
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                })
+        });
+
+        gpui::div()
+            .id("mock-server-hits")
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .py_1()
+            .overflow_y_scroll()
+            .children(rows)
+            .into_any_element()
+    }
+}
+
+fn status_color(status_code: StatusCode) -> Color {
+    if status_code.is_informational() {
+        Color::Info
+    } else if status_code.is_success() {
+        Color::Success
+    } else if status_code.is_redirection() {
+        Color::Warning
+    } else if status_code.is_client_error() || status_code.is_server_error() {
+        Color::Error
+    } else {
+        Color::Muted
+    }
+}
+
+impl Focusable for MockServerPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for MockServerPanel {
+    fn persistent_name() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn panel_key() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn default_size(&self, _window: &Window, _: &App) -> Pixels {
+        Self::DEFAULT_SIZE
+    }
+
+    fn icon(&self, _window: &Window, _: &App) -> Option<IconAsset> {
+        Some(IconAsset::Server)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Mock Server")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        actions::mock_server::ToggleFocus.boxed_clone()
+    }
+
+    fn activation_priority(&self) -> u32 {
+        3
+    }
+}
+
+impl Render for MockServerPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = self.render_header(cx);
+        let hits = self.render_hits(cx);
+
+        gpui::div()
+            .track_focus(&self.focus_handle)
+            .key_context(Self::PANEL_KEY)
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(header)
+            .child(hits)
+    }
+}
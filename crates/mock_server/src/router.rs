@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use path::RelPath;
+use project::{RequestFile, RequestFileExample, path_variable_name, url_path};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Variable(String),
+}
+
+/// The examples of a request file, served at its method and the path of its URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Route {
+    pub(crate) request_path: Arc<RelPath>,
+    method: String,
+    segments: Vec<Segment>,
+    examples: Vec<RequestFileExample>,
+}
+
+impl Route {
+    /// Whether `self` should win over `other` when both match: the first segment where they
+    /// differ is a literal in `self` and a variable in `other`.
+    fn is_more_specific_than(&self, other: &Self) -> bool {
+        self.segments
+            .iter()
+            .zip(&other.segments)
+            .map(|(segment, other_segment)| {
+                (
+                    matches!(segment, Segment::Literal(_)),
+                    matches!(other_segment, Segment::Literal(_)),
+                )
+            })
+            .find(|(literal, other_literal)| literal != other_literal)
+            .is_some_and(|(literal, _)| literal)
+    }
+
+    /// The values of the path variables if `path` matches the route's path.
+    fn match_path(&self, path: &[&str]) -> Option<Vec<(String, String)>> {
+        if path.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut path_variables = Vec::new();
+        for (segment, value) in self.segments.iter().zip(path) {
+            match segment {
+                Segment::Literal(literal) if literal == value => {}
+                Segment::Literal(_) => return None,
+                Segment::Variable(name) => path_variables.push((name.clone(), (*value).to_owned())),
+            }
+        }
+        Some(path_variables)
+    }
+
+    /// Picks the example named by `Prefer: example=<name>`, or the first one with the status
+    /// code in `Prefer: code=<status>`, or else the first example.
+    fn example(&self, prefer: Option<&str>) -> Option<&RequestFileExample> {
+        let preferences = prefer.map(preferences).unwrap_or_default();
+        let preferred = preferences.iter().find_map(|(name, value)| match *name {
+            "example" => self
+                .examples
+                .iter()
+                .find(|example| example.name.eq_ignore_ascii_case(value)),
+            "code" => self
+                .examples
+                .iter()
+                .find(|example| example.status.to_string() == *value),
+            _ => None,
+        });
+        preferred.or_else(|| self.examples.first())
+    }
+}
+
+/// Splits a `Prefer` header into its preferences, unquoting their values.
+fn preferences(prefer: &str) -> Vec<(&str, &str)> {
+    prefer
+        .split([',', ';'])
+        .filter_map(|preference| {
+            let (name, value) = preference.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            Some((name.trim(), value))
+        })
+        .collect()
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RouteMatch<'a> {
+    pub(crate) route: &'a Route,
+    pub(crate) example: &'a RequestFileExample,
+    pub(crate) path_variables: Vec<(String, String)>,
+}
+
+/// The routes served by the mock server, one for each request file with examples.
+#[derive(Debug, Default)]
+pub(crate) struct Router {
+    routes: Vec<Route>,
+}
+
+impl Router {
+    pub(crate) fn new(
+        request_files: impl IntoIterator<Item = (Arc<RelPath>, RequestFile)>,
+    ) -> Self {
+        let routes = request_files
+            .into_iter()
+            .filter(|(_, request_file)| !request_file.examples.is_empty())
+            .map(|(request_path, request_file)| Route {
+                request_path,
+                method: request_file.http.method.trim().to_ascii_uppercase(),
+                segments: path_segments(url_path(&request_file.http.url))
+                    .map(|segment| match path_variable_name(segment) {
+                        Some(name) => Segment::Variable(name.to_owned()),
+                        None => Segment::Literal(segment.to_owned()),
+                    })
+                    .collect(),
+                examples: request_file.examples,
+            })
+            .collect();
+        Self { routes }
+    }
+
+    pub(crate) fn route_count(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Finds the route for a request and the example to respond with. When several routes
+    /// match, literal segments are preferred over variables from left to right, so `/users/me`
+    /// wins over `/users/:id`. Otherwise the first route wins.
+    pub(crate) fn find(
+        &self,
+        method: &str,
+        path: &str,
+        prefer: Option<&str>,
+    ) -> Option<RouteMatch<'_>> {
+        let path = path_segments(path).collect::<Vec<_>>();
+        let (route, path_variables) = self
+            .routes
+            .iter()
+            .filter(|route| route.method.eq_ignore_ascii_case(method))
+            .filter_map(|route| Some((route, route.match_path(&path)?)))
+            .reduce(|best, candidate| {
+                if candidate.0.is_more_specific_than(best.0) {
+                    candidate
+                } else {
+                    best
+                }
+            })?;
+        Some(RouteMatch {
+            route,
+            example: route.example(prefer)?,
+            path_variables,
+        })
+    }
+
+    /// The methods of the routes matching `path`, for answering CORS preflight requests.
+    pub(crate) fn methods(&self, path: &str) -> Vec<&str> {
+        let path = path_segments(path).collect::<Vec<_>>();
+        let mut methods = Vec::new();
+        for route in &self.routes {
+            if route.match_path(&path).is_some() && !methods.contains(&route.method.as_str()) {
+                methods.push(route.method.as_str());
+            }
+        }
+        methods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use path::rel_path;
+    use pretty_assertions::assert_eq;
+    use project::RequestFileHttp;
+
+    fn request_file(method: &str, url: &str, examples: &[(&str, u16)]) -> RequestFile {
+        RequestFile {
+            http: RequestFileHttp {
+                method: method.to_owned(),
+                url: url.to_owned(),
+                ..RequestFileHttp::default()
+            },
+            examples: examples
+                .iter()
+                .map(|(name, status)| RequestFileExample {
+                    name: (*name).to_owned(),
+                    status: *status,
+                    ..RequestFileExample::default()
+                })
+                .collect(),
+            ..RequestFile::default()
+        }
+    }
+
+    fn router() -> Router {
+        Router::new([
+            (
+                rel_path("users/get.toml").into_arc(),
+                request_file(
+                    "GET",
+                    "{{baseUrl}}/users/:id?fields=name",
+                    &[("Found", 200), ("Missing", 404)],
+                ),
+            ),
+            (
+                rel_path("users/me.toml").into_arc(),
+                request_file("get", "https://api.zaku.dev/users/me/", &[("Me", 200)]),
+            ),
+            (
+                rel_path("users/update.toml").into_arc(),
+                request_file(
+                    "PUT",
+                    "https://api.zaku.dev/users/{id}",
+                    &[("Updated", 204)],
+                ),
+            ),
+            (
+                rel_path("users/list.toml").into_arc(),
+                request_file("GET", "https://api.zaku.dev/users", &[]),
+            ),
+        ])
+    }
+
+    fn find(router: &Router, method: &str, path: &str, prefer: Option<&str>) -> Option<String> {
+        router.find(method, path, prefer).map(|route_match| {
+            format!(
+                "{} {} {:?}",
+                route_match.route.request_path.as_unix_str(),
+                route_match.example.name,
+                route_match.path_variables
+            )
+        })
+    }
+
+    #[test]
+    fn test_find() {
+        let router = router();
+
+        assert_eq!(router.route_count(), 3);
+        assert_eq!(
+            find(&router, "GET", "/users/7", None).as_deref(),
+            Some(r#"users/get.toml Found [("id", "7")]"#)
+        );
+        assert_eq!(
+            find(&router, "GET", "/users/me", None).as_deref(),
+            Some("users/me.toml Me []")
+        );
+        assert_eq!(
+            find(&router, "put", "/users/7/", None).as_deref(),
+            Some(r#"users/update.toml Updated [("id", "7")]"#)
+        );
+        assert_eq!(find(&router, "GET", "/users", None), None);
+        assert_eq!(find(&router, "DELETE", "/users/7", None), None);
+        assert_eq!(find(&router, "GET", "/users/7/posts", None), None);
+        assert_eq!(router.methods("/users/7"), vec!["GET", "PUT"]);
+    }
+
+    #[test]
+    fn test_prefer_example() {
+        let router = router();
+
+        assert_eq!(
+            find(&router, "GET", "/users/7", Some("example=missing")).as_deref(),
+            Some(r#"users/get.toml Missing [("id", "7")]"#)
+        );
+        assert_eq!(
+            find(
+                &router,
+                "GET",
+                "/users/7",
+                Some(r#"return=minimal, example="Missing""#)
+            )
+            .as_deref(),
+            Some(r#"users/get.toml Missing [("id", "7")]"#)
+        );
+        assert_eq!(
+            find(&router, "GET", "/users/7", Some("code=404")).as_deref(),
+            Some(r#"users/get.toml Missing [("id", "7")]"#)
+        );
+        assert_eq!(
+            find(&router, "GET", "/users/7", Some("example=Unknown")).as_deref(),
+            Some(r#"users/get.toml Found [("id", "7")]"#)
+        );
+    }
+}
@@ -0,0 +1,610 @@
+use anyhow::{Context as _, bail};
+use futures::{StreamExt as _, channel::mpsc};
+use jiff::Timestamp;
+use parking_lot::RwLock;
+use smol::{
+    Timer,
+    io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use std::{fmt::Write as _, sync::Arc, time::Duration};
+
+use http_client::{Method, StatusCode};
+use path::RelPath;
+
+use crate::router::Router;
+
+const MAX_HEAD_SIZE: u64 = 64 * 1024;
+const MAX_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// What the running server answers with. It is replaced when request files or settings change.
+#[derive(Debug, Default)]
+pub(crate) struct Mocks {
+    pub(crate) router: Router,
+    /// How long to wait before responding with an example that doesn't set its own delay.
+    pub(crate) delay: Duration,
+    /// Whether CORS preflight requests are answered and any origin can read the responses.
+    pub(crate) cors: bool,
+}
+
+/// A request received by the mock server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MockHit {
+    pub(crate) received_at: Timestamp,
+    pub(crate) method: String,
+    /// The path and query string, as requested.
+    pub(crate) target: String,
+    pub(crate) status: u16,
+    /// The request file and example that answered, or `None` if no example matched.
+    pub(crate) example: Option<(Arc<RelPath>, String)>,
+    pub(crate) path_variables: Vec<(String, String)>,
+    pub(crate) delay: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MockRequest {
+    method: String,
+    target: String,
+    headers: Vec<(String, String)>,
+}
+
+impl MockRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn path(&self) -> &str {
+        self.target.split(['?', '#']).next().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    delay: Duration,
+}
+
+impl MockResponse {
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_owned(), value.into()));
+        self
+    }
+
+    fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+    }
+
+    /// Serializes the response, closing the connection after it. The body is left out for
+    /// `HEAD` requests and for statuses that can't have one. Headers that aren't valid, like
+    /// values with line breaks that would inject headers of their own, are left out.
+    fn to_bytes(&self, include_body: bool) -> Vec<u8> {
+        let reason = StatusCode::from_u16(self.status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or_default();
+        let include_body = include_body && !matches!(self.status, 100..=199 | 204 | 304);
+
+        let mut head = String::new();
+        write!(head, "HTTP/1.1 {} {reason}\r\n", self.status)
+            .expect("writing to a string should not fail");
+        for (name, value) in &self.headers {
+            if name.eq_ignore_ascii_case("content-length")
+                || name.eq_ignore_ascii_case("connection")
+                || name.eq_ignore_ascii_case("transfer-encoding")
+            {
+                continue;
+            }
+            if !is_valid_header(name, value) {
+                log::warn!("Mock server left out the invalid header {name:?}: {value:?}");
+                continue;
+            }
+            write!(head, "{name}: {value}\r\n").expect("writing to a string should not fail");
+        }
+        if !matches!(self.status, 100..=199 | 204) {
+            write!(head, "Content-Length: {}\r\n", self.body.len())
+                .expect("writing to a string should not fail");
+        }
+        head.push_str("Connection: close\r\n\r\n");
+
+        let mut bytes = head.into_bytes();
+        if include_body {
+            bytes.extend_from_slice(self.body.as_bytes());
+        }
+        bytes
+    }
+}
+
+/// Whether `name` is an HTTP token and `value` has no line breaks or other control characters
+/// but tabs.
+fn is_valid_header(name: &str, value: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte))
+        && value
+            .chars()
+            .all(|character| character == '\t' || !character.is_control())
+}
+
+/// Answers a request with the example of the matching route, or with a 404 when there is none.
+/// `HEAD` requests are also matched against `GET` routes. When CORS is on, preflight requests
+/// are allowed for the methods of the routes at their path.
+fn respond(mocks: &Mocks, request: &MockRequest) -> (MockResponse, MockHit) {
+    let prefer = request.header("Prefer");
+    let route_match = mocks
+        .router
+        .find(&request.method, request.path(), prefer)
+        .or_else(|| {
+            if request.method.eq_ignore_ascii_case(Method::HEAD.as_str()) {
+                mocks
+                    .router
+                    .find(Method::GET.as_str(), request.path(), prefer)
+            } else {
+                None
+            }
+        });
+    let methods = mocks.router.methods(request.path());
+
+    let (mut response, example, path_variables) = if let Some(route_match) = route_match {
+        let mut response = MockResponse::new(route_match.example.status);
+        response.headers = route_match
+            .example
+            .headers
+            .iter()
+            .filter(|header| !header.disabled && !header.name.trim().is_empty())
+            .map(|header| (header.name.trim().to_owned(), header.value.clone()))
+            .collect();
+        response.body.clone_from(&route_match.example.body);
+        response.delay = route_match
+            .example
+            .delay_ms
+            .map_or(mocks.delay, Duration::from_millis);
+        (
+            response,
+            Some((
+                route_match.route.request_path.clone(),
+                route_match.example.name.clone(),
+            )),
+            route_match.path_variables,
+        )
+    } else if mocks.cors
+        && request
+            .method
+            .eq_ignore_ascii_case(Method::OPTIONS.as_str())
+        && request.header("Access-Control-Request-Method").is_some()
+        && !methods.is_empty()
+    {
+        let response = MockResponse::new(StatusCode::NO_CONTENT.as_u16())
+            .header(
+                "Access-Control-Allow-Methods",
+                format!("{}, OPTIONS", methods.join(", ")),
+            )
+            .header(
+                "Access-Control-Allow-Headers",
+                request
+                    .header("Access-Control-Request-Headers")
+                    .unwrap_or("*"),
+            )
+            .header("Access-Control-Max-Age", "600");
+        (response, None, Vec::new())
+    } else {
+        let mut response = MockResponse::new(StatusCode::NOT_FOUND.as_u16())
+            .header("Content-Type", "application/json");
+        response.body = serde_json::json!({
+            "error": format!("No example matches {} {}", request.method, request.path()),
+        })
+        .to_string();
+        (response, None, Vec::new())
+    };
+    if mocks.cors && !response.has_header("Access-Control-Allow-Origin") {
+        response = response.header("Access-Control-Allow-Origin", "*");
+    }
+
+    let hit = MockHit {
+        received_at: Timestamp::now(),
+        method: request.method.clone(),
+        target: request.target.clone(),
+        status: response.status,
+        example,
+        path_variables,
+        delay: response.delay,
+    };
+    (response, hit)
+}
+
+async fn read_line(reader: &mut BufReader<TcpStream>, line: &mut String) -> anyhow::Result<()> {
+    line.clear();
+    let read = reader.take(MAX_HEAD_SIZE).read_line(line).await?;
+    if read == 0 {
+        bail!("connection closed before the request was complete");
+    }
+    if !line.ends_with('\n') {
+        bail!("request head is too large");
+    }
+    let trimmed_len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(trimmed_len);
+    Ok(())
+}
+
+/// Reads an HTTP/1.x request head and skips its body, which examples don't depend on.
+async fn read_request(reader: &mut BufReader<TcpStream>) -> anyhow::Result<MockRequest> {
+    let mut line = String::new();
+    read_line(reader, &mut line).await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        bail!("invalid request line {line:?}");
+    };
+    if !version.starts_with("HTTP/1.") {
+        bail!("unsupported HTTP version {version:?}");
+    }
+    let mut request = MockRequest {
+        method: method.to_owned(),
+        target: target.to_owned(),
+        headers: Vec::new(),
+    };
+
+    loop {
+        read_line(reader, &mut line).await?;
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .with_context(|| format!("invalid header {line:?}"))?;
+        request
+            .headers
+            .push((name.trim().to_owned(), value.trim().to_owned()));
+    }
+
+    if let Some(content_length) = request.header("Content-Length") {
+        let content_length = content_length
+            .parse::<u64>()
+            .ok()
+            .filter(|content_length| *content_length <= MAX_BODY_SIZE)
+            .context("invalid content length")?;
+        smol::io::copy(reader.take(content_length), smol::io::sink()).await?;
+    }
+    Ok(request)
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    mocks: &RwLock<Mocks>,
+    hits: &mpsc::UnboundedSender<MockHit>,
+) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(stream.clone());
+    let request = match read_request(&mut reader).await {
+        Ok(request) => request,
+        Err(error) => {
+            let response = MockResponse::new(StatusCode::BAD_REQUEST.as_u16());
+            stream.write_all(&response.to_bytes(false)).await?;
+            return Err(error);
+        }
+    };
+
+    let (response, hit) = respond(&mocks.read(), &request);
+    if !response.delay.is_zero() {
+        Timer::after(response.delay).await;
+    }
+    let include_body = !request.method.eq_ignore_ascii_case(Method::HEAD.as_str());
+    stream.write_all(&response.to_bytes(include_body)).await?;
+    stream.flush().await?;
+    if hits.unbounded_send(hit).is_err() {
+        log::debug!("Mock server hit log was dropped");
+    }
+    Ok(())
+}
+
+/// Answers the connections to `listener` until the returned future is dropped, sending each
+/// request that was answered to `hits`.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    mocks: Arc<RwLock<Mocks>>,
+    hits: mpsc::UnboundedSender<MockHit>,
+) {
+    listener
+        .incoming()
+        .for_each_concurrent(None, |stream| {
+            let mocks = &mocks;
+            let hits = &hits;
+            async move {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(error) => {
+                        log::debug!("Failed to accept mock server connection: {error}");
+                        return;
+                    }
+                };
+                if let Err(error) = handle_connection(stream, mocks, hits).await {
+                    log::debug!("Failed to answer mock server request: {error:#}");
+                }
+            }
+        })
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+    use path::rel_path;
+    use pretty_assertions::assert_eq;
+    use project::{RequestFile, RequestFileExample, RequestFileHeader, RequestFileHttp};
+
+    fn mocks() -> Mocks {
+        let request_file = RequestFile {
+            http: RequestFileHttp {
+                url: "https://api.zaku.dev/users/:id".to_owned(),
+                ..RequestFileHttp::default()
+            },
+            examples: vec![
+                RequestFileExample {
+                    name: "Found".to_owned(),
+                    status: 200,
+                    headers: vec![
+                        RequestFileHeader {
+                            name: "Content-Type".to_owned(),
+                            value: "application/json".to_owned(),
+                            disabled: false,
+                        },
+                        RequestFileHeader {
+                            name: "X-Debug".to_owned(),
+                            value: "1".to_owned(),
+                            disabled: true,
+                        },
+                    ],
+                    body: r#"{"id":7}"#.to_owned(),
+                    delay_ms: None,
+                },
+                RequestFileExample {
+                    name: "Slow".to_owned(),
+                    status: 503,
+                    delay_ms: Some(20),
+                    ..RequestFileExample::default()
+                },
+            ],
+            ..RequestFile::default()
+        };
+        Mocks {
+            router: Router::new([(rel_path("users/get.toml").into_arc(), request_file)]),
+            delay: Duration::from_millis(5),
+            cors: true,
+        }
+    }
+
+    async fn send(address: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn test_serve() {
+        smol::block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let (hits_tx, mut hits_rx) = mpsc::unbounded();
+            let server = serve(listener, Arc::new(RwLock::new(mocks())), hits_tx);
+
+            let client = async {
+                let found = send(
+                    address,
+                    "GET /users/7?fields=id HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                )
+                .await;
+                let slow = send(
+                    address,
+                    indoc! {"
+                        GET /users/7 HTTP/1.1\r
+                        Prefer: example=slow\r
+                        Content-Length: 5\r
+                        \r
+                        hello"},
+                )
+                .await;
+                let head = send(address, "HEAD /users/7 HTTP/1.1\r\n\r\n").await;
+                let missing = send(address, "DELETE /users/7 HTTP/1.0\r\n\r\n").await;
+                let preflight = send(
+                    address,
+                    indoc! {"
+                        OPTIONS /users/7 HTTP/1.1\r
+                        Origin: http://localhost:3000\r
+                        Access-Control-Request-Method: GET\r
+                        Access-Control-Request-Headers: authorization\r
+                        \r
+                    "},
+                )
+                .await;
+                let invalid = send(address, "hello\r\n\r\n").await;
+                (found, slow, head, missing, preflight, invalid)
+            };
+            let (found, slow, head, missing, preflight, invalid) =
+                smol::future::or(client, async {
+                    server.await;
+                    panic!("the server should run until it is dropped")
+                })
+                .await;
+
+            assert_eq!(
+                found,
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: application/json\r\n\
+                 Access-Control-Allow-Origin: *\r\n\
+                 Content-Length: 8\r\n\
+                 Connection: close\r\n\
+                 \r\n\
+                 {\"id\":7}"
+            );
+            assert_eq!(
+                slow,
+                "HTTP/1.1 503 Service Unavailable\r\n\
+                 Access-Control-Allow-Origin: *\r\n\
+                 Content-Length: 0\r\n\
+                 Connection: close\r\n\
+                 \r\n"
+            );
+            assert_eq!(
+                head,
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: application/json\r\n\
+                 Access-Control-Allow-Origin: *\r\n\
+                 Content-Length: 8\r\n\
+                 Connection: close\r\n\
+                 \r\n"
+            );
+            assert_eq!(
+                missing,
+                "HTTP/1.1 404 Not Found\r\n\
+                 Content-Type: application/json\r\n\
+                 Access-Control-Allow-Origin: *\r\n\
+                 Content-Length: 46\r\n\
+                 Connection: close\r\n\
+                 \r\n\
+                 {\"error\":\"No example matches DELETE /users/7\"}"
+            );
+            assert_eq!(
+                preflight,
+                "HTTP/1.1 204 No Content\r\n\
+                 Access-Control-Allow-Methods: GET, OPTIONS\r\n\
+                 Access-Control-Allow-Headers: authorization\r\n\
+                 Access-Control-Max-Age: 600\r\n\
+                 Access-Control-Allow-Origin: *\r\n\
+                 Connection: close\r\n\
+                 \r\n"
+            );
+            assert_eq!(
+                invalid,
+                "HTTP/1.1 400 Bad Request\r\n\
+                 Content-Length: 0\r\n\
+                 Connection: close\r\n\
+                 \r\n"
+            );
+
+            let hits = (0..5)
+                .map(|_| hits_rx.try_recv().unwrap())
+                .map(|hit| {
+                    (
+                        hit.method,
+                        hit.target,
+                        hit.status,
+                        hit.example.map(|(request_path, name)| {
+                            format!("{} {name}", request_path.as_unix_str())
+                        }),
+                        hit.path_variables,
+                        hit.delay,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let id = vec![("id".to_owned(), "7".to_owned())];
+            assert_eq!(
+                hits,
+                vec![
+                    (
+                        "GET".to_owned(),
+                        "/users/7?fields=id".to_owned(),
+                        200,
+                        Some("users/get.toml Found".to_owned()),
+                        id.clone(),
+                        Duration::from_millis(5),
+                    ),
+                    (
+                        "GET".to_owned(),
+                        "/users/7".to_owned(),
+                        503,
+                        Some("users/get.toml Slow".to_owned()),
+                        id.clone(),
+                        Duration::from_millis(20),
+                    ),
+                    (
+                        "HEAD".to_owned(),
+                        "/users/7".to_owned(),
+                        200,
+                        Some("users/get.toml Found".to_owned()),
+                        id,
+                        Duration::from_millis(5),
+                    ),
+                    (
+                        "DELETE".to_owned(),
+                        "/users/7".to_owned(),
+                        404,
+                        None,
+                        Vec::new(),
+                        Duration::ZERO,
+                    ),
+                    (
+                        "OPTIONS".to_owned(),
+                        "/users/7".to_owned(),
+                        204,
+                        None,
+                        Vec::new(),
+                        Duration::ZERO,
+                    ),
+                ]
+            );
+            assert_eq!(hits_rx.try_recv().ok(), None);
+        });
+    }
+    #[test]
+    fn test_cors_is_opt_in() {
+        let mocks = Mocks {
+            cors: false,
+            ..mocks()
+        };
+        let request = |method: &str| MockRequest {
+            method: method.to_owned(),
+            target: "/users/7".to_owned(),
+            headers: vec![
+                ("Origin".to_owned(), "https://example.com".to_owned()),
+                ("Access-Control-Request-Method".to_owned(), "GET".to_owned()),
+            ],
+        };
+
+        let (found, _) = respond(&mocks, &request("GET"));
+        assert_eq!(found.status, 200);
+        assert!(!found.has_header("Access-Control-Allow-Origin"));
+
+        let (preflight, _) = respond(&mocks, &request("OPTIONS"));
+        assert_eq!(preflight.status, 404);
+        assert!(!preflight.has_header("Access-Control-Allow-Methods"));
+    }
+
+    #[test]
+    fn test_invalid_headers_are_left_out() {
+        let response = MockResponse::new(200)
+            .header("X-Injected", "1\r\nSet-Cookie: session=stolen")
+            .header("X-Bare-Line-Feed", "1\nX-Other: 2")
+            .header("Bad Name", "1")
+            .header("Bad\r\nName", "1")
+            .header("X-Valid", "a\tb");
+
+        assert_eq!(
+            String::from_utf8(response.to_bytes(true)).unwrap(),
+            "HTTP/1.1 200 OK\r\n\
+             X-Valid: a\tb\r\n\
+             Content-Length: 0\r\n\
+             Connection: close\r\n\
+             \r\n"
+        );
+    }
+}
@@ -999,6 +999,24 @@ impl MultiBufferSnapshot {
         }
     }
 
+    pub fn bracket_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Vec<(Range<MultiBufferOffset>, Range<MultiBufferOffset>)> {
+        let start = range.start.to_offset(self);
+        let end = range.end.to_offset(self);
+        self.buffer
+            .bracket_ranges(start.0..end.0)
+            .into_iter()
+            .map(|(open_range, close_range)| {
+                (
+                    MultiBufferOffset(open_range.start)..MultiBufferOffset(open_range.end),
+                    MultiBufferOffset(close_range.start)..MultiBufferOffset(close_range.end),
+                )
+            })
+            .collect()
+    }
+
     pub fn max_row(&self) -> MultiBufferRow {
         MultiBufferRow(self.text_summary().lines.row)
     }
@@ -1,7 +1,12 @@
 pub mod git_traversal;
 
 use anyhow::Context as AnyhowContext;
-use futures::{FutureExt, StreamExt, channel::mpsc, future, stream::FuturesOrdered};
+use futures::{
+    FutureExt, StreamExt,
+    channel::{mpsc, oneshot},
+    future,
+    stream::FuturesOrdered,
+};
 use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
 };
@@ -11,7 +16,8 @@ use sum_tree::{Bias, Edit, SumTree};
 use collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use git::{
     repository::{
-        Branch, BranchesScanResult, CommitDetails, GitRepository, RepoPath, SystemGitRepository,
+        Branch, BranchesScanResult, CommitDetails, FileHistoryEntry, GitRepository, RepoPath,
+        SystemGitRepository,
     },
     status::{FileStatus, GitStatus, GitSummary},
 };
@@ -303,6 +309,73 @@ impl GitStore {
             .max_by_key(|(repo, _)| repo.read(cx).work_directory_abs_path.clone())
     }
 
+    /// The text of the file at `project_path` in the HEAD commit of its repository, or `None` if
+    /// HEAD doesn't have it.
+    pub fn load_committed_text(
+        &self,
+        project_path: &ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<String>>> {
+        let Some((repository, repo_path)) =
+            self.repository_and_path_for_project_path(project_path, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "{} is not in a Git repository",
+                project_path.path.as_unix_str()
+            )));
+        };
+
+        let committed_text = repository.update(cx, |repository, _| {
+            repository.load_committed_text(repo_path)
+        });
+        cx.background_spawn(async move { committed_text.await? })
+    }
+
+    /// The commits that changed the file at `project_path`, newest first, following it across
+    /// renames.
+    pub fn file_history(
+        &self,
+        project_path: &ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Vec<FileHistoryEntry>>> {
+        let Some((repository, repo_path)) =
+            self.repository_and_path_for_project_path(project_path, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "{} is not in a Git repository",
+                project_path.path.as_unix_str()
+            )));
+        };
+
+        let file_history =
+            repository.update(cx, |repository, _| repository.file_history(repo_path));
+        cx.background_spawn(async move { file_history.await? })
+    }
+
+    /// The text of the file at `project_path` as it was in `entry`'s commit, or `None` if the
+    /// commit doesn't have it.
+    pub fn load_text_at_commit(
+        &self,
+        project_path: &ProjectPath,
+        entry: &FileHistoryEntry,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<String>>> {
+        let Some((repository, _)) = self.repository_and_path_for_project_path(project_path, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "{} is not in a Git repository",
+                project_path.path.as_unix_str()
+            )));
+        };
+
+        let commit = entry.commit.sha.clone();
+        let repo_path = entry.path.clone();
+        let text = repository.update(cx, |repository, _| {
+            repository.load_text_at_commit(commit, repo_path)
+        });
+        cx.background_spawn(async move { text.await? })
+    }
+
     fn on_worktree_store_event(
         &mut self,
         worktree_store: &Entity<WorktreeStore>,
@@ -742,6 +815,127 @@ impl Repository {
         }
     }
 
+    fn load_committed_text(
+        &mut self,
+        repo_path: RepoPath,
+    ) -> oneshot::Receiver<anyhow::Result<Option<String>>> {
+        let (tx, rx) = oneshot::channel();
+        self.send_keyed_job(None, move |state, cx| {
+            cx.background_spawn(async move {
+                let RepositoryState { backend } = state;
+                let committed_text = backend.load_committed_text(repo_path).await;
+                if tx.send(committed_text).is_err() {
+                    log::debug!("Dropped the committed text of a file");
+                }
+            })
+        });
+        rx
+    }
+
+    fn file_history(
+        &mut self,
+        repo_path: RepoPath,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<FileHistoryEntry>>> {
+        let (tx, rx) = oneshot::channel();
+        self.send_keyed_job(None, move |state, cx| {
+            cx.background_spawn(async move {
+                let RepositoryState { backend } = state;
+                let file_history = backend.file_history(repo_path).await;
+                if tx.send(file_history).is_err() {
+                    log::debug!("Dropped the history of a file");
+                }
+            })
+        });
+        rx
+    }
+
+    fn load_text_at_commit(
+        &mut self,
+        commit: SharedString,
+        repo_path: RepoPath,
+    ) -> oneshot::Receiver<anyhow::Result<Option<String>>> {
+        let (tx, rx) = oneshot::channel();
+        self.send_keyed_job(None, move |state, cx| {
+            cx.background_spawn(async move {
+                let RepositoryState { backend } = state;
+                let text = backend.load_text_at_commit(commit, repo_path).await;
+                if tx.send(text).is_err() {
+                    log::debug!("Dropped the text of a file at a commit");
+                }
+            })
+        });
+        rx
+    }
+
+    pub fn stage_paths(
+        &mut self,
+        paths: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.send_job_and_scan(
+            move |backend| async move { backend.stage_paths(paths).await },
+            cx,
+        )
+    }
+
+    pub fn unstage_paths(
+        &mut self,
+        paths: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.send_job_and_scan(
+            move |backend| async move { backend.unstage_paths(paths).await },
+            cx,
+        )
+    }
+
+    pub fn commit(
+        &mut self,
+        message: SharedString,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.send_job_and_scan(
+            move |backend| async move { backend.commit(message).await },
+            cx,
+        )
+    }
+
+    pub fn checkout_branch(
+        &mut self,
+        branch: Branch,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.send_job_and_scan(
+            move |backend| async move { backend.checkout_branch(branch).await },
+            cx,
+        )
+    }
+
+    /// Runs a Git command that changes the repository, then scans the repository again so that
+    /// the snapshot shows its result.
+    fn send_job_and_scan<F, Fut>(
+        &mut self,
+        job: F,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>>
+    where
+        F: FnOnce(Arc<dyn GitRepository>) -> Fut + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.send_keyed_job(None, move |state, cx| {
+            let RepositoryState { backend } = state;
+            let job = job(backend);
+            cx.background_spawn(async move {
+                if tx.send(job.await).is_err() {
+                    log::debug!("Dropped the result of a Git command");
+                }
+            })
+        });
+        self.schedule_scan(cx);
+        cx.background_spawn(async move { rx.await? })
+    }
+
     fn paths_changed(&mut self, paths: Vec<RepoPath>, cx: &mut Context<Self>) {
         if !paths.is_empty() {
             self.paths_needing_status_update.push(paths);
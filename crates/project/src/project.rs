@@ -9,13 +9,18 @@ pub use git_store::{
 };
 pub use request_buffer::{RequestBuffer, RequestBufferEvent};
 pub use worktree::{
-    Entry, EntryKind, File, ProjectEntryId, REQUEST_FILE_VERSION, RequestFile, RequestFileBody,
-    RequestFileBodyType, RequestFileHeader, RequestFileHttp, RequestFileMeta, RequestFileParam,
+    AwsPayloadSigning, Entry, EntryKind, FOLDER_DEFAULTS_FILE_NAME, File, FolderDefaults,
+    OAuth2GrantType, ProjectEntryId, REQUEST_FILE_VERSION, RequestFile, RequestFileAuth,
+    RequestFileAwsSigV4, RequestFileBody, RequestFileBodyType, RequestFileExample,
+    RequestFileHeader, RequestFileHttp, RequestFileMeta, RequestFileOAuth2, RequestFileParam,
+    RequestFilePathVariable, RequestFileRetry, RequestFileSchema, RequestFileScripts,
     RequestFileState, Snapshot, UpdatedEntriesSet, UpdatedGitRepositoriesSet, UpdatedGitRepository,
-    Worktree, WorktreeId, request_method_short_name,
+    Worktree, WorktreeId, escape_query_name, escape_query_value, is_request_path, join_url_query,
+    parse_query, path_variable_name, path_variable_names, request_method_short_name,
+    resolve_query_text, split_url_query, substitute_path_variables, url_path,
 };
 
-use anyhow::anyhow;
+use anyhow::{Context as _, anyhow};
 use futures::{FutureExt, StreamExt};
 #[cfg(any(test, feature = "test"))]
 use gpui::TestAppContext;
@@ -28,7 +33,7 @@ use std::{
 
 use collections::HashMap;
 use fs::{Fs, MTime};
-use git::status::FileStatus;
+use git::{repository::FileHistoryEntry, status::FileStatus};
 use language::{AvailableLanguage, Buffer, BufferEvent, Language, LanguageRegistry, PLAIN_TEXT};
 use path::{PathStyle, RelPath};
 use util::ResultExt;
@@ -150,12 +155,7 @@ impl ProjectItem for RequestBuffer {
         path: &ProjectPath,
         cx: &mut App,
     ) -> Option<Task<anyhow::Result<Entity<Self>>>> {
-        let is_request = path
-            .path
-            .extension()
-            .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
-
-        if !is_request {
+        if !worktree::is_request_path(path.path.as_std_path()) {
             return None;
         }
 
@@ -228,6 +228,12 @@ pub struct Project {
     languages: Arc<LanguageRegistry>,
     active_entry: Option<ProjectEntryId>,
     metadata_by_entry_id: HashMap<ProjectEntryId, EntryMetadataState>,
+    /// The orders loaded by [`Self::load_folder_orders`], by folder, empty for folders without
+    /// one. A folder's order is dropped when its [`FOLDER_DEFAULTS_FILE_NAME`] file changes.
+    folder_orders: HashMap<Arc<RelPath>, Vec<String>>,
+    /// Bumped whenever cached folder orders are dropped, so that orders loaded before then
+    /// aren't cached.
+    folder_orders_version: usize,
     _maintain_buffer_languages: Task<()>,
 }
 
@@ -269,6 +275,8 @@ impl Project {
             languages,
             active_entry: None,
             metadata_by_entry_id: HashMap::default(),
+            folder_orders: HashMap::default(),
+            folder_orders_version: 0,
             _maintain_buffer_languages: maintain_buffer_languages,
         }
     }
@@ -322,12 +330,22 @@ impl Project {
     fn on_worktree_store_event(&mut self, event: &WorktreeStoreEvent, cx: &mut Context<Self>) {
         match event {
             WorktreeStoreEvent::WorktreeAdded(worktree) => {
+                self.clear_folder_orders();
                 cx.emit(ProjectEvent::WorktreeAdded(worktree.read(cx).id()));
             }
             WorktreeStoreEvent::WorktreeRemoved(worktree_id) => {
+                self.clear_folder_orders();
                 cx.emit(ProjectEvent::WorktreeRemoved(*worktree_id));
             }
             WorktreeStoreEvent::WorktreeUpdatedEntries(worktree_id, changes) => {
+                for (path, _, _) in changes.iter() {
+                    if path.file_name() == Some(FOLDER_DEFAULTS_FILE_NAME)
+                        && let Some(folder) = path.parent()
+                    {
+                        self.folder_orders.remove(folder);
+                        self.folder_orders_version += 1;
+                    }
+                }
                 cx.emit(ProjectEvent::WorktreeUpdatedEntries(
                     *worktree_id,
                     changes.clone(),
@@ -341,6 +359,11 @@ impl Project {
         }
     }
 
+    fn clear_folder_orders(&mut self) {
+        self.folder_orders.clear();
+        self.folder_orders_version += 1;
+    }
+
     fn on_buffer_store_event(&mut self, event: &BufferStoreEvent, cx: &mut Context<Self>) {
         match event {
             BufferStoreEvent::BufferAdded(buffer) => {
@@ -712,6 +735,237 @@ impl Project {
         );
     }
 
+    /// Reads and parses every request file of the root worktree from disk, skipping the ones that
+    /// can't be read or parsed.
+    pub fn load_request_files(&self, cx: &App) -> Task<Vec<(ProjectPath, RequestFile)>> {
+        let Some(worktree) = self.root_worktree(cx) else {
+            return Task::ready(Vec::new());
+        };
+        let worktree = worktree.read(cx);
+        let worktree_id = worktree.id();
+        let fs = worktree.fs().clone();
+        let paths = worktree
+            .files(0)
+            .filter(|entry| entry.is_request)
+            .map(|entry| (entry.path.clone(), worktree.absolutize(&entry.path)))
+            .collect::<Vec<_>>();
+
+        cx.background_spawn(async move {
+            let mut request_files = Vec::new();
+            for (path, abs_path) in paths {
+                let Some(text) = fs.load(&abs_path).await.log_err() else {
+                    continue;
+                };
+                if let RequestFileState::Parsed(request_file) = worktree::parse_request_file(&text)
+                {
+                    request_files.push((ProjectPath { worktree_id, path }, request_file));
+                }
+            }
+            request_files
+        })
+    }
+
+    /// Loads the request file at `project_path` from disk.
+    pub fn load_request_file(
+        &self,
+        project_path: &ProjectPath,
+        cx: &App,
+    ) -> Task<anyhow::Result<RequestFile>> {
+        let Some(worktree) = self.worktree_for_id(project_path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!(
+                "no worktree for {}",
+                project_path.path.as_unix_str()
+            )));
+        };
+        let worktree = worktree.read(cx);
+        let fs = worktree.fs().clone();
+        let abs_path = worktree.absolutize(&project_path.path);
+
+        cx.background_spawn(async move {
+            let text = fs.load(&abs_path).await?;
+            parse_valid_request_file(&text)
+                .with_context(|| format!("invalid request file {}", abs_path.display()))
+        })
+    }
+
+    /// Loads the request file at `project_path` as it is in the HEAD commit of its repository, or
+    /// `None` if HEAD doesn't have it.
+    pub fn load_committed_request_file(
+        &mut self,
+        project_path: &ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<RequestFile>>> {
+        let committed_text = self.git_store.update(cx, |git_store, cx| {
+            git_store.load_committed_text(project_path, cx)
+        });
+
+        cx.background_spawn(async move {
+            let Some(text) = committed_text.await? else {
+                return Ok(None);
+            };
+            parse_valid_request_file(&text)
+                .context("invalid committed request file")
+                .map(Some)
+        })
+    }
+
+    /// The commits that changed the request file at `project_path`, newest first.
+    pub fn request_file_history(
+        &mut self,
+        project_path: &ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Vec<FileHistoryEntry>>> {
+        self.git_store
+            .update(cx, |git_store, cx| git_store.file_history(project_path, cx))
+    }
+
+    /// Loads the request file at `project_path` as it was in the commit of `entry`, or `None` if
+    /// the commit doesn't have it.
+    pub fn load_request_file_at_commit(
+        &mut self,
+        project_path: &ProjectPath,
+        entry: &FileHistoryEntry,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<RequestFile>>> {
+        let text = self.git_store.update(cx, |git_store, cx| {
+            git_store.load_text_at_commit(project_path, entry, cx)
+        });
+
+        cx.background_spawn(async move {
+            let Some(text) = text.await? else {
+                return Ok(None);
+            };
+            parse_valid_request_file(&text)
+                .context("invalid request file in the commit")
+                .map(Some)
+        })
+    }
+
+    /// The defaults of the folders the request at `project_path` is in, from the outermost folder
+    /// in.
+    pub fn load_folder_defaults(
+        &self,
+        project_path: &ProjectPath,
+        cx: &App,
+    ) -> Task<anyhow::Result<Vec<FolderDefaults>>> {
+        let Some(worktree) = self.worktree_for_id(project_path.worktree_id, cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let worktree = worktree.read(cx);
+        let fs = worktree.fs().clone();
+        let Ok(file_name) = RelPath::unix(FOLDER_DEFAULTS_FILE_NAME) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let mut paths = project_path
+            .path
+            .parent()
+            .into_iter()
+            .flat_map(RelPath::ancestors)
+            .map(|folder| folder.join(file_name))
+            .filter(|path| {
+                worktree
+                    .entry_for_path(path)
+                    .is_some_and(|entry| entry.kind.is_file())
+            })
+            .map(|path| worktree.absolutize(&path))
+            .collect::<Vec<_>>();
+        paths.reverse();
+
+        cx.background_spawn(async move {
+            let mut folder_defaults = Vec::new();
+            for abs_path in paths {
+                let contents = fs.load(&abs_path).await?;
+                folder_defaults.push(
+                    worktree::parse_folder_defaults(&contents).with_context(|| {
+                        format!("invalid folder defaults {}", abs_path.display())
+                    })?,
+                );
+            }
+            Ok(folder_defaults)
+        })
+    }
+
+    /// The orders the [`FOLDER_DEFAULTS_FILE_NAME`] files of `folders` in the root worktree set
+    /// for the entries in them, by folder. Folders without an order are left out. The orders are
+    /// cached until the files change.
+    pub fn load_folder_orders(
+        &mut self,
+        folders: Vec<Arc<RelPath>>,
+        cx: &mut Context<Self>,
+    ) -> Task<HashMap<Arc<RelPath>, Vec<String>>> {
+        let Some(worktree) = self.root_worktree(cx) else {
+            return Task::ready(HashMap::default());
+        };
+        let worktree = worktree.read(cx);
+        let fs = worktree.fs().clone();
+        let Ok(file_name) = RelPath::unix(FOLDER_DEFAULTS_FILE_NAME) else {
+            return Task::ready(HashMap::default());
+        };
+        let mut folder_orders = HashMap::default();
+        let mut paths = Vec::new();
+        for folder in folders {
+            if let Some(order) = self.folder_orders.get(&folder) {
+                if !order.is_empty() {
+                    folder_orders.insert(folder, order.clone());
+                }
+                continue;
+            }
+            let path = folder.join(file_name);
+            if worktree
+                .entry_for_path(&path)
+                .is_some_and(|entry| entry.kind.is_file())
+            {
+                paths.push((folder, worktree.absolutize(&path)));
+            }
+        }
+        if paths.is_empty() {
+            return Task::ready(folder_orders);
+        }
+
+        let version = self.folder_orders_version;
+        let load_task = cx.background_spawn(async move {
+            let mut loaded = Vec::new();
+            for (folder, abs_path) in paths {
+                let folder_defaults = fs
+                    .load(&abs_path)
+                    .await
+                    .and_then(|contents| worktree::parse_folder_defaults(&contents))
+                    .with_context(|| format!("invalid folder defaults {}", abs_path.display()));
+                if let Some(folder_defaults) = folder_defaults.log_err() {
+                    loaded.push((folder, folder_defaults.order));
+                }
+            }
+            loaded
+        });
+        cx.spawn(async move |this, cx| {
+            let loaded = load_task.await;
+            this.update(cx, |this, _| {
+                if this.folder_orders_version == version {
+                    this.folder_orders.extend(loaded.iter().cloned());
+                }
+            })
+            .log_err();
+            folder_orders.extend(loaded.into_iter().filter(|(_, order)| !order.is_empty()));
+            folder_orders
+        })
+    }
+
+    /// Writes `order` as the order of the entries of `folder` to its
+    /// [`FOLDER_DEFAULTS_FILE_NAME`] file.
+    pub fn write_folder_order(
+        &self,
+        folder: &ProjectPath,
+        order: Vec<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let Some(worktree) = self.worktree_for_id(folder.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("no such worktree")));
+        };
+        worktree.update(cx, |worktree, cx| {
+            worktree.write_folder_order(&folder.path, order, cx)
+        })
+    }
+
     pub fn path_for_entry(&self, entry_id: ProjectEntryId, cx: &App) -> Option<ProjectPath> {
         let worktree = self.worktree_for_entry(entry_id, cx)?;
         let worktree = worktree.read(cx);
@@ -766,6 +1020,29 @@ impl Project {
         })
     }
 
+    /// Writes `request_file` to a new request file at `project_path`.
+    pub fn create_request_file(
+        &mut self,
+        project_path: impl Into<ProjectPath>,
+        request_file: &RequestFile,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Entry>> {
+        let project_path = project_path.into();
+        let Some(worktree) = self.worktree_for_id(project_path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!(format!(
+                "No worktree for path {project_path:?}"
+            ))));
+        };
+        let contents = match worktree::serialize_request_file(request_file) {
+            Ok(contents) => contents,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        worktree.update(cx, |worktree, cx| {
+            worktree.create_entry(project_path.path, false, Some(contents.into_bytes()), cx)
+        })
+    }
+
     #[inline]
     pub fn copy_entry(
         &mut self,
@@ -778,15 +1055,43 @@ impl Project {
         })
     }
 
-    #[inline]
+    /// Renames or moves the entry to `new_path`, renaming it in the order of its folder, or
+    /// removing it from that order when it's moved to another folder.
     pub fn rename_entry(
         &mut self,
         entry_id: ProjectEntryId,
         new_path: ProjectPath,
         cx: &mut Context<Self>,
     ) -> Task<anyhow::Result<Entry>> {
-        self.worktree_store.update(cx, |worktree_store, cx| {
-            worktree_store.rename_entry(entry_id, new_path, cx)
+        let old_path = self.path_for_entry(entry_id, cx);
+        let rename_task = self.worktree_store.update(cx, |worktree_store, cx| {
+            worktree_store.rename_entry(entry_id, new_path.clone(), cx)
+        });
+        cx.spawn(async move |project, cx| {
+            let entry = rename_task.await?;
+            let Some(old_path) = old_path else {
+                return Ok(entry);
+            };
+            let (Some(folder), Some(old_name)) =
+                (old_path.path.parent(), old_path.path.file_name())
+            else {
+                return Ok(entry);
+            };
+            let new_name = new_path
+                .path
+                .file_name()
+                .filter(|_| new_path.path.parent() == Some(folder))
+                .map(ToString::to_string);
+            let order_task = project.update(cx, |project, cx| {
+                let worktree = project.worktree_for_id(old_path.worktree_id, cx)?;
+                Some(worktree.update(cx, |worktree, cx| {
+                    worktree.rename_in_folder_order(folder, old_name.to_string(), new_name, cx)
+                }))
+            })?;
+            if let Some(order_task) = order_task {
+                order_task.await.log_err();
+            }
+            Ok(entry)
         })
     }
 
@@ -913,6 +1218,37 @@ impl Project {
         self.request_buffer_store
             .update(cx, |store, cx| store.reload_request_buffer(buffer, cx))
     }
+
+    /// The paths of the open request buffers with unsaved changes.
+    pub fn dirty_request_paths(&self, cx: &App) -> Vec<ProjectPath> {
+        self.request_buffer_store.read(cx).dirty_request_paths(cx)
+    }
+
+    /// Reloads every open request buffer from disk, dropping unsaved changes.
+    pub fn reload_request_buffers(&self, cx: &mut Context<Self>) -> Task<()> {
+        self.request_buffer_store
+            .update(cx, |store, cx| store.reload_request_buffers(cx))
+    }
+
+    /// Writes `request_file` to the request file at `project_path`, failing if it is open with
+    /// unsaved changes.
+    pub fn write_request_file(
+        &self,
+        project_path: ProjectPath,
+        request_file: RequestFile,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.request_buffer_store.update(cx, |store, cx| {
+            store.write_request_file(project_path, request_file, cx)
+        })
+    }
 }
 
 impl EventEmitter<ProjectEvent> for Project {}
+
+fn parse_valid_request_file(text: &str) -> anyhow::Result<RequestFile> {
+    match worktree::parse_request_file(text) {
+        RequestFileState::Parsed(request_file) => Ok(request_file),
+        RequestFileState::Invalid(error) => Err(anyhow!(error)),
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::anyhow;
-use futures::{FutureExt, future::Shared};
-use gpui::{AppContext, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
+use futures::{
+    FutureExt,
+    future::{self, Shared},
+};
+use gpui::{App, AppContext, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
 use std::{
     collections::{HashMap, hash_map},
     sync::Arc,
@@ -11,7 +14,7 @@ use path::RelPath;
 use request_buffer::RequestBuffer;
 use util::debug_panic;
 use worktree::{
-    DiskState, File, PathChange, ProjectEntryId, RequestFileState, Snapshot, Worktree,
+    DiskState, File, PathChange, ProjectEntryId, RequestFile, RequestFileState, Snapshot, Worktree,
     WorktreeEvent,
 };
 
@@ -231,6 +234,83 @@ impl RequestBufferStore {
         buffer.update(cx, |buffer, cx| buffer.reload(cx))
     }
 
+    /// The paths of the open request buffers with unsaved changes.
+    pub fn dirty_request_paths(&self, cx: &App) -> Vec<ProjectPath> {
+        self.opened_buffers
+            .values()
+            .filter_map(WeakEntity::upgrade)
+            .filter(|buffer| buffer.read(cx).is_dirty())
+            .map(|buffer| {
+                let file = buffer.read(cx).file();
+                ProjectPath {
+                    worktree_id: file.worktree_id(cx),
+                    path: file.path.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Reloads every open request buffer from disk, dropping unsaved changes. Buffers whose
+    /// files are gone are left as they are.
+    pub fn reload_request_buffers(&self, cx: &mut Context<Self>) -> Task<()> {
+        let reloads = self
+            .opened_buffers
+            .values()
+            .filter_map(WeakEntity::upgrade)
+            .filter(|buffer| !buffer.read(cx).file().disk_state.is_deleted())
+            .map(|buffer| buffer.update(cx, |buffer, cx| buffer.reload(cx)))
+            .collect::<Vec<_>>();
+
+        cx.background_spawn(async move {
+            for result in future::join_all(reloads).await {
+                if let Err(error) = result {
+                    log::warn!("Failed to reload request buffer: {error:#}");
+                }
+            }
+        })
+    }
+
+    /// Writes `request_file` to the request file at `project_path`. An open buffer for the file
+    /// shows what was written, unless it has unsaved changes, in which case nothing is written.
+    pub fn write_request_file(
+        &self,
+        project_path: ProjectPath,
+        request_file: RequestFile,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let buffer = self.get_by_path(&project_path);
+        if buffer
+            .as_ref()
+            .is_some_and(|buffer| buffer.read(cx).is_dirty())
+        {
+            return Task::ready(Err(anyhow!(
+                "{} has unsaved changes",
+                project_path.path.as_unix_str()
+            )));
+        }
+        let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow!("no such worktree")));
+        };
+        let write_task = worktree.update(cx, |worktree, cx| {
+            worktree.write_request_file(project_path.path, request_file.clone(), cx)
+        });
+
+        cx.spawn(async move |_, cx| {
+            let new_file = write_task.await?;
+            if let Some(buffer) = buffer {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.file_updated(new_file, cx);
+                    buffer.did_reload(RequestFileState::Parsed(request_file), cx);
+                });
+            }
+            anyhow::Ok(())
+        })
+    }
+
     pub fn get_by_path(&self, path: &ProjectPath) -> Option<Entity<RequestBuffer>> {
         self.path_to_buffer_id
             .get(path)
@@ -9,7 +9,10 @@ use fs::Fs;
 
 use fs::TempFs;
 use path::{RelPath, rel_path};
-use project::{Project, ProjectItem, RequestBuffer, RequestBufferEvent};
+use project::{
+    Project, ProjectItem, ProjectPath, RequestBuffer, RequestBufferEvent, RequestFile,
+    RequestFileState,
+};
 use util_macros::path;
 use worktree::WorktreeModelHandle;
 
@@ -473,3 +476,67 @@ async fn test_buffer_identity_across_renames(cx: &mut TestAppContext) {
         );
     });
 }
+
+#[gpui::test]
+async fn test_write_request_file(cx: &mut TestAppContext) {
+    cx.executor().allow_parking();
+
+    let temp_fs = TempFs::new(cx.executor());
+    temp_fs.insert_tree(
+        path!("project"),
+        json!({
+            "request.toml": indoc! {r#"
+                [meta]
+                version = 1
+
+                [http]
+                method = "GET"
+                url = "https://api.zaku.dev/me"
+            "#}
+        }),
+    );
+
+    let project_path = temp_fs.path().join(path!("project"));
+    let project = Project::test_new(temp_fs, &project_path, cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.root_worktree(cx).unwrap().read(cx).id()
+    });
+    let request_path = ProjectPath::from((worktree_id, rel_path("request.toml")));
+    let buffer = cx
+        .update(|cx| <RequestBuffer as ProjectItem>::try_open(&project, &request_path, cx).unwrap())
+        .await
+        .unwrap();
+
+    let mut request_file = RequestFile::default();
+    request_file.http.url = "https://staging.zaku.dev/me".to_string();
+    project
+        .update(cx, |project, cx| {
+            project.write_request_file(request_path.clone(), request_file.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_dirty());
+        assert_eq!(
+            buffer.request_file(),
+            &RequestFileState::Parsed(request_file.clone())
+        );
+    });
+    let request_files = project
+        .read_with(cx, |project, cx| project.load_request_files(cx))
+        .await;
+    assert_eq!(request_files, vec![(request_path.clone(), request_file)]);
+
+    buffer.update(cx, |buffer, cx| buffer.set_dirty(true, cx));
+    let write_result = project
+        .update(cx, |project, cx| {
+            project.write_request_file(request_path, RequestFile::default(), cx)
+        })
+        .await;
+    assert!(
+        write_result.is_err(),
+        "a request with unsaved changes must not be overwritten"
+    );
+}
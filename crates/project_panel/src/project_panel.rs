@@ -1,10 +1,10 @@
 use anyhow::Context as AnyhowContext;
 use gpui::{
-    Action, Anchor, AnyElement, App, Bounds, ClickEvent, ClipboardItem, Context, DismissEvent, Div,
-    Entity, EventEmitter, FocusHandle, Focusable, FontWeight, KeyContext,
-    ListHorizontalSizingBehavior, ListSizingBehavior, MouseButton, MouseDownEvent, Pixels, Point,
-    PromptLevel, Render, ScrollStrategy, Stateful, Subscription, Task, UniformListScrollHandle,
-    WeakEntity, Window, prelude::*,
+    Action, Anchor, AnyElement, App, BackgroundExecutor, Bounds, ClickEvent, ClipboardItem,
+    Context, DismissEvent, Div, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior, MouseButton, MouseDownEvent,
+    Pixels, Point, PromptLevel, Render, ScrollStrategy, Stateful, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, prelude::*,
 };
 use smallvec::SmallVec;
 use std::{
@@ -12,27 +12,33 @@ use std::{
     collections::BTreeSet,
     ops::Range,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, atomic::AtomicBool},
 };
 
+use collections::HashMap;
 use editor::{
     Editor, EditorEvent, MultiBufferOffset, SelectionEffects,
     items::{entry_git_aware_text_color, entry_text_color},
 };
+use fuzzy_nucleo::StringMatchCandidate;
 use git::status::GitSummary;
+use input::{ErasedEditorEvent, InputField};
 use path::{PathStyle, RelPath, SortMode, SortOrder};
 use project::{
     Entry, EntryKind, GitEntry, GitTraversal, Project, ProjectEntryId, ProjectEvent, ProjectPath,
-    Snapshot, Worktree, WorktreeId,
+    RequestFile, Snapshot, Worktree, WorktreeId,
     git_store::{GitStoreEvent, RepositoryEvent},
 };
-use settings::{GitSettings, Settings, SettingsStore};
+use settings::{
+    GitSettings, ProjectPanelSort, RegisterSetting, Settings, SettingsContent, SettingsStore,
+};
 use theme::ActiveTheme;
 use ui::{
-    ButtonCommon, ButtonLike, ButtonSize, Clickable, Color, ContextMenu, DynamicSpacing,
-    FixedWidth, Icon, IconAsset, IconButton, IconButtonShape, IconSize, IndentGuideColors,
-    IndentGuideLayout, Indicator, KeyBinding, ListItem, ListItemSpacing, RenderedIndentGuide,
-    ScrollAxes, Scrollbars, Text, TextCommon, TextSize, Tooltip, TrackLayout, WithScrollbar,
+    Button, ButtonCommon, ButtonLike, ButtonSize, ButtonVariant, Clickable, Color, ContextMenu,
+    DynamicSpacing, FixedWidth, HighlightedText, Icon, IconAsset, IconButton, IconButtonShape,
+    IconSize, IndentGuideColors, IndentGuideLayout, Indicator, KeyBinding, ListItem,
+    ListItemSpacing, RenderedIndentGuide, ScrollAxes, Scrollbars, Text, TextCommon, TextSize,
+    Toggleable, Tooltip, TrackLayout, WithScrollbar,
 };
 use util::ResultExt;
 use workspace::{Panel, Workspace, WorkspaceEvent};
@@ -54,6 +60,8 @@ struct UpdateVisibleEntriesTask {
     _visible_entries_task: Task<()>,
     focus_file_name_editor: bool,
     autoscroll: bool,
+    /// Whether to expand the folders the filter's matches are in.
+    expand_filter_matches: bool,
 }
 
 impl Default for UpdateVisibleEntriesTask {
@@ -62,20 +70,71 @@ impl Default for UpdateVisibleEntriesTask {
             _visible_entries_task: Task::ready(()),
             focus_file_name_editor: false,
             autoscroll: false,
+            expand_filter_matches: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RegisterSetting)]
+struct ProjectPanelSettings {
+    sort: ProjectPanelSort,
+}
+
+impl Settings for ProjectPanelSettings {
+    fn from_settings(content: &SettingsContent) -> Self {
+        Self {
+            sort: content
+                .project_panel
+                .as_ref()
+                .and_then(|project_panel| project_panel.sort)
+                .expect("project panel sort should be defaulted"),
+        }
+    }
+}
+
+/// The orders the folders' `_folder.toml` files set for their entries, by folder.
+type FolderOrders = HashMap<Arc<RelPath>, Vec<String>>;
+
+/// The entries the filter matches, with the positions of the match in their names. Requests
+/// matched by their method or URL have no positions.
+type FilterMatches = HashMap<ProjectEntryId, Vec<usize>>;
+
+/// The method and URL of a request, lowercased, which the filter matches against.
+#[derive(Clone)]
+struct RequestFields {
+    method: String,
+    url: String,
+}
+
+impl RequestFields {
+    fn new(request_file: &RequestFile) -> Self {
+        Self {
+            method: request_file.http.method.to_lowercase(),
+            url: request_file.http.url.to_lowercase(),
         }
     }
 }
 
+/// The fields of the requests of the root worktree, by entry, `None` for requests that couldn't
+/// be read.
+type RequestFieldsByEntry = HashMap<ProjectEntryId, Option<RequestFields>>;
+
 #[derive(Default)]
 struct TreeState {
     visible_entries: Vec<GitEntry>,
     expanded_dir_ids: Option<Vec<ProjectEntryId>>,
     max_width_item_index: Option<usize>,
     edit_state: Option<EditState>,
+    /// The orders of the expanded folders, empty when sorting alphabetically.
+    folder_orders: Arc<FolderOrders>,
+    /// The entries the filter matches, empty when there is no filter.
+    filter_matches: Arc<FilterMatches>,
 }
 
 struct EntryDetails {
     file_name: String,
+    /// The positions in `file_name` that match the filter.
+    highlight_positions: Vec<usize>,
     prefix: Option<String>,
     depth: u16,
     kind: EntryKind,
@@ -89,6 +148,31 @@ struct EntryDetails {
     git_status: GitSummary,
 }
 
+/// An entry being dragged, along with the other marked entries if it is marked.
+#[derive(Clone)]
+struct DraggedProjectEntry {
+    entry_id: ProjectEntryId,
+    file_name: String,
+}
+
+impl Render for DraggedProjectEntry {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        gpui::div()
+            .px_2()
+            .py_0p5()
+            .rounded_sm()
+            .border_1()
+            .border_color(colors.border)
+            .bg(colors.elevated_surface_background)
+            .child(
+                Text::new(self.file_name.clone())
+                    .size(TextSize::Small)
+                    .single_line(),
+            )
+    }
+}
+
 #[derive(Debug)]
 pub enum ProjectPanelEvent {
     OpenedEntry {
@@ -170,9 +254,23 @@ pub struct ProjectPanel {
     selection: Option<SelectedEntry>,
     context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     file_name_editor: Entity<Editor>,
+    filter_field: Entity<InputField>,
+    /// The filter's query, empty when every entry is shown.
+    filter_query: String,
+    /// Whether the filter also matches requests by their method and URL.
+    filter_request_fields: bool,
+    /// The fields of the requests read for the filter. A request's are read again once its file
+    /// changes.
+    request_fields: Arc<RequestFieldsByEntry>,
+    /// Bumped whenever cached request fields are dropped, so that fields read before then aren't
+    /// cached.
+    request_fields_version: usize,
+    /// The folders that were expanded before filtering, expanded again once the filter is cleared.
+    unfiltered_expanded_dir_ids: Option<Vec<ProjectEntryId>>,
     clipboard: Option<ClipboardEntry>,
     mouse_down: bool,
     _project_subscription: Subscription,
+    _filter_subscription: Subscription,
 }
 
 impl ProjectPanel {
@@ -191,6 +289,23 @@ impl ProjectPanel {
         let workspace_handle = workspace.weak_handle();
         let project_panel = cx.new(|cx| {
             let file_name_editor = cx.new(|cx| Editor::single_line(window, cx));
+            let filter_field = cx.new(|cx| {
+                InputField::new(window, cx, "Filter requests").start_icon(IconAsset::ListSearch)
+            });
+            let project_panel = cx.weak_entity();
+            let filter_subscription = filter_field.read(cx).editor().clone().subscribe(
+                Box::new(move |event, window, cx| {
+                    if event == ErasedEditorEvent::BufferEdited
+                        && let Err(error) = project_panel.update(cx, |project_panel, cx| {
+                            project_panel.update_filter(window, cx);
+                        })
+                    {
+                        log::debug!("Failed to filter the project panel: {error:?}");
+                    }
+                }),
+                window,
+                cx,
+            );
             cx.subscribe_in(
                 &workspace_entity,
                 window,
@@ -240,12 +355,18 @@ impl ProjectPanel {
             .detach();
 
             let mut git_settings = *GitSettings::get_global(cx);
-            cx.observe_global_in::<SettingsStore>(window, move |_, _, cx| {
+            let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
+            cx.observe_global_in::<SettingsStore>(window, move |this, window, cx| {
                 let new_git_settings = *GitSettings::get_global(cx);
                 if git_settings != new_git_settings {
                     git_settings = new_git_settings;
                     cx.notify();
                 }
+                let new_project_panel_settings = *ProjectPanelSettings::get_global(cx);
+                if project_panel_settings != new_project_panel_settings {
+                    project_panel_settings = new_project_panel_settings;
+                    this.update_visible_entries(None, false, false, window, cx);
+                }
             })
             .detach();
 
@@ -299,8 +420,21 @@ impl ProjectPanel {
                     ProjectEvent::ActiveEntryChanged(None) => {
                         this.marked_entries.clear();
                     }
-                    ProjectEvent::WorktreeAdded(worktree_id)
-                    | ProjectEvent::WorktreeUpdatedEntries(worktree_id, _) => {
+                    ProjectEvent::WorktreeAdded(worktree_id) => {
+                        if project
+                            .read(cx)
+                            .worktree_for_id(*worktree_id, cx)
+                            .is_some_and(|worktree| worktree.read(cx).is_visible())
+                        {
+                            this.update_visible_entries(None, false, false, window, cx);
+                        }
+                    }
+                    ProjectEvent::WorktreeUpdatedEntries(worktree_id, changes) => {
+                        let request_fields = Arc::make_mut(&mut this.request_fields);
+                        for (_, entry_id, _) in changes.iter() {
+                            request_fields.remove(entry_id);
+                        }
+                        this.request_fields_version += 1;
                         if project
                             .read(cx)
                             .worktree_for_id(*worktree_id, cx)
@@ -310,6 +444,8 @@ impl ProjectPanel {
                         }
                     }
                     ProjectEvent::WorktreeRemoved(_) => {
+                        this.request_fields = Arc::default();
+                        this.request_fields_version += 1;
                         this.update_visible_entries(None, false, false, window, cx);
                     }
                     ProjectEvent::DeletedEntry(_, _) => {}
@@ -330,9 +466,16 @@ impl ProjectPanel {
                 selection: None,
                 context_menu: None,
                 file_name_editor,
+                filter_field,
+                filter_query: String::new(),
+                filter_request_fields: false,
+                request_fields: Arc::default(),
+                request_fields_version: 0,
+                unfiltered_expanded_dir_ids: None,
                 clipboard: None,
                 mouse_down: false,
                 _project_subscription: project_subscription,
+                _filter_subscription: filter_subscription,
             };
             this.update_visible_entries(None, false, false, window, cx);
             this
@@ -402,7 +545,12 @@ impl ProjectPanel {
         dispatch_context.add(Self::PANEL_KEY);
         dispatch_context.add("menu");
 
-        let identifier = if self.file_name_editor.focus_handle(cx).is_focused(window) {
+        let identifier = if self.file_name_editor.focus_handle(cx).is_focused(window)
+            || self
+                .filter_field
+                .focus_handle(cx)
+                .contains_focused(window, cx)
+        {
             "editing"
         } else {
             "not_editing"
@@ -448,6 +596,12 @@ impl ProjectPanel {
 
         EntryDetails {
             file_name,
+            highlight_positions: self
+                .tree_state
+                .filter_matches
+                .get(&entry.id)
+                .cloned()
+                .unwrap_or_default(),
             prefix,
             depth,
             kind: entry.kind,
@@ -504,13 +658,64 @@ impl ProjectPanel {
             let project = self.project.read(cx);
             project.git_store().read(cx).repo_snapshots(cx)
         };
+        let folder_orders = match ProjectPanelSettings::get_global(cx).sort {
+            ProjectPanelSort::Manual => {
+                let folders = snapshot
+                    .iter()
+                    .flat_map(|snapshot| {
+                        expanded_dir_ids
+                            .iter()
+                            .filter_map(|entry_id| snapshot.entry_for_id(*entry_id))
+                            .map(|entry| entry.path.clone())
+                    })
+                    .collect();
+                self.project
+                    .update(cx, |project, cx| project.load_folder_orders(folders, cx))
+            }
+            ProjectPanelSort::Alphabetical => Task::ready(FolderOrders::default()),
+        };
+        let filter_query = (!self.filter_query.is_empty()).then(|| self.filter_query.clone());
+        let request_fields = match &snapshot {
+            Some(snapshot) if filter_query.is_some() && self.filter_request_fields => {
+                self.load_request_fields(snapshot, cx)
+            }
+            _ => Task::ready(Arc::default()),
+        };
+        let expand_filter_matches = self.update_visible_entries_task.expand_filter_matches;
 
         let visible_entries_task = cx.spawn_in(window, async move |this, cx| {
-            let visible_entries = cx
+            let folder_orders = Arc::new(folder_orders.await);
+            let folder_orders_for_sort = folder_orders.clone();
+            let filter_matches = match (&filter_query, &snapshot) {
+                (Some(query), Some(snapshot)) => {
+                    let request_fields = request_fields.await;
+                    let executor = cx.background_executor().clone();
+                    Some(Arc::new(
+                        match_entries(snapshot, query, &request_fields, executor).await,
+                    ))
+                }
+                _ => None,
+            };
+            let filter_matches_for_traversal = filter_matches.clone();
+            let (visible_entries, max_width_item_index, filter_dir_ids) = cx
                 .background_spawn(async move {
+                    let folder_orders = folder_orders_for_sort;
+                    let filter_matches = filter_matches_for_traversal;
                     let Some(snapshot) = snapshot else {
-                        return (Vec::new(), None);
+                        return (Vec::new(), None, BTreeSet::new());
                     };
+                    // The folders the matches are in, which are shown and, when the query
+                    // changes, expanded along with them.
+                    let filter_dir_ids = filter_matches
+                        .as_ref()
+                        .map(|filter_matches| filter_match_dir_ids(&snapshot, filter_matches))
+                        .unwrap_or_default();
+                    let mut expanded_dir_ids = expanded_dir_ids;
+                    if expand_filter_matches {
+                        expanded_dir_ids.extend(filter_dir_ids.iter().copied());
+                        expanded_dir_ids.sort_unstable();
+                        expanded_dir_ids.dedup();
+                    }
                     let mut entries = Vec::new();
                     let mut traversal = GitTraversal::new(&repo_snapshots, snapshot.entries(0));
                     let mut new_entry_parent_id = None;
@@ -528,6 +733,20 @@ impl ProjectPanel {
 
                     let root_entry_id = snapshot.root_entry().map(|entry| entry.id);
                     while let Some(entry) = traversal.entry() {
+                        let is_filtered_out =
+                            filter_matches.as_ref().is_some_and(|filter_matches| {
+                                !filter_matches.contains_key(&entry.id)
+                                    && !filter_dir_ids.contains(&entry.id)
+                            });
+                        if is_filtered_out {
+                            if entry.kind.is_dir() {
+                                traversal.advance_to_sibling();
+                            } else {
+                                traversal.advance();
+                            }
+                            continue;
+                        }
+
                         if root_entry_id != Some(entry.id)
                             && (entry.kind.is_dir() || entry.is_request)
                         {
@@ -549,14 +768,7 @@ impl ProjectPanel {
                         }
                     }
 
-                    entries.sort_by(|lhs, rhs| {
-                        cmp_worktree_entries(
-                            lhs,
-                            rhs,
-                            SortMode::DirectoriesFirst,
-                            SortOrder::Default,
-                        )
-                    });
+                    entries.sort_by(|lhs, rhs| cmp_entries(lhs, rhs, &folder_orders));
 
                     let mut max_width_item = None;
                     for (index, entry) in entries.iter().enumerate() {
@@ -577,14 +789,29 @@ impl ProjectPanel {
                         }
                     }
 
-                    (entries, max_width_item.map(|(index, _)| index))
+                    (
+                        entries,
+                        max_width_item.map(|(index, _)| index),
+                        filter_dir_ids,
+                    )
                 })
                 .await;
 
             if let Err(error) = this.update_in(cx, |this, window, cx| {
-                let (visible_entries, max_width_item_index) = visible_entries;
                 this.tree_state.visible_entries = visible_entries;
                 this.tree_state.max_width_item_index = max_width_item_index;
+                this.tree_state.folder_orders = folder_orders;
+                this.tree_state.filter_matches = filter_matches.unwrap_or_default();
+                if this.update_visible_entries_task.expand_filter_matches {
+                    this.update_visible_entries_task.expand_filter_matches = false;
+                    if let Some(expanded_dir_ids) = this.tree_state.expanded_dir_ids.as_mut() {
+                        for entry_id in filter_dir_ids {
+                            if let Err(index) = expanded_dir_ids.binary_search(&entry_id) {
+                                expanded_dir_ids.insert(index, entry_id);
+                            }
+                        }
+                    }
+                }
                 if let Some(entry_id) = new_selected_entry {
                     this.selection = Some(SelectedEntry(entry_id));
                 }
@@ -609,9 +836,84 @@ impl ProjectPanel {
             focus_file_name_editor: focus_file_name_editor
                 || self.update_visible_entries_task.focus_file_name_editor,
             autoscroll: autoscroll || self.update_visible_entries_task.autoscroll,
+            expand_filter_matches,
         };
     }
 
+    /// Filters the entries by the filter field's query. The folders that were expanded before
+    /// filtering are expanded again once the query is cleared.
+    fn update_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.filter_field.read(cx).value(cx).trim().to_string();
+        if query == self.filter_query {
+            return;
+        }
+
+        if self.filter_query.is_empty() {
+            self.unfiltered_expanded_dir_ids
+                .clone_from(&self.tree_state.expanded_dir_ids);
+        } else if query.is_empty()
+            && let Some(expanded_dir_ids) = self.unfiltered_expanded_dir_ids.take()
+        {
+            self.tree_state.expanded_dir_ids = Some(expanded_dir_ids);
+        }
+        self.filter_query = query;
+        self.update_visible_entries_task.expand_filter_matches = !self.filter_query.is_empty();
+        self.update_visible_entries(None, false, false, window, cx);
+    }
+
+    /// The fields of the requests in `snapshot`, reading those of the requests that aren't
+    /// cached yet.
+    fn load_request_fields(
+        &mut self,
+        snapshot: &Snapshot,
+        cx: &mut Context<Self>,
+    ) -> Task<Arc<RequestFieldsByEntry>> {
+        let request_fields = self.request_fields.clone();
+        let load_tasks = {
+            let project = self.project.read(cx);
+            snapshot
+                .files(0)
+                .filter(|entry| entry.is_request && !request_fields.contains_key(&entry.id))
+                .map(|entry| {
+                    let project_path = ProjectPath {
+                        worktree_id: snapshot.id(),
+                        path: entry.path.clone(),
+                    };
+                    (entry.id, project.load_request_file(&project_path, cx))
+                })
+                .collect::<Vec<_>>()
+        };
+        if load_tasks.is_empty() {
+            return Task::ready(request_fields);
+        }
+
+        let version = self.request_fields_version;
+        cx.spawn(async move |this, cx| {
+            let mut request_fields = Arc::unwrap_or_clone(request_fields);
+            for (entry_id, load_task) in load_tasks {
+                let request_file = load_task.await.log_err();
+                request_fields.insert(entry_id, request_file.as_ref().map(RequestFields::new));
+            }
+            let request_fields = Arc::new(request_fields);
+            this.update(cx, |this, _| {
+                if this.request_fields_version == version {
+                    this.request_fields = request_fields.clone();
+                }
+            })
+            .log_err();
+            request_fields
+        })
+    }
+
+    fn toggle_filter_request_fields(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_request_fields = !self.filter_request_fields;
+        if !self.filter_query.is_empty() {
+            self.update_visible_entries_task.expand_filter_matches = true;
+            self.update_visible_entries(None, false, false, window, cx);
+        }
+        cx.notify();
+    }
+
     fn index_for_selection(&self, selection: SelectedEntry) -> Option<usize> {
         self.tree_state
             .visible_entries
@@ -1119,6 +1421,142 @@ impl ProjectPanel {
         Some(task)
     }
 
+    /// Moves the dragged entries into the folder they are dropped on, or into the folder of the
+    /// request they are dropped on. When sorting manually, they are placed before that request,
+    /// or at the end of a folder that has an order.
+    fn drop_entries(
+        &mut self,
+        dragged: &DraggedProjectEntry,
+        target_entry_id: ProjectEntryId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selection = SelectedEntry(dragged.entry_id);
+        let dragged_entries = if self.marked_entries.contains(&selection) {
+            self.disjoint_entries(self.marked_entries.iter().copied().collect(), cx)
+        } else {
+            BTreeSet::from([selection])
+        };
+        let Some(worktree) = self.project.read(cx).root_worktree(cx) else {
+            return;
+        };
+        let worktree = worktree.read(cx);
+        let worktree_id = worktree.id();
+        let Some(target_entry) = worktree.entry_for_id(target_entry_id) else {
+            return;
+        };
+        let is_dir = target_entry.is_dir();
+        let (folder, before) = if is_dir {
+            (target_entry.path.clone(), None)
+        } else {
+            let Some(folder) = target_entry.path.parent() else {
+                return;
+            };
+            (folder.into_arc(), target_entry.path.file_name())
+        };
+        let entries = dragged_entries
+            .iter()
+            .filter_map(|entry| worktree.entry_for_id(entry.0))
+            .filter(|entry| entry.id != target_entry_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        // A folder can't be moved into itself.
+        if entries.is_empty() || entries.iter().any(|entry| folder.starts_with(&entry.path)) {
+            return;
+        }
+
+        let order = match ProjectPanelSettings::get_global(cx).sort {
+            ProjectPanelSort::Manual => self.folder_order_after_drop(&folder, &entries, before),
+            ProjectPanelSort::Alphabetical => None,
+        };
+        let rename_tasks = entries
+            .iter()
+            .filter(|entry| entry.path.parent() != Some(folder.as_ref()))
+            .filter_map(|entry| {
+                let file_name = RelPath::unix(entry.path.file_name()?).ok()?;
+                let destination: ProjectPath = (worktree_id, folder.join(file_name)).into();
+                Some(self.project.update(cx, |project, cx| {
+                    project.rename_entry(entry.id, destination, cx)
+                }))
+            })
+            .collect::<Vec<_>>();
+        if rename_tasks.is_empty() && order.is_none() {
+            return;
+        }
+
+        let project = self.project.clone();
+        let folder = ProjectPath {
+            worktree_id,
+            path: folder,
+        };
+        let task = cx.spawn_in(window, async move |project_panel, cx| {
+            for task in rename_tasks {
+                task.await?;
+            }
+            if let Some(order) = order {
+                project
+                    .update(cx, |project, cx| {
+                        project.write_folder_order(&folder, order, cx)
+                    })
+                    .await?;
+            }
+            project_panel.update_in(cx, |project_panel, window, cx| {
+                project_panel.update_visible_entries(None, false, false, window, cx);
+            })?;
+            anyhow::Ok(())
+        });
+
+        if is_dir {
+            self.expand_entry(target_entry_id, cx);
+        }
+        task.detach_and_log_err(cx);
+    }
+
+    /// The order of `folder` once `entries` are dropped into it, before the entry named `before`
+    /// or at the end. Dropping at the end of a folder without an order leaves it without one.
+    fn folder_order_after_drop(
+        &self,
+        folder: &RelPath,
+        entries: &[Entry],
+        before: Option<&str>,
+    ) -> Option<Vec<String>> {
+        let file_names = entries
+            .iter()
+            .filter_map(|entry| entry.path.file_name())
+            .collect::<Vec<_>>();
+        let mut order = match before {
+            // The folder is expanded, as the entry dropped on is visible, so its entries are
+            // visible in their current order.
+            Some(_) => self
+                .tree_state
+                .visible_entries
+                .iter()
+                .filter(|entry| {
+                    entry.id != Self::NEW_ENTRY_ID && entry.path.parent() == Some(folder)
+                })
+                .filter_map(|entry| entry.path.file_name())
+                .filter(|file_name| !file_names.contains(file_name))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            None => self
+                .tree_state
+                .folder_orders
+                .get(folder)?
+                .iter()
+                .filter(|file_name| !file_names.contains(&file_name.as_str()))
+                .cloned()
+                .collect(),
+        };
+        let index = before
+            .and_then(|before| order.iter().position(|file_name| file_name == before))
+            .unwrap_or(order.len());
+        order.splice(
+            index..index,
+            file_names.into_iter().map(ToString::to_string),
+        );
+        Some(order)
+    }
+
     fn duplicate(
         &mut self,
         _: &actions::project_panel::Duplicate,
@@ -1900,6 +2338,7 @@ impl ProjectPanel {
                 };
 
                 if is_edited_entry {
+                    details.highlight_positions.clear();
                     if let Some(processing_file_name) = &edit_state.processing_file_name {
                         details.is_processing = true;
                         details.file_name.clear();
@@ -2117,9 +2556,7 @@ impl ProjectPanel {
         let latest_entry = sanitized_entries
             .iter()
             .filter_map(|entry| worktree.entry_for_id(entry.0))
-            .max_by(|lhs, rhs| {
-                cmp_worktree_entries(lhs, rhs, SortMode::DirectoriesFirst, SortOrder::Default)
-            })?;
+            .max_by(|lhs, rhs| cmp_entries(lhs, rhs, &self.tree_state.folder_orders))?;
         let parent_path = latest_entry.path.parent()?;
         let parent_entry = worktree.entry_for_path(parent_path)?;
 
@@ -2131,9 +2568,7 @@ impl ProjectPanel {
             })
             .cloned()
             .collect::<Vec<_>>();
-        siblings.sort_by(|lhs, rhs| {
-            cmp_worktree_entries(lhs, rhs, SortMode::DirectoriesFirst, SortOrder::Default)
-        });
+        siblings.sort_by(|lhs, rhs| cmp_entries(lhs, rhs, &self.tree_state.folder_orders));
 
         let sibling_entry_index = siblings
             .iter()
@@ -2382,6 +2817,35 @@ impl ProjectPanel {
             .into_any_element()
     }
 
+    fn render_filter(&self, cx: &mut Context<Self>) -> AnyElement {
+        gpui::div()
+            .flex_none()
+            .flex()
+            .items_center()
+            .gap_1()
+            .w_full()
+            .px(DynamicSpacing::Base08.px(cx))
+            .pb_1()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(self.filter_field.clone()),
+            )
+            .child(
+                Button::new("project-panel-filter-request-fields", "URL")
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::Compact)
+                    .toggle_state(self.filter_request_fields)
+                    .tooltip(Tooltip::text("Match Method and URL"))
+                    .on_click(cx.listener(|project_panel, _, window, cx| {
+                        project_panel.toggle_filter_request_fields(window, cx);
+                    })),
+            )
+            .into_any_element()
+    }
+
     fn render_entry_prefix(details: &EntryDetails, window: &Window) -> AnyElement {
         let entry_prefix_slot_width = Self::entry_prefix_slot_width(window);
 
@@ -2548,6 +3012,9 @@ impl ProjectPanel {
         } else {
             None
         };
+        // Dropping on a request places the entries before it when sorting manually.
+        let drops_before =
+            !is_dir && ProjectPanelSettings::get_global(cx).sort == ProjectPanelSort::Manual;
 
         gpui::div()
             .id(entry_id.to_usize())
@@ -2559,6 +3026,28 @@ impl ProjectPanel {
             .border_1()
             .border_color(gpui::transparent_black())
             .hover(move |style| style.bg(bg_hover_color))
+            .when(!show_editor, |this| {
+                this.on_drag(
+                    DraggedProjectEntry {
+                        entry_id,
+                        file_name: details.file_name.clone(),
+                    },
+                    |dragged, _, _, cx| cx.new(|_| dragged.clone()),
+                )
+            })
+            .drag_over::<DraggedProjectEntry>(move |style, _, _, cx| {
+                let colors = cx.theme().colors();
+                if drops_before {
+                    style.border_t_2().border_color(colors.drop_target_border)
+                } else {
+                    style.bg(colors.drop_target_background)
+                }
+            })
+            .on_drop(cx.listener(
+                move |project_panel, dragged: &DraggedProjectEntry, window, cx| {
+                    project_panel.drop_entries(dragged, entry_id, window, cx);
+                },
+            ))
             .on_mouse_down(
                 MouseButton::Left,
                 cx.listener(move |project_panel, _, _, cx| {
@@ -2633,9 +3122,12 @@ impl ProjectPanel {
                             .child(self.file_name_editor.clone())
                     } else {
                         gpui::div().flex().items_center().h_6().child(
-                            Text::new(details.file_name.clone())
-                                .color(text_color)
-                                .single_line(),
+                            HighlightedText::new(
+                                details.file_name.clone(),
+                                details.highlight_positions.clone(),
+                            )
+                            .color(text_color)
+                            .single_line(),
                         )
                     })
                     .on_secondary_mouse_down(cx.listener(
@@ -2762,6 +3254,7 @@ impl Render for ProjectPanel {
             .size_full()
             .bg(panel_background)
             .when_some(root_header, |this, root_header| this.child(root_header))
+            .when(has_root, |this| this.child(self.render_filter(cx)))
             .when(has_root, |this| {
                 this.child(
                     gpui::div()
@@ -2930,6 +3423,26 @@ impl Render for ProjectPanel {
                                     gpui::div()
                                         .id("project-panel-empty-space")
                                         .flex_grow_1()
+                                        .drag_over::<DraggedProjectEntry>(|style, _, _, cx| {
+                                            style.bg(cx.theme().colors().drop_target_background)
+                                        })
+                                        .on_drop(cx.listener(
+                                            |this, dragged: &DraggedProjectEntry, window, cx| {
+                                                let Some(root_entry_id) =
+                                                    this.snapshot(cx).and_then(|snapshot| {
+                                                        snapshot.root_entry().map(|entry| entry.id)
+                                                    })
+                                                else {
+                                                    return;
+                                                };
+                                                this.drop_entries(
+                                                    dragged,
+                                                    root_entry_id,
+                                                    window,
+                                                    cx,
+                                                );
+                                            },
+                                        ))
                                         .on_mouse_down(
                                             MouseButton::Left,
                                             cx.listener(
@@ -3134,6 +3647,40 @@ impl Render for ProjectPanel {
     }
 }
 
+/// Compares entries in the order the folders they are in set, falling back to
+/// [`cmp_worktree_entries`] for the entries an order leaves out, which come after the others.
+fn cmp_entries(left: &Entry, right: &Entry, folder_orders: &FolderOrders) -> cmp::Ordering {
+    let alphabetical =
+        || cmp_worktree_entries(left, right, SortMode::DirectoriesFirst, SortOrder::Default);
+    let Some(folder) = left
+        .path
+        .ancestors()
+        .find(|ancestor| right.path.starts_with(ancestor))
+    else {
+        return alphabetical();
+    };
+    // A folder comes before the entries in it.
+    if folder == left.path.as_ref() || folder == right.path.as_ref() {
+        return alphabetical();
+    }
+    let Some(order) = folder_orders.get(folder) else {
+        return alphabetical();
+    };
+
+    let position = |path: &RelPath| {
+        let file_name = path.strip_prefix(folder).ok()?.components().next()?;
+        order.iter().position(|name| name == file_name)
+    };
+    match (position(&left.path), position(&right.path)) {
+        (Some(left_position), Some(right_position)) => {
+            left_position.cmp(&right_position).then_with(alphabetical)
+        }
+        (Some(_), None) => cmp::Ordering::Less,
+        (None, Some(_)) => cmp::Ordering::Greater,
+        (None, None) => alphabetical(),
+    }
+}
+
 #[inline]
 fn cmp_worktree_entries(
     left: &Entry,
@@ -3146,6 +3693,73 @@ fn cmp_worktree_entries(
     path::compare_rel_paths_by(left, right, mode, order)
 }
 
+/// Fuzzy matches `query` against the names of the folders and requests in `snapshot`. When
+/// `request_fields` are given, the requests whose method or URL contains the query match too.
+async fn match_entries(
+    snapshot: &Snapshot,
+    query: &str,
+    request_fields: &RequestFieldsByEntry,
+    executor: BackgroundExecutor,
+) -> FilterMatches {
+    let root_entry_id = snapshot.root_entry().map(|entry| entry.id);
+    let entries = snapshot
+        .entries(0)
+        .filter(|entry| {
+            root_entry_id != Some(entry.id) && (entry.kind.is_dir() || entry.is_request)
+        })
+        .collect::<Vec<_>>();
+    let names = entries
+        .iter()
+        .map(|entry| file_name_for_entry(snapshot, entry))
+        .collect::<Vec<_>>();
+    let candidates = names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| StringMatchCandidate::new(index, name))
+        .collect::<Vec<_>>();
+
+    let string_matches = fuzzy_nucleo::match_strings_async(
+        &candidates,
+        query,
+        fuzzy_nucleo::Case::Smart,
+        fuzzy_nucleo::LengthPenalty::On,
+        candidates.len(),
+        &AtomicBool::default(),
+        executor,
+    )
+    .await;
+    let mut matches = string_matches
+        .into_iter()
+        .filter_map(|string_match| {
+            let entry = entries.get(string_match.candidate_id)?;
+            Some((entry.id, string_match.positions))
+        })
+        .collect::<FilterMatches>();
+
+    let query = query.to_lowercase();
+    for (entry_id, fields) in request_fields {
+        if let Some(fields) = fields
+            && (fields.method.contains(&query) || fields.url.contains(&query))
+            && snapshot.entry_for_id(*entry_id).is_some()
+        {
+            matches.entry(*entry_id).or_default();
+        }
+    }
+
+    matches
+}
+
+/// The folders that contain the entries the filter matches.
+fn filter_match_dir_ids(snapshot: &Snapshot, matches: &FilterMatches) -> BTreeSet<ProjectEntryId> {
+    matches
+        .keys()
+        .filter_map(|entry_id| snapshot.entry_for_id(*entry_id))
+        .flat_map(|entry| entry.path.ancestors().skip(1))
+        .filter_map(|path| snapshot.entry_for_path(path))
+        .map(|entry| entry.id)
+        .collect()
+}
+
 fn display_depth(entry: &Entry) -> usize {
     entry.path.components().count().saturating_sub(1)
 }
@@ -4777,4 +5391,290 @@ mod tests {
             ]
         );
     }
+
+    fn drop_path_onto(
+        panel: &Entity<ProjectPanel>,
+        path: &str,
+        target_path: &str,
+        cx: &mut VisualTestContext,
+    ) {
+        panel.update_in(cx, |panel, window, cx| {
+            let worktree = panel.project.read(cx).root_worktree(cx).unwrap();
+            let worktree = worktree.read(cx);
+            let entry_id = worktree.entry_for_path(rel_path(path)).unwrap().id;
+            let target_entry_id = worktree.entry_for_path(rel_path(target_path)).unwrap().id;
+            let dragged = DraggedProjectEntry {
+                entry_id,
+                file_name: path.to_string(),
+            };
+            panel.drop_entries(&dragged, target_entry_id, window, cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_manual_order(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let app_state = cx.update(|cx| AppState::test_new(temp_fs.clone(), None, cx));
+        init_test(app_state, cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(r#"{ "project_panel": { "sort": "manual" } }"#, cx)
+                    .result()
+                    .unwrap();
+            });
+        });
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "_folder.toml": "order = [\"logout.toml\", \"orders\", \"login.toml\"]\n",
+                "admin": {},
+                "health.toml": "",
+                "login.toml": "",
+                "logout.toml": "",
+                "orders": {
+                    "_folder.toml": "order = [\"delete.toml\"]\n",
+                    "create.toml": "",
+                    "delete.toml": "",
+                },
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let (workspace, cx) = build_workspace(&project, cx);
+        let panel = workspace.update_in(cx, ProjectPanel::new);
+        cx.run_until_parked();
+        toggle_expand_dir(&panel, "project/orders", cx);
+
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("  logout"),
+                String::from("v orders  <== selected"),
+                String::from("      delete"),
+                String::from("      create"),
+                String::from("  login"),
+                String::from("> admin"),
+                String::from("  health"),
+            ]
+        );
+
+        drop_path_onto(&panel, "health.toml", "logout.toml", cx);
+        panel
+            .condition::<ProjectPanelEvent>(cx, |panel, cx| {
+                panel
+                    .visible_entries(cx)
+                    .first()
+                    .is_some_and(|entry| entry.path.as_ref() == rel_path("health.toml"))
+            })
+            .await;
+        assert_eq!(
+            temp_fs
+                .load(&project_path.join("_folder.toml"))
+                .await
+                .unwrap(),
+            "order = [\"health.toml\", \"logout.toml\", \"orders\", \"login.toml\", \"admin\"]\n"
+        );
+
+        drop_path_onto(&panel, "login.toml", "orders/create.toml", cx);
+        panel
+            .condition::<ProjectPanelEvent>(cx, |panel, cx| {
+                panel
+                    .visible_entries(cx)
+                    .iter()
+                    .any(|entry| entry.path.as_ref() == rel_path("orders/login.toml"))
+            })
+            .await;
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("  health"),
+                String::from("  logout"),
+                String::from("v orders  <== selected"),
+                String::from("      delete"),
+                String::from("      login"),
+                String::from("      create"),
+                String::from("> admin"),
+            ]
+        );
+        assert_eq!(
+            temp_fs
+                .load(&project_path.join("_folder.toml"))
+                .await
+                .unwrap(),
+            "order = [\"health.toml\", \"logout.toml\", \"orders\", \"admin\"]\n"
+        );
+
+        project
+            .update(cx, |project, cx| {
+                let worktree = project.root_worktree(cx).unwrap();
+                let worktree = worktree.read(cx);
+                let worktree_id = worktree.id();
+                let entry_id = worktree.entry_for_path(rel_path("logout.toml")).unwrap().id;
+                project.rename_entry(
+                    entry_id,
+                    (worktree_id, rel_path("sign_out.toml")).into(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            temp_fs
+                .load(&project_path.join("_folder.toml"))
+                .await
+                .unwrap(),
+            "order = [\"health.toml\", \"sign_out.toml\", \"orders\", \"admin\"]\n"
+        );
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..2, cx),
+            vec![String::from("  health"), String::from("  sign_out")]
+        );
+
+        cx.update(|_, cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(r#"{ "project_panel": { "sort": "alphabetical" } }"#, cx)
+                    .result()
+                    .unwrap();
+            });
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("> admin"),
+                String::from("v orders  <== selected"),
+                String::from("      create"),
+                String::from("      delete"),
+                String::from("      login"),
+                String::from("  health"),
+                String::from("  sign_out"),
+            ]
+        );
+    }
+
+    fn set_filter(panel: &Entity<ProjectPanel>, query: &str, cx: &mut VisualTestContext) {
+        panel.update_in(cx, |panel, window, cx| {
+            let filter_field = panel.filter_field.clone();
+            filter_field.read(cx).set_value(query, window, cx);
+        });
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_filter(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let app_state = cx.update(|cx| AppState::test_new(temp_fs.clone(), None, cx));
+        init_test(app_state, cx);
+
+        let request = |method: &str, url: &str| {
+            format!("[meta]\nversion = 1\n\n[http]\nmethod = \"{method}\"\nurl = \"{url}\"\n")
+        };
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "auth": {
+                    "login.toml": request("POST", "https://api.zaku.dev/session"),
+                    "logout.toml": request("DELETE", "https://api.zaku.dev/session"),
+                },
+                "health.toml": request("GET", "https://api.zaku.dev/health"),
+                "orders": {
+                    "archive": {
+                        "list.toml": request("GET", "https://api.zaku.dev/orders/archived"),
+                    },
+                    "create.toml": request("POST", "https://api.zaku.dev/orders"),
+                },
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let (workspace, cx) = build_workspace(&project, cx);
+        let panel = workspace.update_in(cx, ProjectPanel::new);
+        cx.run_until_parked();
+        toggle_expand_dir(&panel, "project/auth", cx);
+
+        set_filter(&panel, "list", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("v orders"),
+                String::from("    v archive"),
+                String::from("          list"),
+            ]
+        );
+        let highlight_positions = panel.update_in(cx, |panel, window, cx| {
+            let mut highlight_positions = Vec::new();
+            panel.for_each_visible_entry(0..10, window, cx, &mut |_, details, _, _| {
+                highlight_positions.push(details.highlight_positions);
+            });
+            highlight_positions
+        });
+        assert_eq!(highlight_positions, vec![vec![], vec![], vec![0, 1, 2, 3]]);
+
+        set_filter(&panel, "session", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            Vec::<String>::new()
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.toggle_filter_request_fields(window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("v auth  <== selected"),
+                String::from("      login"),
+                String::from("      logout"),
+            ]
+        );
+
+        temp_fs
+            .write(
+                &project_path.join("health.toml"),
+                request("GET", "https://api.zaku.dev/session/health").as_bytes(),
+            )
+            .await
+            .unwrap();
+        panel
+            .condition::<ProjectPanelEvent>(cx, |panel, cx| {
+                panel
+                    .visible_entries(cx)
+                    .iter()
+                    .any(|entry| entry.path.as_ref() == rel_path("health.toml"))
+            })
+            .await;
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("v auth  <== selected"),
+                String::from("      login"),
+                String::from("      logout"),
+                String::from("  health"),
+            ]
+        );
+
+        set_filter(&panel, "", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            vec![
+                String::from("v auth  <== selected"),
+                String::from("      login"),
+                String::from("      logout"),
+                String::from("> orders"),
+                String::from("  health"),
+            ]
+        );
+    }
 }
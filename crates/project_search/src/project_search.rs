@@ -0,0 +1,665 @@
+mod request_matches;
+
+use anyhow::Context as _;
+use gpui::{
+    Action, AnyElement, App, Context, Entity, FocusHandle, Focusable, Pixels, Render, SharedString,
+    Subscription, Task, WeakEntity, Window, prelude::*,
+};
+use std::{collections::BTreeSet, ops::Range};
+
+use editor::SearchQuery;
+use input::{ErasedEditorEvent, InputField};
+use project::{Project, request_method_short_name};
+use request_editor::RequestEditor;
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, Disableable,
+    HighlightedText, IconAsset, Text, TextCommon, TextSize, Toggleable, Tooltip,
+};
+use workspace::{Panel, Toast, Workspace, notifications::NotificationId};
+
+use request_matches::{RequestMatches, SearchField, replace_in_request_file, search_request_files};
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace.register_action(
+                |workspace, _: &actions::project_search::ToggleFocus, window, cx| {
+                    workspace.toggle_panel_focus::<ProjectSearchPanel>(window, cx);
+                },
+            );
+        },
+    )
+    .detach();
+}
+
+/// A row of the results, which are grouped by request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultRow {
+    Request(usize),
+    Match { request: usize, field_match: usize },
+}
+
+/// Searches the URL, method, headers and body of every request file in the project, and replaces
+/// what it finds.
+pub struct ProjectSearchPanel {
+    focus_handle: FocusHandle,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    query_field: Entity<InputField>,
+    replacement_field: Entity<InputField>,
+    case_sensitive: bool,
+    whole_word: bool,
+    regex: bool,
+    fields: BTreeSet<SearchField>,
+    results: Vec<RequestMatches>,
+    rows: Vec<ResultRow>,
+    selected_row: Option<ResultRow>,
+    error: Option<SharedString>,
+    search_task: Task<()>,
+    replace_task: Task<()>,
+    open_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ProjectSearchPanel {
+    const PANEL_KEY: &str = "ProjectSearchPanel";
+    const DEFAULT_SIZE: Pixels = gpui::px(360.0);
+
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        cx.new(|cx| {
+            let focus_handle = cx.focus_handle();
+            let query_field = cx.new(|cx| {
+                InputField::new(window, cx, "Search requests").start_icon(IconAsset::ListSearch)
+            });
+            let replacement_field = cx.new(|cx| InputField::new(window, cx, "Replace"));
+            let panel = cx.weak_entity();
+            let query_subscription = query_field.read(cx).editor().clone().subscribe(
+                Box::new(move |event, _window, cx| {
+                    if event == ErasedEditorEvent::BufferEdited
+                        && let Err(error) = panel.update(cx, |panel, cx| panel.search(cx))
+                    {
+                        log::debug!("Failed to update project search: {error:?}");
+                    }
+                }),
+                window,
+                cx,
+            );
+            let subscriptions = vec![
+                cx.on_focus(&focus_handle, window, |this, window, cx| {
+                    window.focus(&this.query_field.focus_handle(cx), cx);
+                }),
+                query_subscription,
+            ];
+
+            Self {
+                focus_handle,
+                project,
+                workspace: workspace_handle,
+                query_field,
+                replacement_field,
+                case_sensitive: false,
+                whole_word: false,
+                regex: false,
+                fields: SearchField::ALL.into_iter().collect(),
+                results: Vec::new(),
+                rows: Vec::new(),
+                selected_row: None,
+                error: None,
+                search_task: Task::ready(()),
+                replace_task: Task::ready(()),
+                open_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    fn query(&self, cx: &App) -> SearchQuery {
+        SearchQuery {
+            text: self.query_field.read(cx).value(cx),
+            case_sensitive: self.case_sensitive,
+            whole_word: self.whole_word,
+            regex: self.regex,
+        }
+    }
+
+    /// Searches the request files as they are on disk, replacing the results once it's done.
+    fn search(&mut self, cx: &mut Context<Self>) {
+        let query = self.query(cx);
+        if query.text.is_empty() {
+            self.search_task = Task::ready(());
+            self.set_results(Ok(Vec::new()), cx);
+            return;
+        }
+
+        let fields = self.fields.clone();
+        let load_request_files = self.project.read(cx).load_request_files(cx);
+        self.search_task = cx.spawn(async move |this, cx| {
+            let request_files = load_request_files.await;
+            let results = cx
+                .background_spawn(
+                    async move { search_request_files(request_files, &query, &fields) },
+                )
+                .await;
+            if let Err(error) = this.update(cx, |this, cx| this.set_results(results, cx)) {
+                log::debug!("Failed to update project search results: {error:?}");
+            }
+        });
+    }
+
+    fn set_results(
+        &mut self,
+        results: anyhow::Result<Vec<RequestMatches>>,
+        cx: &mut Context<Self>,
+    ) {
+        match results {
+            Ok(results) => {
+                self.results = results;
+                self.error = None;
+            }
+            Err(error) => {
+                self.results.clear();
+                self.error = Some(error.to_string().into());
+            }
+        }
+        self.rows = self
+            .results
+            .iter()
+            .enumerate()
+            .flat_map(|(request, request_matches)| {
+                std::iter::once(ResultRow::Request(request)).chain(
+                    (0..request_matches.matches.len()).map(move |field_match| ResultRow::Match {
+                        request,
+                        field_match,
+                    }),
+                )
+            })
+            .collect();
+        if self
+            .selected_row
+            .is_some_and(|selected_row| !self.rows.contains(&selected_row))
+        {
+            self.selected_row = None;
+        }
+        cx.notify();
+    }
+
+    fn toggle_field(&mut self, field: SearchField, cx: &mut Context<Self>) {
+        if !self.fields.remove(&field) {
+            self.fields.insert(field);
+        }
+        self.search(cx);
+    }
+
+    fn show_message(&self, message: impl Into<SharedString>, cx: &mut App) {
+        if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<Self>(), message).autohide(),
+                cx,
+            );
+        }) {
+            log::debug!("Failed to show project search message: {error:?}");
+        }
+    }
+
+    /// Opens the request of the row, focusing the field of the match if the row is one.
+    fn open_row(&mut self, row: ResultRow, window: &mut Window, cx: &mut Context<Self>) {
+        let (request, field) = match row {
+            ResultRow::Request(request) => (request, None),
+            ResultRow::Match {
+                request,
+                field_match,
+            } => {
+                let field = self
+                    .results
+                    .get(request)
+                    .and_then(|request_matches| request_matches.matches.get(field_match))
+                    .map(|field_match| field_match.field.clone());
+                (request, field)
+            }
+        };
+        let Some(project_path) = self
+            .results
+            .get(request)
+            .map(|request_matches| request_matches.project_path.clone())
+        else {
+            return;
+        };
+        self.selected_row = Some(row);
+        cx.notify();
+
+        let workspace = self.workspace.clone();
+        self.open_task = cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let request_editor = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_path(project_path, None, true, window, cx)
+                    })?
+                    .await?
+                    .downcast::<RequestEditor>()
+                    .context("The request file couldn't be opened as a request.")?;
+                if let Some(field) = field {
+                    request_editor.update_in(cx, |request_editor, window, cx| {
+                        request_editor.focus_field(field, window, cx);
+                    })?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result
+                && let Err(error) =
+                    this.update(cx, |this, cx| this.show_message(error.to_string(), cx))
+            {
+                log::debug!("Failed to report project search error: {error:?}");
+            }
+        });
+    }
+
+    /// Replaces every match in the requests that have one, writing each changed request back to
+    /// its file. Requests with unsaved changes are left alone.
+    fn replace_all(&mut self, cx: &mut Context<Self>) {
+        let query = self.query(cx);
+        if query.text.is_empty() || self.results.is_empty() {
+            return;
+        }
+
+        let replacement = self.replacement_field.read(cx).value(cx);
+        let fields = self.fields.clone();
+        let paths = self
+            .results
+            .iter()
+            .map(|request_matches| request_matches.project_path.clone())
+            .collect::<BTreeSet<_>>();
+        let project = self.project.clone();
+        let load_request_files = project.read(cx).load_request_files(cx);
+        self.replace_task = cx.spawn(async move |this, cx| {
+            let mut replaced = 0;
+            let mut changed_requests = 0;
+            let mut errors = Vec::new();
+            for (project_path, mut request_file) in load_request_files.await {
+                if !paths.contains(&project_path) {
+                    continue;
+                }
+                let count =
+                    match replace_in_request_file(&mut request_file, &query, &replacement, &fields)
+                    {
+                        Ok(count) => count,
+                        Err(error) => {
+                            errors.push(error);
+                            break;
+                        }
+                    };
+                if count == 0 {
+                    continue;
+                }
+                let write_request_file = project.update(cx, |project, cx| {
+                    project.write_request_file(project_path, request_file, cx)
+                });
+                match write_request_file.await {
+                    Ok(()) => {
+                        replaced += count;
+                        changed_requests += 1;
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+
+            let message = format!(
+                "Replaced {replaced} {} in {changed_requests} {}.",
+                if replaced == 1 { "match" } else { "matches" },
+                if changed_requests == 1 {
+                    "request"
+                } else {
+                    "requests"
+                },
+            );
+            let message = match errors.first() {
+                Some(error) => format!("{message} {} failed: {error}", errors.len()),
+                None => message,
+            };
+            if let Err(error) = this.update(cx, |this, cx| {
+                this.show_message(message, cx);
+                this.search(cx);
+            }) {
+                log::debug!("Failed to update project search after replacing: {error:?}");
+            }
+        });
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let match_count = self
+            .results
+            .iter()
+            .map(|request_matches| {
+                request_matches
+                    .matches
+                    .iter()
+                    .map(|field_match| field_match.ranges.len())
+                    .sum::<usize>()
+            })
+            .sum::<usize>();
+        let (status, status_color) = match &self.error {
+            Some(error) => (error.clone(), Color::Error),
+            None if self.query_field.read(cx).value(cx).is_empty() => {
+                (SharedString::default(), Color::Muted)
+            }
+            None if match_count == 0 => ("No results".into(), Color::Muted),
+            None => (
+                format!(
+                    "{match_count} {} in {} {}",
+                    if match_count == 1 { "match" } else { "matches" },
+                    self.results.len(),
+                    if self.results.len() == 1 {
+                        "request"
+                    } else {
+                        "requests"
+                    },
+                )
+                .into(),
+                Color::Muted,
+            ),
+        };
+
+        let option_button = |id: &'static str, label: &'static str, tooltip: &'static str| {
+            Button::new(id, label)
+                .variant(ButtonVariant::Ghost)
+                .size(ButtonSize::Compact)
+                .tooltip(Tooltip::text(tooltip))
+        };
+
+        let search_row = gpui::div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(self.query_field.clone()),
+            )
+            .child(
+                option_button("project-search-case-sensitive", "Aa", "Match Case")
+                    .toggle_state(self.case_sensitive)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.case_sensitive = !this.case_sensitive;
+                        this.search(cx);
+                    })),
+            )
+            .child(
+                option_button("project-search-whole-word", "W", "Match Whole Word")
+                    .toggle_state(self.whole_word)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.whole_word = !this.whole_word;
+                        this.search(cx);
+                    })),
+            )
+            .child(
+                option_button("project-search-regex", ".*", "Use Regular Expression")
+                    .toggle_state(self.regex)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.regex = !this.regex;
+                        this.search(cx);
+                    })),
+            );
+
+        let replace_row = gpui::div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(self.replacement_field.clone()),
+            )
+            .child(
+                Button::new("project-search-replace-all", "Replace All")
+                    .variant(ButtonVariant::OutlinedGhost)
+                    .size(ButtonSize::Compact)
+                    .disabled(match_count == 0)
+                    .on_click(cx.listener(|this, _, _, cx| this.replace_all(cx))),
+            );
+
+        let fields_row = gpui::div()
+            .flex()
+            .flex_wrap()
+            .items_center()
+            .gap_1()
+            .children(
+                SearchField::ALL
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        Button::new(("project-search-field", index), field.label())
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::Compact)
+                            .toggle_state(self.fields.contains(&field))
+                            .on_click(
+                                cx.listener(move |this, _, _, cx| this.toggle_field(field, cx)),
+                            )
+                    }),
+            );
+
+        gpui::div()
+            .flex()
+            .flex_none()
+            .flex_col()
+            .gap_1()
+            .px_3()
+            .py_1()
+            .border_b_1()
+            .border_color(colors.border)
+            .bg(colors.panel_tab_bar_background)
+            .child(search_row)
+            .child(replace_row)
+            .child(fields_row)
+            .child(
+                Text::new(status)
+                    .size(TextSize::Small)
+                    .color(status_color)
+                    .single_line()
+                    .truncate(),
+            )
+            .into_any_element()
+    }
+
+    fn render_row(&self, row: ResultRow, index: usize, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let is_selected = self.selected_row == Some(row);
+        let content = match row {
+            ResultRow::Request(request) => {
+                let Some(request_matches) = self.results.get(request) else {
+                    return gpui::div().into_any_element();
+                };
+                let path = request_matches
+                    .project_path
+                    .path
+                    .display(self.project.read(cx).path_style(cx))
+                    .into_owned();
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        gpui::div().w(gpui::rems(2.5)).child(
+                            Text::new(request_method_short_name(
+                                &request_matches.request_file.http.method,
+                            ))
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                        ),
+                    )
+                    .child(
+                        gpui::div()
+                            .flex_1()
+                            .min_w_0()
+                            .child(Text::new(path).size(TextSize::Small).truncate()),
+                    )
+            }
+            ResultRow::Match {
+                request,
+                field_match,
+            } => {
+                let Some(field_match) = self
+                    .results
+                    .get(request)
+                    .and_then(|request_matches| request_matches.matches.get(field_match))
+                else {
+                    return gpui::div().into_any_element();
+                };
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .pl_4()
+                    .child(
+                        gpui::div().flex_none().child(
+                            Text::new(field_match.search_field.label())
+                                .size(TextSize::XSmall)
+                                .color(Color::Muted)
+                                .single_line(),
+                        ),
+                    )
+                    .child(
+                        gpui::div().flex_1().min_w_0().child(
+                            HighlightedText::new(
+                                field_match.preview.clone(),
+                                highlight_indices(&field_match.preview, &field_match.ranges),
+                            )
+                            .size(TextSize::Small)
+                            .single_line()
+                            .truncate(),
+                        ),
+                    )
+            }
+        };
+
+        gpui::div()
+            .id(("project-search-row", index))
+            .w_full()
+            .px_3()
+            .py_0p5()
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(colors.ghost_element_selected))
+            .hover(|this| this.bg(colors.ghost_element_hover))
+            .on_click(cx.listener(move |this, _, window, cx| this.open_row(row, window, cx)))
+            .child(content)
+            .into_any_element()
+    }
+
+    fn render_results(&self, cx: &mut Context<Self>) -> AnyElement {
+        if self.rows.is_empty() {
+            let message = if self.query_field.read(cx).value(cx).is_empty() {
+                "Search the URL, method, headers and body of every request."
+            } else {
+                "No requests match your search."
+            };
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .px_3()
+                .child(Text::new(message).size(TextSize::Small).color(Color::Muted))
+                .into_any_element();
+        }
+
+        gpui::div()
+            .flex_1()
+            .min_h_0()
+            .py_1()
+            .child(
+                gpui::uniform_list(
+                    "project-search-results",
+                    self.rows.len(),
+                    cx.processor(|this, range: Range<usize>, _window, cx| {
+                        range
+                            .filter_map(|index| {
+                                let row = *this.rows.get(index)?;
+                                Some(this.render_row(row, index, cx))
+                            })
+                            .collect()
+                    }),
+                )
+                .size_full(),
+            )
+            .into_any_element()
+    }
+}
+
+/// The indices of the characters of `text` in `ranges`, as [`HighlightedText`] takes them.
+fn highlight_indices(text: &str, ranges: &[Range<usize>]) -> Vec<usize> {
+    text.char_indices()
+        .map(|(index, _)| index)
+        .filter(|index| ranges.iter().any(|range| range.contains(index)))
+        .collect()
+}
+
+impl Focusable for ProjectSearchPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for ProjectSearchPanel {
+    fn persistent_name() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn panel_key() -> &'static str {
+        Self::PANEL_KEY
+    }
+
+    fn default_size(&self, _window: &Window, _: &App) -> Pixels {
+        Self::DEFAULT_SIZE
+    }
+
+    fn icon(&self, _window: &Window, _: &App) -> Option<IconAsset> {
+        Some(IconAsset::ListSearch)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Search")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        actions::project_search::ToggleFocus.boxed_clone()
+    }
+
+    fn set_active(&mut self, active: bool, _window: &mut Window, cx: &mut Context<Self>) {
+        if active {
+            self.search(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        5
+    }
+}
+
+impl Render for ProjectSearchPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = self.render_header(cx);
+        let results = self.render_results(cx);
+
+        gpui::div()
+            .track_focus(&self.focus_handle)
+            .key_context(Self::PANEL_KEY)
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(header)
+            .child(results)
+    }
+}
@@ -0,0 +1,395 @@
+use std::{collections::BTreeSet, ops::Range};
+
+use editor::SearchQuery;
+use project::{ProjectPath, RequestFile};
+use request_editor::RequestField;
+
+/// How much of a body line is shown before the first match on it.
+const BODY_PREVIEW_CONTEXT: usize = 40;
+
+/// A part of a request that a search looks in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SearchField {
+    Url,
+    Method,
+    HeaderName,
+    HeaderValue,
+    Body,
+}
+
+impl SearchField {
+    pub(crate) const ALL: [Self; 5] = [
+        Self::Url,
+        Self::Method,
+        Self::HeaderName,
+        Self::HeaderValue,
+        Self::Body,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Url => "URL",
+            Self::Method => "Method",
+            Self::HeaderName => "Header Name",
+            Self::HeaderValue => "Header Value",
+            Self::Body => "Body",
+        }
+    }
+}
+
+/// The matches of a search in one field of a request, or in one line of its body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FieldMatch {
+    pub(crate) search_field: SearchField,
+    /// Where the request editor goes to show the match.
+    pub(crate) field: RequestField,
+    /// The text of the field, or the part of the body line the match is on.
+    pub(crate) preview: String,
+    /// The byte ranges of `preview` that match.
+    pub(crate) ranges: Vec<Range<usize>>,
+}
+
+/// A request file with the matches of a search in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestMatches {
+    pub(crate) project_path: ProjectPath,
+    pub(crate) request_file: RequestFile,
+    pub(crate) matches: Vec<FieldMatch>,
+}
+
+/// Searches the `fields` of every request file, keeping the ones with matches, ordered by path.
+pub(crate) fn search_request_files(
+    request_files: Vec<(ProjectPath, RequestFile)>,
+    query: &SearchQuery,
+    fields: &BTreeSet<SearchField>,
+) -> anyhow::Result<Vec<RequestMatches>> {
+    let mut results = Vec::new();
+    for (project_path, request_file) in request_files {
+        let matches = search_request_file(&request_file, query, fields)?;
+        if !matches.is_empty() {
+            results.push(RequestMatches {
+                project_path,
+                request_file,
+                matches,
+            });
+        }
+    }
+    results.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+    Ok(results)
+}
+
+/// The matches of `query` in the `fields` of `request_file`, in the order the fields appear in
+/// the request editor.
+pub(crate) fn search_request_file(
+    request_file: &RequestFile,
+    query: &SearchQuery,
+    fields: &BTreeSet<SearchField>,
+) -> anyhow::Result<Vec<FieldMatch>> {
+    let http = &request_file.http;
+    let mut matches = Vec::new();
+    let mut search_in = |search_field: SearchField, field: RequestField, text: &str| {
+        if !fields.contains(&search_field) {
+            return anyhow::Ok(());
+        }
+        let ranges = query.find_matches(text)?;
+        if !ranges.is_empty() {
+            matches.push(FieldMatch {
+                search_field,
+                field,
+                preview: text.to_string(),
+                ranges,
+            });
+        }
+        anyhow::Ok(())
+    };
+
+    search_in(SearchField::Method, RequestField::Method, &http.method)?;
+    search_in(SearchField::Url, RequestField::Url, &http.url)?;
+    for (index, header) in http.headers.iter().enumerate() {
+        search_in(
+            SearchField::HeaderName,
+            RequestField::HeaderName(index),
+            &header.name,
+        )?;
+        search_in(
+            SearchField::HeaderValue,
+            RequestField::HeaderValue(index),
+            &header.value,
+        )?;
+    }
+    if fields.contains(&SearchField::Body)
+        && let Some(body) = &http.body
+    {
+        let ranges = query.find_matches(&body.data)?;
+        matches.extend(body_matches(&body.data, ranges));
+    }
+    Ok(matches)
+}
+
+/// Groups the matches in a body by the line they start on, previewing each line from a little
+/// before its first match.
+fn body_matches(body: &str, ranges: Vec<Range<usize>>) -> Vec<FieldMatch> {
+    let mut matches: Vec<FieldMatch> = Vec::new();
+    let mut current_line_start = None;
+    let mut preview_start = 0;
+    let mut preview_end = 0;
+
+    for range in ranges {
+        let line_start = body
+            .get(..range.start)
+            .and_then(|text| text.rfind('\n'))
+            .map_or(0, |index| index + 1);
+        if current_line_start != Some(line_start) {
+            current_line_start = Some(line_start);
+            preview_start = floor_char_boundary(
+                body,
+                range
+                    .start
+                    .saturating_sub(BODY_PREVIEW_CONTEXT)
+                    .max(line_start),
+            );
+            preview_end = body
+                .get(range.start..)
+                .and_then(|text| text.find('\n'))
+                .map_or(body.len(), |index| range.start + index);
+            matches.push(FieldMatch {
+                search_field: SearchField::Body,
+                field: RequestField::Body(range.clone()),
+                preview: body
+                    .get(preview_start..preview_end)
+                    .unwrap_or_default()
+                    .to_string(),
+                ranges: Vec::new(),
+            });
+        }
+        if let Some(field_match) = matches.last_mut() {
+            field_match
+                .ranges
+                .push(range.start - preview_start..range.end.min(preview_end) - preview_start);
+        }
+    }
+    matches
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Replaces the matches of `query` in the `fields` of `request_file` with `replacement`,
+/// returning how many were replaced.
+pub(crate) fn replace_in_request_file(
+    request_file: &mut RequestFile,
+    query: &SearchQuery,
+    replacement: &str,
+    fields: &BTreeSet<SearchField>,
+) -> anyhow::Result<usize> {
+    let http = &mut request_file.http;
+    let replace_in = |search_field: SearchField, text: &mut String| {
+        if fields.contains(&search_field) {
+            replace_matches(text, query, replacement)
+        } else {
+            Ok(0)
+        }
+    };
+
+    let mut count = replace_in(SearchField::Method, &mut http.method)?;
+    count += replace_in(SearchField::Url, &mut http.url)?;
+    for header in &mut http.headers {
+        count += replace_in(SearchField::HeaderName, &mut header.name)?;
+        count += replace_in(SearchField::HeaderValue, &mut header.value)?;
+    }
+    if let Some(body) = &mut http.body {
+        count += replace_in(SearchField::Body, &mut body.data)?;
+    }
+    Ok(count)
+}
+
+fn replace_matches(
+    text: &mut String,
+    query: &SearchQuery,
+    replacement: &str,
+) -> anyhow::Result<usize> {
+    let replacements = query.replacements(text, replacement)?;
+    if replacements.is_empty() {
+        return Ok(0);
+    }
+
+    let mut new_text = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for (range, replacement) in &replacements {
+        new_text.push_str(text.get(last_end..range.start).unwrap_or_default());
+        new_text.push_str(replacement);
+        last_end = range.end;
+    }
+    new_text.push_str(text.get(last_end..).unwrap_or_default());
+    *text = new_text;
+    Ok(replacements.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use project::{RequestFileBody, RequestFileBodyType, RequestFileHeader};
+
+    fn request_file() -> RequestFile {
+        let mut request_file = RequestFile::default();
+        request_file.http.method = "POST".to_string();
+        request_file.http.url = "https://api.zaku.dev/v2/orders".to_string();
+        request_file.http.headers = vec![
+            RequestFileHeader {
+                name: "Accept".to_string(),
+                value: "application/json".to_string(),
+                disabled: false,
+            },
+            RequestFileHeader {
+                name: "Referer".to_string(),
+                value: "https://api.zaku.dev/v2/carts".to_string(),
+                disabled: true,
+            },
+        ];
+        request_file.http.body = Some(RequestFileBody {
+            r#type: RequestFileBodyType::Json,
+            data: "{\n  \"callback\": \"https://api.zaku.dev/hooks\",\n  \"orders\": [\"v2\"]\n}"
+                .to_string(),
+        });
+        request_file
+    }
+
+    fn all_fields() -> BTreeSet<SearchField> {
+        SearchField::ALL.into_iter().collect()
+    }
+
+    /// Describes the matches as the field to focus and the preview, with the matching ranges in
+    /// brackets.
+    fn describe(matches: &[FieldMatch]) -> Vec<String> {
+        matches
+            .iter()
+            .map(|field_match| {
+                let mut preview = String::new();
+                for (index, character) in field_match.preview.char_indices() {
+                    if field_match.ranges.iter().any(|range| range.start == index) {
+                        preview.push('[');
+                    }
+                    if field_match.ranges.iter().any(|range| range.end == index) {
+                        preview.push(']');
+                    }
+                    preview.push(character);
+                }
+                if field_match
+                    .ranges
+                    .iter()
+                    .any(|range| range.end == field_match.preview.len())
+                {
+                    preview.push(']');
+                }
+                format!("{:?}: {preview}", field_match.field)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search_request_file() {
+        let request_file = request_file();
+        let search = |query: &SearchQuery, fields: &BTreeSet<SearchField>| {
+            describe(&search_request_file(&request_file, query, fields).unwrap())
+        };
+
+        assert_eq!(
+            search(&SearchQuery::new("api.zaku.dev"), &all_fields()),
+            vec![
+                "Url: https://[api.zaku.dev]/v2/orders",
+                "HeaderValue(1): https://[api.zaku.dev]/v2/carts",
+                "Body(25..37):   \"callback\": \"https://[api.zaku.dev]/hooks\",",
+            ]
+        );
+        assert_eq!(
+            search(
+                &SearchQuery {
+                    regex: true,
+                    ..SearchQuery::new(r"/v\d+/orders")
+                },
+                &BTreeSet::from([SearchField::Url]),
+            ),
+            vec!["Url: https://api.zaku.dev[/v2/orders]"]
+        );
+        assert_eq!(
+            search(
+                &SearchQuery::new("accept"),
+                &BTreeSet::from([SearchField::HeaderName, SearchField::HeaderValue]),
+            ),
+            vec!["HeaderName(0): [Accept]"]
+        );
+        assert_eq!(
+            search(&SearchQuery::new("post"), &all_fields()),
+            vec!["Method: [POST]"]
+        );
+
+        let query = SearchQuery {
+            regex: true,
+            ..SearchQuery::new("(")
+        };
+        search_request_file(&request_file, &query, &all_fields())
+            .expect_err("an invalid regex should fail the search");
+    }
+
+    #[test]
+    fn test_body_matches_preview_long_lines() {
+        let body = format!("{}needle and needle\nneedle", "x".repeat(100));
+        let ranges = SearchQuery::new("needle").find_matches(&body).unwrap();
+
+        assert_eq!(
+            describe(&body_matches(&body, ranges)),
+            vec![
+                format!("Body(100..106): {}[needle] and [needle]", "x".repeat(40)),
+                "Body(118..124): [needle]".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_in_request_file() {
+        let mut request_file = request_file();
+        let count = replace_in_request_file(
+            &mut request_file,
+            &SearchQuery::new("api.zaku.dev"),
+            "staging.zaku.dev",
+            &all_fields(),
+        )
+        .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(request_file.http.url, "https://staging.zaku.dev/v2/orders");
+        assert_eq!(
+            request_file.http.headers.get(1).unwrap().value,
+            "https://staging.zaku.dev/v2/carts"
+        );
+        assert_eq!(
+            request_file.http.body.as_ref().unwrap().data,
+            "{\n  \"callback\": \"https://staging.zaku.dev/hooks\",\n  \"orders\": [\"v2\"]\n}"
+        );
+
+        let query = SearchQuery {
+            regex: true,
+            ..SearchQuery::new(r"/v(\d+)/")
+        };
+        let url_only = BTreeSet::from([SearchField::Url]);
+        let count =
+            replace_in_request_file(&mut request_file, &query, "/v${1}beta/", &url_only).unwrap();
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            request_file.http.url,
+            "https://staging.zaku.dev/v2beta/orders"
+        );
+        assert_eq!(
+            request_file.http.headers.get(1).unwrap().value,
+            "https://staging.zaku.dev/v2/carts"
+        );
+    }
+}
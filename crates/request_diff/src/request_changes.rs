@@ -0,0 +1,540 @@
+use std::ops::Range;
+
+use project::{
+    RequestFile, RequestFileBody, RequestFileBodyType, RequestFileHeader, RequestFileParam,
+};
+
+/// A part of a request that differs between two versions of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RequestChange {
+    Method,
+    Url,
+    Param(ListChange),
+    Header(ListChange),
+    /// The body was added, removed or changed type.
+    BodyType,
+    /// A run of changed lines in the body.
+    BodyText(BodyHunk),
+    Section(RequestSection),
+}
+
+/// How an entry of a list, like the headers, differs. Entries are matched by name, so moving an
+/// entry isn't a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListChange {
+    Added { new_index: usize },
+    Removed { old_index: usize },
+    Changed { old_index: usize, new_index: usize },
+}
+
+/// Byte ranges of the old and new body that differ, made of whole lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BodyHunk {
+    pub(crate) old_range: Range<usize>,
+    pub(crate) new_range: Range<usize>,
+}
+
+/// A part of a request that is only compared as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestSection {
+    PathVariables,
+    Schema,
+    Retry,
+    Auth,
+    Scripts,
+    Examples,
+}
+
+impl RequestSection {
+    const ALL: [Self; 6] = [
+        Self::PathVariables,
+        Self::Schema,
+        Self::Retry,
+        Self::Auth,
+        Self::Scripts,
+        Self::Examples,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::PathVariables => "Path variables",
+            Self::Schema => "Schema",
+            Self::Retry => "Retry",
+            Self::Auth => "Authorization",
+            Self::Scripts => "Scripts",
+            Self::Examples => "Examples",
+        }
+    }
+
+    fn is_changed(self, old: &RequestFile, new: &RequestFile) -> bool {
+        match self {
+            Self::PathVariables => old.http.path_variables != new.http.path_variables,
+            Self::Schema => old.http.schema != new.http.schema,
+            Self::Retry => old.http.retry != new.http.retry,
+            Self::Auth => old.http.auth != new.http.auth,
+            Self::Scripts => old.http.scripts != new.http.scripts,
+            Self::Examples => old.examples != new.examples,
+        }
+    }
+
+    fn revert(self, old: &RequestFile, new: &mut RequestFile) {
+        match self {
+            Self::PathVariables => new.http.path_variables = old.http.path_variables.clone(),
+            Self::Schema => new.http.schema = old.http.schema.clone(),
+            Self::Retry => new.http.retry = old.http.retry.clone(),
+            Self::Auth => new.http.auth = old.http.auth.clone(),
+            Self::Scripts => new.http.scripts = old.http.scripts.clone(),
+            Self::Examples => new.examples = old.examples.clone(),
+        }
+    }
+}
+
+/// What a change looks like to someone reviewing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChangeDescription {
+    pub(crate) title: String,
+    /// What the old version has, if the change can be shown as text.
+    pub(crate) old: Option<String>,
+    /// What the new version has, if the change can be shown as text.
+    pub(crate) new: Option<String>,
+}
+
+/// The changes from `old` to `new`, in the order their fields appear in the request editor.
+pub(crate) fn diff_request_files(old: &RequestFile, new: &RequestFile) -> Vec<RequestChange> {
+    let mut changes = Vec::new();
+    if old.http.method != new.http.method {
+        changes.push(RequestChange::Method);
+    }
+    if old.http.url != new.http.url {
+        changes.push(RequestChange::Url);
+    }
+    changes.extend(
+        diff_lists(&old.http.params, &new.http.params, |old, new| {
+            old.name == new.name
+        })
+        .into_iter()
+        .map(RequestChange::Param),
+    );
+    changes.extend(
+        diff_lists(&old.http.headers, &new.http.headers, |old, new| {
+            old.name.eq_ignore_ascii_case(&new.name)
+        })
+        .into_iter()
+        .map(RequestChange::Header),
+    );
+    if body_type(old) != body_type(new) {
+        changes.push(RequestChange::BodyType);
+    }
+    changes.extend(
+        body_hunks(body_data(old), body_data(new))
+            .into_iter()
+            .map(RequestChange::BodyText),
+    );
+    changes.extend(
+        RequestSection::ALL
+            .into_iter()
+            .filter(|section| section.is_changed(old, new))
+            .map(RequestChange::Section),
+    );
+    changes
+}
+
+/// Matches the entries of two lists, first to equal entries and then to the next unmatched entry
+/// with the same name.
+fn diff_lists<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    same_name: impl Fn(&T, &T) -> bool,
+) -> Vec<ListChange> {
+    let mut old_matched = vec![false; old.len()];
+    let mut new_matches = vec![None; new.len()];
+
+    for (new_index, new_entry) in new.iter().enumerate() {
+        let old_index = old.iter().enumerate().position(|(old_index, old_entry)| {
+            !old_matched.get(old_index).copied().unwrap_or(true) && old_entry == new_entry
+        });
+        if let Some(old_index) = old_index {
+            set(&mut old_matched, old_index, true);
+            set(&mut new_matches, new_index, Some(old_index));
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (new_index, new_entry) in new.iter().enumerate() {
+        if new_matches.get(new_index).copied().flatten().is_some() {
+            continue;
+        }
+        let old_index = old.iter().enumerate().position(|(old_index, old_entry)| {
+            !old_matched.get(old_index).copied().unwrap_or(true) && same_name(old_entry, new_entry)
+        });
+        match old_index {
+            Some(old_index) => {
+                set(&mut old_matched, old_index, true);
+                changes.push(ListChange::Changed {
+                    old_index,
+                    new_index,
+                });
+            }
+            None => changes.push(ListChange::Added { new_index }),
+        }
+    }
+    changes.extend(
+        old_matched
+            .iter()
+            .enumerate()
+            .filter(|(_, matched)| !**matched)
+            .map(|(old_index, _)| ListChange::Removed { old_index }),
+    );
+    changes
+}
+
+fn set<T>(items: &mut [T], index: usize, value: T) {
+    if let Some(item) = items.get_mut(index) {
+        *item = value;
+    }
+}
+
+fn body_type(request_file: &RequestFile) -> Option<RequestFileBodyType> {
+    request_file.http.body.as_ref().map(|body| body.r#type)
+}
+
+fn body_data(request_file: &RequestFile) -> &str {
+    request_file
+        .http
+        .body
+        .as_ref()
+        .map_or("", |body| body.data.as_str())
+}
+
+fn body_hunks(old: &str, new: &str) -> Vec<BodyHunk> {
+    let mut new_offset: isize = 0;
+    language::text_diff(old, new)
+        .into_iter()
+        .map(|(old_range, new_text)| {
+            let new_start = old_range.start.saturating_add_signed(new_offset);
+            let new_range = new_start..new_start + new_text.len();
+            new_offset += new_text.len() as isize - old_range.len() as isize;
+            BodyHunk {
+                old_range,
+                new_range,
+            }
+        })
+        .collect()
+}
+
+impl RequestChange {
+    pub(crate) fn describe(&self, old: &RequestFile, new: &RequestFile) -> ChangeDescription {
+        match self {
+            Self::Method => ChangeDescription {
+                title: "Method".to_string(),
+                old: Some(old.http.method.clone()),
+                new: Some(new.http.method.clone()),
+            },
+            Self::Url => ChangeDescription {
+                title: "URL".to_string(),
+                old: Some(old.http.url.clone()),
+                new: Some(new.http.url.clone()),
+            },
+            Self::Param(change) => {
+                describe_list_change("Parameter", *change, &old.http.params, &new.http.params)
+            }
+            Self::Header(change) => {
+                describe_list_change("Header", *change, &old.http.headers, &new.http.headers)
+            }
+            Self::BodyType => ChangeDescription {
+                title: "Body type".to_string(),
+                old: Some(body_type_label(body_type(old))),
+                new: Some(body_type_label(body_type(new))),
+            },
+            Self::BodyText(hunk) => {
+                let new_body = body_data(new);
+                let line = new_body
+                    .get(..hunk.new_range.start)
+                    .map_or(0, |text| text.matches('\n').count())
+                    + 1;
+                let lines = |body: &str, range: &Range<usize>| {
+                    body.get(range.clone())
+                        .filter(|text| !text.is_empty())
+                        .map(|text| text.strip_suffix('\n').unwrap_or(text).to_string())
+                };
+                ChangeDescription {
+                    title: format!("Body, line {line}"),
+                    old: lines(body_data(old), &hunk.old_range),
+                    new: lines(new_body, &hunk.new_range),
+                }
+            }
+            Self::Section(section) => ChangeDescription {
+                title: section.label().to_string(),
+                old: None,
+                new: None,
+            },
+        }
+    }
+}
+
+trait ListEntry {
+    fn name(&self) -> &str;
+    fn display(&self) -> String;
+}
+
+impl ListEntry for RequestFileParam {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn display(&self) -> String {
+        let param = format!("{}={}", self.name, self.value);
+        if self.disabled {
+            format!("{param} (disabled)")
+        } else {
+            param
+        }
+    }
+}
+
+impl ListEntry for RequestFileHeader {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn display(&self) -> String {
+        let header = format!("{}: {}", self.name, self.value);
+        if self.disabled {
+            format!("{header} (disabled)")
+        } else {
+            header
+        }
+    }
+}
+
+fn describe_list_change<T: ListEntry>(
+    kind: &str,
+    change: ListChange,
+    old: &[T],
+    new: &[T],
+) -> ChangeDescription {
+    let (old_entry, new_entry) = match change {
+        ListChange::Added { new_index } => (None, new.get(new_index)),
+        ListChange::Removed { old_index } => (old.get(old_index), None),
+        ListChange::Changed {
+            old_index,
+            new_index,
+        } => (old.get(old_index), new.get(new_index)),
+    };
+    let name = new_entry.or(old_entry).map_or("", |entry| entry.name());
+    ChangeDescription {
+        title: format!("{kind} {name}"),
+        old: old_entry.map(ListEntry::display),
+        new: new_entry.map(ListEntry::display),
+    }
+}
+
+fn body_type_label(body_type: Option<RequestFileBodyType>) -> String {
+    body_type
+        .map_or("None", |body_type| body_type.display_name())
+        .to_string()
+}
+
+/// Undoes `change` in `new`, bringing back what `old` has there.
+pub(crate) fn revert_change(change: &RequestChange, old: &RequestFile, new: &mut RequestFile) {
+    match change {
+        RequestChange::Method => new.http.method = old.http.method.clone(),
+        RequestChange::Url => new.http.url = old.http.url.clone(),
+        RequestChange::Param(change) => {
+            revert_list_change(*change, &old.http.params, &mut new.http.params);
+        }
+        RequestChange::Header(change) => {
+            revert_list_change(*change, &old.http.headers, &mut new.http.headers);
+        }
+        RequestChange::BodyType => match (&old.http.body, &mut new.http.body) {
+            (Some(old_body), Some(new_body)) => new_body.r#type = old_body.r#type,
+            (old_body, new_body) => new_body.clone_from(old_body),
+        },
+        RequestChange::BodyText(hunk) => {
+            let Some(old_text) = body_data(old).get(hunk.old_range.clone()) else {
+                return;
+            };
+            let body = new.http.body.get_or_insert_with(|| RequestFileBody {
+                r#type: body_type(old).unwrap_or(RequestFileBodyType::Text),
+                data: String::new(),
+            });
+            if body.data.get(hunk.new_range.clone()).is_some() {
+                body.data.replace_range(hunk.new_range.clone(), old_text);
+            }
+        }
+        RequestChange::Section(section) => section.revert(old, new),
+    }
+}
+
+fn revert_list_change<T: Clone>(change: ListChange, old: &[T], new: &mut Vec<T>) {
+    match change {
+        ListChange::Added { new_index } => {
+            if new_index < new.len() {
+                new.remove(new_index);
+            }
+        }
+        ListChange::Removed { old_index } => {
+            if let Some(old_entry) = old.get(old_index) {
+                new.insert(old_index.min(new.len()), old_entry.clone());
+            }
+        }
+        ListChange::Changed {
+            old_index,
+            new_index,
+        } => {
+            if let (Some(old_entry), Some(new_entry)) = (old.get(old_index), new.get_mut(new_index))
+            {
+                new_entry.clone_from(old_entry);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use project::{RequestFileAuth, RequestFileOAuth2};
+
+    fn header(name: &str, value: &str) -> RequestFileHeader {
+        RequestFileHeader {
+            name: name.to_string(),
+            value: value.to_string(),
+            disabled: false,
+        }
+    }
+
+    fn param(name: &str, value: &str) -> RequestFileParam {
+        RequestFileParam {
+            name: name.to_string(),
+            value: value.to_string(),
+            disabled: false,
+        }
+    }
+
+    fn request_file() -> RequestFile {
+        let mut request_file = RequestFile::default();
+        request_file.http.method = "POST".to_string();
+        request_file.http.url = "https://api.zaku.dev/orders".to_string();
+        request_file.http.params = vec![param("tag", "a"), param("tag", "b")];
+        request_file.http.headers = vec![
+            header("Accept", "application/json"),
+            header("Authorization", "Bearer {{token}}"),
+            header("X-Trace", "on"),
+        ];
+        request_file.http.body = Some(RequestFileBody {
+            r#type: RequestFileBodyType::Json,
+            data: "{\n  \"sku\": \"zaku-1\",\n  \"quantity\": 1\n}\n".to_string(),
+        });
+        request_file
+    }
+
+    fn describe(old: &RequestFile, new: &RequestFile) -> Vec<String> {
+        diff_request_files(old, new)
+            .iter()
+            .map(|change| {
+                let description = change.describe(old, new);
+                format!(
+                    "{}: {} -> {}",
+                    description.title,
+                    description.old.as_deref().unwrap_or("-"),
+                    description.new.as_deref().unwrap_or("-"),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reordering_is_not_a_change() {
+        let old = request_file();
+        let mut new = request_file();
+        new.http.headers.reverse();
+        new.http.params.reverse();
+
+        assert_eq!(diff_request_files(&old, &new), Vec::new());
+    }
+
+    #[test]
+    fn test_diff_request_files() {
+        let old = request_file();
+        let mut new = request_file();
+        new.http.method = "PUT".to_string();
+        new.http.params = vec![param("tag", "b"), param("tag", "c"), param("page", "2")];
+        new.http.headers = vec![
+            header("x-trace", "off"),
+            header("Accept", "application/json"),
+            header("Content-Type", "application/json"),
+        ];
+        if let Some(body) = &mut new.http.body {
+            body.data = "{\n  \"sku\": \"zaku-2\",\n  \"quantity\": 1\n}\n".to_string();
+        }
+        new.http.auth = Some(RequestFileAuth::OAuth2(RequestFileOAuth2::default()));
+
+        assert_eq!(
+            describe(&old, &new),
+            vec![
+                "Method: POST -> PUT",
+                "Parameter tag: tag=a -> tag=c",
+                "Parameter page: - -> page=2",
+                "Header x-trace: X-Trace: on -> x-trace: off",
+                "Header Content-Type: - -> Content-Type: application/json",
+                "Header Authorization: Authorization: Bearer {{token}} -> -",
+                "Body, line 2:   \"sku\": \"zaku-1\", ->   \"sku\": \"zaku-2\",",
+                "Authorization: - -> -",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_added_body() {
+        let mut old = request_file();
+        old.http.body = None;
+        let new = request_file();
+
+        assert_eq!(
+            describe(&old, &new),
+            vec![
+                "Body type: None -> JSON",
+                "Body, line 1: - -> {\n  \"sku\": \"zaku-1\",\n  \"quantity\": 1\n}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_revert_change() {
+        let old = request_file();
+        let mut new = request_file();
+        new.http.url = "https://staging.zaku.dev/orders".to_string();
+        new.http.headers.remove(0);
+        new.http.headers.push(header("Cache-Control", "no-cache"));
+        if let Some(body) = &mut new.http.body {
+            body.data =
+                "{\n  \"sku\": \"zaku-1\",\n  \"quantity\": 2,\n  \"gift\": true\n}\n".to_string();
+        }
+
+        let revert = |title: &str, new: &mut RequestFile| {
+            let change = diff_request_files(&old, new)
+                .into_iter()
+                .find(|change| change.describe(&old, new).title == title)
+                .unwrap();
+            revert_change(&change, &old, new);
+        };
+
+        revert("Header Accept", &mut new);
+        assert_eq!(
+            describe(&old, &new),
+            vec![
+                "URL: https://api.zaku.dev/orders -> https://staging.zaku.dev/orders",
+                "Header Cache-Control: - -> Cache-Control: no-cache",
+                "Body, line 3:   \"quantity\": 1 ->   \"quantity\": 2,\n  \"gift\": true",
+            ]
+        );
+
+        revert("Body, line 3", &mut new);
+        revert("Header Cache-Control", &mut new);
+        revert("URL", &mut new);
+        assert_eq!(new, old);
+    }
+}
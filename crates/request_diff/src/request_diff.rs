@@ -0,0 +1,414 @@
+mod request_changes;
+mod request_history;
+
+use gpui::{
+    AnyElement, App, Context, Div, Entity, EventEmitter, FocusHandle, Focusable, Render,
+    SharedString, Subscription, Task, WeakEntity, Window, prelude::*,
+};
+
+use project::{
+    Project, ProjectPath, RequestFile,
+    git_store::{GitStoreEvent, RepositoryEvent},
+};
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, Text, TextCommon, TextSize,
+    Tooltip,
+};
+use workspace::{Item, ItemEvent, Toast, Workspace, notifications::NotificationId};
+
+use request_changes::{RequestChange, diff_request_files, revert_change};
+use request_history::RequestHistoryView;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace.register_action(
+                |workspace, _: &actions::request_diff::CompareWithHead, window, cx| {
+                    RequestDiffView::deploy(workspace, window, cx);
+                },
+            );
+            workspace.register_action(
+                |workspace, _: &actions::request_diff::ShowFileHistory, window, cx| {
+                    RequestHistoryView::deploy(workspace, window, cx);
+                },
+            );
+        },
+    )
+    .detach();
+}
+
+/// The path of the request in the active item, if it shows one.
+fn active_request_path(workspace: &Workspace, cx: &App) -> Option<ProjectPath> {
+    workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .filter(|project_path| project::is_request_path(project_path.path.as_std_path()))
+}
+
+/// The version of a request in HEAD, the version on disk and the changes between them.
+struct RequestDiff {
+    committed: RequestFile,
+    current: RequestFile,
+    changes: Vec<RequestChange>,
+}
+
+enum DiffState {
+    Loading,
+    Loaded(RequestDiff),
+    /// HEAD doesn't have the request, so all of it is new.
+    NotCommitted,
+    Error(SharedString),
+}
+
+/// Compares a request with its version in HEAD field by field, so that moving a header or
+/// parameter doesn't show up as a change.
+pub struct RequestDiffView {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    project_path: ProjectPath,
+    state: DiffState,
+    focus_handle: FocusHandle,
+    load_task: Task<()>,
+    revert_task: Task<()>,
+    _git_store_subscription: Subscription,
+}
+
+impl RequestDiffView {
+    /// Opens the comparison of the active request, or activates it if it is already open.
+    fn deploy(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(project_path) = active_request_path(workspace, cx) else {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<Self>(),
+                    "Open a request to compare it with HEAD.",
+                )
+                .autohide(),
+                cx,
+            );
+            return;
+        };
+
+        let existing = workspace
+            .pane()
+            .read(cx)
+            .items()
+            .enumerate()
+            .find_map(|(index, item)| {
+                let view = item.downcast::<Self>()?;
+                (view.read(cx).project_path == project_path).then_some((index, view))
+            });
+        if let Some((index, view)) = existing {
+            view.update(cx, |view, cx| view.load(cx));
+            workspace.pane().update(cx, |pane, cx| {
+                pane.activate_item(index, true, true, window, cx);
+            });
+            return;
+        }
+
+        let workspace_handle = cx.weak_entity();
+        let project = workspace.project().clone();
+        let view = cx.new(|cx| Self::new(workspace_handle, project, project_path, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let git_store_subscription = cx.subscribe(
+            project.read(cx).git_store(),
+            |this, _, event: &GitStoreEvent, cx| {
+                if let GitStoreEvent::RepositoryUpdated(
+                    _,
+                    RepositoryEvent::StatusesChanged | RepositoryEvent::HeadChanged,
+                    _,
+                ) = event
+                {
+                    this.load(cx);
+                }
+            },
+        );
+
+        let mut this = Self {
+            workspace,
+            project,
+            project_path,
+            state: DiffState::Loading,
+            focus_handle: cx.focus_handle(),
+            load_task: Task::ready(()),
+            revert_task: Task::ready(()),
+            _git_store_subscription: git_store_subscription,
+        };
+        this.load(cx);
+        this
+    }
+
+    /// Loads both versions of the request again. The last comparison stays on screen until then.
+    fn load(&mut self, cx: &mut Context<Self>) {
+        let (committed, current) = self.project.update(cx, |project, cx| {
+            (
+                project.load_committed_request_file(&self.project_path, cx),
+                project.load_request_file(&self.project_path, cx),
+            )
+        });
+
+        self.load_task = cx.spawn(async move |this, cx| {
+            let state = match (committed.await, current.await) {
+                (Ok(Some(committed)), Ok(current)) => {
+                    let changes = diff_request_files(&committed, &current);
+                    DiffState::Loaded(RequestDiff {
+                        committed,
+                        current,
+                        changes,
+                    })
+                }
+                (Ok(None), Ok(_)) => DiffState::NotCommitted,
+                (Err(error), _) | (_, Err(error)) => DiffState::Error(format!("{error:#}").into()),
+            };
+
+            if let Err(error) = this.update(cx, |this, cx| {
+                this.state = state;
+                cx.notify();
+            }) {
+                log::debug!("Failed to show request diff: {error:?}");
+            }
+        });
+    }
+
+    /// Undoes a change by writing the request back with that part of HEAD's version.
+    fn revert(&mut self, index: usize, cx: &mut Context<Self>) {
+        let DiffState::Loaded(diff) = &self.state else {
+            return;
+        };
+        let Some(change) = diff.changes.get(index) else {
+            return;
+        };
+        let mut request_file = diff.current.clone();
+        revert_change(change, &diff.committed, &mut request_file);
+
+        let write_request_file = self.project.update(cx, |project, cx| {
+            project.write_request_file(self.project_path.clone(), request_file, cx)
+        });
+        self.revert_task = cx.spawn(async move |this, cx| {
+            let result = write_request_file.await;
+            if let Err(error) = this.update(cx, |this, cx| {
+                if let Err(error) = result {
+                    this.show_message(format!("Failed to revert the change: {error:#}"), cx);
+                }
+                this.load(cx);
+            }) {
+                log::debug!("Failed to revert request change: {error:?}");
+            }
+        });
+    }
+
+    fn show_message(&self, message: impl Into<SharedString>, cx: &mut App) {
+        if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<Self>(), message).autohide(),
+                cx,
+            );
+        }) {
+            log::debug!("Failed to show request diff message: {error:?}");
+        }
+    }
+
+    fn render_row(diff: &RequestDiff, index: usize, cx: &mut Context<Self>) -> AnyElement {
+        let Some(change) = diff.changes.get(index) else {
+            return gpui::div().into_any_element();
+        };
+        let revert_button = Button::new(("request-diff-revert", index), "Revert")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::Compact)
+            .tooltip(Tooltip::text("Revert to the version in HEAD"))
+            .on_click(cx.listener(move |this, _, _, cx| this.revert(index, cx)));
+        render_change(
+            change,
+            &diff.committed,
+            &diff.current,
+            Some(revert_button.into_any_element()),
+            cx,
+        )
+    }
+}
+
+fn render_values(values: Option<String>, prefix: &'static str, color: Color, cx: &App) -> Div {
+    gpui::div().flex().flex_col().children(
+        values
+            .into_iter()
+            .flat_map(|values| {
+                values
+                    .lines()
+                    .map(|line| format!("{prefix} {line}"))
+                    .collect::<Vec<_>>()
+            })
+            .map(|line| {
+                Text::new(line)
+                    .size(TextSize::Small)
+                    .color(color)
+                    .font_buffer(cx)
+            }),
+    )
+}
+
+/// A row showing `change` from the `old` version of a request to the `new` one, with `action` at
+/// its end.
+fn render_change(
+    change: &RequestChange,
+    old: &RequestFile,
+    new: &RequestFile,
+    action: Option<AnyElement>,
+    cx: &App,
+) -> AnyElement {
+    let description = change.describe(old, new);
+    let is_whole_section = description.old.is_none() && description.new.is_none();
+
+    gpui::div()
+        .flex()
+        .items_start()
+        .gap_2()
+        .px_3()
+        .py_2()
+        .border_b_1()
+        .border_color(cx.theme().colors().border_variant)
+        .child(
+            gpui::div()
+                .flex_1()
+                .min_w_0()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(Text::new(description.title).size(TextSize::Small))
+                .when(is_whole_section, |this| {
+                    this.child(
+                        Text::new("Changed")
+                            .size(TextSize::Small)
+                            .color(Color::Modified),
+                    )
+                })
+                .child(render_values(description.old, "-", Color::Deleted, cx))
+                .child(render_values(description.new, "+", Color::Created, cx)),
+        )
+        .children(action)
+        .into_any_element()
+}
+
+fn render_message(message: impl Into<SharedString>, color: Color) -> AnyElement {
+    gpui::div()
+        .flex_1()
+        .flex()
+        .items_center()
+        .justify_center()
+        .px_3()
+        .child(Text::new(message).size(TextSize::Small).color(color))
+        .into_any_element()
+}
+
+impl EventEmitter<ItemEvent> for RequestDiffView {}
+
+impl Focusable for RequestDiffView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RequestDiffView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let path = self
+            .project_path
+            .path
+            .display(self.project.read(cx).path_style(cx))
+            .into_owned();
+
+        let (summary, content) = match &self.state {
+            DiffState::Loading => (
+                SharedString::default(),
+                render_message("Loading…", Color::Muted),
+            ),
+            DiffState::NotCommitted => (
+                SharedString::default(),
+                render_message("This request isn't in HEAD yet.", Color::Muted),
+            ),
+            DiffState::Error(error) => (
+                SharedString::default(),
+                render_message(error.clone(), Color::Error),
+            ),
+            DiffState::Loaded(diff) if diff.changes.is_empty() => (
+                SharedString::default(),
+                render_message("No changes since HEAD.", Color::Muted),
+            ),
+            DiffState::Loaded(diff) => {
+                let count = diff.changes.len();
+                let summary = format!(
+                    "{count} {} since HEAD",
+                    if count == 1 { "change" } else { "changes" }
+                );
+                let rows = (0..count)
+                    .map(|index| Self::render_row(diff, index, cx))
+                    .collect::<Vec<_>>();
+                (
+                    summary.into(),
+                    gpui::div()
+                        .id("request-diff-changes")
+                        .flex_1()
+                        .min_h_0()
+                        .overflow_y_scroll()
+                        .children(rows)
+                        .into_any_element(),
+                )
+            }
+        };
+
+        let colors = cx.theme().colors();
+        gpui::div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(colors.editor_background)
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_2()
+                    .px_3()
+                    .py_1p5()
+                    .border_b_1()
+                    .border_color(colors.border)
+                    .child(Text::new(path).size(TextSize::Small).truncate())
+                    .child(
+                        Text::new(summary)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+            )
+            .child(content)
+    }
+}
+
+impl Item for RequestDiffView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, emitter: &mut dyn FnMut(ItemEvent)) {
+        emitter(*event);
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let name = self.project_path.path.file_name().unwrap_or_default();
+        format!("{name} vs HEAD").into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self
+            .project_path
+            .path
+            .display(self.project.read(cx).path_style(cx))
+            .into_owned();
+        Some(format!("Changes to {path} since HEAD").into())
+    }
+}
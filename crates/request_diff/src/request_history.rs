@@ -0,0 +1,561 @@
+use anyhow::Context as _;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel, Render,
+    SharedString, Subscription, Task, WeakEntity, Window, prelude::*,
+};
+use jiff::{Timestamp, tz::TimeZone};
+
+use git::repository::FileHistoryEntry;
+use project::{
+    Project, ProjectPath, RequestFile,
+    git_store::{GitStoreEvent, RepositoryEvent},
+};
+use request_editor::RequestEditor;
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, Disableable, Text,
+    TextCommon, TextSize, Tooltip,
+};
+use workspace::{Item, ItemEvent, Toast, Workspace, notifications::NotificationId};
+
+use crate::{
+    active_request_path, render_change, render_message,
+    request_changes::{RequestChange, diff_request_files},
+};
+
+const SHORT_SHA_LENGTH: usize = 7;
+
+enum HistoryState {
+    Loading,
+    Loaded(Vec<FileHistoryEntry>),
+    Error(SharedString),
+}
+
+/// The request as a commit left it and what the commit changed in it.
+struct CommitChanges {
+    version: RequestFile,
+    /// The request before the commit, or `None` if the commit added it.
+    previous: Option<RequestFile>,
+    changes: Vec<RequestChange>,
+}
+
+enum CommitState {
+    Loading,
+    Loaded(CommitChanges),
+    /// The commit doesn't have the request, as it deleted it.
+    Deleted,
+    Error(SharedString),
+}
+
+/// Lists the commits that changed a request and what each of them changed, field by field.
+pub struct RequestHistoryView {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    project_path: ProjectPath,
+    history: HistoryState,
+    selected_index: Option<usize>,
+    commit: CommitState,
+    focus_handle: FocusHandle,
+    history_task: Task<()>,
+    commit_task: Task<()>,
+    open_task: Task<()>,
+    _git_store_subscription: Subscription,
+}
+
+impl RequestHistoryView {
+    /// Opens the history of the active request, or activates it if it is already open.
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(project_path) = active_request_path(workspace, cx) else {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<Self>(),
+                    "Open a request to show its history.",
+                )
+                .autohide(),
+                cx,
+            );
+            return;
+        };
+
+        let existing = workspace
+            .pane()
+            .read(cx)
+            .items()
+            .enumerate()
+            .find_map(|(index, item)| {
+                let view = item.downcast::<Self>()?;
+                (view.read(cx).project_path == project_path).then_some((index, view))
+            });
+        if let Some((index, view)) = existing {
+            view.update(cx, |view, cx| view.load_history(cx));
+            workspace.pane().update(cx, |pane, cx| {
+                pane.activate_item(index, true, true, window, cx);
+            });
+            return;
+        }
+
+        let workspace_handle = cx.weak_entity();
+        let project = workspace.project().clone();
+        let view = cx.new(|cx| Self::new(workspace_handle, project, project_path, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let git_store_subscription = cx.subscribe(
+            project.read(cx).git_store(),
+            |this, _, event: &GitStoreEvent, cx| {
+                if let GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::HeadChanged, _) = event
+                {
+                    this.load_history(cx);
+                }
+            },
+        );
+
+        let mut this = Self {
+            workspace,
+            project,
+            project_path,
+            history: HistoryState::Loading,
+            selected_index: None,
+            commit: CommitState::Loading,
+            focus_handle: cx.focus_handle(),
+            history_task: Task::ready(()),
+            commit_task: Task::ready(()),
+            open_task: Task::ready(()),
+            _git_store_subscription: git_store_subscription,
+        };
+        this.load_history(cx);
+        this
+    }
+
+    fn entries(&self) -> &[FileHistoryEntry] {
+        match &self.history {
+            HistoryState::Loaded(entries) => entries,
+            HistoryState::Loading | HistoryState::Error(_) => &[],
+        }
+    }
+
+    /// Lists the commits again, keeping the selected one selected if it is still there.
+    fn load_history(&mut self, cx: &mut Context<Self>) {
+        let file_history = self.project.update(cx, |project, cx| {
+            project.request_file_history(&self.project_path, cx)
+        });
+
+        self.history_task = cx.spawn(async move |this, cx| {
+            let history = file_history.await;
+            if let Err(error) = this.update(cx, |this, cx| {
+                let selected_sha = this
+                    .selected_index
+                    .and_then(|index| this.entries().get(index))
+                    .map(|entry| entry.commit.sha.clone());
+                this.history = match history {
+                    Ok(entries) => HistoryState::Loaded(entries),
+                    Err(error) => HistoryState::Error(format!("{error:#}").into()),
+                };
+                let selected_index = selected_sha
+                    .and_then(|sha| {
+                        this.entries()
+                            .iter()
+                            .position(|entry| entry.commit.sha == sha)
+                    })
+                    .unwrap_or(0);
+                if this.entries().is_empty() {
+                    this.selected_index = None;
+                    cx.notify();
+                } else {
+                    this.select(selected_index, cx);
+                }
+            }) {
+                log::debug!("Failed to show request history: {error:?}");
+            }
+        });
+    }
+
+    /// Shows what the commit at `index` changed, comparing the request as it left it with the
+    /// request as the commit before it in the history left it.
+    fn select(&mut self, index: usize, cx: &mut Context<Self>) {
+        let entries = self.entries();
+        let Some(entry) = entries.get(index) else {
+            return;
+        };
+        let previous_entry = entries.get(index + 1);
+        let (version, previous) = self.project.update(cx, |project, cx| {
+            (
+                project.load_request_file_at_commit(&self.project_path, entry, cx),
+                previous_entry.map(|previous_entry| {
+                    project.load_request_file_at_commit(&self.project_path, previous_entry, cx)
+                }),
+            )
+        });
+
+        self.selected_index = Some(index);
+        self.commit = CommitState::Loading;
+        cx.notify();
+        self.commit_task = cx.spawn(async move |this, cx| {
+            let previous = match previous {
+                Some(previous) => previous.await,
+                None => Ok(None),
+            };
+            let state = match (version.await, previous) {
+                (Ok(Some(version)), Ok(previous)) => {
+                    let changes = previous
+                        .as_ref()
+                        .map(|previous| diff_request_files(previous, &version))
+                        .unwrap_or_default();
+                    CommitState::Loaded(CommitChanges {
+                        version,
+                        previous,
+                        changes,
+                    })
+                }
+                (Ok(None), Ok(_)) => CommitState::Deleted,
+                (Err(error), _) | (_, Err(error)) => {
+                    CommitState::Error(format!("{error:#}").into())
+                }
+            };
+
+            if let Err(error) = this.update(cx, |this, cx| {
+                this.commit = state;
+                cx.notify();
+            }) {
+                log::debug!("Failed to show request changes of a commit: {error:?}");
+            }
+        });
+    }
+
+    /// Opens the request with the selected version in place of its content, as unsaved changes.
+    /// If the request already has unsaved changes, asks before discarding them.
+    fn open_version(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let CommitState::Loaded(commit) = &self.commit else {
+            return;
+        };
+        let request_file = commit.version.clone();
+        let project_path = self.project_path.clone();
+        let workspace = self.workspace.clone();
+
+        self.open_task = cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let request_editor = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_path(project_path, None, true, window, cx)
+                    })?
+                    .await?
+                    .downcast::<RequestEditor>()
+                    .context("The request file couldn't be opened as a request.")?;
+                let prompt = request_editor.update_in(cx, |request_editor, window, cx| {
+                    request_editor.is_dirty(cx).then(|| {
+                        window.prompt(
+                            PromptLevel::Warning,
+                            "Discard unsaved changes to open this version?",
+                            None,
+                            &["Discard Changes", "Cancel"],
+                            cx,
+                        )
+                    })
+                })?;
+                if let Some(prompt) = prompt
+                    && !matches!(prompt.await, Ok(0))
+                {
+                    return anyhow::Ok(());
+                }
+                request_editor.update_in(cx, |request_editor, window, cx| {
+                    request_editor.restore_request_file(request_file, window, cx);
+                })?;
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result
+                && let Err(error) =
+                    this.update(cx, |this, cx| this.show_message(error.to_string(), cx))
+            {
+                log::debug!("Failed to report request history error: {error:?}");
+            }
+        });
+    }
+
+    fn show_message(&self, message: impl Into<SharedString>, cx: &mut App) {
+        if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<Self>(), message).autohide(),
+                cx,
+            );
+        }) {
+            log::debug!("Failed to show request history message: {error:?}");
+        }
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        entry: &FileHistoryEntry,
+        time_zone: &TimeZone,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let is_selected = self.selected_index == Some(index);
+        let details = format!(
+            "{} · {} · {}",
+            entry.commit.author_name,
+            format_commit_time(entry.commit.commit_timestamp, time_zone),
+            short_sha(&entry.commit.sha),
+        );
+
+        gpui::div()
+            .id(("request-history-commit", index))
+            .flex()
+            .flex_col()
+            .px_3()
+            .py_1()
+            .cursor_pointer()
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .when(is_selected, |this| this.bg(colors.ghost_element_selected))
+            .hover(|this| this.bg(colors.ghost_element_hover))
+            .on_click(cx.listener(move |this, _, _, cx| this.select(index, cx)))
+            .child(
+                Text::new(entry.commit.subject.clone())
+                    .size(TextSize::Small)
+                    .single_line()
+                    .truncate(),
+            )
+            .child(
+                Text::new(details)
+                    .size(TextSize::XSmall)
+                    .color(Color::Muted)
+                    .single_line()
+                    .truncate(),
+            )
+            .into_any_element()
+    }
+
+    fn render_commit(&self, time_zone: &TimeZone, cx: &mut Context<Self>) -> AnyElement {
+        let Some(entry) = self
+            .selected_index
+            .and_then(|index| self.entries().get(index))
+        else {
+            return render_message("Select a commit to see what it changed.", Color::Muted);
+        };
+
+        let content = match &self.commit {
+            CommitState::Loading => render_message("Loading…", Color::Muted),
+            CommitState::Deleted => {
+                render_message("This commit deleted the request.", Color::Muted)
+            }
+            CommitState::Error(error) => render_message(error.clone(), Color::Error),
+            CommitState::Loaded(commit) => match &commit.previous {
+                None => render_message("This commit added the request.", Color::Muted),
+                Some(_) if commit.changes.is_empty() => {
+                    render_message("This commit didn't change the request.", Color::Muted)
+                }
+                Some(previous) => {
+                    let rows = commit
+                        .changes
+                        .iter()
+                        .map(|change| render_change(change, previous, &commit.version, None, cx))
+                        .collect::<Vec<_>>();
+                    gpui::div()
+                        .id("request-history-changes")
+                        .flex_1()
+                        .min_h_0()
+                        .overflow_y_scroll()
+                        .children(rows)
+                        .into_any_element()
+                }
+            },
+        };
+
+        let colors = cx.theme().colors();
+        let details = format!(
+            "{} · {} · {}",
+            entry.commit.author_name,
+            format_commit_time(entry.commit.commit_timestamp, time_zone),
+            entry.commit.sha,
+        );
+        gpui::div()
+            .flex_1()
+            .min_w_0()
+            .flex()
+            .flex_col()
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_3()
+                    .py_1p5()
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(
+                        gpui::div()
+                            .flex_1()
+                            .min_w_0()
+                            .flex()
+                            .flex_col()
+                            .child(
+                                Text::new(entry.commit.subject.clone())
+                                    .size(TextSize::Small)
+                                    .truncate(),
+                            )
+                            .child(
+                                Text::new(details)
+                                    .size(TextSize::XSmall)
+                                    .color(Color::Muted)
+                                    .single_line()
+                                    .truncate(),
+                            ),
+                    )
+                    .child(
+                        Button::new("request-history-open-version", "Open this version")
+                            .variant(ButtonVariant::OutlinedGhost)
+                            .size(ButtonSize::Compact)
+                            .disabled(!matches!(self.commit, CommitState::Loaded(_)))
+                            .tooltip(Tooltip::text(
+                                "Open the request as it was in this commit, as unsaved changes",
+                            ))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.open_version(window, cx)),
+                            ),
+                    ),
+            )
+            .child(content)
+            .into_any_element()
+    }
+}
+
+fn short_sha(sha: &str) -> String {
+    sha.chars().take(SHORT_SHA_LENGTH).collect()
+}
+
+fn format_commit_time(commit_timestamp: i64, time_zone: &TimeZone) -> String {
+    Timestamp::from_second(commit_timestamp)
+        .map(|timestamp| {
+            timestamp
+                .to_zoned(time_zone.clone())
+                .strftime("%b %d, %Y %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+impl EventEmitter<ItemEvent> for RequestHistoryView {}
+
+impl Focusable for RequestHistoryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RequestHistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let path = self
+            .project_path
+            .path
+            .display(self.project.read(cx).path_style(cx))
+            .into_owned();
+        let time_zone = TimeZone::system();
+
+        let (summary, content) = match &self.history {
+            HistoryState::Loading => (
+                SharedString::default(),
+                render_message("Loading…", Color::Muted),
+            ),
+            HistoryState::Error(error) => (
+                SharedString::default(),
+                render_message(error.clone(), Color::Error),
+            ),
+            HistoryState::Loaded(entries) if entries.is_empty() => (
+                SharedString::default(),
+                render_message("No commit has this request yet.", Color::Muted),
+            ),
+            HistoryState::Loaded(entries) => {
+                let count = entries.len();
+                let summary = format!("{count} {}", if count == 1 { "commit" } else { "commits" });
+                let rows = entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| self.render_entry(index, entry, &time_zone, cx))
+                    .collect::<Vec<_>>();
+                let commit = self.render_commit(&time_zone, cx);
+                let border_color = cx.theme().colors().border;
+                (
+                    summary.into(),
+                    gpui::div()
+                        .flex_1()
+                        .min_h_0()
+                        .flex()
+                        .child(
+                            gpui::div()
+                                .id("request-history-commits")
+                                .flex_none()
+                                .w(gpui::rems(20.0))
+                                .overflow_y_scroll()
+                                .border_r_1()
+                                .border_color(border_color)
+                                .children(rows),
+                        )
+                        .child(commit)
+                        .into_any_element(),
+                )
+            }
+        };
+
+        let colors = cx.theme().colors();
+        gpui::div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(colors.editor_background)
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_2()
+                    .px_3()
+                    .py_1p5()
+                    .border_b_1()
+                    .border_color(colors.border)
+                    .child(Text::new(path).size(TextSize::Small).truncate())
+                    .child(
+                        Text::new(summary)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+            )
+            .child(content)
+    }
+}
+
+impl Item for RequestHistoryView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, emitter: &mut dyn FnMut(ItemEvent)) {
+        emitter(*event);
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let name = self.project_path.path.file_name().unwrap_or_default();
+        format!("{name} history").into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self
+            .project_path
+            .path
+            .display(self.project.read(cx).path_style(cx))
+            .into_owned();
+        Some(format!("Commits that changed {path}").into())
+    }
+}
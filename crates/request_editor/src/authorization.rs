@@ -0,0 +1,185 @@
+use gpui::{App, Entity, Window, prelude::*};
+
+use input::InputField;
+use project::{
+    AwsPayloadSigning, OAuth2GrantType, RequestFileAuth, RequestFileAwsSigV4, RequestFileOAuth2,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestAuthKind {
+    OAuth2,
+    AwsSigV4,
+}
+
+impl RequestAuthKind {
+    pub(crate) fn display_name(self) -> &'static str {
+        match self {
+            Self::OAuth2 => "OAuth 2.0",
+            Self::AwsSigV4 => "AWS Signature v4",
+        }
+    }
+}
+
+/// The authorization being edited in the request editor's Auth tab. The fields of every kind
+/// are kept while another kind is chosen, so switching back doesn't lose them before saving.
+pub(crate) struct RequestAuth {
+    pub(crate) kind: Option<RequestAuthKind>,
+    pub(crate) oauth2: OAuth2Fields,
+    pub(crate) aws_sigv4: AwsSigV4Fields,
+}
+
+pub(crate) struct OAuth2Fields {
+    pub(crate) grant_type: OAuth2GrantType,
+    pub(crate) token_url: Entity<InputField>,
+    pub(crate) authorization_url: Entity<InputField>,
+    pub(crate) client_id: Entity<InputField>,
+    pub(crate) client_secret: Entity<InputField>,
+    pub(crate) scope: Entity<InputField>,
+    pub(crate) username: Entity<InputField>,
+    pub(crate) password: Entity<InputField>,
+    pub(crate) redirect_port: Entity<InputField>,
+}
+
+pub(crate) struct AwsSigV4Fields {
+    pub(crate) access_key_id: Entity<InputField>,
+    pub(crate) secret_access_key: Entity<InputField>,
+    pub(crate) session_token: Entity<InputField>,
+    pub(crate) region: Entity<InputField>,
+    pub(crate) service: Entity<InputField>,
+    pub(crate) payload: AwsPayloadSigning,
+}
+
+impl RequestAuth {
+    pub(crate) fn from_request_file_auth(
+        auth: Option<&RequestFileAuth>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let default_oauth2 = RequestFileOAuth2::default();
+        let default_aws_sigv4 = RequestFileAwsSigV4::default();
+        let (kind, oauth2, aws_sigv4) = match auth {
+            Some(RequestFileAuth::OAuth2(oauth2)) => {
+                (Some(RequestAuthKind::OAuth2), oauth2, &default_aws_sigv4)
+            }
+            Some(RequestFileAuth::AwsSigV4(aws_sigv4)) => {
+                (Some(RequestAuthKind::AwsSigV4), &default_oauth2, aws_sigv4)
+            }
+            None => (None, &default_oauth2, &default_aws_sigv4),
+        };
+        let mut field = |placeholder: &str, value: &str, masked: bool| {
+            let field = cx.new(|cx| InputField::new(window, cx, placeholder));
+            field.update(cx, |field, cx| field.set_value(value, window, cx));
+            if masked {
+                field.read(cx).editor().clone().set_masked(true, window, cx);
+            }
+            field
+        };
+
+        Self {
+            kind,
+            oauth2: OAuth2Fields {
+                grant_type: oauth2.grant_type,
+                token_url: field(
+                    "https://auth.example.com/oauth/token",
+                    &oauth2.token_url,
+                    false,
+                ),
+                authorization_url: field(
+                    "https://auth.example.com/oauth/authorize",
+                    &oauth2.authorization_url,
+                    false,
+                ),
+                client_id: field("Client ID", &oauth2.client_id, false),
+                client_secret: field("{{secret.client_secret}}", &oauth2.client_secret, true),
+                scope: field("read write", &oauth2.scope, false),
+                username: field("Username", &oauth2.username, false),
+                password: field("{{secret.password}}", &oauth2.password, true),
+                redirect_port: field(
+                    "Any",
+                    &oauth2
+                        .redirect_port
+                        .map(|port| port.to_string())
+                        .unwrap_or_default(),
+                    false,
+                ),
+            },
+            aws_sigv4: AwsSigV4Fields {
+                access_key_id: field(
+                    "{{secret.aws_access_key_id}}",
+                    &aws_sigv4.access_key_id,
+                    false,
+                ),
+                secret_access_key: field(
+                    "{{secret.aws_secret_access_key}}",
+                    &aws_sigv4.secret_access_key,
+                    true,
+                ),
+                session_token: field("Optional", &aws_sigv4.session_token, true),
+                region: field("us-east-1", &aws_sigv4.region, false),
+                service: field("execute-api", &aws_sigv4.service, false),
+                payload: aws_sigv4.payload,
+            },
+        }
+    }
+
+    pub(crate) fn to_request_file_auth(&self, cx: &App) -> Option<RequestFileAuth> {
+        match self.kind? {
+            RequestAuthKind::OAuth2 => Some(RequestFileAuth::OAuth2(self.oauth2.to_config(cx))),
+            RequestAuthKind::AwsSigV4 => {
+                Some(RequestFileAuth::AwsSigV4(self.aws_sigv4.to_config(cx)))
+            }
+        }
+    }
+
+    /// The inputs of every kind of authorization, for subscribing to their edits.
+    pub(crate) fn inputs(&self) -> [&Entity<InputField>; 13] {
+        let oauth2 = &self.oauth2;
+        let aws_sigv4 = &self.aws_sigv4;
+        [
+            &oauth2.token_url,
+            &oauth2.authorization_url,
+            &oauth2.client_id,
+            &oauth2.client_secret,
+            &oauth2.scope,
+            &oauth2.username,
+            &oauth2.password,
+            &oauth2.redirect_port,
+            &aws_sigv4.access_key_id,
+            &aws_sigv4.secret_access_key,
+            &aws_sigv4.session_token,
+            &aws_sigv4.region,
+            &aws_sigv4.service,
+        ]
+    }
+}
+
+impl OAuth2Fields {
+    fn to_config(&self, cx: &App) -> RequestFileOAuth2 {
+        let value = |field: &Entity<InputField>| field.read(cx).value(cx);
+        RequestFileOAuth2 {
+            grant_type: self.grant_type,
+            token_url: value(&self.token_url),
+            authorization_url: value(&self.authorization_url),
+            client_id: value(&self.client_id),
+            client_secret: value(&self.client_secret),
+            scope: value(&self.scope),
+            username: value(&self.username),
+            password: value(&self.password),
+            redirect_port: value(&self.redirect_port).trim().parse().ok(),
+        }
+    }
+}
+
+impl AwsSigV4Fields {
+    fn to_config(&self, cx: &App) -> RequestFileAwsSigV4 {
+        let value = |field: &Entity<InputField>| field.read(cx).value(cx);
+        RequestFileAwsSigV4 {
+            access_key_id: value(&self.access_key_id),
+            secret_access_key: value(&self.secret_access_key),
+            session_token: value(&self.session_token),
+            region: value(&self.region),
+            service: value(&self.service),
+            payload: self.payload,
+        }
+    }
+}
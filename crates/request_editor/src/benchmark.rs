@@ -0,0 +1,308 @@
+use futures::{channel::mpsc, future::join_all, io::AsyncReadExt};
+use gpui::{App, BackgroundExecutor, Entity, SharedString, Window, prelude::*};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use http_client::HttpClient;
+use input::InputField;
+use jiff::Timestamp;
+use response_panel::BenchmarkSample;
+
+use crate::OutgoingRequest;
+
+/// The most requests a benchmark keeps in flight at a time, so a typo doesn't open thousands of
+/// connections at once.
+const MAX_CONCURRENCY: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BenchmarkLimit {
+    Requests(u64),
+    Duration(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BenchmarkConfig {
+    pub(crate) limit: BenchmarkLimit,
+    pub(crate) concurrency: u32,
+    /// The target number of requests per second, or `None` to send them as fast as possible.
+    pub(crate) rate: Option<u32>,
+}
+
+impl BenchmarkConfig {
+    /// Parses the benchmark settings as they are typed in the form. `duration` is in seconds.
+    pub(crate) fn parse(
+        by_duration: bool,
+        requests: &str,
+        duration: &str,
+        concurrency: &str,
+        rate: &str,
+    ) -> Result<Self, String> {
+        let limit = if by_duration {
+            BenchmarkLimit::Duration(Duration::from_secs(parse_positive(duration, "Duration")?))
+        } else {
+            BenchmarkLimit::Requests(parse_positive(requests, "Requests")?)
+        };
+        let concurrency = parse_positive(concurrency, "Concurrency")?;
+        if concurrency > MAX_CONCURRENCY {
+            return Err(format!("Concurrency can be at most {MAX_CONCURRENCY}."));
+        }
+        let rate = if rate.trim().is_empty() {
+            None
+        } else {
+            Some(parse_positive(rate, "Target RPS")?)
+        };
+
+        Ok(Self {
+            limit,
+            concurrency,
+            rate,
+        })
+    }
+
+    fn is_done(&self, index: u64, elapsed: Duration) -> bool {
+        match self.limit {
+            BenchmarkLimit::Requests(count) => index >= count,
+            BenchmarkLimit::Duration(duration) => elapsed >= duration,
+        }
+    }
+
+    /// When the request at `index` should be sent to keep to the target rate.
+    fn scheduled_at(&self, index: u64) -> Duration {
+        self.rate.map_or(Duration::ZERO, |rate| {
+            Duration::from_nanos(index.saturating_mul(1_000_000_000) / u64::from(rate))
+        })
+    }
+}
+
+fn parse_positive<T: std::str::FromStr + Default + PartialOrd>(
+    value: &str,
+    name: &str,
+) -> Result<T, String> {
+    value
+        .trim()
+        .parse::<T>()
+        .ok()
+        .filter(|value| *value > T::default())
+        .ok_or_else(|| format!("{name} must be a positive whole number."))
+}
+
+/// The benchmark settings being edited in the request editor's Benchmark tab.
+pub(crate) struct BenchmarkForm {
+    pub(crate) by_duration: bool,
+    pub(crate) requests: Entity<InputField>,
+    pub(crate) duration: Entity<InputField>,
+    pub(crate) concurrency: Entity<InputField>,
+    pub(crate) rate: Entity<InputField>,
+    pub(crate) error: Option<SharedString>,
+}
+
+impl BenchmarkForm {
+    pub(crate) fn new(window: &mut Window, cx: &mut App) -> Self {
+        let mut field = |placeholder: &str, value: &str| {
+            let field = cx.new(|cx| InputField::new(window, cx, placeholder));
+            field.update(cx, |field, cx| field.set_value(value, window, cx));
+            field
+        };
+
+        Self {
+            by_duration: false,
+            requests: field("100", "100"),
+            duration: field("10", "10"),
+            concurrency: field("10", "10"),
+            rate: field("Unlimited", ""),
+            error: None,
+        }
+    }
+
+    pub(crate) fn config(&self, cx: &App) -> Result<BenchmarkConfig, String> {
+        BenchmarkConfig::parse(
+            self.by_duration,
+            &self.requests.read(cx).value(cx),
+            &self.duration.read(cx).value(cx),
+            &self.concurrency.read(cx).value(cx),
+            &self.rate.read(cx).value(cx),
+        )
+    }
+}
+
+struct BenchmarkRun {
+    http_client: Arc<dyn HttpClient>,
+    request: OutgoingRequest,
+    config: BenchmarkConfig,
+    executor: BackgroundExecutor,
+    cancelled: Arc<AtomicBool>,
+    next_index: AtomicU64,
+    started_at: Instant,
+}
+
+/// Sends `request`, whose references must already be resolved, as `config` describes, with
+/// `config.concurrency` requests in flight at a time. Its dynamic variables are evaluated for each
+/// request. Each request's outcome is sent to the returned receiver, which closes once the returned
+/// future completes. Setting `cancelled` stops sending new requests.
+pub(crate) fn run_benchmark(
+    http_client: Arc<dyn HttpClient>,
+    request: OutgoingRequest,
+    config: BenchmarkConfig,
+    executor: BackgroundExecutor,
+    cancelled: Arc<AtomicBool>,
+) -> (
+    mpsc::UnboundedReceiver<BenchmarkSample>,
+    impl Future<Output = ()> + Send + 'static,
+) {
+    let (sender, receiver) = mpsc::unbounded();
+    let run = Arc::new(BenchmarkRun {
+        http_client,
+        request,
+        config,
+        executor,
+        cancelled,
+        next_index: AtomicU64::new(0),
+        started_at: Instant::now(),
+    });
+    let workers = (0..config.concurrency).map(move |_| run_worker(run.clone(), sender.clone()));
+    (receiver, async move {
+        join_all(workers).await;
+    })
+}
+
+async fn run_worker(run: Arc<BenchmarkRun>, sender: mpsc::UnboundedSender<BenchmarkSample>) {
+    loop {
+        let index = run.next_index.fetch_add(1, Ordering::SeqCst);
+        if run.cancelled.load(Ordering::SeqCst)
+            || run.config.is_done(index, run.started_at.elapsed())
+        {
+            break;
+        }
+
+        let delay = run
+            .config
+            .scheduled_at(index)
+            .saturating_sub(run.started_at.elapsed());
+        if !delay.is_zero() {
+            run.executor.timer(delay).await;
+            if run.cancelled.load(Ordering::SeqCst)
+                || run.config.is_done(index, run.started_at.elapsed())
+            {
+                break;
+            }
+        }
+
+        let started_at = run.started_at.elapsed();
+        let sent_at = Instant::now();
+        let outcome = send(&run).await;
+        let sample = BenchmarkSample {
+            started_at,
+            latency: sent_at.elapsed(),
+            outcome,
+        };
+        if sender.unbounded_send(sample).is_err() {
+            break;
+        }
+    }
+}
+
+/// Sends the request and reads the whole response body, returning the status code, or the error
+/// with the values of secrets masked.
+async fn send(run: &BenchmarkRun) -> Result<u16, String> {
+    let (request, url) = run
+        .request
+        .evaluate_for_send(Timestamp::now())
+        .map_err(|error| run.request.mask(&format!("{error:#}")))?;
+    let http_request = request
+        .to_http_request(&url)
+        .map_err(|error| request.mask(&error.to_string()))?;
+    let mut response = run
+        .http_client
+        .send(http_request)
+        .await
+        .map_err(|error| request.mask(&error.to_string()))?;
+    let mut buffer = [0; 8192];
+    loop {
+        match response.body_mut().read(&mut buffer).await {
+            Ok(0) => return Ok(response.status().as_u16()),
+            Ok(_) => {}
+            Err(error) => return Err(request.mask(&error.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(
+            BenchmarkConfig::parse(false, " 50 ", "", "4", ""),
+            Ok(BenchmarkConfig {
+                limit: BenchmarkLimit::Requests(50),
+                concurrency: 4,
+                rate: None,
+            })
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(true, "", "30", "1", "20"),
+            Ok(BenchmarkConfig {
+                limit: BenchmarkLimit::Duration(Duration::from_secs(30)),
+                concurrency: 1,
+                rate: Some(20),
+            })
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(false, "0", "", "4", ""),
+            Err("Requests must be a positive whole number.".to_string())
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(false, "10", "", "four", ""),
+            Err("Concurrency must be a positive whole number.".to_string())
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(false, "10", "", "0", ""),
+            Err("Concurrency must be a positive whole number.".to_string())
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(false, "10", "", "100", ""),
+            Ok(BenchmarkConfig {
+                limit: BenchmarkLimit::Requests(10),
+                concurrency: 100,
+                rate: None,
+            })
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(false, "10", "", "10000", ""),
+            Err("Concurrency can be at most 100.".to_string())
+        );
+        assert_eq!(
+            BenchmarkConfig::parse(true, "", "1.5", "1", ""),
+            Err("Duration must be a positive whole number.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_schedule() {
+        let config = BenchmarkConfig {
+            limit: BenchmarkLimit::Requests(3),
+            concurrency: 2,
+            rate: Some(4),
+        };
+
+        assert_eq!(config.scheduled_at(0), Duration::ZERO);
+        assert_eq!(config.scheduled_at(3), Duration::from_millis(750));
+        assert!(!config.is_done(2, Duration::from_secs(60)));
+        assert!(config.is_done(3, Duration::ZERO));
+
+        let config = BenchmarkConfig {
+            limit: BenchmarkLimit::Duration(Duration::from_secs(5)),
+            rate: None,
+            ..config
+        };
+        assert_eq!(config.scheduled_at(100), Duration::ZERO);
+        assert!(!config.is_done(1000, Duration::from_millis(4999)));
+        assert!(config.is_done(0, Duration::from_secs(5)));
+    }
+}
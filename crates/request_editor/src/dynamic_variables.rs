@@ -0,0 +1,270 @@
+use anyhow::{Context as _, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use hmac::{Hmac, KeyInit as _, Mac as _};
+use jiff::Timestamp;
+use sha2::{Digest as _, Sha256};
+use uuid::Uuid;
+
+const RANDOM_STRING_CHARS: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// The built-in generator expressions of one send, like `{{$uuid}}` or
+/// `{{$randomInt 1 100}}`. Each expression is evaluated once, the first time it is resolved, so it
+/// has the same value everywhere it appears in the request.
+#[derive(Clone)]
+pub(crate) struct DynamicVariables {
+    now: Timestamp,
+    /// The expressions resolved so far and their values, in the order they were first used.
+    values: Vec<(String, String)>,
+}
+
+impl DynamicVariables {
+    pub(crate) fn new(now: Timestamp) -> Self {
+        Self {
+            now,
+            values: Vec::new(),
+        }
+    }
+
+    /// The expressions that were resolved and their values, for recording with the request.
+    pub(crate) fn values(&self) -> &[(String, String)] {
+        &self.values
+    }
+
+    /// Replaces every `{{$name arguments}}` expression in `text` with its value. Other `{{…}}`
+    /// sequences are left as they are.
+    pub(crate) fn resolve(&mut self, text: &str) -> anyhow::Result<String> {
+        let mut resolved = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some((before, after_open)) = rest.split_once("{{") {
+            resolved.push_str(before);
+            let expression = after_open
+                .split_once("}}")
+                .map(|(expression, after_close)| (expression.trim(), after_close))
+                .filter(|(expression, _)| expression.starts_with('$'));
+            if let Some((expression, after_close)) = expression {
+                resolved.push_str(&self.value(expression)?);
+                rest = after_close;
+            } else {
+                resolved.push_str("{{");
+                rest = after_open;
+            }
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+
+    fn value(&mut self, expression: &str) -> anyhow::Result<String> {
+        if let Some((_, value)) = self.values.iter().find(|(known, _)| known == expression) {
+            return Ok(value.clone());
+        }
+        let value = evaluate(expression, self.now)
+            .with_context(|| format!("failed to evaluate {{{{{expression}}}}}"))?;
+        self.values.push((expression.to_owned(), value.clone()));
+        Ok(value)
+    }
+}
+
+fn evaluate(expression: &str, now: Timestamp) -> anyhow::Result<String> {
+    let (name, arguments) = expression
+        .split_once(char::is_whitespace)
+        .unwrap_or((expression, ""));
+    let arguments = parse_arguments(arguments)?;
+    match name {
+        "$uuid" => {
+            let [] = arity(name, arguments)?;
+            Ok(Uuid::new_v4().to_string())
+        }
+        "$timestamp" => {
+            let [] = arity(name, arguments)?;
+            Ok(now.as_second().to_string())
+        }
+        "$isoTimestamp" => {
+            let [] = arity(name, arguments)?;
+            Ok(now.strftime("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+        }
+        "$randomInt" => {
+            let (min, max) = match arguments.as_slice() {
+                [] => (0, 1000),
+                [min, max] => (parse_integer(min)?, parse_integer(max)?),
+                _ => bail!("{name} takes no arguments or a minimum and a maximum"),
+            };
+            if min > max {
+                bail!("the minimum {min} is greater than the maximum {max}");
+            }
+            Ok(rand::random_range(min..=max).to_string())
+        }
+        "$randomString" => {
+            let length = match arguments.as_slice() {
+                [] => 16,
+                [length] => length
+                    .parse::<usize>()
+                    .with_context(|| format!("invalid length {length:?}"))?,
+                _ => bail!("{name} takes no arguments or a length"),
+            };
+            Ok((0..length)
+                .filter_map(|_| {
+                    RANDOM_STRING_CHARS
+                        .get(rand::random_range(0..RANDOM_STRING_CHARS.len()))
+                        .map(|&char| char::from(char))
+                })
+                .collect())
+        }
+        "$base64" => {
+            let [text] = arity(name, arguments)?;
+            Ok(STANDARD.encode(text))
+        }
+        "$sha256" => {
+            let [text] = arity(name, arguments)?;
+            Ok(hex::encode(Sha256::digest(text)))
+        }
+        "$hmacSha256" => {
+            let [key, data] = arity(name, arguments)?;
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(data.as_bytes());
+            Ok(hex::encode(mac.finalize().into_bytes()))
+        }
+        _ => Err(anyhow!("unknown dynamic variable {name}")),
+    }
+}
+
+/// Splits the arguments of an expression on whitespace. An argument that contains whitespace is
+/// written in double quotes.
+fn parse_arguments(text: &str) -> anyhow::Result<Vec<String>> {
+    let mut arguments = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let (argument, after) = if let Some(quoted) = rest.strip_prefix('"') {
+            quoted
+                .split_once('"')
+                .with_context(|| format!("unterminated quote in {text:?}"))?
+        } else {
+            rest.split_once(char::is_whitespace).unwrap_or((rest, ""))
+        };
+        arguments.push(argument.to_owned());
+        rest = after.trim_start();
+    }
+    Ok(arguments)
+}
+
+fn arity<const N: usize>(name: &str, arguments: Vec<String>) -> anyhow::Result<[String; N]> {
+    arguments.try_into().map_err(|arguments: Vec<String>| {
+        anyhow!(
+            "{name} takes {N} argument{}, but {} were given",
+            if N == 1 { "" } else { "s" },
+            arguments.len()
+        )
+    })
+}
+
+fn parse_integer(text: &str) -> anyhow::Result<i64> {
+    text.parse()
+        .with_context(|| format!("invalid integer {text:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dynamic_variables() -> DynamicVariables {
+        DynamicVariables::new("2024-05-01T12:30:45.123456Z".parse().unwrap())
+    }
+
+    #[test]
+    fn test_resolve_dynamic_variables() {
+        let mut variables = dynamic_variables();
+
+        assert_eq!(
+            variables
+                .resolve("t={{$timestamp}}&at={{ $isoTimestamp }}")
+                .unwrap(),
+            "t=1714566645&at=2024-05-01T12:30:45.123Z"
+        );
+        assert_eq!(
+            variables
+                .resolve("Basic {{$base64 \"user:pass word\"}}")
+                .unwrap(),
+            "Basic dXNlcjpwYXNzIHdvcmQ="
+        );
+        assert_eq!(
+            variables.resolve("{{$sha256 abc}}").unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            variables
+                .resolve("{{$hmacSha256 key \"The quick brown fox jumps over the lazy dog\"}}")
+                .unwrap(),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(variables.resolve("{{$randomInt 7 7}}").unwrap(), "7");
+        assert_eq!(
+            variables
+                .resolve("{{name}} {{secret.token}} {{$unclosed")
+                .unwrap(),
+            "{{name}} {{secret.token}} {{$unclosed"
+        );
+
+        let uuid = variables.resolve("{{$uuid}}").unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.get(14..15), Some("4"));
+        assert_eq!(variables.resolve("{{ $uuid }}").unwrap(), uuid);
+
+        let random_string = variables.resolve("{{$randomString 24}}").unwrap();
+        assert_eq!(random_string.len(), 24);
+        assert!(
+            random_string
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric())
+        );
+
+        let random_int = variables.resolve("{{$randomInt}}").unwrap();
+        assert!((0..=1000).contains(&random_int.parse::<i64>().unwrap()));
+
+        assert_eq!(
+            variables
+                .values()
+                .iter()
+                .map(|(expression, _)| expression.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "$timestamp",
+                "$isoTimestamp",
+                "$base64 \"user:pass word\"",
+                "$sha256 abc",
+                "$hmacSha256 key \"The quick brown fox jumps over the lazy dog\"",
+                "$randomInt 7 7",
+                "$uuid",
+                "$randomString 24",
+                "$randomInt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_dynamic_variables() {
+        let mut variables = dynamic_variables();
+
+        assert_eq!(
+            format!("{:#}", variables.resolve("{{$nope}}").unwrap_err()),
+            "failed to evaluate {{$nope}}: unknown dynamic variable $nope"
+        );
+        assert_eq!(
+            format!("{:#}", variables.resolve("{{$base64}}").unwrap_err()),
+            "failed to evaluate {{$base64}}: $base64 takes 1 argument, but 0 were given"
+        );
+        assert_eq!(
+            format!("{:#}", variables.resolve("{{$uuid v4}}").unwrap_err()),
+            "failed to evaluate {{$uuid v4}}: $uuid takes 0 arguments, but 1 were given"
+        );
+        assert_eq!(
+            format!("{:#}", variables.resolve("{{$randomInt 9 1}}").unwrap_err()),
+            "failed to evaluate {{$randomInt 9 1}}: the minimum 9 is greater than the maximum 1"
+        );
+        assert_eq!(
+            format!("{:#}", variables.resolve("{{$base64 \"open}}").unwrap_err()),
+            "failed to evaluate {{$base64 \"open}}: unterminated quote in \"\\\"open\""
+        );
+        assert!(variables.values().is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use http_client::StatusCode;
+use project::{RequestFileExample, RequestFileHeader};
+use response_panel::ResponseHeader;
+
+/// Headers that describe how the response was transferred rather than what it contains. The
+/// body is saved decoded, so serving them again would misdescribe it.
+const TRANSFER_HEADERS: &[&str] = &[
+    "connection",
+    "content-encoding",
+    "content-length",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+/// Builds an example from a received response, named after its status and kept distinct from the
+/// names in `examples`.
+pub(crate) fn example_from_response(
+    examples: &[RequestFileExample],
+    status_code: StatusCode,
+    headers: &[ResponseHeader],
+    body: String,
+) -> RequestFileExample {
+    let base_name = match status_code.canonical_reason() {
+        Some(reason) => format!("{} {reason}", status_code.as_u16()),
+        None => status_code.as_u16().to_string(),
+    };
+    let mut name = base_name.clone();
+    let mut suffix = 2;
+    while examples.iter().any(|example| example.name == name) {
+        name = format!("{base_name} ({suffix})");
+        suffix += 1;
+    }
+
+    RequestFileExample {
+        name,
+        status: status_code.as_u16(),
+        headers: headers
+            .iter()
+            .filter(|header| {
+                !TRANSFER_HEADERS
+                    .iter()
+                    .any(|name| header.name().eq_ignore_ascii_case(name))
+            })
+            .map(|header| RequestFileHeader {
+                name: header.name().to_string(),
+                value: header.value().to_string(),
+                disabled: false,
+            })
+            .collect(),
+        body,
+        delay_ms: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_from_response() {
+        let headers = [
+            ResponseHeader::new("Content-Type", "application/json"),
+            ResponseHeader::new("Content-Encoding", "gzip"),
+            ResponseHeader::new("content-length", "11"),
+            ResponseHeader::new("X-Request-Id", "abc"),
+        ];
+        let example =
+            example_from_response(&[], StatusCode::OK, &headers, "{\"id\": 1}".to_string());
+        assert_eq!(
+            example,
+            RequestFileExample {
+                name: "200 OK".to_string(),
+                status: 200,
+                headers: vec![
+                    RequestFileHeader {
+                        name: "Content-Type".to_string(),
+                        value: "application/json".to_string(),
+                        disabled: false,
+                    },
+                    RequestFileHeader {
+                        name: "X-Request-Id".to_string(),
+                        value: "abc".to_string(),
+                        disabled: false,
+                    },
+                ],
+                body: "{\"id\": 1}".to_string(),
+                delay_ms: None,
+            }
+        );
+
+        let examples = vec![
+            example,
+            RequestFileExample {
+                name: "200 OK (2)".to_string(),
+                ..RequestFileExample::default()
+            },
+        ];
+        let example = example_from_response(&examples, StatusCode::OK, &[], String::new());
+        assert_eq!(example.name, "200 OK (3)");
+    }
+}
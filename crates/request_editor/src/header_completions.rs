@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use project::RequestFile;
+
+/// Header fields from the IANA HTTP Field Name Registry that are sent or received by clients,
+/// plus a few de facto standard ones.
+const STANDARD_HEADERS: &[&str] = &[
+    "A-IM",
+    "Accept",
+    "Accept-CH",
+    "Accept-Charset",
+    "Accept-Datetime",
+    "Accept-Encoding",
+    "Accept-Language",
+    "Accept-Patch",
+    "Accept-Post",
+    "Accept-Ranges",
+    "Access-Control-Allow-Credentials",
+    "Access-Control-Allow-Headers",
+    "Access-Control-Allow-Methods",
+    "Access-Control-Allow-Origin",
+    "Access-Control-Expose-Headers",
+    "Access-Control-Max-Age",
+    "Access-Control-Request-Headers",
+    "Access-Control-Request-Method",
+    "Age",
+    "Allow",
+    "ALPN",
+    "Alt-Svc",
+    "Alt-Used",
+    "Authentication-Info",
+    "Authorization",
+    "Cache-Control",
+    "Cache-Status",
+    "CDN-Cache-Control",
+    "Clear-Site-Data",
+    "Connection",
+    "Content-Digest",
+    "Content-Disposition",
+    "Content-Encoding",
+    "Content-Language",
+    "Content-Length",
+    "Content-Location",
+    "Content-Range",
+    "Content-Security-Policy",
+    "Content-Security-Policy-Report-Only",
+    "Content-Type",
+    "Cookie",
+    "Cross-Origin-Embedder-Policy",
+    "Cross-Origin-Opener-Policy",
+    "Cross-Origin-Resource-Policy",
+    "Date",
+    "Digest",
+    "DNT",
+    "Early-Data",
+    "ETag",
+    "Expect",
+    "Expires",
+    "Forwarded",
+    "From",
+    "Host",
+    "Idempotency-Key",
+    "If-Match",
+    "If-Modified-Since",
+    "If-None-Match",
+    "If-Range",
+    "If-Schedule-Tag-Match",
+    "If-Unmodified-Since",
+    "Keep-Alive",
+    "Last-Modified",
+    "Link",
+    "Location",
+    "Max-Forwards",
+    "Origin",
+    "Prefer",
+    "Preference-Applied",
+    "Priority",
+    "Proxy-Authenticate",
+    "Proxy-Authentication-Info",
+    "Proxy-Authorization",
+    "Range",
+    "Referer",
+    "Referrer-Policy",
+    "Refresh",
+    "Repr-Digest",
+    "Retry-After",
+    "Sec-Fetch-Dest",
+    "Sec-Fetch-Mode",
+    "Sec-Fetch-Site",
+    "Sec-Fetch-User",
+    "Sec-WebSocket-Accept",
+    "Sec-WebSocket-Extensions",
+    "Sec-WebSocket-Key",
+    "Sec-WebSocket-Protocol",
+    "Sec-WebSocket-Version",
+    "Server",
+    "Server-Timing",
+    "Set-Cookie",
+    "Strict-Transport-Security",
+    "TE",
+    "Timing-Allow-Origin",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+    "Upgrade-Insecure-Requests",
+    "User-Agent",
+    "Vary",
+    "Via",
+    "Want-Content-Digest",
+    "Want-Repr-Digest",
+    "WWW-Authenticate",
+];
+
+const MEDIA_TYPES: &[&str] = &[
+    "application/json",
+    "application/problem+json",
+    "application/ld+json",
+    "application/graphql-response+json",
+    "application/xml",
+    "application/x-www-form-urlencoded",
+    "application/octet-stream",
+    "application/pdf",
+    "multipart/form-data",
+    "text/plain",
+    "text/html",
+    "text/csv",
+    "text/xml",
+    "text/event-stream",
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "image/svg+xml",
+];
+
+const ACCEPT_VALUES: &[&str] = &["*/*", "application/*", "text/*", "image/*"];
+
+const CACHE_CONTROL_VALUES: &[&str] = &[
+    "no-cache",
+    "no-store",
+    "no-transform",
+    "max-age=0",
+    "max-age=3600",
+    "max-stale",
+    "min-fresh=60",
+    "only-if-cached",
+    "stale-if-error=60",
+];
+
+const ACCEPT_ENCODING_VALUES: &[&str] = &[
+    "gzip, deflate, br, zstd",
+    "gzip",
+    "deflate",
+    "br",
+    "zstd",
+    "identity",
+    "*",
+];
+
+const CONTENT_ENCODING_VALUES: &[&str] = &["gzip", "deflate", "br", "zstd"];
+
+const ACCEPT_LANGUAGE_VALUES: &[&str] = &["*", "en", "en-US", "en-US,en;q=0.9"];
+
+const AUTHORIZATION_VALUES: &[&str] = &["Bearer ", "Basic "];
+
+const CONNECTION_VALUES: &[&str] = &["keep-alive", "close"];
+
+const PREFER_VALUES: &[&str] = &[
+    "return=minimal",
+    "return=representation",
+    "respond-async",
+    "handling=strict",
+    "handling=lenient",
+];
+
+fn standard_values(name: &str) -> &'static [&'static str] {
+    match name.to_ascii_lowercase().as_str() {
+        "content-type" => MEDIA_TYPES,
+        "cache-control" => CACHE_CONTROL_VALUES,
+        "accept-encoding" => ACCEPT_ENCODING_VALUES,
+        "content-encoding" => CONTENT_ENCODING_VALUES,
+        "accept-language" | "content-language" => ACCEPT_LANGUAGE_VALUES,
+        "authorization" | "proxy-authorization" => AUTHORIZATION_VALUES,
+        "connection" => CONNECTION_VALUES,
+        "prefer" => PREFER_VALUES,
+        _ => &[],
+    }
+}
+
+/// The header names and values used by the other request files in the project.
+#[derive(Default)]
+pub(crate) struct ProjectHeaders {
+    /// Keyed by lowercase name, with the spelling that was seen first.
+    headers: BTreeMap<String, (String, BTreeSet<String>)>,
+}
+
+impl ProjectHeaders {
+    pub(crate) fn new<'a>(request_files: impl IntoIterator<Item = &'a RequestFile>) -> Self {
+        let mut headers = BTreeMap::<String, (String, BTreeSet<String>)>::new();
+        for header in request_files
+            .into_iter()
+            .flat_map(|request_file| &request_file.http.headers)
+        {
+            let name = header.name.trim();
+            if name.is_empty() {
+                continue;
+            }
+
+            let (_, values) = headers
+                .entry(name.to_ascii_lowercase())
+                .or_insert_with(|| (name.to_owned(), BTreeSet::new()));
+            if !header.value.trim().is_empty() {
+                values.insert(header.value.clone());
+            }
+        }
+        Self { headers }
+    }
+
+    /// Suggested header names: the standard ones followed by those only used in the project.
+    pub(crate) fn name_candidates(&self) -> Vec<String> {
+        let mut candidates = STANDARD_HEADERS
+            .iter()
+            .map(|name| (*name).to_owned())
+            .collect::<Vec<_>>();
+        candidates.extend(
+            self.headers
+                .values()
+                .map(|(name, _)| name)
+                .filter(|name| !is_standard_header(name))
+                .cloned(),
+        );
+        candidates
+    }
+
+    /// Suggested values for the header `name`: the well-known ones followed by those used in the
+    /// project.
+    pub(crate) fn value_candidates(&self, name: &str) -> Vec<String> {
+        let name = name.trim();
+        let mut candidates = standard_values(name)
+            .iter()
+            .map(|value| (*value).to_owned())
+            .collect::<Vec<_>>();
+        if name.eq_ignore_ascii_case("accept") {
+            candidates.extend(ACCEPT_VALUES.iter().map(|value| (*value).to_owned()));
+            candidates.extend(MEDIA_TYPES.iter().map(|value| (*value).to_owned()));
+        }
+        if let Some((_, values)) = self.headers.get(&name.to_ascii_lowercase()) {
+            for value in values {
+                if !candidates.contains(value) {
+                    candidates.push(value.clone());
+                }
+            }
+        }
+        candidates
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.headers.contains_key(&name.to_ascii_lowercase())
+    }
+}
+
+fn is_standard_header(name: &str) -> bool {
+    STANDARD_HEADERS
+        .iter()
+        .any(|standard| standard.eq_ignore_ascii_case(name))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HeaderIssue {
+    Unknown,
+    Duplicate,
+}
+
+impl HeaderIssue {
+    pub(crate) fn message(self) -> &'static str {
+        match self {
+            Self::Unknown => "Unknown header. Check the name for typos.",
+            Self::Duplicate => "Duplicate header. It is also set by another row.",
+        }
+    }
+}
+
+/// Flags each of the enabled header `names` that is set more than once, or that is neither
+/// standard nor used elsewhere in the project. `X-` headers are custom by convention, so they are
+/// never reported as unknown.
+pub(crate) fn header_issues(
+    names: &[Option<String>],
+    project_headers: &ProjectHeaders,
+) -> Vec<Option<HeaderIssue>> {
+    let lowercase_names = names
+        .iter()
+        .map(|name| {
+            name.as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_ascii_lowercase)
+        })
+        .collect::<Vec<_>>();
+
+    lowercase_names
+        .iter()
+        .map(|name| {
+            let name = name.as_deref()?;
+            let count = lowercase_names
+                .iter()
+                .filter(|other| other.as_deref() == Some(name))
+                .count();
+            if count > 1 {
+                Some(HeaderIssue::Duplicate)
+            } else if !name.starts_with("x-")
+                && !is_standard_header(name)
+                && !project_headers.contains(name)
+            {
+                Some(HeaderIssue::Unknown)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use project::{RequestFileHeader, RequestFileHttp};
+
+    fn request_file(headers: &[(&str, &str)]) -> RequestFile {
+        RequestFile {
+            http: RequestFileHttp {
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| RequestFileHeader {
+                        name: (*name).to_owned(),
+                        value: (*value).to_owned(),
+                        disabled: false,
+                    })
+                    .collect(),
+                ..RequestFileHttp::default()
+            },
+            ..RequestFile::default()
+        }
+    }
+
+    #[test]
+    fn test_candidates() {
+        let request_files = [
+            request_file(&[
+                ("content-type", "application/vnd.api+json"),
+                ("Tenant", "a"),
+            ]),
+            request_file(&[("Tenant", "b"), ("Content-Type", "application/json")]),
+        ];
+        let project_headers = ProjectHeaders::new(&request_files);
+
+        let names = project_headers.name_candidates();
+        assert!(names.contains(&"Content-Type".to_owned()));
+        assert!(!names.contains(&"content-type".to_owned()));
+        assert_eq!(names.last().map(String::as_str), Some("Tenant"));
+
+        assert_eq!(
+            project_headers.value_candidates("Tenant"),
+            vec!["a".to_owned(), "b".to_owned()]
+        );
+        let values = project_headers.value_candidates("CONTENT-TYPE");
+        assert_eq!(values.first().map(String::as_str), Some("application/json"));
+        assert_eq!(
+            values.last().map(String::as_str),
+            Some("application/vnd.api+json")
+        );
+        assert_eq!(
+            values
+                .iter()
+                .filter(|value| *value == "application/json")
+                .count(),
+            1
+        );
+        assert!(
+            project_headers
+                .value_candidates("Accept")
+                .contains(&"*/*".to_owned())
+        );
+        assert_eq!(
+            project_headers.value_candidates("X-Unknown"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_header_issues() {
+        let project_headers = ProjectHeaders::new(&[request_file(&[("Tenant", "a")])]);
+        let names = [
+            Some("Conent-Type"),
+            Some("accept"),
+            Some("X-Trace"),
+            Some("tenant"),
+            Some("Accept "),
+            None,
+            Some(""),
+        ]
+        .map(|name| name.map(str::to_owned));
+
+        assert_eq!(
+            header_issues(&names, &project_headers),
+            vec![
+                Some(HeaderIssue::Unknown),
+                Some(HeaderIssue::Duplicate),
+                None,
+                None,
+                Some(HeaderIssue::Duplicate),
+                None,
+                None,
+            ]
+        );
+    }
+}
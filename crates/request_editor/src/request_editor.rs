@@ -1,41 +1,82 @@
+mod authorization;
+mod benchmark;
+mod dynamic_variables;
+mod examples;
+mod header_completions;
 mod items;
 mod persistence;
+mod retry;
+mod scripts;
 
-use futures::{FutureExt, io::AsyncReadExt};
+use anyhow::Context as _;
+use futures::{FutureExt, StreamExt as _, io::AsyncReadExt};
+use fuzzy_nucleo::{StringMatch, StringMatchCandidate};
 use gpui::{
-    Anchor, AnyElement, App, Context, Div, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
-    FontWeight, ScrollHandle, SharedString, Subscription, WeakEntity, Window, prelude::*,
+    Anchor, AnyElement, App, AsyncApp, Context, Div, ElementId, Entity, EntityId, EventEmitter,
+    FocusHandle, Focusable, FontWeight, MouseButton, ScrollHandle, SharedString, Subscription,
+    Task, WeakEntity, Window, prelude::*,
 };
+use jiff::{Timestamp, tz::TimeZone};
 use std::{
+    collections::BTreeMap,
+    mem,
+    ops::Range,
+    path::PathBuf,
     rc::Rc,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::{Duration, Instant},
 };
 
-use editor::{Editor, EditorEvent};
+use auth::{OAuth2Token, OAuth2Tokens, sign_aws_sigv4};
+use editor::{Diagnostic, DiagnosticSeverity, Editor, EditorEvent, SelectionEffects};
 use http_client::{
-    AsyncBody, Builder, HttpClient, HttpRequestExt, Method, RedirectPolicy, Url, http,
+    AsyncBody, Builder, HttpClient, HttpRequestExt, Method, RedirectPolicy, SendErrorKind, Url,
+    http,
 };
 use input::{ErasedEditorEvent, InputField};
-use language::{Buffer, PLAIN_TEXT};
-use multi_buffer::MultiBuffer;
-use path::PathStyle;
+use json_schema::{JsonSchema, SourceError};
+use language::{Buffer, PLAIN_TEXT, TextFormat};
+use multi_buffer::{MultiBuffer, MultiBufferOffset};
+use path::{PathStyle, RelPath};
 use project::{
-    Project, ProjectPath, RequestBuffer, RequestBufferEvent, RequestFile, RequestFileBody,
-    RequestFileBodyType, RequestFileHeader, RequestFileHttp, RequestFileMeta, RequestFileParam,
-    RequestFileState,
+    AwsPayloadSigning, OAuth2GrantType, Project, ProjectPath, RequestBuffer, RequestBufferEvent,
+    RequestFile, RequestFileAuth, RequestFileBody, RequestFileBodyType, RequestFileExample,
+    RequestFileHeader, RequestFileHttp, RequestFileMeta, RequestFileOAuth2, RequestFileParam,
+    RequestFilePathVariable, RequestFileRetry, RequestFileSchema, RequestFileState,
+    escape_query_name, escape_query_value, join_url_query, parse_query, path_variable_names,
+    resolve_query_text, split_url_query, substitute_path_variables,
 };
 use response_panel::{
-    Response, ResponseCookie, ResponseHeader, ResponsePanel, ResponsePanelTab, ResponseState,
+    BenchmarkReport, Response, ResponseAttempt, ResponseCookie, ResponseHeader,
+    ResponseHistoryEntry, ResponsePanel, ResponsePanelTab, ResponseState, SentRequest,
 };
+use scripting::{ScriptRequest, ScriptResponse, ScriptVariables, resolve_variables};
+use secrets::{SecretStore, Secrets, mask_value};
+use settings::Settings;
 use theme::ActiveTheme;
 use ui::{
-    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, ContextMenu, DropdownMenu,
-    DropdownVariant, DynamicSpacing, FixedWidth, IconAsset, IconButton, IconButtonShape,
-    IconPosition, IconSize, LineHeightStyle, ScrollAxes, Scrollbars, Text, TextCommon, TextSize,
-    ToggleState, Tooltip, TrackLayout, WithScrollbar,
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, ContextMenu, Disableable,
+    DropdownMenu, DropdownVariant, DynamicSpacing, FixedWidth, HighlightedText, IconAsset,
+    IconButton, IconButtonShape, IconPosition, IconSize, LineHeightStyle, ListItem,
+    ListItemSpacing, ScrollAxes, Scrollbars, StyledExt, Text, TextCommon, TextSize, ToggleState,
+    Toggleable, Tooltip, TrackLayout, WithScrollbar,
+};
+use workspace::{
+    AppState, Toast, Workspace, WorkspaceEvent, notifications::NotificationId, pane::Pane,
+};
+
+use crate::{
+    authorization::{RequestAuth, RequestAuthKind},
+    benchmark::{BenchmarkForm, run_benchmark},
+    dynamic_variables::DynamicVariables,
+    examples::example_from_response,
+    header_completions::{HeaderIssue, ProjectHeaders, header_issues},
+    retry::{RetrySettings, retry_after},
+    scripts::{RequestScripts, ScriptRun},
 };
-use workspace::{AppState, Workspace, WorkspaceEvent, pane::Pane};
 
 pub fn init(cx: &mut App) {
     workspace::register_project_item::<RequestEditor>(cx);
@@ -87,7 +128,7 @@ fn update_response_panel(
                 match &request_editor.request {
                     RequestEditorState::Ready(_) => (
                         true,
-                        request_editor.response(),
+                        Some(request_editor.response()),
                         request_editor.active_response_tab(),
                     ),
                     RequestEditorState::Invalid { .. } => (false, None, ResponsePanelTab::Body),
@@ -246,6 +287,24 @@ fn normalize_url(url: &str) -> Option<Url> {
     Url::parse(&url).ok()
 }
 
+/// Loads a JSON Schema from `path`, relative to the project's root worktree.
+fn load_json_schema(
+    project: &Project,
+    path: &str,
+    cx: &App,
+) -> Task<anyhow::Result<Arc<JsonSchema>>> {
+    let abs_path = RelPath::unix(path)
+        .ok()
+        .and_then(|rel_path| project.absolutize(rel_path, cx));
+    let fs = AppState::global(cx).fs.clone();
+    let path = path.to_owned();
+    cx.background_spawn(async move {
+        let abs_path = abs_path.with_context(|| format!("`{path}` is not in the worktree"))?;
+        let contents = fs.load(&abs_path).await?;
+        Ok(Arc::new(JsonSchema::parse(&contents)?))
+    })
+}
+
 enum RequestEditorState {
     Ready(Request),
     Invalid {
@@ -258,7 +317,23 @@ enum RequestEditorState {
 enum RequestEditorTab {
     Parameters,
     Headers,
+    Auth,
     Body,
+    Scripts,
+    Examples,
+    Benchmark,
+}
+
+/// A field of the request that [`RequestEditor::focus_field`] can focus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestField {
+    Method,
+    Url,
+    /// The name of the header at the index, in the order the headers are in the request file.
+    HeaderName(usize),
+    HeaderValue(usize),
+    /// The body, with the byte range of it selected.
+    Body(Range<usize>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -270,22 +345,47 @@ pub enum RequestEditorEvent {
     FileHandleChanged,
 }
 
+/// Emitted when a response to the request has been received or the request failed.
+#[derive(Clone)]
+pub struct RequestSent {
+    /// The absolute path of the request file, if it has one.
+    pub path: Option<PathBuf>,
+    /// The request as it was in the editor when it was sent, including unsaved changes.
+    pub request_file: RequestFile,
+    pub response: ResponseHistoryEntry,
+}
+
 type RequestMeta = RequestFileMeta;
 
 type RequestBodyType = RequestFileBodyType;
 
+fn body_language_name(body_type: Option<RequestBodyType>) -> Option<&'static str> {
+    match body_type {
+        Some(RequestBodyType::Json) => Some("JSON"),
+        Some(RequestBodyType::Html) => Some("HTML"),
+        Some(RequestBodyType::Xml) => Some("XML"),
+        Some(RequestBodyType::Text) | None => None,
+    }
+}
+
 struct RequestHttp {
     method: Method,
     url: Entity<InputField>,
     params: Vec<RequestParam>,
+    path_variables: Vec<RequestPathVariable>,
     headers: Vec<RequestHeader>,
     body_type: Option<RequestBodyType>,
     body: Option<RequestBody>,
+    schema: Option<RequestFileSchema>,
+    retry: Option<RequestFileRetry>,
+    auth: RequestAuth,
+    scripts: RequestScripts,
 }
 
 struct Request {
     meta: RequestMeta,
     http: RequestHttp,
+    examples: Vec<RequestFileExample>,
 }
 
 impl Request {
@@ -302,7 +402,7 @@ impl Request {
         })?;
         let url = cx.new(|cx| InputField::new(window, cx, "https://example.com"));
         url.update(cx, |field, cx| {
-            field.set_value(&request_file.http.url, window, cx);
+            field.set_value(&request_file.http.url_with_params(), window, cx);
         });
         let mut params = Vec::new();
         for param in &request_file.http.params {
@@ -318,6 +418,19 @@ impl Request {
             }
             params.push(request_param);
         }
+        let path_variables = path_variable_names(&request_file.http.url)
+            .into_iter()
+            .map(|name| {
+                let value = request_file
+                    .http
+                    .path_variables
+                    .iter()
+                    .find(|variable| variable.name == name)
+                    .map(|variable| variable.value.as_str())
+                    .unwrap_or_default();
+                RequestPathVariable::new(name, value, window, cx)
+            })
+            .collect();
         let mut headers = Vec::new();
         for header in &request_file.http.headers {
             let mut request_header = RequestHeader::new(window, cx);
@@ -338,6 +451,12 @@ impl Request {
             .body
             .as_ref()
             .map(|body| RequestBody::from_request_file_body(body, window, cx));
+        let auth = RequestAuth::from_request_file_auth(request_file.http.auth.as_ref(), window, cx);
+        let scripts = RequestScripts::from_request_file_scripts(
+            request_file.http.scripts.as_ref(),
+            window,
+            cx,
+        );
 
         Ok(Self {
             meta: request_file.meta.clone(),
@@ -345,10 +464,16 @@ impl Request {
                 method,
                 url,
                 params,
+                path_variables,
                 headers,
                 body_type,
                 body,
+                schema: request_file.http.schema.clone(),
+                retry: request_file.http.retry.clone(),
+                auth,
+                scripts,
             },
+            examples: request_file.examples.clone(),
         })
     }
 
@@ -369,6 +494,320 @@ impl Request {
             false
         }
     }
+
+    fn delete_example(&mut self, index: usize) -> bool {
+        if index < self.examples.len() {
+            self.examples.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// What is sent for a request, read from the editor's fields.
+#[derive(Clone)]
+struct OutgoingRequest {
+    method: Method,
+    url: String,
+    path_variables: Vec<RequestFilePathVariable>,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    auth: Option<RequestFileAuth>,
+    /// The secrets the request can reference, whose values are masked wherever it is shown.
+    secrets: Secrets,
+    /// Credentials added to the request, like access tokens, which are masked like secrets.
+    credentials: Vec<String>,
+    /// The variables set by scripts, which the request references as `{{var.name}}`.
+    variables: BTreeMap<String, String>,
+    dynamic_variables: DynamicVariables,
+}
+
+impl OutgoingRequest {
+    fn new(
+        request: &Request,
+        secrets: Secrets,
+        variables: BTreeMap<String, String>,
+        cx: &App,
+    ) -> Self {
+        Self {
+            method: request.http.method.clone(),
+            url: request.http.url.read(cx).value(cx),
+            path_variables: request
+                .http
+                .path_variables
+                .iter()
+                .map(|variable| variable.to_request_file_path_variable(cx))
+                .collect(),
+            headers: request
+                .http
+                .headers
+                .iter()
+                .filter_map(|header| {
+                    if header.disabled {
+                        return None;
+                    }
+
+                    let name = header.name.read(cx).value(cx).trim().to_string();
+                    if name.is_empty() {
+                        return None;
+                    }
+
+                    let value = header.value.read(cx).value(cx);
+                    Some((name, value))
+                })
+                .collect(),
+            body: request
+                .http
+                .body_type
+                .and_then(|_| request.http.body.as_ref().map(|body| body.data(cx)))
+                .filter(|body| !body.is_empty()),
+            auth: request.http.auth.to_request_file_auth(cx),
+            secrets,
+            credentials: Vec::new(),
+            variables,
+            dynamic_variables: DynamicVariables::new(Timestamp::now()),
+        }
+    }
+
+    /// The request as its scripts see it, with its references unresolved.
+    fn script_request(&self) -> ScriptRequest {
+        ScriptRequest {
+            method: self.method.to_string(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+        }
+    }
+
+    /// Applies the changes the pre-request scripts made to the request.
+    fn apply_script_request(&mut self, request: ScriptRequest) -> anyhow::Result<()> {
+        self.method = Method::from_bytes(request.method.as_bytes())
+            .with_context(|| format!("invalid request method `{}`", request.method))?;
+        self.url = request.url;
+        self.headers = request.headers;
+        self.body = request.body;
+        Ok(())
+    }
+
+    /// Replaces the `{{var.name}}` and `{{secret.name}}` references in the request with the
+    /// values of the variables and secrets, then evaluates its dynamic variables, like
+    /// `{{$uuid}}`.
+    fn resolve(self) -> anyhow::Result<Self> {
+        self.resolve_references()?.evaluate_dynamic_variables()
+    }
+
+    /// Replaces the `{{var.name}}` and `{{secret.name}}` references in the request. Secrets are
+    /// resolved before dynamic variables so they can be passed to them, as in
+    /// `{{$base64 {{secret.credentials}}}}`.
+    fn resolve_references(mut self) -> anyhow::Result<Self> {
+        let (variables, secrets) = (&self.variables, &self.secrets);
+        resolve_request_texts(
+            &mut self.url,
+            &mut self.path_variables,
+            &mut self.headers,
+            &mut self.body,
+            |text| secrets.resolve(&resolve_variables(text, variables)?),
+        )?;
+        match &mut self.auth {
+            Some(RequestFileAuth::OAuth2(config)) => resolve_oauth2_secrets(config, &self.secrets)?,
+            Some(RequestFileAuth::AwsSigV4(config)) => {
+                for value in [
+                    &mut config.access_key_id,
+                    &mut config.secret_access_key,
+                    &mut config.session_token,
+                    &mut config.region,
+                    &mut config.service,
+                ] {
+                    *value = self.secrets.resolve(value)?;
+                }
+            }
+            None => {}
+        }
+        Ok(self)
+    }
+
+    fn evaluate_dynamic_variables(mut self) -> anyhow::Result<Self> {
+        let dynamic_variables = &mut self.dynamic_variables;
+        resolve_request_texts(
+            &mut self.url,
+            &mut self.path_variables,
+            &mut self.headers,
+            &mut self.body,
+            |text| dynamic_variables.resolve(text),
+        )?;
+        // What is derived from a secret, like its Base64 encoding, is masked like the secret.
+        for (expression, value) in self.dynamic_variables.values() {
+            if self.secrets.mask(expression) != *expression {
+                self.credentials.push(value.clone());
+            }
+        }
+        Ok(self)
+    }
+
+    /// A copy of the request, whose references must already be resolved, with its dynamic
+    /// variables evaluated anew as of `now`, along with its URL. Each request a benchmark sends
+    /// gets its own values this way.
+    fn evaluate_for_send(&self, now: Timestamp) -> anyhow::Result<(Self, Url)> {
+        let mut request = self.clone();
+        request.dynamic_variables = DynamicVariables::new(now);
+        let request = request.evaluate_dynamic_variables()?;
+        let url = request.url()?;
+        Ok((request, url))
+    }
+
+    /// Obtains the token the request's authorization needs and adds it as the `Authorization`
+    /// header, unless the request already sets one.
+    async fn authorize(
+        &mut self,
+        http_client: &Arc<dyn HttpClient>,
+        cx: &mut AsyncApp,
+    ) -> anyhow::Result<()> {
+        let Some(RequestFileAuth::OAuth2(config)) = self.auth.clone() else {
+            return Ok(());
+        };
+        let oauth2_tokens = cx
+            .update(|cx| OAuth2Tokens::try_global(cx))
+            .context("OAuth 2.0 isn't available")?;
+        let token = oauth2_tokens
+            .update(cx, |tokens, cx| {
+                tokens.token(config, http_client.clone(), cx)
+            })
+            .await
+            .context("failed to get an OAuth 2.0 token")?;
+        self.add_token(&token);
+        Ok(())
+    }
+
+    fn add_token(&mut self, token: &OAuth2Token) {
+        if !self
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+        {
+            self.headers
+                .push(("Authorization".to_owned(), token.authorization()));
+        }
+        self.credentials.push(token.access_token.clone());
+    }
+
+    fn mask(&self, text: &str) -> String {
+        self.credentials
+            .iter()
+            .fold(self.secrets.mask(text), |masked, credential| {
+                mask_value(&masked, credential)
+            })
+    }
+
+    /// The request as it is shown and recorded, with the values of secrets masked.
+    fn sent_request(&self, url: &Url) -> SentRequest {
+        SentRequest {
+            method: self.method.to_string(),
+            url: self.mask(url.as_str()),
+            headers: self
+                .headers
+                .iter()
+                .map(|(name, value)| (self.mask(name), self.mask(value)))
+                .collect(),
+            body: self.body.as_deref().map(|body| self.mask(body)),
+            variables: self
+                .dynamic_variables
+                .values()
+                .iter()
+                .map(|(expression, value)| (self.mask(expression), self.mask(value)))
+                .collect(),
+        }
+    }
+
+    /// The URL with the path variables substituted.
+    fn url(&self) -> anyhow::Result<Url> {
+        substitute_path_variables(&self.url, &self.path_variables)
+            .and_then(|url| normalize_url(&url).context("invalid URL"))
+    }
+
+    /// The request to send, signed as of now if its authorization signs requests.
+    fn to_http_request(&self, url: &Url) -> http::Result<http::Request<AsyncBody>> {
+        let mut headers = self.headers.clone();
+        let mut body = self.body.clone().map(String::into_bytes);
+        if let Some(RequestFileAuth::AwsSigV4(config)) = &self.auth {
+            let signed_body = sign_aws_sigv4(
+                config,
+                self.method.as_str(),
+                url,
+                &mut headers,
+                body.unwrap_or_default(),
+                Timestamp::now(),
+            );
+            body = Some(signed_body).filter(|body| !body.is_empty());
+        }
+
+        let mut builder = Builder::new()
+            .method(self.method.clone())
+            .uri(url.as_str())
+            .follow_redirects(RedirectPolicy::FollowAll);
+
+        for (name, value) in &headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        builder.body(body.map_or_else(AsyncBody::empty, AsyncBody::from))
+    }
+}
+
+/// Applies `resolve` to the parts of a request that can contain references. The values that
+/// references in query parameters resolve to are encoded, so they can contain any character.
+fn resolve_request_texts(
+    url: &mut String,
+    path_variables: &mut [RequestFilePathVariable],
+    headers: &mut [(String, String)],
+    body: &mut Option<String>,
+    mut resolve: impl FnMut(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<()> {
+    let (base_url, query) = split_url_query(url);
+    let base_url = resolve(&base_url)?;
+    let params = query
+        .map(parse_query)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| {
+            anyhow::Ok((
+                resolve_query_text(&name, &mut resolve)?,
+                resolve_query_text(&value, &mut resolve)?,
+            ))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    *url = join_url_query(
+        &base_url,
+        params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+    );
+    for variable in path_variables {
+        variable.value = resolve(&variable.value)?;
+    }
+    for (name, value) in headers {
+        *name = resolve(name)?;
+        *value = resolve(value)?;
+    }
+    if let Some(body) = body {
+        *body = resolve(body)?;
+    }
+    Ok(())
+}
+
+fn resolve_oauth2_secrets(config: &mut RequestFileOAuth2, secrets: &Secrets) -> anyhow::Result<()> {
+    for value in [
+        &mut config.token_url,
+        &mut config.authorization_url,
+        &mut config.client_id,
+        &mut config.client_secret,
+        &mut config.scope,
+        &mut config.username,
+        &mut config.password,
+    ] {
+        *value = secrets.resolve(value)?;
+    }
+    Ok(())
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -380,16 +819,18 @@ impl RequestSnapshot {
             meta: request.meta.clone(),
             http: RequestFileHttp {
                 method: request.http.method.as_str().to_owned(),
-                url: request.http.url.read(cx).value(cx),
+                url: split_url_query(&request.http.url.read(cx).value(cx)).0,
                 params: request
                     .http
                     .params
                     .iter()
-                    .map(|param| RequestFileParam {
-                        name: param.name.read(cx).value(cx),
-                        value: param.value.read(cx).value(cx),
-                        disabled: param.disabled,
-                    })
+                    .map(|param| param.to_request_file_param(cx))
+                    .collect(),
+                path_variables: request
+                    .http
+                    .path_variables
+                    .iter()
+                    .map(|variable| variable.to_request_file_path_variable(cx))
                     .collect(),
                 headers: request
                     .http
@@ -407,7 +848,12 @@ impl RequestSnapshot {
                         data: body.data(cx),
                     })
                 }),
+                schema: request.http.schema.clone(),
+                retry: request.http.retry.clone(),
+                auth: request.http.auth.to_request_file_auth(cx),
+                scripts: request.http.scripts.to_request_file_scripts(cx),
             },
+            examples: request.examples.clone(),
         })
     }
 
@@ -438,6 +884,37 @@ impl RequestParam {
         self.value
             .update(cx, |field, cx| field.set_muted(disabled, window, cx));
     }
+
+    fn to_request_file_param(&self, cx: &App) -> RequestFileParam {
+        RequestFileParam {
+            name: self.name.read(cx).value(cx),
+            value: self.value.read(cx).value(cx),
+            disabled: self.disabled,
+        }
+    }
+}
+
+struct RequestPathVariable {
+    name: SharedString,
+    value: Entity<InputField>,
+}
+
+impl RequestPathVariable {
+    fn new(name: impl Into<SharedString>, value: &str, window: &mut Window, cx: &mut App) -> Self {
+        let value_field = cx.new(|cx| InputField::new(window, cx, "Value"));
+        value_field.update(cx, |field, cx| field.set_value(value, window, cx));
+        Self {
+            name: name.into(),
+            value: value_field,
+        }
+    }
+
+    fn to_request_file_path_variable(&self, cx: &App) -> RequestFilePathVariable {
+        RequestFilePathVariable {
+            name: self.name.to_string(),
+            value: self.value.read(cx).value(cx),
+        }
+    }
 }
 
 struct RequestHeader {
@@ -499,6 +976,39 @@ impl RequestBody {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeaderField {
+    Name,
+    Value,
+}
+
+/// The completions shown under the header input that is being edited.
+struct HeaderCompletions {
+    input: EntityId,
+    field: HeaderField,
+    candidates: Vec<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl HeaderCompletions {
+    fn selected_candidate(&self) -> Option<&String> {
+        self.matches
+            .get(self.selected_index)
+            .and_then(|string_match| self.candidates.get(string_match.candidate_id))
+    }
+}
+
+const MAX_HEADER_COMPLETIONS: usize = 8;
+const BENCHMARK_REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The JSON Schema configured for the request body, and the errors the body currently has.
+struct BodySchema {
+    path: String,
+    schema: Result<Arc<JsonSchema>, SharedString>,
+    errors: Vec<SourceError>,
+}
+
 pub struct RequestEditor {
     focus_handle: FocusHandle,
     workspace: WeakEntity<Workspace>,
@@ -508,13 +1018,30 @@ pub struct RequestEditor {
     request_snapshot: Option<RequestSnapshot>,
     active_tab: RequestEditorTab,
     active_response_tab: ResponsePanelTab,
-    response: Option<Entity<Response>>,
+    response: Entity<Response>,
     http_client: Arc<dyn HttpClient>,
     params_scroll_handle: ScrollHandle,
     headers_scroll_handle: ScrollHandle,
     input_subscriptions: Vec<Subscription>,
     body_subscription: Option<Subscription>,
+    body_schema: Option<BodySchema>,
+    project_headers: ProjectHeaders,
+    header_completions: Option<HeaderCompletions>,
+    /// The value of the header input whose completion was just accepted, so the edit that
+    /// accepting it makes doesn't open the completions again.
+    accepted_header_completion: Option<(EntityId, String)>,
+    header_completions_task: Option<Task<()>>,
+    project_headers_task: Option<Task<()>>,
+    benchmark_form: BenchmarkForm,
+    benchmark_task: Option<BenchmarkTask>,
     _buffer_subscription: Subscription,
+    _response_subscription: Subscription,
+    _oauth2_tokens_subscription: Option<Subscription>,
+}
+
+struct BenchmarkTask {
+    cancelled: Arc<AtomicBool>,
+    _task: Task<()>,
 }
 
 impl RequestEditor {
@@ -543,7 +1070,8 @@ impl RequestEditor {
                     }
                 }
                 RequestBufferEvent::FileHandleChanged => {
-                    if let Err(error) = request_editor.update(cx, |_, cx| {
+                    if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                        request_editor.sync_response_history_path(window, cx);
                         cx.emit(RequestEditorEvent::TitleChanged);
                         cx.emit(RequestEditorEvent::FileHandleChanged);
                         cx.notify();
@@ -569,6 +1097,8 @@ impl RequestEditor {
                         request_editor.input_subscriptions = input_subscriptions;
                         request_editor.body_subscription = body_subscription;
                         request_editor.set_language_for_body(cx);
+                        request_editor.load_body_schema(cx);
+                        request_editor.dismiss_header_completions(cx);
                         cx.emit(RequestEditorEvent::TitleChanged);
                         cx.notify();
                     }) {
@@ -579,7 +1109,21 @@ impl RequestEditor {
             },
         );
 
-        let this = Self {
+        let response = cx.new(|cx| Response::new(window, cx));
+        let response_subscription = cx.observe(&response, |request_editor, _, cx| {
+            if request_editor.active_tab == RequestEditorTab::Examples {
+                cx.notify();
+            }
+        });
+        let oauth2_tokens_subscription = OAuth2Tokens::try_global(cx).map(|oauth2_tokens| {
+            cx.observe(&oauth2_tokens, |request_editor, _, cx| {
+                if request_editor.active_tab == RequestEditorTab::Auth {
+                    cx.notify();
+                }
+            })
+        });
+
+        let mut this = Self {
             focus_handle,
             workspace,
             project,
@@ -588,15 +1132,28 @@ impl RequestEditor {
             request_snapshot,
             active_tab: RequestEditorTab::Parameters,
             active_response_tab: ResponsePanelTab::Body,
-            response: None,
+            response,
             http_client: AppState::global(cx).http_client.clone(),
             params_scroll_handle: ScrollHandle::new(),
             headers_scroll_handle: ScrollHandle::new(),
             input_subscriptions,
             body_subscription,
+            body_schema: None,
+            project_headers: ProjectHeaders::default(),
+            header_completions: None,
+            accepted_header_completion: None,
+            header_completions_task: None,
+            project_headers_task: None,
+            benchmark_form: BenchmarkForm::new(window, cx),
+            benchmark_task: None,
             _buffer_subscription: buffer_subscription,
+            _response_subscription: response_subscription,
+            _oauth2_tokens_subscription: oauth2_tokens_subscription,
         };
         this.set_language_for_body(cx);
+        this.load_body_schema(cx);
+        this.load_project_headers(cx);
+        this.sync_response_history_path(window, cx);
         this
     }
 
@@ -604,14 +1161,41 @@ impl RequestEditor {
         project::ProjectItem::project_path(self.buffer.read(cx), cx)
     }
 
+    /// The secrets of the active environment of the project the request belongs to.
+    fn secrets(&self, cx: &App) -> Secrets {
+        self.project
+            .read(cx)
+            .root(cx)
+            .map(|project_path| SecretStore::active_secrets(&project_path, cx))
+            .unwrap_or_default()
+    }
+
+    /// The variables scripts have set for the project the request belongs to.
+    fn script_variables(&self, cx: &App) -> BTreeMap<String, String> {
+        self.project
+            .read(cx)
+            .root(cx)
+            .map(|project_path| ScriptVariables::project_variables(&project_path, cx))
+            .unwrap_or_default()
+    }
+
     fn path_style(&self, cx: &App) -> PathStyle {
         self.project.read(cx).path_style(cx)
     }
 
-    fn response(&self) -> Option<Entity<Response>> {
+    fn response(&self) -> Entity<Response> {
         self.response.clone()
     }
 
+    fn sync_response_history_path(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let path = self
+            .project_path(cx)
+            .and_then(|project_path| self.project.read(cx).absolute_path(&project_path, cx));
+        self.response.update(cx, |response, cx| {
+            response.set_history_path(path, window, cx);
+        });
+    }
+
     fn active_response_tab(&self) -> ResponsePanelTab {
         self.active_response_tab
     }
@@ -695,29 +1279,51 @@ impl RequestEditor {
         cx: &mut Context<Self>,
     ) -> Vec<Subscription> {
         let mut subscriptions = Vec::new();
-        subscriptions.push(Self::subscribe_to_input(&request.http.url, window, cx));
+        subscriptions.push(Self::subscribe_to_input(
+            &request.http.url,
+            Self::sync_from_url,
+            window,
+            cx,
+        ));
         for param in &request.http.params {
-            subscriptions.push(Self::subscribe_to_input(&param.name, window, cx));
-            subscriptions.push(Self::subscribe_to_input(&param.value, window, cx));
+            subscriptions.extend(Self::subscribe_to_param(param, window, cx));
+        }
+        for variable in &request.http.path_variables {
+            subscriptions.push(Self::subscribe_to_input(
+                &variable.value,
+                |_, _, _| {},
+                window,
+                cx,
+            ));
         }
         for header in &request.http.headers {
-            subscriptions.push(Self::subscribe_to_input(&header.name, window, cx));
-            subscriptions.push(Self::subscribe_to_input(&header.value, window, cx));
+            subscriptions.push(Self::subscribe_to_header_input(&header.name, window, cx));
+            subscriptions.push(Self::subscribe_to_header_input(&header.value, window, cx));
+        }
+        for input in request.http.auth.inputs() {
+            subscriptions.push(Self::subscribe_to_input(input, |_, _, _| {}, window, cx));
+        }
+        for editor in request.http.scripts.editors() {
+            subscriptions.push(Self::subscribe_to_script(editor, window, cx));
         }
         subscriptions
     }
 
+    /// Subscribes to edits of `input`, which are synced to the rest of the request with `sync`
+    /// before they are recorded.
     fn subscribe_to_input(
         input: &Entity<InputField>,
+        sync: fn(&mut Self, &mut Window, &mut Context<Self>),
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Subscription {
         let request_editor = cx.weak_entity();
         let editor = input.read(cx).editor().clone();
         editor.subscribe(
-            Box::new(move |event, _window, cx| {
+            Box::new(move |event, window, cx| {
                 if event == ErasedEditorEvent::BufferEdited
                     && let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                        sync(request_editor, window, cx);
                         request_editor.mark_edited(cx);
                     })
                 {
@@ -729,10 +1335,210 @@ impl RequestEditor {
         )
     }
 
+    fn subscribe_to_param(
+        param: &RequestParam,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> [Subscription; 2] {
+        [
+            Self::subscribe_to_input(&param.name, Self::sync_url_from_params, window, cx),
+            Self::subscribe_to_input(&param.value, Self::sync_url_from_params, window, cx),
+        ]
+    }
+
+    fn sync_from_url(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.sync_params_from_url(window, cx);
+        self.sync_path_variables_from_url(window, cx);
+    }
+
+    /// Makes the path variables match the `:name` and `{name}` segments of the URL, keeping the
+    /// values of the ones that are still there.
+    fn sync_path_variables_from_url(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &mut self.request else {
+            return;
+        };
+
+        let names = path_variable_names(&request.http.url.read(cx).value(cx));
+        if request
+            .http
+            .path_variables
+            .iter()
+            .map(|variable| variable.name.as_ref())
+            .eq(names.iter().map(String::as_str))
+        {
+            return;
+        }
+
+        let mut previous_variables = mem::take(&mut request.http.path_variables);
+        let mut subscriptions = Vec::new();
+        for name in names {
+            let variable = match previous_variables
+                .iter()
+                .position(|variable| variable.name.as_ref() == name)
+            {
+                Some(index) => previous_variables.remove(index),
+                None => {
+                    let variable = RequestPathVariable::new(name, "", window, cx);
+                    subscriptions.push(Self::subscribe_to_input(
+                        &variable.value,
+                        |_, _, _| {},
+                        window,
+                        cx,
+                    ));
+                    variable
+                }
+            };
+            request.http.path_variables.push(variable);
+        }
+        self.input_subscriptions.extend(subscriptions);
+        cx.notify();
+    }
+
+    /// Makes the enabled parameters match the query string of the URL. Disabled and blank
+    /// parameters aren't part of the URL, so they are kept where they are.
+    fn sync_params_from_url(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &mut self.request else {
+            return;
+        };
+
+        // A parameter whose text encodes to the same query text is left as it was typed.
+        let url = request.http.url.read(cx).value(cx);
+        let mut query_params = split_url_query(&url)
+            .1
+            .map(parse_query)
+            .unwrap_or_default()
+            .into_iter();
+        let mut index = 0;
+        while let Some(param) = request.http.params.get(index) {
+            if !param.to_request_file_param(cx).is_in_url() {
+                index += 1;
+                continue;
+            }
+
+            let Some((name, value)) = query_params.next() else {
+                request.http.params.remove(index);
+                continue;
+            };
+            for (field, text, escape) in [
+                (&param.name, name, escape_query_name as fn(&str) -> String),
+                (&param.value, value, escape_query_value),
+            ] {
+                if escape(&field.read(cx).value(cx)) != escape(&text) {
+                    field.update(cx, |field, cx| field.set_value(&text, window, cx));
+                }
+            }
+            index += 1;
+        }
+
+        let mut subscriptions = Vec::new();
+        for (name, value) in query_params {
+            let param = RequestParam::new(window, cx);
+            param
+                .name
+                .update(cx, |field, cx| field.set_value(&name, window, cx));
+            param
+                .value
+                .update(cx, |field, cx| field.set_value(&value, window, cx));
+            subscriptions.extend(Self::subscribe_to_param(&param, window, cx));
+            request.http.params.push(param);
+        }
+        self.input_subscriptions.extend(subscriptions);
+    }
+
+    /// Rewrites the query string of the URL from the enabled parameters, unless it already encodes
+    /// to the same text, so the URL keeps the exact text it was typed with.
+    fn sync_url_from_params(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+
+        let url = request.http.url.read(cx).value(cx);
+        let (base_url, query) = split_url_query(&url);
+        let params = request
+            .http
+            .params
+            .iter()
+            .map(|param| param.to_request_file_param(cx))
+            .filter(RequestFileParam::is_in_url)
+            .map(|param| (param.name, param.value))
+            .collect::<Vec<_>>();
+        let escaped = |params: &[(String, String)]| {
+            params
+                .iter()
+                .map(|(name, value)| (escape_query_name(name), escape_query_value(value)))
+                .collect::<Vec<_>>()
+        };
+        if escaped(&query.map(parse_query).unwrap_or_default()) == escaped(&params) {
+            return;
+        }
+
+        let url = join_url_query(
+            &base_url,
+            params
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        request
+            .http
+            .url
+            .update(cx, |field, cx| field.set_value(&url, window, cx));
+    }
+
+    fn subscribe_to_header_input(
+        input: &Entity<InputField>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Subscription {
+        let request_editor = cx.weak_entity();
+        let input_id = input.entity_id();
+        let editor = input.read(cx).editor().clone();
+        editor.subscribe(
+            Box::new(move |event, _window, cx| {
+                if let Err(error) = request_editor.update(cx, |request_editor, cx| match event {
+                    ErasedEditorEvent::BufferEdited => {
+                        request_editor.mark_edited(cx);
+                        request_editor.update_header_completions(input_id, cx);
+                    }
+                    ErasedEditorEvent::Blurred => {
+                        if request_editor
+                            .header_completions
+                            .as_ref()
+                            .is_some_and(|completions| completions.input == input_id)
+                        {
+                            request_editor.dismiss_header_completions(cx);
+                        }
+                    }
+                }) {
+                    log::debug!("Failed to update request editor header: {error:?}");
+                }
+            }),
+            window,
+            cx,
+        )
+    }
+
     fn subscribe_to_body(
         editor: &Entity<Editor>,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> Subscription {
+        let request_editor = cx.weak_entity();
+        window.subscribe(editor, cx, move |_, event: &EditorEvent, _, cx| {
+            if *event == EditorEvent::BufferEdited
+                && let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                    request_editor.mark_edited(cx);
+                    request_editor.validate_body(cx);
+                })
+            {
+                log::debug!("Failed to update request editor edit state: {error:?}");
+            }
+        })
+    }
+
+    fn subscribe_to_script(
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Subscription {
         let request_editor = cx.weak_entity();
         window.subscribe(editor, cx, move |_, event: &EditorEvent, _, cx| {
@@ -758,20 +1564,15 @@ impl RequestEditor {
             return;
         };
 
-        let language_name = match body_type {
-            Some(RequestBodyType::Json) => "JSON",
-            Some(RequestBodyType::Html) => "HTML",
-            Some(RequestBodyType::Xml) => "XML",
-            Some(RequestBodyType::Text) | None => {
-                payload.update(cx, |payload, cx| {
-                    if let Some(buffer) = payload.as_singleton() {
-                        buffer.update(cx, |buffer, cx| {
-                            buffer.set_language(Some(PLAIN_TEXT.clone()), cx);
-                        });
-                    }
-                });
-                return;
-            }
+        let Some(language_name) = body_language_name(body_type) else {
+            payload.update(cx, |payload, cx| {
+                if let Some(buffer) = payload.as_singleton() {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_language(Some(PLAIN_TEXT.clone()), cx);
+                    });
+                }
+            });
+            return;
         };
 
         let payload_id = payload.entity_id();
@@ -814,6 +1615,109 @@ impl RequestEditor {
         .detach();
     }
 
+    fn format_body(&mut self, format: TextFormat, window: &mut Window, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+        let Some(body) = request.http.body.as_ref() else {
+            return;
+        };
+        let Some(language_name) = body_language_name(request.http.body_type) else {
+            return;
+        };
+
+        match language::format_text(language_name, &body.data(cx), format) {
+            Ok(formatted) => {
+                body.editor().update(cx, |editor, cx| {
+                    editor.apply_text(&formatted, window, cx);
+                });
+            }
+            Err(error) => {
+                let message = format!("Failed to format {language_name} body: {error}");
+                if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(NotificationId::unique::<RequestBody>(), message).autohide(),
+                        cx,
+                    );
+                }) {
+                    log::debug!("Failed to show request body format error: {error:?}");
+                }
+            }
+        }
+    }
+
+    fn body_schema_path(&self) -> Option<&str> {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return None;
+        };
+        request.http.schema.as_ref()?.body.as_deref()
+    }
+
+    /// Loads the schema configured for the body and validates the body against it.
+    fn load_body_schema(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.body_schema_path().map(str::to_owned) else {
+            self.body_schema = None;
+            self.validate_body(cx);
+            return;
+        };
+
+        let load_schema = load_json_schema(self.project.read(cx), &path, cx);
+        cx.spawn(async move |this, cx| {
+            let schema: Result<_, SharedString> = load_schema
+                .await
+                .map_err(|error| format!("Failed to load schema {path}: {error:#}").into());
+            if let Err(error) = this.update(cx, |request_editor, cx| {
+                if request_editor.body_schema_path() != Some(path.as_str()) {
+                    return;
+                }
+                request_editor.body_schema = Some(BodySchema {
+                    path,
+                    schema,
+                    errors: Vec::new(),
+                });
+                request_editor.validate_body(cx);
+            }) {
+                log::debug!("Failed to update request body schema: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    /// Validates a JSON body against its schema and shows the errors as diagnostics in the body
+    /// editor.
+    fn validate_body(&mut self, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+        let Some(body) = request.http.body.as_ref() else {
+            return;
+        };
+
+        let errors = match self.body_schema.as_ref() {
+            Some(BodySchema {
+                schema: Ok(schema), ..
+            }) if request.http.body_type == Some(RequestBodyType::Json) => {
+                json_schema::validate_source(schema, &body.data(cx))
+            }
+            Some(_) | None => Vec::new(),
+        };
+        let diagnostics = errors
+            .iter()
+            .map(|error| Diagnostic {
+                range: MultiBufferOffset(error.range.start)..MultiBufferOffset(error.range.end),
+                severity: DiagnosticSeverity::Error,
+                message: error.message.clone().into(),
+            })
+            .collect::<Vec<_>>();
+        body.editor().update(cx, |editor, cx| {
+            editor.set_diagnostics(diagnostics, cx);
+        });
+        if let Some(body_schema) = self.body_schema.as_mut() {
+            body_schema.errors = errors;
+        }
+        cx.notify();
+    }
+
     fn state_from_request_file(
         request_file: RequestFileState,
         window: &mut Window,
@@ -864,6 +1768,206 @@ impl RequestEditor {
         )
     }
 
+    /// Collects the headers of the other requests in the project, which are suggested as
+    /// completions and aren't reported as unknown.
+    fn load_project_headers(&mut self, cx: &mut Context<Self>) {
+        let project_path = self.project_path(cx);
+        let load_request_files = self.project.read(cx).load_request_files(cx);
+        self.project_headers_task = Some(cx.spawn(async move |this, cx| {
+            let request_files = load_request_files.await;
+            let project_headers = ProjectHeaders::new(
+                request_files
+                    .iter()
+                    .filter(|(path, _)| Some(path) != project_path.as_ref())
+                    .map(|(_, request_file)| request_file),
+            );
+            if let Err(error) = this.update(cx, |request_editor, cx| {
+                request_editor.project_headers = project_headers;
+                cx.notify();
+            }) {
+                log::debug!("Failed to update project headers: {error:?}");
+            }
+        }));
+    }
+
+    fn update_header_completions(&mut self, input_id: EntityId, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+        let Some((field, query, candidates)) = request.http.headers.iter().find_map(|header| {
+            if header.name.entity_id() == input_id {
+                Some((
+                    HeaderField::Name,
+                    header.name.read(cx).value(cx),
+                    self.project_headers.name_candidates(),
+                ))
+            } else if header.value.entity_id() == input_id {
+                let name = header.name.read(cx).value(cx);
+                Some((
+                    HeaderField::Value,
+                    header.value.read(cx).value(cx),
+                    self.project_headers.value_candidates(&name),
+                ))
+            } else {
+                None
+            }
+        }) else {
+            return;
+        };
+
+        if let Some((accepted_input, accepted_value)) = &self.accepted_header_completion {
+            if *accepted_input == input_id && *accepted_value == query {
+                return;
+            }
+            self.accepted_header_completion = None;
+        }
+
+        let query = query.trim().to_owned();
+        if query.is_empty() || candidates.is_empty() {
+            self.dismiss_header_completions(cx);
+            return;
+        }
+
+        let executor = cx.background_executor().clone();
+        self.header_completions_task = Some(cx.spawn(async move |this, cx| {
+            let string_candidates = candidates
+                .iter()
+                .enumerate()
+                .map(|(index, candidate)| StringMatchCandidate::new(index, candidate))
+                .collect::<Vec<_>>();
+            let matches = fuzzy_nucleo::match_strings_async(
+                &string_candidates,
+                &query,
+                fuzzy_nucleo::Case::Smart,
+                fuzzy_nucleo::LengthPenalty::On,
+                MAX_HEADER_COMPLETIONS,
+                &AtomicBool::default(),
+                executor,
+            )
+            .await
+            .into_iter()
+            .filter(|string_match| {
+                candidates
+                    .get(string_match.candidate_id)
+                    .is_some_and(|candidate| !candidate.eq_ignore_ascii_case(&query))
+            })
+            .collect::<Vec<_>>();
+
+            if let Err(error) = this.update(cx, |request_editor, cx| {
+                request_editor.header_completions =
+                    (!matches.is_empty()).then(|| HeaderCompletions {
+                        input: input_id,
+                        field,
+                        candidates,
+                        matches,
+                        selected_index: 0,
+                    });
+                cx.notify();
+            }) {
+                log::debug!("Failed to update header completions: {error:?}");
+            }
+        }));
+    }
+
+    fn dismiss_header_completions(&mut self, cx: &mut Context<Self>) {
+        self.header_completions_task = None;
+        if self.header_completions.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn select_next_header_completion(
+        &mut self,
+        _: &actions::menu::SelectNext,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(completions) = &mut self.header_completions {
+            completions.selected_index =
+                (completions.selected_index + 1) % completions.matches.len().max(1);
+            cx.notify();
+        }
+    }
+
+    fn select_previous_header_completion(
+        &mut self,
+        _: &actions::menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(completions) = &mut self.header_completions {
+            completions.selected_index = completions
+                .selected_index
+                .checked_sub(1)
+                .unwrap_or_else(|| completions.matches.len().saturating_sub(1));
+            cx.notify();
+        }
+    }
+
+    fn confirm_header_completion(
+        &mut self,
+        _: &actions::menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selected_index = self
+            .header_completions
+            .as_ref()
+            .map(|completions| completions.selected_index);
+        if let Some(selected_index) = selected_index {
+            self.accept_header_completion(selected_index, window, cx);
+        }
+    }
+
+    fn cancel_header_completions(
+        &mut self,
+        _: &actions::menu::Cancel,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.dismiss_header_completions(cx);
+    }
+
+    /// Replaces the header input with the completion at `index`. Accepting a name moves the focus
+    /// to the value of the same header.
+    fn accept_header_completion(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.header_completions_task = None;
+        let Some(mut completions) = self.header_completions.take() else {
+            return;
+        };
+        completions.selected_index = index;
+        let Some(candidate) = completions.selected_candidate().cloned() else {
+            return;
+        };
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+        let Some(header) = request.http.headers.iter().find(|header| {
+            header.name.entity_id() == completions.input
+                || header.value.entity_id() == completions.input
+        }) else {
+            return;
+        };
+        let (input, value) = (header.name.clone(), header.value.clone());
+
+        self.accepted_header_completion = Some((completions.input, candidate.clone()));
+        match completions.field {
+            HeaderField::Name => {
+                input.update(cx, |field, cx| field.set_value(&candidate, window, cx));
+                window.focus(&value.read(cx).focus_handle(cx), cx);
+            }
+            HeaderField::Value => {
+                value.update(cx, |field, cx| field.set_value(&candidate, window, cx));
+            }
+        }
+        cx.notify();
+    }
+
     fn mark_edited(&mut self, cx: &mut Context<Self>) {
         let request_snapshot = match &self.request {
             RequestEditorState::Ready(request) => Some(RequestSnapshot::from_request(request, cx)),
@@ -899,13 +2003,11 @@ impl RequestEditor {
         }
 
         let param = RequestParam::new(window, cx);
-        let name_subscription = Self::subscribe_to_input(&param.name, window, cx);
-        let value_subscription = Self::subscribe_to_input(&param.value, window, cx);
+        let subscriptions = Self::subscribe_to_param(&param, window, cx);
         if let RequestEditorState::Ready(request) = &mut self.request {
             request.http.params.push(param);
         }
-        self.input_subscriptions.push(name_subscription);
-        self.input_subscriptions.push(value_subscription);
+        self.input_subscriptions.extend(subscriptions);
         self.mark_edited(cx);
     }
 
@@ -915,8 +2017,8 @@ impl RequestEditor {
         }
 
         let header = RequestHeader::new(window, cx);
-        let name_subscription = Self::subscribe_to_input(&header.name, window, cx);
-        let value_subscription = Self::subscribe_to_input(&header.value, window, cx);
+        let name_subscription = Self::subscribe_to_header_input(&header.name, window, cx);
+        let value_subscription = Self::subscribe_to_header_input(&header.value, window, cx);
         if let RequestEditorState::Ready(request) = &mut self.request {
             request.http.headers.push(header);
         }
@@ -966,79 +2068,223 @@ impl RequestEditor {
 
         if edited {
             self.mark_edited(cx);
+            self.validate_body(cx);
         }
     }
 
-    pub fn send_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let RequestEditorState::Ready(request) = &self.request else {
-            return;
-        };
+    /// Opens the response panel on this editor's response. Returns `false` if the workspace has
+    /// no response panel.
+    fn set_auth_kind(&mut self, kind: Option<RequestAuthKind>, cx: &mut Context<Self>) {
+        if let RequestEditorState::Ready(request) = &mut self.request
+            && request.http.auth.kind != kind
+        {
+            request.http.auth.kind = kind;
+            self.mark_edited(cx);
+        }
+    }
 
-        let request_method = request.http.method.clone();
-        let request_url = request.http.url.read(cx).value(cx);
-        let request_params = request
-            .http
-            .params
-            .iter()
-            .filter_map(|param| {
-                if param.disabled {
-                    return None;
-                }
+    fn set_oauth2_grant_type(&mut self, grant_type: OAuth2GrantType, cx: &mut Context<Self>) {
+        if let RequestEditorState::Ready(request) = &mut self.request
+            && request.http.auth.oauth2.grant_type != grant_type
+        {
+            request.http.auth.oauth2.grant_type = grant_type;
+            self.mark_edited(cx);
+        }
+    }
 
-                let name = param.name.read(cx).value(cx).trim().to_string();
-                if name.is_empty() {
-                    return None;
-                }
+    fn set_aws_payload_signing(&mut self, payload: AwsPayloadSigning, cx: &mut Context<Self>) {
+        if let RequestEditorState::Ready(request) = &mut self.request
+            && request.http.auth.aws_sigv4.payload != payload
+        {
+            request.http.auth.aws_sigv4.payload = payload;
+            self.mark_edited(cx);
+        }
+    }
 
-                let value = param.value.read(cx).value(cx);
-                Some((name, value))
-            })
-            .collect::<Vec<_>>();
-        let request_headers = request
-            .http
-            .headers
-            .iter()
-            .filter_map(|header| {
-                if header.disabled {
-                    return None;
-                }
+    /// The request's OAuth 2.0 configuration with its secrets resolved, which is what its
+    /// tokens are cached by.
+    fn resolved_oauth2_config(&self, cx: &App) -> Option<anyhow::Result<RequestFileOAuth2>> {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return None;
+        };
+        let Some(RequestFileAuth::OAuth2(mut config)) = request.http.auth.to_request_file_auth(cx)
+        else {
+            return None;
+        };
+        Some(resolve_oauth2_secrets(&mut config, &self.secrets(cx)).map(|()| config))
+    }
 
-                let name = header.name.read(cx).value(cx).trim().to_string();
-                if name.is_empty() {
-                    return None;
+    fn fetch_new_oauth2_token(&mut self, cx: &mut Context<Self>) {
+        let Some(oauth2_tokens) = OAuth2Tokens::try_global(cx) else {
+            return;
+        };
+        match self.resolved_oauth2_config(cx) {
+            Some(Ok(config)) => {
+                let http_client = self.http_client.clone();
+                oauth2_tokens
+                    .update(cx, |tokens, cx| {
+                        tokens.fetch_new_token(config, http_client, cx)
+                    })
+                    .detach_and_log_err(cx);
+            }
+            Some(Err(error)) => {
+                let message = format!("Failed to get an OAuth 2.0 token: {error}");
+                if let Err(error) = self.workspace.update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(NotificationId::unique::<RequestAuth>(), message).autohide(),
+                        cx,
+                    );
+                }) {
+                    log::debug!("Failed to show OAuth 2.0 token error: {error:?}");
                 }
+            }
+            None => {}
+        }
+    }
 
-                let value = header.value.read(cx).value(cx);
-                Some((name, value))
-            })
-            .collect::<Vec<_>>();
-        let request_body = request
-            .http
-            .body_type
-            .and_then(|_| request.http.body.as_ref().map(|body| body.data(cx)))
-            .filter(|body| !body.is_empty());
+    fn clear_oauth2_token(&mut self, cx: &mut Context<Self>) {
+        if let Some(oauth2_tokens) = OAuth2Tokens::try_global(cx)
+            && let Some(Ok(config)) = self.resolved_oauth2_config(cx)
+        {
+            oauth2_tokens.update(cx, |tokens, cx| tokens.clear_token(&config, cx));
+        }
+    }
 
+    fn show_response_panel(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
         let Ok(Some(response_panel)) = self.workspace.update(cx, |workspace, cx| {
             workspace.open_panel::<ResponsePanel>(window, cx);
             workspace.panel::<ResponsePanel>(cx)
         }) else {
-            return;
+            return false;
         };
-        let response = self
-            .response
-            .get_or_insert_with(|| cx.new(|cx| Response::new(window, cx)))
-            .clone();
+        let response = self.response.clone();
         let active_response_tab = self.active_response_tab;
         let on_active_response_tab_change = on_active_response_tab_change(cx.weak_entity());
         response_panel.update(cx, |panel, cx| {
             panel.set_response(
-                Some(response.clone()),
+                Some(response),
                 active_response_tab,
                 Some(on_active_response_tab_change),
                 true,
                 cx,
             );
         });
+        true
+    }
+
+    /// Loads a request as it was sent earlier, as unsaved changes, and shows the response it got.
+    pub fn restore_sent_request(
+        &mut self,
+        request_file: RequestFile,
+        response: ResponseHistoryEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.restore_request_file(request_file, window, cx);
+
+        self.response.update(cx, |current_response, cx| {
+            current_response.show_entry(response, window, cx);
+        });
+        self.show_response_panel(window, cx);
+        cx.notify();
+    }
+
+    /// Replaces the request with `request_file` as unsaved changes, leaving the file as it is.
+    pub fn restore_request_file(
+        &mut self,
+        request_file: RequestFile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (request, _, input_subscriptions, body_subscription) =
+            Self::state_from_request_file(RequestFileState::Parsed(request_file), window, cx);
+        self.request = request;
+        self.input_subscriptions = input_subscriptions;
+        self.body_subscription = body_subscription;
+        self.set_language_for_body(cx);
+        self.load_body_schema(cx);
+        self.dismiss_header_completions(cx);
+        self.mark_edited(cx);
+        cx.notify();
+    }
+
+    /// Shows the tab `field` is on and focuses it. The method has no input of its own, so focusing
+    /// it focuses the URL next to it.
+    pub fn focus_field(
+        &mut self,
+        field: RequestField,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+
+        match field {
+            RequestField::Method | RequestField::Url => {
+                let url = request.http.url.clone();
+                window.focus(&url.read(cx).focus_handle(cx), cx);
+            }
+            RequestField::HeaderName(index) | RequestField::HeaderValue(index) => {
+                let Some(header) = request.http.headers.get(index) else {
+                    return;
+                };
+                let input = if matches!(field, RequestField::HeaderName(_)) {
+                    header.name.clone()
+                } else {
+                    header.value.clone()
+                };
+                self.active_tab = RequestEditorTab::Headers;
+                window.focus(&input.read(cx).focus_handle(cx), cx);
+            }
+            RequestField::Body(range) => {
+                let Some(body) = &request.http.body else {
+                    return;
+                };
+                let editor = body.editor.clone();
+                self.active_tab = RequestEditorTab::Body;
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(SelectionEffects::default(), cx, |selections| {
+                        selections.select_ranges([
+                            MultiBufferOffset(range.start)..MultiBufferOffset(range.end)
+                        ]);
+                    });
+                });
+                window.focus(&editor.focus_handle(cx), cx);
+            }
+        }
+        cx.notify();
+    }
+
+    pub fn send_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+
+        let outgoing_request =
+            OutgoingRequest::new(request, self.secrets(cx), self.script_variables(cx), cx);
+        let request_scripts = request.http.scripts.to_request_file_scripts(cx);
+        let load_folder_defaults = self.project_path(cx).map(|project_path| {
+            self.project
+                .read(cx)
+                .load_folder_defaults(&project_path, cx)
+        });
+        let script_variables = ScriptVariables::try_global(cx).zip(self.project.read(cx).root(cx));
+        let request_schema = request.http.schema.clone();
+        let retry_settings = RetrySettings::get_global(cx)
+            .clone()
+            .with_overrides(request.http.retry.as_ref());
+
+        let sent_request_file = RequestSnapshot::from_request(request, cx).0;
+        let request_path = self
+            .project_path(cx)
+            .and_then(|project_path| self.project.read(cx).absolute_path(&project_path, cx));
+        let request_editor = cx.weak_entity();
+
+        if !self.show_response_panel(window, cx) {
+            return;
+        }
+        let response = self.response.clone();
 
         let request_id = response.update(cx, |response, cx| response.begin_response(window, cx));
         response.update(cx, |response, cx| {
@@ -1054,47 +2300,54 @@ impl RequestEditor {
 
         let request_started_at = Instant::now();
         let http_client = self.http_client.clone();
+        let project = self.project.clone();
         let languages = AppState::global(cx).languages.clone();
 
         window
             .spawn(cx, {
                 async move |cx| {
-                    let Some(mut request_url) = normalize_url(&request_url) else {
+                    let mut outgoing_request = outgoing_request;
+                    let secrets = outgoing_request.secrets.clone();
+                    let executor = cx.background_executor().clone();
+                    let mut script_run = None;
+                    let run_pre_request_scripts = async {
+                        let folder_defaults = match load_folder_defaults {
+                            Some(load_folder_defaults) => load_folder_defaults.await?,
+                            None => Vec::new(),
+                        };
+                        let script_run = script_run.insert(ScriptRun::new(
+                            folder_defaults,
+                            request_scripts,
+                            outgoing_request.variables.clone(),
+                            &secrets,
+                        ));
+                        let script_request = script_run
+                            .run_pre_request(outgoing_request.script_request(), &executor)
+                            .await?;
+                        outgoing_request.variables = script_run.variables().clone();
+                        outgoing_request.apply_script_request(script_request)
+                    };
+                    let pre_request_result = run_pre_request_scripts.await;
+                    if let Some(script_run) = &mut script_run {
+                        let console = script_run.take_console(|text| secrets.mask(text));
                         response.update(cx, |response, cx| {
-                            response.set_state(
-                                request_id,
-                                ResponseState::Error {
-                                    bytes_received: 0,
-                                    elapsed_duration: request_started_at.elapsed(),
-                                },
-                                cx,
-                            );
-                            response.set_payload(request_id, "Error: invalid URL", None, cx);
+                            response.push_console_entries(request_id, console, cx);
                         });
-                        return;
-                    };
-
-                    if !request_params.is_empty() {
-                        let mut query_pairs = request_url.query_pairs_mut();
-                        for (name, value) in request_params {
-                            query_pairs.append_pair(&name, &value);
-                        }
-                    }
-
-                    let mut builder = Builder::new()
-                        .method(request_method)
-                        .uri(request_url.as_str())
-                        .follow_redirects(RedirectPolicy::FollowAll);
-
-                    if !request_headers.is_empty() {
-                        for (name, value) in request_headers {
-                            builder = builder.header(name.as_str(), value.as_str());
+                        if let Some((variables, project_path)) = &script_variables {
+                            variables.update(cx, |variables, cx| {
+                                script_run.record_variables(variables, project_path.clone(), cx);
+                            });
                         }
                     }
 
-                    let request_body = request_body.map_or_else(AsyncBody::empty, AsyncBody::from);
-                    let request = match builder.body(request_body) {
-                        Ok(request) => request,
+                    let resolved_request = pre_request_result
+                        .and_then(|()| outgoing_request.resolve())
+                        .and_then(|outgoing_request| {
+                            let request_url = outgoing_request.url()?;
+                            Ok((outgoing_request, request_url))
+                        });
+                    let (mut outgoing_request, request_url) = match resolved_request {
+                        Ok(resolved_request) => resolved_request,
                         Err(error) => {
                             response.update(cx, |response, cx| {
                                 response.set_state(
@@ -1107,7 +2360,7 @@ impl RequestEditor {
                                 );
                                 response.set_payload(
                                     request_id,
-                                    format!("Error: {error}"),
+                                    secrets.mask(&format!("Error: {error:#}")),
                                     None,
                                     cx,
                                 );
@@ -1115,68 +2368,209 @@ impl RequestEditor {
                             return;
                         }
                     };
+                    if let Err(error) = outgoing_request.authorize(&http_client, cx).await {
+                        response.update(cx, |response, cx| {
+                            response.set_state(
+                                request_id,
+                                ResponseState::Error {
+                                    bytes_received: 0,
+                                    elapsed_duration: request_started_at.elapsed(),
+                                },
+                                cx,
+                            );
+                            response.set_payload(
+                                request_id,
+                                outgoing_request.mask(&format!("Error: {error:#}")),
+                                None,
+                                cx,
+                            );
+                        });
+                        return;
+                    }
 
+                    let sent_request = outgoing_request.sent_request(&request_url);
+
+                    // Reports progress while sending each attempt and then while reading the body.
                     let progress_timer = cx
                         .background_executor()
                         .timer(Duration::from_millis(50))
                         .fuse();
                     futures::pin_mut!(progress_timer);
 
-                    let send_request = http_client.send(request).fuse();
-                    futures::pin_mut!(send_request);
-
-                    let mut received = loop {
-                        futures::select_biased! {
-                            send_result = send_request => {
-                                match send_result {
-                                    Ok(response) => break response,
-                                    Err(error) => {
-                                        response.update(cx, |response, cx| {
-                                            response.set_state(
-                                                request_id,
-                                                ResponseState::Error {
-                                                    bytes_received: 0,
-                                                    elapsed_duration: request_started_at.elapsed(),
-                                                },
-                                                cx,
-                                            );
-                                            response.set_payload(
-                                                request_id,
-                                                format!("Error: {error}"),
-                                                None,
-                                                cx,
-                                            );
-                                        });
-                                        return;
-                                    }
-                                }
-                            }
-                            () = progress_timer => {
-                                let still_active = response.update(cx, |response, cx| {
+                    let mut attempt = 1;
+                    let send_result = loop {
+                        let request = match outgoing_request.to_http_request(&request_url) {
+                            Ok(request) => request,
+                            Err(error) => {
+                                response.update(cx, |response, cx| {
                                     response.set_state(
                                         request_id,
-                                        ResponseState::Fetching {
+                                        ResponseState::Error {
                                             bytes_received: 0,
                                             elapsed_duration: request_started_at.elapsed(),
                                         },
                                         cx,
-                                    )
+                                    );
+                                    response.set_payload(
+                                        request_id,
+                                        outgoing_request.mask(&format!("Error: {error}")),
+                                        None,
+                                        cx,
+                                    );
                                 });
-                                if !still_active {
-                                    return;
+                                return;
+                            }
+                        };
+
+                        let attempt_started_at = Instant::now();
+                        progress_timer.set(
+                            cx.background_executor()
+                                .timer(Duration::from_millis(50))
+                                .fuse(),
+                        );
+
+                        let send_request = http_client.send(request).fuse();
+                        futures::pin_mut!(send_request);
+
+                        let send_result = loop {
+                            futures::select_biased! {
+                                send_result = send_request => break send_result,
+                                () = progress_timer => {
+                                    let still_active = response.update(cx, |response, cx| {
+                                        response.set_state(
+                                            request_id,
+                                            ResponseState::Fetching {
+                                                bytes_received: 0,
+                                                elapsed_duration: request_started_at.elapsed(),
+                                            },
+                                            cx,
+                                        )
+                                    });
+                                    if !still_active {
+                                        return;
+                                    }
+
+                                    progress_timer.set(
+                                        cx.background_executor()
+                                            .timer(Duration::from_millis(50))
+                                            .fuse(),
+                                    );
                                 }
+                            }
+                        };
+                        let attempt_duration = attempt_started_at.elapsed();
+
+                        let jitter = rand::random::<f64>();
+                        let (retry_delay, response_attempt) = match &send_result {
+                            Ok(received) => (
+                                retry_settings.delay_after_status(
+                                    &outgoing_request.method,
+                                    attempt,
+                                    received.status(),
+                                    retry_after(received.headers(), Timestamp::now()),
+                                    jitter,
+                                ),
+                                ResponseAttempt::completed(received.status(), attempt_duration),
+                            ),
+                            Err(error) => (
+                                retry_settings.delay_after_error(
+                                    &outgoing_request.method,
+                                    attempt,
+                                    SendErrorKind::of(error),
+                                    jitter,
+                                ),
+                                ResponseAttempt::failed(
+                                    outgoing_request.mask(&error.to_string()),
+                                    attempt_duration,
+                                ),
+                            ),
+                        };
+                        let still_active = response.update(cx, |response, cx| {
+                            response.push_attempt(
+                                request_id,
+                                response_attempt.retry_delay(retry_delay),
+                                cx,
+                            )
+                        });
+                        if !still_active {
+                            return;
+                        }
+                        let Some(mut remaining_duration) = retry_delay else {
+                            break send_result;
+                        };
 
-                                progress_timer.set(
-                                    cx.background_executor()
-                                        .timer(Duration::from_millis(50))
-                                        .fuse(),
+                        attempt += 1;
+                        while !remaining_duration.is_zero() {
+                            let still_active = response.update(cx, |response, cx| {
+                                response.set_state(
+                                    request_id,
+                                    ResponseState::Waiting {
+                                        attempt,
+                                        remaining_duration,
+                                        elapsed_duration: request_started_at.elapsed(),
+                                    },
+                                    cx,
+                                )
+                            });
+                            if !still_active {
+                                return;
+                            }
+
+                            let tick = remaining_duration.min(Duration::from_millis(100));
+                            cx.background_executor().timer(tick).await;
+                            remaining_duration = remaining_duration.saturating_sub(tick);
+                        }
+                    };
+
+                    let mut received = match send_result {
+                        Ok(received) => received,
+                        Err(error) => {
+                            let entry = response.update(cx, |response, cx| {
+                                response.set_state(
+                                    request_id,
+                                    ResponseState::Error {
+                                        bytes_received: 0,
+                                        elapsed_duration: request_started_at.elapsed(),
+                                    },
+                                    cx,
+                                );
+                                response.set_payload(
+                                    request_id,
+                                    outgoing_request.mask(&format!("Error: {error}")),
+                                    None,
+                                    cx,
                                 );
+                                response.record_history(request_id, sent_request, cx)
+                            });
+                            if let Some(entry) = entry
+                                && let Err(error) = request_editor.update(cx, |_, cx| {
+                                    cx.emit(RequestSent {
+                                        path: request_path,
+                                        request_file: sent_request_file,
+                                        response: entry,
+                                    });
+                                })
+                            {
+                                log::debug!("Failed to report the sent request: {error:?}");
                             }
+                            return;
                         }
                     };
 
                     let status_code = received.status();
+                    let response_schema = request_schema
+                        .as_ref()
+                        .and_then(|schema| schema.response_schema(status_code.as_u16()))
+                        .map(|path| {
+                            let load_schema = project
+                                .read_with(cx, |project, cx| load_json_schema(project, path, cx));
+                            (path.to_owned(), load_schema)
+                        });
                     let response_headers = response_headers(received.headers());
+                    let script_response_headers = response_headers
+                        .iter()
+                        .map(|header| (header.name().to_string(), header.value().to_string()))
+                        .collect::<Vec<_>>();
                     let response_cookies = response_cookies(received.headers());
                     let still_active = response.update(cx, |response, cx| {
                         response.set_headers(request_id, response_headers, cx)
@@ -1285,7 +2679,8 @@ impl RequestEditor {
                     let read_succeeded = read_error.is_none();
                     let (payload, response_state) = match read_error {
                         Some(ref error) => (
-                            format!("(failed to read response body: {error})"),
+                            outgoing_request
+                                .mask(&format!("(failed to read response body: {error})")),
                             ResponseState::Error {
                                 bytes_received,
                                 elapsed_duration,
@@ -1300,6 +2695,12 @@ impl RequestEditor {
                             },
                         ),
                     };
+                    let script_response = read_succeeded.then(|| ScriptResponse {
+                        status: status_code.as_u16(),
+                        headers: script_response_headers,
+                        body: payload.clone(),
+                        elapsed_duration,
+                    });
                     let language = if read_succeeded {
                         match language {
                             Some(language) => language.await,
@@ -1309,15 +2710,162 @@ impl RequestEditor {
                         None
                     };
 
-                    response.update(cx, |response, cx| {
+                    let entry = response.update(cx, |response, cx| {
                         response.set_state(request_id, response_state, cx);
                         response.set_payload(request_id, payload, language, cx);
+                        response.record_history(request_id, sent_request, cx)
                     });
+                    if let Some(entry) = entry
+                        && let Err(error) = request_editor.update(cx, |_, cx| {
+                            cx.emit(RequestSent {
+                                path: request_path,
+                                request_file: sent_request_file,
+                                response: entry,
+                            });
+                        })
+                    {
+                        log::debug!("Failed to report the sent request: {error:?}");
+                    }
+
+                    if let (Some(script_run), Some(script_response)) =
+                        (&mut script_run, script_response)
+                    {
+                        script_run
+                            .run_post_response(script_response, &executor)
+                            .await;
+                        let console = script_run.take_console(|text| outgoing_request.mask(text));
+                        response.update(cx, |response, cx| {
+                            response.push_console_entries(request_id, console, cx);
+                        });
+                        if let Some((variables, project_path)) = &script_variables {
+                            variables.update(cx, |variables, cx| {
+                                script_run.record_variables(variables, project_path.clone(), cx);
+                            });
+                        }
+                    }
+
+                    if read_succeeded && let Some((path, load_schema)) = response_schema {
+                        let schema = load_schema.await;
+                        response.update(cx, |response, cx| {
+                            response.validate_schema(request_id, path, schema, cx);
+                        });
+                    }
                 }
             })
             .detach();
     }
 
+    fn run_benchmark(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RequestEditorState::Ready(request) = &self.request else {
+            return;
+        };
+        if self.benchmark_task.is_some() {
+            return;
+        }
+
+        let outgoing_request =
+            OutgoingRequest::new(request, self.secrets(cx), self.script_variables(cx), cx);
+        let secrets = outgoing_request.secrets.clone();
+        let config = self.benchmark_form.config(cx).and_then(|config| {
+            let outgoing_request = outgoing_request
+                .resolve_references()
+                .map_err(|error| secrets.mask(&format!("{error:#}")))?;
+            // Each send evaluates the dynamic variables again, but mistakes are reported before
+            // the benchmark starts.
+            outgoing_request
+                .evaluate_for_send(Timestamp::now())
+                .map_err(|error| outgoing_request.mask(&format!("{error:#}")))?;
+            Ok((config, outgoing_request))
+        });
+        let (config, mut outgoing_request) = match config {
+            Ok(config) => config,
+            Err(error) => {
+                self.benchmark_form.error = Some(error.into());
+                cx.notify();
+                return;
+            }
+        };
+        self.benchmark_form.error = None;
+
+        self.active_response_tab = ResponsePanelTab::Benchmark;
+        if !self.show_response_panel(window, cx) {
+            return;
+        }
+        let response = self.response.clone();
+        let http_client = self.http_client.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let task = cx.spawn({
+            let cancelled = cancelled.clone();
+            async move |request_editor, cx| {
+                if let Err(error) = outgoing_request.authorize(&http_client, cx).await {
+                    let error = outgoing_request.mask(&format!("{error:#}"));
+                    if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                        request_editor.benchmark_form.error = Some(error.into());
+                        request_editor.benchmark_task = None;
+                        cx.notify();
+                    }) {
+                        log::debug!("Failed to report benchmark error: {error:?}");
+                    }
+                    return;
+                }
+                response.update(cx, |response, cx| {
+                    response.set_benchmark(
+                        BenchmarkReport::new(Vec::new(), Duration::ZERO),
+                        true,
+                        cx,
+                    );
+                });
+
+                let started_at = Instant::now();
+                let (mut samples_rx, run) = run_benchmark(
+                    http_client,
+                    outgoing_request,
+                    config,
+                    cx.background_executor().clone(),
+                    cancelled,
+                );
+                let run = cx.background_spawn(run);
+                let mut samples = Vec::new();
+                let mut reported_at = started_at;
+                while let Some(sample) = samples_rx.next().await {
+                    samples.push(sample);
+                    if reported_at.elapsed() >= BENCHMARK_REPORT_INTERVAL {
+                        reported_at = Instant::now();
+                        let report = BenchmarkReport::new(samples.clone(), started_at.elapsed());
+                        response.update(cx, |response, cx| {
+                            response.set_benchmark(report, true, cx);
+                        });
+                    }
+                }
+                run.await;
+
+                let report = BenchmarkReport::new(samples, started_at.elapsed());
+                response.update(cx, |response, cx| {
+                    response.set_benchmark(report, false, cx);
+                });
+                if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                    request_editor.benchmark_task = None;
+                    cx.notify();
+                }) {
+                    log::debug!("Failed to finish benchmark: {error:?}");
+                }
+            }
+        });
+        self.benchmark_task = Some(BenchmarkTask {
+            cancelled,
+            _task: task,
+        });
+        cx.notify();
+    }
+
+    /// Stops sending new benchmark requests. The report is finished once those in flight complete.
+    fn stop_benchmark(&mut self, cx: &mut Context<Self>) {
+        if let Some(benchmark_task) = &self.benchmark_task {
+            benchmark_task.cancelled.store(true, Ordering::SeqCst);
+            cx.notify();
+        }
+    }
+
     fn render_invalid(&self, error: &str, cx: &mut Context<Self>) -> Div {
         gpui::div()
             .flex()
@@ -1417,12 +2965,36 @@ impl RequestEditor {
                 "Headers".into(),
                 RequestEditorTab::Headers,
             ))
+            .child(render_tab(
+                ElementId::Name("auth-tab".into()),
+                active_tab == RequestEditorTab::Auth,
+                "Auth".into(),
+                RequestEditorTab::Auth,
+            ))
             .child(render_tab(
                 ElementId::Name("body-tab".into()),
                 active_tab == RequestEditorTab::Body,
                 "Body".into(),
                 RequestEditorTab::Body,
             ))
+            .child(render_tab(
+                ElementId::Name("scripts-tab".into()),
+                active_tab == RequestEditorTab::Scripts,
+                "Scripts".into(),
+                RequestEditorTab::Scripts,
+            ))
+            .child(render_tab(
+                ElementId::Name("examples-tab".into()),
+                active_tab == RequestEditorTab::Examples,
+                "Examples".into(),
+                RequestEditorTab::Examples,
+            ))
+            .child(render_tab(
+                ElementId::Name("benchmark-tab".into()),
+                active_tab == RequestEditorTab::Benchmark,
+                "Benchmark".into(),
+                RequestEditorTab::Benchmark,
+            ))
             .into_any_element()
     }
 
@@ -1435,7 +3007,11 @@ impl RequestEditor {
         match self.active_tab {
             RequestEditorTab::Parameters => self.render_parameters(request, window, cx),
             RequestEditorTab::Headers => self.render_headers(request, window, cx),
-            RequestEditorTab::Body => Self::render_body(request, window, cx),
+            RequestEditorTab::Auth => self.render_auth(request, window, cx),
+            RequestEditorTab::Body => self.render_body(request, window, cx),
+            RequestEditorTab::Scripts => Self::render_scripts(request, cx),
+            RequestEditorTab::Examples => self.render_examples(request, cx),
+            RequestEditorTab::Benchmark => self.render_benchmark(cx),
         }
     }
 
@@ -1464,6 +3040,7 @@ impl RequestEditor {
                     }
 
                     if edited {
+                        request_editor.sync_url_from_params(window, cx);
                         request_editor.mark_edited(cx);
                     }
                 },
@@ -1473,13 +3050,14 @@ impl RequestEditor {
                 .variant(ButtonVariant::Outline)
                 .icon_color(Color::Muted)
                 .tooltip(Tooltip::text("Delete"))
-                .on_click(cx.listener(move |request_editor, _, _, cx| {
+                .on_click(cx.listener(move |request_editor, _, window, cx| {
                     let mut edited = false;
                     if let RequestEditorState::Ready(request) = &mut request_editor.request {
                         edited = request.delete_param(index);
                     }
 
                     if edited {
+                        request_editor.sync_url_from_params(window, cx);
                         request_editor.mark_edited(cx);
                     }
                 }));
@@ -1536,7 +3114,10 @@ impl RequestEditor {
                     .gap_2()
                     .py_3()
                     .children(rows)
-                    .child(gpui::div().flex().items_center().pl_1().child(add_button)),
+                    .child(gpui::div().flex().items_center().pl_1().child(add_button))
+                    .when(!request.http.path_variables.is_empty(), |this| {
+                        this.child(Self::render_path_variables(request, cx))
+                    }),
             )
             .custom_scrollbars(
                 Scrollbars::new(ScrollAxes::Vertical)
@@ -1553,14 +3134,60 @@ impl RequestEditor {
             .into_any_element()
     }
 
+    fn render_path_variables(request: &Request, cx: &App) -> impl IntoElement {
+        let rows = request.http.path_variables.iter().map(|variable| {
+            gpui::div()
+                .flex()
+                .items_center()
+                .w_full()
+                .gap_2p5()
+                .pl_1()
+                .child(
+                    gpui::div().flex_1().min_w_0().child(
+                        Text::new(variable.name.clone())
+                            .size(TextSize::Small)
+                            .font_buffer(cx)
+                            .truncate(),
+                    ),
+                )
+                .child(gpui::div().flex_1().child(variable.value.clone()))
+        });
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .pt_3()
+            .child(
+                gpui::div().pl_1().child(
+                    Text::new("Path Variables")
+                        .size(TextSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .children(rows)
+    }
+
     fn render_headers(
         &self,
         request: &Request,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> AnyElement {
+        let names = request
+            .http
+            .headers
+            .iter()
+            .map(|header| (!header.disabled).then(|| header.name.read(cx).value(cx)))
+            .collect::<Vec<_>>();
+        let issues = header_issues(&names, &self.project_headers);
+
         let mut rows = Vec::new();
         for (index, header) in request.http.headers.iter().enumerate() {
+            let completions = self.header_completions.as_ref().filter(|completions| {
+                completions.input == header.name.entity_id()
+                    || completions.input == header.value.entity_id()
+            });
             let checkbox = ui::checkbox(
                 ("header-disabled", index),
                 ToggleState::from(!header.disabled),
@@ -1594,9 +3221,22 @@ impl RequestEditor {
                     }
 
                     if edited {
+                        request_editor.dismiss_header_completions(cx);
                         request_editor.mark_edited(cx);
                     }
                 }));
+            let issue_indicator = issues.get(index).copied().flatten().map(|issue| {
+                IconButton::new(("header-issue", index), IconAsset::Warning)
+                    .shape(IconButtonShape::Square)
+                    .icon_color(Color::Warning)
+                    .tooltip(Tooltip::text(issue.message()))
+            });
+            let name_completions = completions
+                .filter(|completions| completions.field == HeaderField::Name)
+                .map(|completions| Self::render_header_completions(completions, cx));
+            let value_completions = completions
+                .filter(|completions| completions.field == HeaderField::Value)
+                .map(|completions| Self::render_header_completions(completions, cx));
 
             rows.push(
                 gpui::div()
@@ -1604,6 +3244,13 @@ impl RequestEditor {
                     .flex()
                     .items_center()
                     .w_full()
+                    .when(completions.is_some(), |this| {
+                        this.key_context("HeaderCompletions")
+                            .on_action(cx.listener(Self::select_next_header_completion))
+                            .on_action(cx.listener(Self::select_previous_header_completion))
+                            .on_action(cx.listener(Self::confirm_header_completion))
+                            .on_action(cx.listener(Self::cancel_header_completions))
+                    })
                     .child(gpui::div().pr_1p5().child(checkbox))
                     .child(
                         gpui::div()
@@ -1611,8 +3258,23 @@ impl RequestEditor {
                             .items_center()
                             .flex_1()
                             .gap_2p5()
-                            .child(gpui::div().flex_1().child(header.name.clone()))
-                            .child(gpui::div().flex_1().child(header.value.clone()))
+                            .child(
+                                gpui::div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .child(header.name.clone())
+                                    .children(name_completions),
+                            )
+                            .child(
+                                gpui::div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .child(header.value.clone())
+                                    .children(value_completions),
+                            )
+                            .children(issue_indicator)
                             .child(delete_button),
                     )
                     .into_any_element(),
@@ -1667,35 +3329,82 @@ impl RequestEditor {
             .into_any_element()
     }
 
-    fn render_body(request: &Request, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
-        let body_type = request.http.body_type;
-        let body_type_display_name = body_type.map_or("None", |body_type| body_type.display_name());
-        let body = match body_type {
-            Some(_) => request.http.body.as_ref(),
-            None => None,
-        };
+    fn render_header_completions(
+        completions: &HeaderCompletions,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let items = completions
+            .matches
+            .iter()
+            .enumerate()
+            .filter_map(|(index, string_match)| {
+                let candidate = completions.candidates.get(string_match.candidate_id)?;
+                Some(
+                    gpui::div()
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |request_editor, _, window, cx| {
+                                cx.stop_propagation();
+                                request_editor.accept_header_completion(index, window, cx);
+                            }),
+                        )
+                        .child(
+                            ListItem::new(("header-completion", index))
+                                .inset(true)
+                                .spacing(ListItemSpacing::Sparse)
+                                .toggle_state(index == completions.selected_index)
+                                .child(HighlightedText::new(
+                                    candidate.clone(),
+                                    string_match.positions.clone(),
+                                )),
+                        ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        gpui::deferred(
+            gpui::anchored()
+                .snap_to_window_with_margin(gpui::px(8.0))
+                .child(
+                    gpui::div()
+                        .occlude()
+                        .elevation_2(cx)
+                        .min_w(gpui::px(240.0))
+                        .py_1()
+                        .children(items),
+                ),
+        )
+        .with_priority(1)
+        .into_any_element()
+    }
+
+    fn render_auth(
+        &self,
+        request: &Request,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let auth = &request.http.auth;
+        let kind = auth.kind;
         let request_editor = cx.weak_entity();
-        let context_menu = ContextMenu::build(window, cx, move |menu, _, _| {
+        let kind_menu = ContextMenu::build(window, cx, move |menu, _, _| {
             let mut menu = menu;
-            for type_option in [
+            for kind_option in [
                 None,
-                Some(RequestBodyType::Text),
-                Some(RequestBodyType::Json),
-                Some(RequestBodyType::Html),
-                Some(RequestBodyType::Xml),
+                Some(RequestAuthKind::OAuth2),
+                Some(RequestAuthKind::AwsSigV4),
             ] {
                 let request_editor = request_editor.clone();
-                let display_name = type_option.map_or("None", |body_type| body_type.display_name());
                 menu = menu.toggleable_entry(
-                    display_name,
-                    type_option == body_type,
+                    kind_option.map_or("None", RequestAuthKind::display_name),
+                    kind_option == kind,
                     IconPosition::End,
                     None,
-                    move |window, cx| {
+                    move |_, cx| {
                         if let Err(error) = request_editor.update(cx, |request_editor, cx| {
-                            request_editor.set_body_type(type_option, window, cx);
+                            request_editor.set_auth_kind(kind_option, cx);
                         }) {
-                            log::debug!("Failed to update request body type: {error:?}");
+                            log::debug!("Failed to update request auth type: {error:?}");
                         }
                     },
                 );
@@ -1705,7 +3414,7 @@ impl RequestEditor {
         let colors = cx.theme().colors();
 
         gpui::div()
-            .id("body")
+            .id("auth")
             .flex()
             .flex_col()
             .w_full()
@@ -1724,92 +3433,375 @@ impl RequestEditor {
                     .border_color(colors.border)
                     .bg(colors.panel_tab_bar_background.opacity(0.5))
                     .child(
-                        Text::new("Content Type")
+                        Text::new("Type")
                             .size(TextSize::Small)
                             .color(Color::Muted)
                             .single_line(),
                     )
                     .child(
-                        DropdownMenu::new("body-type", body_type_display_name, context_menu)
-                            .variant(DropdownVariant::OutlinedGhost)
-                            .attach(Anchor::BottomLeft)
-                            .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
-                            .trigger_size(ButtonSize::Default),
+                        DropdownMenu::new(
+                            "auth-type",
+                            kind.map_or("None", RequestAuthKind::display_name),
+                            kind_menu,
+                        )
+                        .variant(DropdownVariant::OutlinedGhost)
+                        .attach(Anchor::BottomLeft)
+                        .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
+                        .trigger_size(ButtonSize::Default),
                     ),
             )
-            .when_some(body, |this, body| {
-                this.child(
-                    gpui::div()
-                        .flex_1()
-                        .min_h_0()
-                        .w_full()
-                        .min_w_0()
-                        .child(body.editor()),
-                )
+            .map(|this| match kind {
+                Some(RequestAuthKind::OAuth2) => {
+                    this.child(self.render_oauth2(request, window, cx))
+                }
+                Some(RequestAuthKind::AwsSigV4) => {
+                    this.child(self.render_aws_sigv4(request, window, cx))
+                }
+                None => this.child(
+                    gpui::div().p_3().child(
+                        Text::new("This request doesn't use authorization.")
+                            .size(TextSize::Small)
+                            .color(Color::Muted),
+                    ),
+                ),
             })
             .into_any_element()
     }
 
-    fn render_request(
-        &self,
-        request: &Request,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> Div {
-        let url = request.http.url.clone();
-        let request_method_menu = {
-            let available_request_methods = [
-                Method::GET,
-                Method::POST,
-                Method::PUT,
-                Method::PATCH,
-                Method::DELETE,
-                Method::HEAD,
-                Method::OPTIONS,
-            ];
-            let selected_request_method = request.http.method.clone();
-            let request_editor = cx.weak_entity();
+    fn render_auth_field(label: &'static str, input: impl IntoElement) -> Div {
+        gpui::div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(
+                gpui::div().w(gpui::rems(9.)).child(
+                    Text::new(label)
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                ),
+            )
+            .child(gpui::div().w(gpui::rems(24.)).child(input))
+    }
 
-            ContextMenu::build(window, cx, move |menu, _, _| {
-                let mut menu = menu;
-                for request_method in available_request_methods {
-                    let toggled = request_method == selected_request_method;
-                    let request_editor = request_editor.clone();
-                    let request_method_for_handler = request_method.clone();
-                    menu = menu.toggleable_entry(
-                        request_method.as_str().to_owned(),
-                        toggled,
-                        IconPosition::End,
-                        None,
-                        move |_, cx| {
-                            if let Err(error) = request_editor.update(cx, |request_editor, cx| {
-                                let mut edited = false;
-                                if let RequestEditorState::Ready(request) =
-                                    &mut request_editor.request
-                                    && request.http.method != request_method_for_handler
-                                {
-                                    request.http.method = request_method_for_handler.clone();
-                                    edited = true;
-                                }
+    fn render_oauth2(
+        &self,
+        request: &Request,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let oauth2 = &request.http.auth.oauth2;
+        let grant_type = oauth2.grant_type;
+        let request_editor = cx.weak_entity();
+        let grant_type_menu = ContextMenu::build(window, cx, move |menu, _, _| {
+            let mut menu = menu;
+            for grant_type_option in [
+                OAuth2GrantType::ClientCredentials,
+                OAuth2GrantType::Password,
+                OAuth2GrantType::AuthorizationCode,
+            ] {
+                let request_editor = request_editor.clone();
+                menu = menu.toggleable_entry(
+                    grant_type_option.display_name(),
+                    grant_type_option == grant_type,
+                    IconPosition::End,
+                    None,
+                    move |_, cx| {
+                        if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                            request_editor.set_oauth2_grant_type(grant_type_option, cx);
+                        }) {
+                            log::debug!("Failed to update OAuth 2.0 grant type: {error:?}");
+                        }
+                    },
+                );
+            }
+            menu
+        });
 
-                                if edited {
-                                    request_editor.mark_edited(cx);
-                                }
-                            }) {
-                                log::debug!("Failed to update request method: {error:?}");
-                            }
-                        },
-                    );
-                }
-                menu
+        gpui::div()
+            .id("oauth2")
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .overflow_y_scroll()
+            .p_3()
+            .gap_2()
+            .child(Self::render_auth_field(
+                "Grant Type",
+                gpui::div().flex().child(
+                    DropdownMenu::new(
+                        "oauth2-grant-type",
+                        grant_type.display_name(),
+                        grant_type_menu,
+                    )
+                    .variant(DropdownVariant::OutlinedGhost)
+                    .attach(Anchor::BottomLeft)
+                    .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
+                    .trigger_size(ButtonSize::Default),
+                ),
+            ))
+            .when(grant_type == OAuth2GrantType::AuthorizationCode, |this| {
+                this.child(Self::render_auth_field(
+                    "Authorization URL",
+                    oauth2.authorization_url.clone(),
+                ))
+            })
+            .child(Self::render_auth_field(
+                "Token URL",
+                oauth2.token_url.clone(),
+            ))
+            .child(Self::render_auth_field(
+                "Client ID",
+                oauth2.client_id.clone(),
+            ))
+            .child(Self::render_auth_field(
+                "Client Secret",
+                oauth2.client_secret.clone(),
+            ))
+            .when(grant_type == OAuth2GrantType::Password, |this| {
+                this.child(Self::render_auth_field("Username", oauth2.username.clone()))
+                    .child(Self::render_auth_field("Password", oauth2.password.clone()))
+            })
+            .child(Self::render_auth_field("Scope", oauth2.scope.clone()))
+            .when(grant_type == OAuth2GrantType::AuthorizationCode, |this| {
+                this.child(Self::render_auth_field(
+                    "Redirect Port",
+                    oauth2.redirect_port.clone(),
+                ))
+            })
+            .child(self.render_oauth2_token(cx))
+            .into_any_element()
+    }
+
+    fn render_aws_sigv4(
+        &self,
+        request: &Request,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let aws_sigv4 = &request.http.auth.aws_sigv4;
+        let payload = aws_sigv4.payload;
+        let request_editor = cx.weak_entity();
+        let payload_menu = ContextMenu::build(window, cx, move |menu, _, _| {
+            let mut menu = menu;
+            for payload_option in [
+                AwsPayloadSigning::Signed,
+                AwsPayloadSigning::Unsigned,
+                AwsPayloadSigning::Streaming,
+            ] {
+                let request_editor = request_editor.clone();
+                menu = menu.toggleable_entry(
+                    payload_option.display_name(),
+                    payload_option == payload,
+                    IconPosition::End,
+                    None,
+                    move |_, cx| {
+                        if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                            request_editor.set_aws_payload_signing(payload_option, cx);
+                        }) {
+                            log::debug!("Failed to update AWS payload signing: {error:?}");
+                        }
+                    },
+                );
+            }
+            menu
+        });
+
+        gpui::div()
+            .id("aws-sigv4")
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .overflow_y_scroll()
+            .p_3()
+            .gap_2()
+            .child(Self::render_auth_field(
+                "Access Key ID",
+                aws_sigv4.access_key_id.clone(),
+            ))
+            .child(Self::render_auth_field(
+                "Secret Access Key",
+                aws_sigv4.secret_access_key.clone(),
+            ))
+            .child(Self::render_auth_field(
+                "Session Token",
+                aws_sigv4.session_token.clone(),
+            ))
+            .child(Self::render_auth_field("Region", aws_sigv4.region.clone()))
+            .child(Self::render_auth_field(
+                "Service",
+                aws_sigv4.service.clone(),
+            ))
+            .child(Self::render_auth_field(
+                "Payload",
+                gpui::div().flex().child(
+                    DropdownMenu::new("aws-payload-signing", payload.display_name(), payload_menu)
+                        .variant(DropdownVariant::OutlinedGhost)
+                        .attach(Anchor::BottomLeft)
+                        .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
+                        .trigger_size(ButtonSize::Default),
+                ),
+            ))
+            .child(
+                Text::new("Each request is signed right before it is sent.")
+                    .size(TextSize::Small)
+                    .color(Color::Muted),
+            )
+            .into_any_element()
+    }
+
+    /// The token cached for the request's OAuth 2.0 configuration.
+    fn render_oauth2_token(&self, cx: &mut Context<Self>) -> AnyElement {
+        let config = self.resolved_oauth2_config(cx);
+        let oauth2_tokens = OAuth2Tokens::try_global(cx);
+        let (token, fetching, error) = match (&config, &oauth2_tokens) {
+            (Some(Ok(config)), Some(oauth2_tokens)) => {
+                let oauth2_tokens = oauth2_tokens.read(cx);
+                (
+                    oauth2_tokens.cached_token(config).cloned(),
+                    oauth2_tokens.is_fetching(config),
+                    oauth2_tokens.error(config).map(str::to_owned),
+                )
+            }
+            (Some(Err(error)), _) => (None, false, Some(error.to_string())),
+            _ => (None, false, None),
+        };
+        let now = Timestamp::now();
+        let time_zone = TimeZone::system();
+        let format_timestamp = |timestamp: Timestamp| {
+            timestamp
+                .to_zoned(time_zone.clone())
+                .strftime("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        let status = if fetching {
+            "Getting a token…".to_owned()
+        } else {
+            match &token {
+                Some(token) if token.is_expired(now) => "Expired".to_owned(),
+                Some(token) => match token.expires_at {
+                    Some(expires_at) => format!("Valid until {}", format_timestamp(expires_at)),
+                    None => "Valid".to_owned(),
+                },
+                None => "No token".to_owned(),
+            }
+        };
+        let row = |label: &'static str, value: String| {
+            gpui::div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    gpui::div().w(gpui::rems(9.)).child(
+                        Text::new(label)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+                )
+                .child(Text::new(value).size(TextSize::Small).single_line())
+        };
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .mt_2()
+            .pt_3()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(row("Token", status))
+            .when_some(token, |this, token| {
+                this.child(row("Token Type", token.token_type))
+                    .when(!token.scopes.is_empty(), |this| {
+                        this.child(row("Scopes", token.scopes.join(" ")))
+                    })
+                    .child(row("Obtained", format_timestamp(token.obtained_at)))
+                    .child(row(
+                        "Refreshable",
+                        if token.refresh_token.is_some() {
+                            "Yes"
+                        } else {
+                            "No"
+                        }
+                        .to_owned(),
+                    ))
             })
+            .when_some(error, |this, error| {
+                this.child(Text::new(error).size(TextSize::Small).color(Color::Error))
+            })
+            .child(
+                gpui::div()
+                    .flex()
+                    .gap_1()
+                    .child(
+                        Button::new("oauth2-get-token", "Get New Token")
+                            .variant(ButtonVariant::Outline)
+                            .disabled(fetching)
+                            .on_click(cx.listener(|request_editor, _, _, cx| {
+                                request_editor.fetch_new_oauth2_token(cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("oauth2-clear-token", "Clear Token")
+                            .variant(ButtonVariant::Ghost)
+                            .on_click(cx.listener(|request_editor, _, _, cx| {
+                                request_editor.clear_oauth2_token(cx);
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn render_body(
+        &self,
+        request: &Request,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let body_type = request.http.body_type;
+        let body_type_display_name = body_type.map_or("None", |body_type| body_type.display_name());
+        let formattable = body_language_name(body_type).is_some();
+        let body = match body_type {
+            Some(_) => request.http.body.as_ref(),
+            None => None,
         };
+        let request_editor = cx.weak_entity();
+        let context_menu = ContextMenu::build(window, cx, move |menu, _, _| {
+            let mut menu = menu;
+            for type_option in [
+                None,
+                Some(RequestBodyType::Text),
+                Some(RequestBodyType::Json),
+                Some(RequestBodyType::Html),
+                Some(RequestBodyType::Xml),
+            ] {
+                let request_editor = request_editor.clone();
+                let display_name = type_option.map_or("None", |body_type| body_type.display_name());
+                menu = menu.toggleable_entry(
+                    display_name,
+                    type_option == body_type,
+                    IconPosition::End,
+                    None,
+                    move |window, cx| {
+                        if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                            request_editor.set_body_type(type_option, window, cx);
+                        }) {
+                            log::debug!("Failed to update request body type: {error:?}");
+                        }
+                    },
+                );
+            }
+            menu
+        });
         let colors = cx.theme().colors();
 
         gpui::div()
+            .id("body")
             .flex()
             .flex_col()
-            .track_focus(&self.focus_handle)
             .w_full()
             .flex_1()
             .min_h_0()
@@ -1819,193 +3811,1931 @@ impl RequestEditor {
                     .flex()
                     .items_center()
                     .w_full()
+                    .h(DynamicSpacing::Base36.px(cx))
                     .px_3()
-                    .py_3()
                     .gap_2()
-                    .key_context("RequestUrl")
-                    .on_action(cx.listener(
-                        move |request_editor, _: &actions::workspace::SendRequest, window, cx| {
-                            request_editor.unpreview_tab(cx);
-                            request_editor.send_request(window, cx);
-                        },
-                    ))
+                    .border_b_1()
+                    .border_color(colors.border)
+                    .bg(colors.panel_tab_bar_background.opacity(0.5))
                     .child(
-                        DropdownMenu::new(
-                            "request-method",
-                            request.http.method.as_str().to_owned(),
-                            request_method_menu,
-                        )
-                        .variant(DropdownVariant::OutlinedGhost)
-                        .attach(Anchor::BottomLeft)
-                        .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
-                        .trigger_size(ButtonSize::Large),
+                        Text::new("Content Type")
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
                     )
-                    .child(gpui::div().flex_1().child(url))
                     .child(
-                        Button::new("request-send", "Send")
-                            .variant(ButtonVariant::Custom {
-                                background: colors.text_accent.opacity(0.8),
-                                foreground: colors.surface_background,
-                                hover_background: colors.text_accent.opacity(0.8),
-                                border: gpui::transparent_black(),
-                            })
-                            .size(ButtonSize::Large)
-                            .width(ui::rems_from_px(60.0_f32))
-                            .font_weight(FontWeight::MEDIUM)
-                            .on_click(cx.listener(move |request_editor, _, window, cx| {
-                                request_editor.unpreview_tab(cx);
-                                request_editor.send_request(window, cx);
-                            })),
-                    ),
-            )
-            .child(self.render_tab_bar(cx))
-            .child(self.render_tab_content(request, window, cx))
-    }
-}
-
-impl EventEmitter<RequestEditorEvent> for RequestEditor {}
+                        DropdownMenu::new("body-type", body_type_display_name, context_menu)
+                            .variant(DropdownVariant::OutlinedGhost)
+                            .attach(Anchor::BottomLeft)
+                            .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
+                            .trigger_size(ButtonSize::Default),
+                    )
+                    .when(body.is_some() && formattable, |this| {
+                        this.child(gpui::div().flex_1()).child(
+                            gpui::div()
+                                .flex()
+                                .items_center()
+                                .gap_1()
+                                .child(
+                                    Button::new("format-body", "Format")
+                                        .variant(ButtonVariant::Ghost)
+                                        .size(ButtonSize::Compact)
+                                        .tooltip(Tooltip::for_action_title(
+                                            "Format Body",
+                                            &actions::request_editor::FormatBody,
+                                        ))
+                                        .on_click(cx.listener(|request_editor, _, window, cx| {
+                                            request_editor.format_body(
+                                                TextFormat::Pretty,
+                                                window,
+                                                cx,
+                                            );
+                                        })),
+                                )
+                                .child(
+                                    Button::new("minify-body", "Minify")
+                                        .variant(ButtonVariant::Ghost)
+                                        .size(ButtonSize::Compact)
+                                        .tooltip(Tooltip::for_action_title(
+                                            "Minify Body",
+                                            &actions::request_editor::MinifyBody,
+                                        ))
+                                        .on_click(cx.listener(|request_editor, _, window, cx| {
+                                            request_editor.format_body(
+                                                TextFormat::Minified,
+                                                window,
+                                                cx,
+                                            );
+                                        })),
+                                ),
+                        )
+                    }),
+            )
+            .when_some(body, |this, body| {
+                this.child(
+                    gpui::div()
+                        .key_context("RequestBody")
+                        .on_action(cx.listener(
+                            |request_editor,
+                             _: &actions::request_editor::FormatBody,
+                             window,
+                             cx| {
+                                request_editor.format_body(TextFormat::Pretty, window, cx);
+                            },
+                        ))
+                        .on_action(cx.listener(
+                            |request_editor,
+                             _: &actions::request_editor::MinifyBody,
+                             window,
+                             cx| {
+                                request_editor.format_body(TextFormat::Minified, window, cx);
+                            },
+                        ))
+                        .flex_1()
+                        .min_h_0()
+                        .w_full()
+                        .min_w_0()
+                        .child(body.editor()),
+                )
+            })
+            .when(
+                body.is_some() && body_type == Some(RequestBodyType::Json),
+                |this| this.children(self.render_body_schema(cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_body_schema(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let body_schema = self.body_schema.as_ref()?;
+        let colors = cx.theme().colors();
+        let (summary, color) = match &body_schema.schema {
+            Ok(_) if body_schema.errors.is_empty() => {
+                (format!("Matches {}", body_schema.path), Color::Success)
+            }
+            Ok(_) => (
+                format!(
+                    "{} schema {} against {}",
+                    body_schema.errors.len(),
+                    if body_schema.errors.len() == 1 {
+                        "error"
+                    } else {
+                        "errors"
+                    },
+                    body_schema.path
+                ),
+                Color::Error,
+            ),
+            Err(error) => (error.to_string(), Color::Error),
+        };
+
+        let rows = body_schema
+            .errors
+            .iter()
+            .enumerate()
+            .map(|(index, error)| {
+                let pointer = if error.pointer.is_empty() {
+                    "(root)"
+                } else {
+                    error.pointer.as_str()
+                };
+
+                gpui::div()
+                    .id(("body-schema-error", index))
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .w_full()
+                    .px_3()
+                    .py_0p5()
+                    .cursor_pointer()
+                    .hover(|this| this.bg(colors.ghost_element_hover))
+                    .on_click(cx.listener(move |request_editor, _, window, cx| {
+                        let RequestEditorState::Ready(request) = &request_editor.request else {
+                            return;
+                        };
+                        if let Some(body) = request.http.body.as_ref() {
+                            body.editor().update(cx, |editor, cx| {
+                                editor.select_diagnostic(index, window, cx);
+                            });
+                        }
+                    }))
+                    .child(
+                        Text::new(pointer.to_owned())
+                            .size(TextSize::Small)
+                            .font_buffer(cx)
+                            .single_line(),
+                    )
+                    .child(
+                        gpui::div().flex_1().min_w_0().child(
+                            Text::new(error.message.clone())
+                                .size(TextSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        Some(
+            gpui::div()
+                .flex()
+                .flex_col()
+                .flex_none()
+                .border_t_1()
+                .border_color(colors.border)
+                .child(
+                    gpui::div().px_3().py_1().child(
+                        Text::new(summary)
+                            .size(TextSize::Small)
+                            .color(color)
+                            .single_line()
+                            .truncate(),
+                    ),
+                )
+                .child(
+                    gpui::div()
+                        .id("body-schema-errors")
+                        .flex()
+                        .flex_col()
+                        .max_h(gpui::rems(8.0))
+                        .overflow_y_scroll()
+                        .children(rows),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn render_scripts(request: &Request, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let section = |title: &'static str, hint: &'static str, editor: Entity<Editor>| {
+            gpui::div()
+                .flex()
+                .flex_col()
+                .flex_1()
+                .min_h_0()
+                .child(
+                    gpui::div()
+                        .flex()
+                        .items_center()
+                        .w_full()
+                        .h(DynamicSpacing::Base36.px(cx))
+                        .px_3()
+                        .gap_2()
+                        .border_b_1()
+                        .border_color(colors.border)
+                        .bg(colors.panel_tab_bar_background.opacity(0.5))
+                        .child(
+                            Text::new(title)
+                                .size(TextSize::Small)
+                                .weight(FontWeight::MEDIUM)
+                                .single_line(),
+                        )
+                        .child(
+                            Text::new(hint)
+                                .size(TextSize::Small)
+                                .color(Color::Muted)
+                                .single_line()
+                                .truncate(),
+                        ),
+                )
+                .child(
+                    gpui::div()
+                        .flex_1()
+                        .min_h_0()
+                        .w_full()
+                        .min_w_0()
+                        .child(editor),
+                )
+        };
+
+        gpui::div()
+            .id("scripts")
+            .flex()
+            .flex_col()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .bg(colors.panel_background)
+            .child(section(
+                "Pre-request",
+                "Can change `request` and `variables` before the request is sent.",
+                request.http.scripts.pre_request.clone(),
+            ))
+            .child(
+                section(
+                    "Post-response",
+                    "Can check `response` with `assert` and set `variables`.",
+                    request.http.scripts.post_response.clone(),
+                )
+                .border_t_1()
+                .border_color(colors.border),
+            )
+            .into_any_element()
+    }
+
+    fn render_examples(&self, request: &Request, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let rows = request
+            .examples
+            .iter()
+            .enumerate()
+            .map(|(index, example)| {
+                let delete_button = IconButton::new(("example-delete", index), IconAsset::Trash)
+                    .shape(IconButtonShape::Square)
+                    .variant(ButtonVariant::Outline)
+                    .icon_color(Color::Muted)
+                    .tooltip(Tooltip::text("Delete"))
+                    .on_click(cx.listener(move |request_editor, _, _, cx| {
+                        let mut edited = false;
+                        if let RequestEditorState::Ready(request) = &mut request_editor.request {
+                            edited = request.delete_example(index);
+                        }
+
+                        if edited {
+                            request_editor.mark_edited(cx);
+                        }
+                    }));
+                let details = match example.delay_ms {
+                    Some(delay_ms) => format!("{} · {delay_ms} ms delay", example.status),
+                    None => example.status.to_string(),
+                };
+
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_2p5()
+                    .w_full()
+                    .child(
+                        gpui::div().flex_1().min_w_0().child(
+                            Text::new(example.name.clone())
+                                .size(TextSize::Small)
+                                .truncate(),
+                        ),
+                    )
+                    .child(
+                        Text::new(details)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .child(delete_button)
+                    .into_any_element()
+            })
+            .collect::<Vec<_>>();
+        let has_response = matches!(
+            self.response.read(cx).state(),
+            ResponseState::Completed { .. }
+        );
+        let save_button = Button::new("example-save", "Save Response as Example")
+            .icon(IconAsset::Plus)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .variant(ButtonVariant::OutlinedGhost)
+            .size(ButtonSize::Medium)
+            .disabled(!has_response)
+            .on_click(cx.listener(|request_editor, _, _, cx| {
+                request_editor.save_response_as_example(cx);
+            }));
+
+        gpui::div()
+            .id("examples")
+            .flex()
+            .flex_col()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .p_3()
+            .gap_2()
+            .overflow_y_scroll()
+            .bg(colors.panel_background)
+            .when(rows.is_empty(), |this| {
+                this.child(
+                    Text::new("The mock server answers with the examples saved here.")
+                        .size(TextSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .children(rows)
+            .child(gpui::div().flex().child(save_button))
+            .into_any_element()
+    }
+
+    /// Adds the last completed response to the request's examples.
+    fn save_response_as_example(&mut self, cx: &mut Context<Self>) {
+        let response = self.response.read(cx);
+        let ResponseState::Completed { status_code, .. } = *response.state() else {
+            return;
+        };
+        let headers = response.headers().to_vec();
+        let body = response.text(cx);
+        let RequestEditorState::Ready(request) = &mut self.request else {
+            return;
+        };
+        let example = example_from_response(&request.examples, status_code, &headers, body);
+        request.examples.push(example);
+        self.unpreview_tab(cx);
+        self.mark_edited(cx);
+    }
+
+    fn render_benchmark(&self, cx: &mut Context<Self>) -> AnyElement {
+        let form = &self.benchmark_form;
+        let by_duration = form.by_duration;
+        let running = self.benchmark_task.is_some();
+        let colors = cx.theme().colors();
+        let field = |label: &'static str, input: Entity<InputField>| {
+            gpui::div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    gpui::div().w(gpui::rems(7.)).child(
+                        Text::new(label)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+                )
+                .child(gpui::div().w(gpui::rems(12.)).child(input))
+        };
+        let limit_button = |id: &'static str, label: &'static str, set_by_duration: bool| {
+            Button::new(id, label)
+                .variant(ButtonVariant::Ghost)
+                .size(ButtonSize::Compact)
+                .toggle_state(by_duration == set_by_duration)
+                .disabled(running)
+                .on_click(cx.listener(move |request_editor, _, _, cx| {
+                    if request_editor.benchmark_form.by_duration != set_by_duration {
+                        request_editor.benchmark_form.by_duration = set_by_duration;
+                        cx.notify();
+                    }
+                }))
+        };
+        let run_button = if running {
+            Button::new("benchmark-stop", "Stop")
+                .variant(ButtonVariant::Outline)
+                .on_click(cx.listener(|request_editor, _, _, cx| {
+                    request_editor.stop_benchmark(cx);
+                }))
+        } else {
+            Button::new("benchmark-run", "Run Benchmark")
+                .variant(ButtonVariant::Outline)
+                .on_click(cx.listener(|request_editor, _, window, cx| {
+                    request_editor.unpreview_tab(cx);
+                    request_editor.run_benchmark(window, cx);
+                }))
+        };
+
+        gpui::div()
+            .id("benchmark")
+            .flex()
+            .flex_col()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .p_3()
+            .gap_2()
+            .bg(colors.panel_background)
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(limit_button("benchmark-by-requests", "Requests", false))
+                    .child(limit_button("benchmark-by-duration", "Duration", true)),
+            )
+            .child(if by_duration {
+                field("Duration (s)", form.duration.clone())
+            } else {
+                field("Requests", form.requests.clone())
+            })
+            .child(field("Concurrency", form.concurrency.clone()))
+            .child(field("Target RPS", form.rate.clone()))
+            .when_some(form.error.clone(), |this, error| {
+                this.child(Text::new(error).size(TextSize::Small).color(Color::Error))
+            })
+            .child(gpui::div().flex().child(run_button))
+            .into_any_element()
+    }
+
+    fn render_request(
+        &self,
+        request: &Request,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let url = request.http.url.clone();
+        let request_method_menu = {
+            let available_request_methods = [
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+                Method::HEAD,
+                Method::OPTIONS,
+            ];
+            let selected_request_method = request.http.method.clone();
+            let request_editor = cx.weak_entity();
+
+            ContextMenu::build(window, cx, move |menu, _, _| {
+                let mut menu = menu;
+                for request_method in available_request_methods {
+                    let toggled = request_method == selected_request_method;
+                    let request_editor = request_editor.clone();
+                    let request_method_for_handler = request_method.clone();
+                    menu = menu.toggleable_entry(
+                        request_method.as_str().to_owned(),
+                        toggled,
+                        IconPosition::End,
+                        None,
+                        move |_, cx| {
+                            if let Err(error) = request_editor.update(cx, |request_editor, cx| {
+                                let mut edited = false;
+                                if let RequestEditorState::Ready(request) =
+                                    &mut request_editor.request
+                                    && request.http.method != request_method_for_handler
+                                {
+                                    request.http.method = request_method_for_handler.clone();
+                                    edited = true;
+                                }
+
+                                if edited {
+                                    request_editor.mark_edited(cx);
+                                }
+                            }) {
+                                log::debug!("Failed to update request method: {error:?}");
+                            }
+                        },
+                    );
+                }
+                menu
+            })
+        };
+        let colors = cx.theme().colors();
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .track_focus(&self.focus_handle)
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .bg(colors.panel_background)
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .w_full()
+                    .px_3()
+                    .py_3()
+                    .gap_2()
+                    .key_context("RequestUrl")
+                    .on_action(cx.listener(
+                        move |request_editor, _: &actions::workspace::SendRequest, window, cx| {
+                            request_editor.unpreview_tab(cx);
+                            request_editor.send_request(window, cx);
+                        },
+                    ))
+                    .child(
+                        DropdownMenu::new(
+                            "request-method",
+                            request.http.method.as_str().to_owned(),
+                            request_method_menu,
+                        )
+                        .variant(DropdownVariant::OutlinedGhost)
+                        .attach(Anchor::BottomLeft)
+                        .offset(gpui::point(gpui::px(0.0), gpui::px(0.5)))
+                        .trigger_size(ButtonSize::Large),
+                    )
+                    .child(gpui::div().flex_1().child(url))
+                    .child(
+                        Button::new("request-send", "Send")
+                            .variant(ButtonVariant::Custom {
+                                background: colors.text_accent.opacity(0.8),
+                                foreground: colors.surface_background,
+                                hover_background: colors.text_accent.opacity(0.8),
+                                border: gpui::transparent_black(),
+                            })
+                            .size(ButtonSize::Large)
+                            .width(ui::rems_from_px(60.0_f32))
+                            .font_weight(FontWeight::MEDIUM)
+                            .on_click(cx.listener(move |request_editor, _, window, cx| {
+                                request_editor.unpreview_tab(cx);
+                                request_editor.send_request(window, cx);
+                            })),
+                    ),
+            )
+            .child(self.render_tab_bar(cx))
+            .child(self.render_tab_content(request, window, cx))
+    }
+}
+
+impl EventEmitter<RequestEditorEvent> for RequestEditor {}
+
+impl EventEmitter<RequestSent> for RequestEditor {}
+
+impl Focusable for RequestEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RequestEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        match &self.request {
+            RequestEditorState::Ready(request) => self.render_request(request, window, cx),
+            RequestEditorState::Invalid { error, .. } => self.render_invalid(error, cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::channel::oneshot;
+    use gpui::{TestAppContext, VisualTestContext};
+    use indoc::indoc;
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use fs::{Fs, TempFs};
+    use http_client::{FakeHttpClient, Response, StatusCode};
+    use path::rel_path;
+    use project::RequestFileScripts;
+    use response_panel::ResponseConsoleEntry;
+    use secrets::SECRET_MASK;
+    use settings::SettingsStore;
+    use theme::LoadThemes;
+    use util_macros::path;
+    use workspace::{AppState, DockPosition, Item, Root};
+    use worktree::WorktreeModelHandle;
+
+    fn init_test(app_state: Arc<AppState>, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test_new(cx);
+            cx.set_global(settings_store);
+            theme::init(LoadThemes::JustBase, cx);
+            workspace::init(app_state, cx);
+            editor::init(cx);
+            crate::init(cx);
+            response_panel::init(cx);
+            auth::init(cx);
+            scripting::init(cx);
+        });
+    }
+
+    fn build_workspace<'a>(
+        project: &Entity<Project>,
+        cx: &'a mut TestAppContext,
+    ) -> (
+        Entity<Workspace>,
+        Entity<ResponsePanel>,
+        &'a mut VisualTestContext,
+    ) {
+        let project = project.clone();
+        let (root, cx) = cx.add_window_view(move |window, cx| {
+            Root::new(cx.new(|cx| Workspace::test_new(project, window, cx)))
+        });
+        let workspace = root.update_in(cx, |root, _, _| root.workspace().clone());
+        let response_panel = workspace.update_in(cx, |workspace, window, cx| {
+            let response_panel = cx.new(|cx| ResponsePanel::new(window, cx));
+            workspace.add_panel(response_panel.clone(), DockPosition::Bottom, window, cx);
+            response_panel
+        });
+
+        (workspace, response_panel, cx)
+    }
+
+    #[gpui::test]
+    async fn test_send_request_respects_disabled(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let http_client = FakeHttpClient::with_response(StatusCode::NOT_FOUND);
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        let (tx, rx) = oneshot::channel();
+        let rx = Arc::new(Mutex::new(Some(rx)));
+
+        http_client.replace_handler({
+            move |_, request| {
+                assert_eq!(request.uri().path(), "/search");
+                assert_eq!(request.uri().query(), Some("query=zaku&test=1"));
+                assert_eq!(
+                    request
+                        .headers()
+                        .get("Content-Type")
+                        .and_then(|value| value.to_str().ok()),
+                    Some("application/json")
+                );
+                assert!(request.headers().get("X-Debug").is_none());
+                let rx = rx.lock().take().unwrap();
+
+                async move {
+                    let mut body = request.into_body();
+                    let mut data = String::new();
+                    body.read_to_string(&mut data).await?;
+                    assert_eq!(
+                        data,
+                        indoc! {r#"
+                            {
+                              "hello": "world"
+                            }
+                        "#}
+                    );
+
+                    Ok(rx.await.unwrap())
+                }
+            }
+        });
+
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "collection": {
+                    "request.toml": indoc! {r#"
+                        [meta]
+                        version = 1
+
+                        [http]
+                        method = "POST"
+                        url = "https://api.zaku.dev/search"
+                        params = [
+                            { name = "query", value = "zaku" },
+                            { name = "debug", value = "1", disabled = true },
+                            { name = "test", value = "1", disabled = false },
+                        ]
+                        headers = [
+                            { name = "Content-Type", value = "application/json" },
+                            { name = "X-Debug", value = "1", disabled = true },
+                        ]
+                        body = { type = "json", data = '''
+                        {
+                          "hello": "world"
+                        }
+                        ''' }
+                    "#}
+                }
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+        let pane = workspace.update_in(cx, |workspace, _, _| workspace.pane().clone());
+
+        let request_path = ProjectPath {
+            worktree_id,
+            path: Arc::from(rel_path("collection/request.toml")),
+        };
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(request_path, None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+        pane.update_in(cx, |pane, window, cx| {
+            pane.send_request(window, cx);
+        });
+        cx.run_until_parked();
+
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .body(AsyncBody::empty())
+            .unwrap();
+        assert!(
+            matches!(tx.send(response), Ok(())),
+            "response receiver should be active"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_send_request_records_response_history(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let http_client = FakeHttpClient::create({
+            let request_count = request_count.clone();
+            move |_| {
+                let request_count = request_count.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .header("Content-Type", "application/json")
+                        .body(AsyncBody::from(format!("{{\"count\": {request_count}}}")))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "users.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/users"
+                    params = [{ name = "page", value = "1" }]
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+        let pane = workspace.update_in(cx, |workspace, _, _| workspace.pane().clone());
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    ProjectPath {
+                        worktree_id,
+                        path: Arc::from(rel_path("users.toml")),
+                    },
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        for _ in 0..2 {
+            pane.update_in(cx, |pane, window, cx| {
+                pane.send_request(window, cx);
+            });
+            cx.run_until_parked();
+        }
+
+        let response = request_editor.read_with(cx, |request_editor, _| request_editor.response());
+        let summaries = response.read_with(cx, |response, _| response.history().to_vec());
+        let mut history = Vec::new();
+        for summary in summaries {
+            let entry = response
+                .read_with(cx, |response, cx| {
+                    response.load_history_entry(summary.id(), cx)
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            history.push((
+                summary.request().method.clone(),
+                summary.request().url.clone(),
+                summary.status_code(),
+                entry.body().to_string(),
+            ));
+        }
+        assert_eq!(
+            history,
+            vec![
+                (
+                    "GET".to_string(),
+                    "https://api.zaku.dev/users?page=1".to_string(),
+                    Some(StatusCode::OK),
+                    "{\"count\": 2}".to_string(),
+                ),
+                (
+                    "GET".to_string(),
+                    "https://api.zaku.dev/users?page=1".to_string(),
+                    Some(StatusCode::OK),
+                    "{\"count\": 1}".to_string(),
+                ),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_send_request_substitutes_path_variables(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let requested_uris = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let requested_uris = requested_uris.clone();
+            move |request| {
+                requested_uris.lock().push(request.uri().to_string());
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .body(AsyncBody::from("{}"))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "user.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/orgs/{org}/users/:id"
+                    params = [{ name = "fields", value = "name" }]
+                    path_variables = [{ name = "org", value = "zaku dev" }]
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("user.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert!(requested_uris.lock().is_empty());
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor.response().read(cx).text(cx)),
+            "Error: path variable `id` has no value"
+        );
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            assert_eq!(
+                request
+                    .http
+                    .path_variables
+                    .iter()
+                    .map(|variable| variable.name.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["org", "id"]
+            );
+            let id = request.http.path_variables.get(1).unwrap();
+            id.value
+                .update(cx, |field, cx| field.set_value("7", window, cx));
+        });
+        cx.run_until_parked();
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert_eq!(
+            *requested_uris.lock(),
+            vec!["https://api.zaku.dev/orgs/zaku%20dev/users/7?fields=name".to_string()]
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| {
+                let RequestEditorState::Ready(request) = &editor.request else {
+                    panic!("Expected request editor to be ready");
+                };
+                RequestSnapshot::from_request(request, cx)
+                    .0
+                    .http
+                    .path_variables
+            }),
+            vec![
+                RequestFilePathVariable {
+                    name: "org".to_string(),
+                    value: "zaku dev".to_string(),
+                },
+                RequestFilePathVariable {
+                    name: "id".to_string(),
+                    value: "7".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_send_request_resolves_secrets(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let received_requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let received_requests = received_requests.clone();
+            move |request| {
+                received_requests.lock().push((
+                    request.uri().to_string(),
+                    request
+                        .headers()
+                        .get("Authorization")
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_owned),
+                ));
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .body(AsyncBody::from("{}"))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+        cx.update(|cx| secrets::init_test(temp_fs.path().join("secrets.key"), cx));
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "users.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/users"
+                    params = [{ name = "key", value = "{{secret.api_key}}" }]
+                    headers = [{ name = "Authorization", value = "Bearer {{ secret.token }}" }]
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("users.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert!(received_requests.lock().is_empty());
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor.response().read(cx).text(cx)),
+            "Error: unknown secret \"api_key\""
+        );
+
+        let project_root = cx.update(|_, cx| project.read(cx).root(cx).unwrap());
+        cx.update(|_, cx| {
+            SecretStore::global(cx).update(cx, |store, cx| {
+                store
+                    .set_secret(&project_root, "default", "api_key", "k3y", cx)
+                    .unwrap();
+                store
+                    .set_secret(&project_root, "default", "token", "t0ken", cx)
+                    .unwrap();
+            });
+        });
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert_eq!(
+            *received_requests.lock(),
+            vec![(
+                "https://api.zaku.dev/users?key=k3y".to_string(),
+                Some("Bearer t0ken".to_string())
+            )]
+        );
+        let sent_request = request_editor.read_with(cx, |editor, cx| {
+            editor
+                .response()
+                .read(cx)
+                .history()
+                .first()
+                .unwrap()
+                .request()
+                .clone()
+        });
+        assert_eq!(
+            sent_request.url,
+            format!("https://api.zaku.dev/users?key={SECRET_MASK}")
+        );
+        assert_eq!(
+            sent_request.headers,
+            vec![("Authorization".to_string(), format!("Bearer {SECRET_MASK}"))]
+        );
+
+        // The key is percent-encoded in the URL, and the encoded form is masked too.
+        cx.update(|_, cx| {
+            SecretStore::global(cx).update(cx, |store, cx| {
+                store
+                    .set_secret(&project_root, "default", "api_key", "k3y+/=", cx)
+                    .unwrap();
+            });
+        });
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert_eq!(
+            received_requests.lock().last().unwrap().0,
+            "https://api.zaku.dev/users?key=k3y%2B%2F%3D"
+        );
+        let sent_request = request_editor.read_with(cx, |editor, cx| {
+            editor
+                .response()
+                .read(cx)
+                .history()
+                .first()
+                .unwrap()
+                .request()
+                .clone()
+        });
+        assert_eq!(
+            sent_request.url,
+            format!("https://api.zaku.dev/users?key={SECRET_MASK}")
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| {
+                let RequestEditorState::Ready(request) = &editor.request else {
+                    panic!("Expected request editor to be ready");
+                };
+                RequestSnapshot::from_request(request, cx).0.http.headers
+            }),
+            vec![RequestFileHeader {
+                name: "Authorization".to_string(),
+                value: "Bearer {{ secret.token }}".to_string(),
+                disabled: false,
+            }]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_send_request_resolves_dynamic_variables(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let received_requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let received_requests = received_requests.clone();
+            move |request| {
+                received_requests.lock().push((
+                    request.uri().to_string(),
+                    request
+                        .headers()
+                        .iter()
+                        .filter_map(|(name, value)| {
+                            Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                        })
+                        .collect::<Vec<_>>(),
+                ));
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .body(AsyncBody::from("{}"))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+        cx.update(|cx| secrets::init_test(temp_fs.path().join("secrets.key"), cx));
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "users.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/users"
+                    params = [{ name = "page", value = "{{$randomInt 5 5}}" }]
+                    headers = [
+                        { name = "X-Request-Id", value = "{{$uuid}}" },
+                        { name = "X-Correlation-Id", value = "{{ $uuid }}" },
+                        { name = "Authorization", value = "Basic {{$base64 {{secret.credentials}}}}" },
+                    ]
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+        let project_root = cx.update(|_, cx| project.read(cx).root(cx).unwrap());
+        cx.update(|_, cx| {
+            SecretStore::global(cx).update(cx, |store, cx| {
+                store
+                    .set_secret(&project_root, "default", "credentials", "user:pass", cx)
+                    .unwrap();
+            });
+        });
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("users.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        let (url, headers) = received_requests.lock().first().cloned().unwrap();
+        assert_eq!(url, "https://api.zaku.dev/users?page=5");
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        let uuid = header("X-Request-Id");
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(header("X-Correlation-Id"), uuid);
+        assert_eq!(header("Authorization"), "Basic dXNlcjpwYXNz");
+
+        let sent_request = request_editor.read_with(cx, |editor, cx| {
+            editor
+                .response()
+                .read(cx)
+                .history()
+                .first()
+                .unwrap()
+                .request()
+                .clone()
+        });
+        assert_eq!(sent_request.url, "https://api.zaku.dev/users?page=5");
+        assert_eq!(
+            sent_request.headers,
+            vec![
+                ("X-Request-Id".to_string(), uuid.clone()),
+                ("X-Correlation-Id".to_string(), uuid.clone()),
+                ("Authorization".to_string(), format!("Basic {SECRET_MASK}")),
+            ]
+        );
+        assert_eq!(
+            sent_request.variables,
+            vec![
+                ("$randomInt 5 5".to_string(), "5".to_string()),
+                ("$uuid".to_string(), uuid),
+                (format!("$base64 {SECRET_MASK}"), SECRET_MASK.to_string()),
+            ]
+        );
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            let header = request.http.headers.first().unwrap().value.clone();
+            header.update(cx, |header, cx| header.set_value("{{$nope}}", window, cx));
+            editor.send_request(window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor.response().read(cx).text(cx)),
+            "Error: failed to evaluate {{$nope}}: unknown dynamic variable $nope"
+        );
+        assert_eq!(received_requests.lock().len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_send_request_runs_scripts(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let received_requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let received_requests = received_requests.clone();
+            move |request| {
+                received_requests.lock().push((
+                    request.uri().to_string(),
+                    request
+                        .headers()
+                        .get("x-signature")
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_owned),
+                ));
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::CREATED)
+                        .body(AsyncBody::from(r#"{"id":42,"name":"zaku"}"#))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+        cx.update(|cx| secrets::init_test(temp_fs.path().join("secrets.key"), cx));
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "_folder.toml": indoc! {r#"
+                    [scripts]
+                    pre_request = 'variables.tenant = "acme";'
+                    post_response = 'assert(response.status == 201, "created");'
+                "#},
+                "users": {
+                    "create.toml": indoc! {r#"
+                        [meta]
+                        version = 1
+
+                        [http]
+                        method = "POST"
+                        url = "https://api.zaku.dev/{{var.tenant}}/users"
+                        body = { type = "json", data = '{"name":"zaku"}' }
+
+                        [http.scripts]
+                        pre_request = """
+                        request.headers["X-Signature"] = hmac_sha256(secrets.token, request.body);
+                        print("signed with " + secrets.token);
+                        """
+                        post_response = """
+                        let user = parse_json(response.body);
+                        variables.user_id = user.id;
+                        assert(user.name == "zaku", "name is kept");
+                        """
+                    "#}
+                }
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+        let project_root = cx.update(|_, cx| project.read(cx).root(cx).unwrap());
+        cx.update(|_, cx| {
+            SecretStore::global(cx).update(cx, |store, cx| {
+                store
+                    .set_secret(&project_root, "default", "token", "t0ken", cx)
+                    .unwrap();
+            });
+        });
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("users/create.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert_eq!(
+            *received_requests.lock(),
+            vec![(
+                "https://api.zaku.dev/acme/users".to_string(),
+                Some(
+                    "089824b6c4b287313905fc75ca389cfdcef8a7d97da1de529fefc5726e17a755".to_string()
+                )
+            )]
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor
+                .response()
+                .read(cx)
+                .console()
+                .to_vec()),
+            vec![
+                ResponseConsoleEntry::Log(format!("signed with {SECRET_MASK}").into()),
+                ResponseConsoleEntry::Assertion {
+                    message: "created".into(),
+                    passed: true,
+                },
+                ResponseConsoleEntry::Assertion {
+                    message: "name is kept".into(),
+                    passed: true,
+                },
+            ]
+        );
+        assert_eq!(
+            cx.update(|_, cx| ScriptVariables::project_variables(&project_root, cx)),
+            BTreeMap::from([
+                ("tenant".to_string(), "acme".to_string()),
+                ("user_id".to_string(), "42".to_string()),
+            ])
+        );
 
-impl Focusable for RequestEditor {
-    fn focus_handle(&self, _cx: &App) -> FocusHandle {
-        self.focus_handle.clone()
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            let pre_request = request.http.scripts.pre_request.clone();
+            pre_request.update(cx, |pre_request, cx| {
+                pre_request.set_text(r#"throw "boom";"#, cx);
+            });
+            editor.send_request(window, cx);
+        });
+        cx.run_until_parked();
+
+        assert_eq!(received_requests.lock().len(), 1);
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor.response().read(cx).text(cx)),
+            "Error: pre-request script failed: Runtime error: boom (line 1, position 1)"
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor
+                .response()
+                .read(cx)
+                .console()
+                .to_vec()),
+            vec![ResponseConsoleEntry::Error(
+                "Runtime error: boom (line 1, position 1)".into()
+            )]
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| {
+                let RequestEditorState::Ready(request) = &editor.request else {
+                    panic!("Expected request editor to be ready");
+                };
+                RequestSnapshot::from_request(request, cx).0.http.scripts
+            }),
+            Some(RequestFileScripts {
+                pre_request: r#"throw "boom";"#.to_string(),
+                post_response: indoc! {r#"
+                    let user = parse_json(response.body);
+                    variables.user_id = user.id;
+                    assert(user.name == "zaku", "name is kept");
+                "#}
+                .to_string(),
+            })
+        );
     }
-}
 
-impl Render for RequestEditor {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        match &self.request {
-            RequestEditorState::Ready(request) => self.render_request(request, window, cx),
-            RequestEditorState::Invalid { error, .. } => self.render_invalid(error, cx),
+    #[gpui::test]
+    async fn test_send_request_with_oauth2(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let token_requests = Arc::new(AtomicUsize::new(0));
+        let received_authorizations = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let token_requests = token_requests.clone();
+            let received_authorizations = received_authorizations.clone();
+            move |request| {
+                let body = if request.uri() == "https://auth.zaku.dev/token" {
+                    token_requests.fetch_add(1, Ordering::SeqCst);
+                    r#"{"access_token": "t0k3n", "token_type": "bearer", "expires_in": 3600}"#
+                } else {
+                    received_authorizations.lock().push(
+                        request
+                            .headers()
+                            .get("Authorization")
+                            .and_then(|value| value.to_str().ok())
+                            .map(str::to_owned),
+                    );
+                    "{}"
+                };
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .body(AsyncBody::from(body))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "users.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/users"
+                    auth = { type = "oauth2", grant_type = "client_credentials", token_url = "https://auth.zaku.dev/token", client_id = "zaku", client_secret = "shh" }
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("users.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        for _ in 0..2 {
+            request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+            cx.run_until_parked();
         }
+
+        assert_eq!(token_requests.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            *received_authorizations.lock(),
+            vec![Some("Bearer t0k3n".to_string()); 2]
+        );
+        let sent_request = request_editor.read_with(cx, |editor, cx| {
+            editor
+                .response()
+                .read(cx)
+                .history()
+                .first()
+                .unwrap()
+                .request()
+                .clone()
+        });
+        assert_eq!(
+            sent_request.headers,
+            vec![("Authorization".to_string(), format!("Bearer {SECRET_MASK}"))]
+        );
+        assert!(!request_editor.read_with(cx, |editor, cx| editor.is_dirty(cx)));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[gpui::test]
+    async fn test_send_request_with_aws_sigv4(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
 
-    use futures::channel::oneshot;
-    use gpui::{TestAppContext, VisualTestContext};
-    use indoc::indoc;
-    use parking_lot::Mutex;
-    use serde_json::json;
-    use std::{cell::RefCell, rc::Rc};
+        let temp_fs = TempFs::new(cx.executor());
+        let received_headers = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let received_headers = received_headers.clone();
+            move |request| {
+                let header = |name: &str| {
+                    request
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_owned)
+                };
+                received_headers.lock().push((
+                    header("Authorization"),
+                    header("X-Amz-Content-Sha256"),
+                    header("X-Amz-Date").is_some(),
+                ));
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .body(AsyncBody::from("{}"))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
 
-    use fs::{Fs, TempFs};
-    use http_client::{FakeHttpClient, Response, StatusCode};
-    use path::rel_path;
-    use settings::SettingsStore;
-    use theme::LoadThemes;
-    use util_macros::path;
-    use workspace::{AppState, DockPosition, Item, Root};
-    use worktree::WorktreeModelHandle;
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "upload.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "PUT"
+                    url = "https://zaku.s3.amazonaws.com/upload.json"
+                    headers = [{ name = "Content-Type", value = "application/json" }]
+                    body = { type = "json", data = "{}" }
+                    auth = { type = "aws_sigv4", access_key_id = "AKIDEXAMPLE", secret_access_key = "shh", region = "us-east-1", service = "s3", payload = "unsigned" }
+                "#}
+            }),
+        );
 
-    fn init_test(app_state: Arc<AppState>, cx: &mut TestAppContext) {
-        cx.update(|cx| {
-            let settings_store = SettingsStore::test_new(cx);
-            cx.set_global(settings_store);
-            theme::init(LoadThemes::JustBase, cx);
-            workspace::init(app_state, cx);
-            editor::init(cx);
-            crate::init(cx);
-            response_panel::init(cx);
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("upload.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        let received_headers = received_headers.lock().clone();
+        let [(Some(authorization), content_sha256, has_date)] = received_headers.as_slice() else {
+            panic!("expected one signed request, got {received_headers:?}");
+        };
+        assert!(
+            authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"),
+            "{authorization}"
+        );
+        assert!(
+            authorization.contains(
+                "/us-east-1/s3/aws4_request, SignedHeaders=content-type;host;x-amz-content-sha256;x-amz-date, Signature="
+            ),
+            "{authorization}"
+        );
+        assert_eq!(content_sha256.as_deref(), Some("UNSIGNED-PAYLOAD"));
+        assert!(*has_date);
+    }
+
+    #[gpui::test]
+    async fn test_run_benchmark(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let requested_uris = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let requested_uris = requested_uris.clone();
+            move |request| {
+                requested_uris.lock().push(request.uri().to_string());
+                async move {
+                    Ok(Response::builder()
+                        .status(StatusCode::OK)
+                        .body(AsyncBody::from("{}"))
+                        .unwrap())
+                }
+            }
+        });
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "users.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/users?id={{$uuid}}"
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("users.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+        let set_field = |field: fn(&BenchmarkForm) -> &Entity<InputField>,
+                         value: &'static str,
+                         cx: &mut VisualTestContext| {
+            request_editor.update_in(cx, |editor, window, cx| {
+                field(&editor.benchmark_form)
+                    .clone()
+                    .update(cx, |field, cx| field.set_value(value, window, cx));
+            });
+        };
+
+        set_field(|form| &form.concurrency, "0", cx);
+        request_editor.update_in(cx, |editor, window, cx| editor.run_benchmark(window, cx));
+        cx.run_until_parked();
+
+        assert!(requested_uris.lock().is_empty());
+        request_editor.read_with(cx, |editor, cx| {
+            assert_eq!(
+                editor.benchmark_form.error.as_deref(),
+                Some("Concurrency must be a positive whole number.")
+            );
+            assert!(editor.response().read(cx).benchmark().is_none());
+        });
+
+        set_field(|form| &form.requests, "5", cx);
+        set_field(|form| &form.concurrency, "2", cx);
+        request_editor.update_in(cx, |editor, window, cx| editor.run_benchmark(window, cx));
+        cx.run_until_parked();
+
+        let requested_uris = requested_uris.lock().clone();
+        assert_eq!(requested_uris.len(), 5);
+        // Every request gets its own `$uuid`.
+        assert_eq!(
+            requested_uris
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len(),
+            5
+        );
+        assert!(
+            requested_uris
+                .iter()
+                .all(|uri| uri.starts_with("https://api.zaku.dev/users?id=") && !uri.contains("{{"))
+        );
+        request_editor.read_with(cx, |editor, cx| {
+            assert!(editor.benchmark_task.is_none());
+            assert_eq!(editor.benchmark_form.error, None);
+            assert!(editor.active_response_tab() == ResponsePanelTab::Benchmark);
+
+            let response = editor.response().read(cx);
+            assert!(!response.is_benchmark_running());
+            let report = response.benchmark().unwrap();
+            assert_eq!(report.request_count(), 5);
+            assert_eq!(report.status_count(200), 5);
+            assert_eq!(report.error_count(), 0);
         });
     }
 
-    fn build_workspace<'a>(
-        project: &Entity<Project>,
-        cx: &'a mut TestAppContext,
-    ) -> (
-        Entity<Workspace>,
-        Entity<ResponsePanel>,
-        &'a mut VisualTestContext,
-    ) {
-        let project = project.clone();
-        let (root, cx) = cx.add_window_view(move |window, cx| {
-            Root::new(cx.new(|cx| Workspace::test_new(project, window, cx)))
+    #[gpui::test]
+    async fn test_send_request_retries(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                let mut requests = requests.lock();
+                let unavailable = requests.is_empty() || request.method() == Method::POST;
+                requests.push(request.method().to_string());
+                async move {
+                    Ok(if unavailable {
+                        Response::builder()
+                            .status(StatusCode::SERVICE_UNAVAILABLE)
+                            .header("Retry-After", "1")
+                            .body(AsyncBody::from("busy"))
+                            .unwrap()
+                    } else {
+                        Response::builder()
+                            .status(StatusCode::OK)
+                            .body(AsyncBody::from("{}"))
+                            .unwrap()
+                    })
+                }
+            }
         });
-        let workspace = root.update_in(cx, |root, _, _| root.workspace().clone());
-        let response_panel = workspace.update_in(cx, |workspace, window, cx| {
-            let response_panel = cx.new(|cx| ResponsePanel::new(window, cx));
-            workspace.add_panel(response_panel.clone(), DockPosition::Bottom, window, cx);
-            response_panel
+        let app_state =
+            cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "get.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/users"
+                    retry = { max_attempts = 3 }
+                "#},
+                "post.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "POST"
+                    url = "https://api.zaku.dev/users"
+                    retry = { max_attempts = 3 }
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+        let open_request_editor = |path: &'static str, cx: &mut VisualTestContext| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path(path)).into(), None, true, window, cx)
+            })
+        };
+        let attempts = |request_editor: &Entity<RequestEditor>, cx: &mut VisualTestContext| {
+            request_editor.read_with(cx, |editor, cx| {
+                editor
+                    .response()
+                    .read(cx)
+                    .attempts()
+                    .iter()
+                    .map(ResponseAttempt::status_code)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let request_editor = open_request_editor("get.toml", cx)
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert_eq!(*requests.lock(), vec!["GET".to_string()]);
+        assert_eq!(
+            attempts(&request_editor, cx),
+            vec![Some(StatusCode::SERVICE_UNAVAILABLE)]
+        );
+        request_editor.read_with(cx, |editor, cx| {
+            let state = editor.response().read(cx).state().clone();
+            assert!(
+                matches!(
+                    state,
+                    ResponseState::Waiting {
+                        attempt: 2,
+                        remaining_duration,
+                        ..
+                    } if remaining_duration == Duration::from_secs(1)
+                ),
+                "expected to wait for the second attempt"
+            );
         });
 
-        (workspace, response_panel, cx)
+        for _ in 0..10 {
+            cx.executor().advance_clock(Duration::from_millis(100));
+            cx.run_until_parked();
+        }
+
+        assert_eq!(*requests.lock(), vec!["GET".to_string(), "GET".to_string()]);
+        assert_eq!(
+            attempts(&request_editor, cx),
+            vec![Some(StatusCode::SERVICE_UNAVAILABLE), Some(StatusCode::OK)]
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor.response().read(cx).text(cx)),
+            "{}"
+        );
+
+        requests.lock().clear();
+        let request_editor = open_request_editor("post.toml", cx)
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+        request_editor.update_in(cx, |editor, window, cx| editor.send_request(window, cx));
+        cx.run_until_parked();
+
+        assert_eq!(*requests.lock(), vec!["POST".to_string()]);
+        assert_eq!(
+            attempts(&request_editor, cx),
+            vec![Some(StatusCode::SERVICE_UNAVAILABLE)]
+        );
+        assert_eq!(
+            request_editor.read_with(cx, |editor, cx| editor.response().read(cx).text(cx)),
+            "busy"
+        );
     }
 
     #[gpui::test]
-    async fn test_send_request_respects_disabled(cx: &mut TestAppContext) {
+    async fn test_validate_against_json_schema(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let temp_fs = TempFs::new(cx.executor());
-        let http_client = FakeHttpClient::with_response(StatusCode::NOT_FOUND);
+        let http_client = FakeHttpClient::create(|_| async move {
+            Ok(Response::builder()
+                .status(StatusCode::CREATED)
+                .header("Content-Type", "application/json")
+                .body(AsyncBody::from(r#"{"id": "7", "name": "zaku"}"#))
+                .unwrap())
+        });
         let app_state =
             cx.update(|cx| AppState::test_new(temp_fs.clone(), Some(http_client.clone()), cx));
-        let (tx, rx) = oneshot::channel();
-        let rx = Arc::new(Mutex::new(Some(rx)));
-
-        http_client.replace_handler({
-            move |_, request| {
-                assert_eq!(request.uri().path(), "/search");
-                assert_eq!(request.uri().query(), Some("query=zaku&test=1"));
-                assert_eq!(
-                    request
-                        .headers()
-                        .get("Content-Type")
-                        .and_then(|value| value.to_str().ok()),
-                    Some("application/json")
-                );
-                assert!(request.headers().get("X-Debug").is_none());
-                let rx = rx.lock().take().unwrap();
-
-                async move {
-                    let mut body = request.into_body();
-                    let mut data = String::new();
-                    body.read_to_string(&mut data).await?;
-                    assert_eq!(
-                        data,
-                        indoc! {r#"
-                            {
-                              "hello": "world"
-                            }
-                        "#}
-                    );
-
-                    Ok(rx.await.unwrap())
-                }
-            }
-        });
-
         init_test(app_state, cx);
 
         temp_fs.insert_tree(
             path!("project"),
             json!({
-                "collection": {
-                    "request.toml": indoc! {r#"
-                        [meta]
-                        version = 1
-
-                        [http]
-                        method = "POST"
-                        url = "https://api.zaku.dev/search"
-                        params = [
-                            { name = "query", value = "zaku" },
-                            { name = "debug", value = "1", disabled = true },
-                            { name = "test", value = "1", disabled = false },
-                        ]
-                        headers = [
-                            { name = "Content-Type", value = "application/json" },
-                            { name = "X-Debug", value = "1", disabled = true },
-                        ]
-                        body = { type = "json", data = '''
-                        {
-                          "hello": "world"
+                "schemas": {
+                    "user.json": r#"{
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "name": { "type": "string" }
                         }
-                        ''' }
-                    "#}
-                }
+                    }"#
+                },
+                "users.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "POST"
+                    url = "https://api.zaku.dev/users"
+                    body = { type = "json", data = '{"name": 1}' }
+                    schema = { body = "schemas/user.json", responses = { 2XX = "schemas/user.json" } }
+                "#}
             }),
         );
 
@@ -2015,31 +5745,88 @@ mod tests {
         let (workspace, _, cx) = build_workspace(&project, cx);
         let pane = workspace.update_in(cx, |workspace, _, _| workspace.pane().clone());
 
-        let request_path = ProjectPath {
-            worktree_id,
-            path: Arc::from(rel_path("collection/request.toml")),
-        };
-
-        workspace
+        let request_editor = workspace
             .update_in(cx, |workspace, window, cx| {
-                workspace.open_path(request_path, None, true, window, cx)
+                workspace.open_path(
+                    ProjectPath {
+                        worktree_id,
+                        path: Arc::from(rel_path("users.toml")),
+                    },
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
             })
             .await
             .unwrap()
             .downcast::<RequestEditor>()
             .unwrap();
+        cx.run_until_parked();
+
+        let body_diagnostics = |cx: &mut VisualTestContext| {
+            request_editor.read_with(cx, |request_editor, cx| {
+                let RequestEditorState::Ready(request) = &request_editor.request else {
+                    panic!("Expected request editor to be ready");
+                };
+                request
+                    .http
+                    .body
+                    .as_ref()
+                    .unwrap()
+                    .editor()
+                    .read(cx)
+                    .diagnostics(cx)
+                    .into_iter()
+                    .map(|diagnostic| (diagnostic.range, diagnostic.message.to_string()))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            body_diagnostics(cx),
+            vec![(
+                MultiBufferOffset(1)..MultiBufferOffset(7),
+                "Expected string, got number".to_string()
+            )]
+        );
+
+        request_editor.update_in(cx, |request_editor, window, cx| {
+            let RequestEditorState::Ready(request) = &request_editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            request
+                .http
+                .body
+                .as_ref()
+                .unwrap()
+                .editor()
+                .update(cx, |editor, cx| {
+                    editor.apply_text(r#"{"name": "zaku"}"#, window, cx);
+                });
+        });
+        cx.run_until_parked();
+        assert_eq!(body_diagnostics(cx), Vec::new());
+
         pane.update_in(cx, |pane, window, cx| {
             pane.send_request(window, cx);
         });
         cx.run_until_parked();
 
-        let response = Response::builder()
-            .status(StatusCode::OK)
-            .body(AsyncBody::empty())
-            .unwrap();
-        assert!(
-            matches!(tx.send(response), Ok(())),
-            "response receiver should be active"
+        let schema_errors = request_editor.read_with(cx, |request_editor, cx| {
+            request_editor
+                .response()
+                .read(cx)
+                .schema_errors()
+                .iter()
+                .map(|error| (error.pointer.clone(), error.message.clone()))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            schema_errors,
+            vec![(
+                "/id".to_string(),
+                "Expected integer, got string".to_string()
+            )]
         );
     }
 
@@ -2102,7 +5889,7 @@ mod tests {
                 panic!("Expected request editor to be ready");
             };
             request.http.url.update(cx, |field, cx| {
-                field.set_value("https://api.zaku.dev/me/edit", window, cx);
+                field.set_value("https://api.zaku.dev/me/edit?query=zaku&test=1", window, cx);
             });
             editor.mark_edited(cx);
         });
@@ -2146,6 +5933,7 @@ mod tests {
                         disabled: false,
                     },
                 ],
+                path_variables: Vec::new(),
                 headers: vec![
                     RequestFileHeader {
                         name: "Content-Type".to_string(),
@@ -2159,7 +5947,12 @@ mod tests {
                     },
                 ],
                 body: None,
+                schema: None,
+                retry: None,
+                auth: None,
+                scripts: None,
             },
+            examples: Vec::new(),
         };
 
         assert_eq!(saved_request, expected_request);
@@ -2272,4 +6065,249 @@ mod tests {
             "renamed"
         );
     }
+
+    #[gpui::test]
+    async fn test_sync_url_and_params(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let app_state = cx.update(|cx| AppState::test_new(temp_fs.clone(), None, cx));
+
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "request.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "GET"
+                    url = "https://api.zaku.dev/search?q=a%20b"
+                    params = [
+                        { name = "debug", value = "1", disabled = true },
+                        { name = "page", value = "1" },
+                    ]
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("request.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+        cx.run_until_parked();
+
+        let url_and_params = |request_editor: &Entity<RequestEditor>,
+                              cx: &mut VisualTestContext| {
+            request_editor.read_with(cx, |editor, cx| {
+                let RequestEditorState::Ready(request) = &editor.request else {
+                    panic!("Expected request editor to be ready");
+                };
+                (
+                    request.http.url.read(cx).value(cx),
+                    request
+                        .http
+                        .params
+                        .iter()
+                        .map(|param| {
+                            let param = param.to_request_file_param(cx);
+                            (param.name, param.value, param.disabled)
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+        };
+        let param = |name: &str, value: &str, disabled: bool| {
+            (name.to_string(), value.to_string(), disabled)
+        };
+
+        assert_eq!(
+            url_and_params(&request_editor, cx),
+            (
+                "https://api.zaku.dev/search?q=a%20b&page=1".to_string(),
+                vec![
+                    param("q", "a%20b", false),
+                    param("debug", "1", true),
+                    param("page", "1", false),
+                ]
+            )
+        );
+        assert!(!request_editor.read_with(cx, |editor, cx| editor.is_dirty(cx)));
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            request.http.url.update(cx, |field, cx| {
+                field.set_value(
+                    "https://api.zaku.dev/search?q=c%2Bd&page=2&sort=",
+                    window,
+                    cx,
+                );
+            });
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            url_and_params(&request_editor, cx),
+            (
+                "https://api.zaku.dev/search?q=c%2Bd&page=2&sort=".to_string(),
+                vec![
+                    param("q", "c%2Bd", false),
+                    param("debug", "1", true),
+                    param("page", "2", false),
+                    param("sort", "", false),
+                ]
+            )
+        );
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &mut editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            let page = request.http.params.get(2).unwrap();
+            page.value
+                .update(cx, |field, cx| field.set_value("3", window, cx));
+            request
+                .http
+                .params
+                .get_mut(1)
+                .unwrap()
+                .set_disabled(false, window, cx);
+            editor.sync_url_from_params(window, cx);
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            url_and_params(&request_editor, cx),
+            (
+                "https://api.zaku.dev/search?q=c%2Bd&debug=1&page=3&sort".to_string(),
+                vec![
+                    param("q", "c%2Bd", false),
+                    param("debug", "1", false),
+                    param("page", "3", false),
+                    param("sort", "", false),
+                ]
+            )
+        );
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            let sort = request.http.params.get(3).unwrap();
+            sort.value
+                .update(cx, |field, cx| field.set_value("a b&c=#d", window, cx));
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            url_and_params(&request_editor, cx),
+            (
+                "https://api.zaku.dev/search?q=c%2Bd&debug=1&page=3&sort=a%20b%26c=%23d"
+                    .to_string(),
+                vec![
+                    param("q", "c%2Bd", false),
+                    param("debug", "1", false),
+                    param("page", "3", false),
+                    param("sort", "a b&c=#d", false),
+                ]
+            )
+        );
+    }
+
+    #[gpui::test]
+    async fn test_focus_field(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let app_state = cx.update(|cx| AppState::test_new(temp_fs.clone(), None, cx));
+
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(
+            path!("project"),
+            json!({
+                "request.toml": indoc! {r#"
+                    [meta]
+                    version = 1
+
+                    [http]
+                    method = "POST"
+                    url = "https://api.zaku.dev/orders"
+                    headers = [
+                        { name = "Accept", value = "application/json" },
+                        { name = "X-Api-Key", value = "secret" },
+                    ]
+
+                    [http.body]
+                    type = "json"
+                    data = '{"sku": "zaku-1"}'
+                "#}
+            }),
+        );
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let worktree_id = cx.update(|cx| project.read(cx).root_worktree(cx).unwrap().read(cx).id());
+        let (workspace, _, cx) = build_workspace(&project, cx);
+
+        let request_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    (worktree_id, rel_path("request.toml")).into(),
+                    None,
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<RequestEditor>()
+            .unwrap();
+        cx.run_until_parked();
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            editor.focus_field(RequestField::HeaderValue(1), window, cx);
+        });
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            assert!(editor.active_tab == RequestEditorTab::Headers);
+            let value = &request.http.headers.get(1).unwrap().value;
+            assert!(value.read(cx).focus_handle(cx).is_focused(window));
+        });
+
+        request_editor.update_in(cx, |editor, window, cx| {
+            editor.focus_field(RequestField::Body(9..15), window, cx);
+        });
+        request_editor.update_in(cx, |editor, window, cx| {
+            let RequestEditorState::Ready(request) = &editor.request else {
+                panic!("Expected request editor to be ready");
+            };
+            assert!(editor.active_tab == RequestEditorTab::Body);
+            let body_editor = &request.http.body.as_ref().unwrap().editor;
+            assert!(body_editor.focus_handle(cx).is_focused(window));
+            assert_eq!(body_editor.read(cx).selected_range(cx), 9..15);
+        });
+    }
 }
@@ -0,0 +1,303 @@
+use jiff::{Timestamp, fmt::rfc2822::DateTimeParser};
+use std::time::Duration;
+
+use http_client::{Method, SendErrorKind, StatusCode, http::HeaderMap};
+use project::RequestFileRetry;
+use settings::{RegisterSetting, RetryErrorKind, Settings, SettingsContent};
+
+static HTTP_DATE_PARSER: DateTimeParser = DateTimeParser::new();
+
+/// When and how often a failed request is sent again. The global settings apply to every
+/// request, and a request file can override each field.
+#[derive(Debug, Clone, PartialEq, Eq, RegisterSetting)]
+pub(crate) struct RetrySettings {
+    /// The total number of attempts, including the first one.
+    pub(crate) max_attempts: u32,
+    pub(crate) statuses: Vec<u16>,
+    pub(crate) errors: Vec<RetryErrorKind>,
+    pub(crate) initial_delay: Duration,
+    pub(crate) max_delay: Duration,
+    pub(crate) retry_non_idempotent: bool,
+}
+
+impl Settings for RetrySettings {
+    fn from_settings(content: &SettingsContent) -> Self {
+        let retry = content.retry.as_ref();
+
+        Self {
+            max_attempts: retry
+                .and_then(|retry| retry.max_attempts)
+                .expect("retry max attempts should be defaulted"),
+            statuses: retry
+                .and_then(|retry| retry.statuses.clone())
+                .expect("retry statuses should be defaulted"),
+            errors: retry
+                .and_then(|retry| retry.errors.clone())
+                .expect("retry errors should be defaulted"),
+            initial_delay: retry
+                .and_then(|retry| retry.initial_delay_ms)
+                .map(Duration::from_millis)
+                .expect("retry initial delay should be defaulted"),
+            max_delay: retry
+                .and_then(|retry| retry.max_delay_ms)
+                .map(Duration::from_millis)
+                .expect("retry max delay should be defaulted"),
+            retry_non_idempotent: retry
+                .and_then(|retry| retry.retry_non_idempotent)
+                .expect("retry non-idempotent should be defaulted"),
+        }
+    }
+}
+
+impl RetrySettings {
+    pub(crate) fn with_overrides(mut self, retry: Option<&RequestFileRetry>) -> Self {
+        let Some(retry) = retry else {
+            return self;
+        };
+
+        if let Some(max_attempts) = retry.max_attempts {
+            self.max_attempts = max_attempts;
+        }
+        if let Some(statuses) = &retry.statuses {
+            self.statuses.clone_from(statuses);
+        }
+        if let Some(errors) = &retry.errors {
+            self.errors.clone_from(errors);
+        }
+        if let Some(initial_delay_ms) = retry.initial_delay_ms {
+            self.initial_delay = Duration::from_millis(initial_delay_ms);
+        }
+        if let Some(max_delay_ms) = retry.max_delay_ms {
+            self.max_delay = Duration::from_millis(max_delay_ms);
+        }
+        if let Some(retry_non_idempotent) = retry.retry_non_idempotent {
+            self.retry_non_idempotent = retry_non_idempotent;
+        }
+        self
+    }
+
+    /// Returns how long to wait before sending the request again after attempt number `attempt`
+    /// (counting from 1) received `status`, or `None` if it should not be retried.
+    ///
+    /// `Retry-After` is honoured on 429 and 503. When it asks for a longer wait than the maximum
+    /// delay, the request is not retried, since retrying sooner would be rejected again.
+    pub(crate) fn delay_after_status(
+        &self,
+        method: &Method,
+        attempt: u32,
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        jitter: f64,
+    ) -> Option<Duration> {
+        if !self.can_retry(method, attempt) || !self.statuses.contains(&status.as_u16()) {
+            return None;
+        }
+
+        match retry_after {
+            Some(retry_after)
+                if status == StatusCode::TOO_MANY_REQUESTS
+                    || status == StatusCode::SERVICE_UNAVAILABLE =>
+            {
+                (retry_after <= self.max_delay).then_some(retry_after)
+            }
+            Some(_) | None => Some(self.backoff(attempt, jitter)),
+        }
+    }
+
+    /// Returns how long to wait before sending the request again after attempt number `attempt`
+    /// failed to be sent, or `None` if it should not be retried.
+    pub(crate) fn delay_after_error(
+        &self,
+        method: &Method,
+        attempt: u32,
+        kind: SendErrorKind,
+        jitter: f64,
+    ) -> Option<Duration> {
+        let kind = match kind {
+            SendErrorKind::Connect => RetryErrorKind::Connect,
+            SendErrorKind::Timeout => RetryErrorKind::Timeout,
+            SendErrorKind::Other => return None,
+        };
+        (self.can_retry(method, attempt) && self.errors.contains(&kind))
+            .then(|| self.backoff(attempt, jitter))
+    }
+
+    fn can_retry(&self, method: &Method, attempt: u32) -> bool {
+        attempt < self.max_attempts && (method.is_idempotent() || self.retry_non_idempotent)
+    }
+
+    /// Doubles the initial delay after every attempt, up to the maximum delay, and randomizes
+    /// the upper half of it with `jitter`, in `0.0..1.0`, so that clients don't retry in step.
+    fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let delay = self
+            .initial_delay
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = delay / 2;
+        half + half.mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+/// Parses the `Retry-After` header, given either as a number of seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap, now: Timestamp) -> Option<Duration> {
+    let value = headers
+        .get(http_client::http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = HTTP_DATE_PARSER.parse_timestamp(value).ok()?;
+    Some(Duration::try_from(date.duration_since(now)).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_client::http::HeaderValue;
+
+    fn settings() -> RetrySettings {
+        RetrySettings {
+            max_attempts: 4,
+            statuses: vec![429, 503],
+            errors: vec![RetryErrorKind::Connect],
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+            retry_non_idempotent: false,
+        }
+    }
+
+    #[test]
+    fn test_delay_after_status() {
+        let settings = settings();
+        let delay = |attempt, jitter| {
+            settings.delay_after_status(
+                &Method::GET,
+                attempt,
+                StatusCode::SERVICE_UNAVAILABLE,
+                None,
+                jitter,
+            )
+        };
+
+        assert_eq!(delay(1, 0.0), Some(Duration::from_millis(250)));
+        assert_eq!(delay(1, 1.0), Some(Duration::from_millis(500)));
+        assert_eq!(delay(2, 0.5), Some(Duration::from_millis(750)));
+        assert_eq!(delay(3, 1.0), Some(Duration::from_secs(2)));
+        assert_eq!(delay(4, 0.0), None);
+
+        assert_eq!(
+            settings.delay_after_status(&Method::GET, 1, StatusCode::BAD_GATEWAY, None, 0.0),
+            None
+        );
+        assert_eq!(
+            settings.delay_after_status(
+                &Method::POST,
+                1,
+                StatusCode::SERVICE_UNAVAILABLE,
+                None,
+                0.0
+            ),
+            None
+        );
+        assert_eq!(
+            settings
+                .clone()
+                .with_overrides(Some(&RequestFileRetry {
+                    retry_non_idempotent: Some(true),
+                    ..RequestFileRetry::default()
+                }))
+                .delay_after_status(&Method::POST, 1, StatusCode::SERVICE_UNAVAILABLE, None, 0.0),
+            Some(Duration::from_millis(250))
+        );
+
+        let max_delay = RetrySettings {
+            max_attempts: 20,
+            ..settings.clone()
+        };
+        assert_eq!(
+            max_delay.delay_after_status(
+                &Method::GET,
+                19,
+                StatusCode::TOO_MANY_REQUESTS,
+                None,
+                1.0
+            ),
+            Some(Duration::from_secs(3))
+        );
+
+        assert_eq!(
+            settings.delay_after_status(
+                &Method::GET,
+                1,
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(2)),
+                0.0
+            ),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            settings.delay_after_status(
+                &Method::GET,
+                1,
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(60)),
+                0.0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_delay_after_error() {
+        let settings = settings();
+
+        assert_eq!(
+            settings.delay_after_error(&Method::PUT, 1, SendErrorKind::Connect, 0.0),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            settings.delay_after_error(&Method::PUT, 1, SendErrorKind::Timeout, 0.0),
+            None
+        );
+        assert_eq!(
+            settings.delay_after_error(&Method::PUT, 1, SendErrorKind::Other, 0.0),
+            None
+        );
+        assert_eq!(
+            settings.delay_after_error(&Method::PATCH, 1, SendErrorKind::Connect, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now: Timestamp = "2015-10-21T07:28:00Z".parse().unwrap();
+        let headers = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                http_client::http::header::RETRY_AFTER,
+                HeaderValue::from_static(value),
+            );
+            headers
+        };
+
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+        assert_eq!(
+            retry_after(&headers(" 120 "), now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:30 GMT"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:27:00 GMT"), now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_after(&headers("soon"), now), None);
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Context as _;
+use gpui::{App, BackgroundExecutor, Context, Entity, Window, prelude::*};
+use std::{collections::BTreeMap, mem, path::PathBuf};
+
+use editor::Editor;
+use language::{Buffer, PLAIN_TEXT};
+use multi_buffer::MultiBuffer;
+use project::{FolderDefaults, RequestFileScripts};
+use response_panel::ResponseConsoleEntry;
+use scripting::{ConsoleEntry, ScriptContext, ScriptRequest, ScriptResponse, ScriptVariables};
+use secrets::Secrets;
+
+/// The scripts being edited in the request editor's Scripts tab.
+pub(crate) struct RequestScripts {
+    pub(crate) pre_request: Entity<Editor>,
+    pub(crate) post_response: Entity<Editor>,
+}
+
+impl RequestScripts {
+    pub(crate) fn from_request_file_scripts(
+        scripts: Option<&RequestFileScripts>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let scripts = scripts.cloned().unwrap_or_default();
+
+        Self {
+            pre_request: script_editor(scripts.pre_request, window, cx),
+            post_response: script_editor(scripts.post_response, window, cx),
+        }
+    }
+
+    /// The scripts as they are saved, or `None` if both are blank.
+    pub(crate) fn to_request_file_scripts(&self, cx: &App) -> Option<RequestFileScripts> {
+        let scripts = RequestFileScripts {
+            pre_request: self.pre_request.read(cx).text(cx),
+            post_response: self.post_response.read(cx).text(cx),
+        };
+        (!scripts.is_empty()).then_some(scripts)
+    }
+
+    pub(crate) fn editors(&self) -> [&Entity<Editor>; 2] {
+        [&self.pre_request, &self.post_response]
+    }
+}
+
+fn script_editor(text: String, window: &mut Window, cx: &mut App) -> Entity<Editor> {
+    let payload = cx.new(move |cx| {
+        let buffer =
+            cx.new(move |cx| Buffer::local(text, cx).with_language(PLAIN_TEXT.clone(), cx));
+        MultiBuffer::singleton(buffer, cx)
+    });
+    cx.new(|cx| Editor::for_multibuffer(payload, window, cx))
+}
+
+/// The scripts run for one send of a request, with the state they share. The scripts of the
+/// folders the request is in run first, from the outermost folder in, then those of the request.
+pub(crate) struct ScriptRun {
+    pre_request: Vec<String>,
+    post_response: Vec<String>,
+    context: ScriptContext,
+    /// The request as the pre-request scripts left it, which post-response scripts see.
+    request: Option<ScriptRequest>,
+    /// The variables as they were when the changes of the scripts were last recorded.
+    recorded_variables: BTreeMap<String, String>,
+}
+
+impl ScriptRun {
+    pub(crate) fn new(
+        folder_defaults: Vec<FolderDefaults>,
+        request_scripts: Option<RequestFileScripts>,
+        variables: BTreeMap<String, String>,
+        secrets: &Secrets,
+    ) -> Self {
+        let mut pre_request = Vec::new();
+        let mut post_response = Vec::new();
+        let scripts = folder_defaults
+            .into_iter()
+            .filter_map(|folder_defaults| folder_defaults.scripts)
+            .chain(request_scripts);
+        for scripts in scripts {
+            if !scripts.pre_request.trim().is_empty() {
+                pre_request.push(scripts.pre_request);
+            }
+            if !scripts.post_response.trim().is_empty() {
+                post_response.push(scripts.post_response);
+            }
+        }
+
+        Self {
+            pre_request,
+            post_response,
+            context: ScriptContext {
+                variables: variables.clone(),
+                secrets: secrets
+                    .iter()
+                    .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                    .collect(),
+                console: Vec::new(),
+            },
+            request: None,
+            recorded_variables: variables,
+        }
+    }
+
+    pub(crate) fn variables(&self) -> &BTreeMap<String, String> {
+        &self.context.variables
+    }
+
+    /// Runs the pre-request scripts in the background, each on the request as the previous one
+    /// left it. Stops at the first script that fails.
+    pub(crate) async fn run_pre_request(
+        &mut self,
+        mut request: ScriptRequest,
+        executor: &BackgroundExecutor,
+    ) -> anyhow::Result<ScriptRequest> {
+        if !self.pre_request.is_empty() {
+            let scripts = self.pre_request.clone();
+            let mut context = mem::take(&mut self.context);
+            let (context, result) = executor
+                .spawn(async move {
+                    let result = scripts
+                        .iter()
+                        .try_for_each(|script| context.run_pre_request(script, &mut request))
+                        .map(|()| request);
+                    (context, result)
+                })
+                .await;
+            self.context = context;
+            request = result.context("pre-request script failed")?;
+        }
+        self.request = Some(request.clone());
+        Ok(request)
+    }
+
+    /// Runs the post-response scripts in the background, until one fails. Failures are written to
+    /// the console, since the response has already been received.
+    pub(crate) async fn run_post_response(
+        &mut self,
+        response: ScriptResponse,
+        executor: &BackgroundExecutor,
+    ) {
+        if self.post_response.is_empty() {
+            return;
+        }
+
+        let scripts = self.post_response.clone();
+        let request = self.request.clone().unwrap_or_default();
+        let mut context = mem::take(&mut self.context);
+        self.context = executor
+            .spawn(async move {
+                for script in &scripts {
+                    if context
+                        .run_post_response(script, &request, &response)
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                context
+            })
+            .await;
+    }
+
+    /// Takes what the scripts wrote to the console since it was last taken, masked with `mask`.
+    pub(crate) fn take_console(
+        &mut self,
+        mask: impl Fn(&str) -> String,
+    ) -> Vec<ResponseConsoleEntry> {
+        mem::take(&mut self.context.console)
+            .into_iter()
+            .map(|entry| match entry {
+                ConsoleEntry::Log(text) => ResponseConsoleEntry::Log(mask(&text).into()),
+                ConsoleEntry::Assertion { message, passed } => ResponseConsoleEntry::Assertion {
+                    message: mask(&message).into(),
+                    passed,
+                },
+                ConsoleEntry::Error(text) => ResponseConsoleEntry::Error(mask(&text).into()),
+            })
+            .collect()
+    }
+
+    /// Records the changes the scripts made to the variables since they were last recorded.
+    pub(crate) fn record_variables(
+        &mut self,
+        variables: &mut ScriptVariables,
+        project_path: PathBuf,
+        cx: &mut Context<ScriptVariables>,
+    ) {
+        variables.apply_changes(
+            project_path,
+            &self.recorded_variables,
+            &self.context.variables,
+            cx,
+        );
+        self.recorded_variables = self.context.variables.clone();
+    }
+}
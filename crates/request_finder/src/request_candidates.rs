@@ -0,0 +1,202 @@
+use path::PathStyle;
+use project::{ProjectPath, RequestFile, request_method_short_name};
+
+/// The methods a query can start with to only find the requests that use them.
+const METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// A finder query, split into the method it filters by and the text to fuzzy match.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RequestQuery {
+    pub(crate) method: Option<&'static str>,
+    pub(crate) text: String,
+}
+
+impl RequestQuery {
+    /// Parses `POST orders` into the `POST` method and `orders`. The method can also be given by
+    /// its short name, like `DEL`. A method without anything after it is matched as text, since it
+    /// could be the start of a path.
+    pub(crate) fn parse(query: &str) -> Self {
+        let query = query.trim_start();
+        if let Some((first_word, rest)) = query.split_once(char::is_whitespace)
+            && let Some(method) = METHODS.into_iter().find(|method| {
+                method.eq_ignore_ascii_case(first_word)
+                    || request_method_short_name(method).eq_ignore_ascii_case(first_word)
+            })
+        {
+            return Self {
+                method: Some(method),
+                text: rest.trim().to_string(),
+            };
+        }
+
+        Self {
+            method: None,
+            text: query.trim_end().to_string(),
+        }
+    }
+}
+
+/// A request file the finder can go to.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestCandidate {
+    pub(crate) project_path: ProjectPath,
+    pub(crate) method: String,
+    /// The file name without its extension.
+    pub(crate) name: String,
+    /// The path of the folder the request is in, empty for requests at the root.
+    pub(crate) parent: String,
+    pub(crate) url: String,
+    /// The text the query is matched against: the parent path and name, then the URL.
+    pub(crate) text: String,
+    name_start: usize,
+    url_start: usize,
+}
+
+/// The match positions of a candidate, split by the part of it they are in.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct RequestHighlights {
+    pub(crate) name: Vec<usize>,
+    pub(crate) parent: Vec<usize>,
+    pub(crate) url: Vec<usize>,
+}
+
+impl RequestCandidate {
+    pub(crate) fn new(
+        project_path: ProjectPath,
+        request_file: &RequestFile,
+        path_style: PathStyle,
+    ) -> Self {
+        let name = project_path
+            .path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string();
+        let parent = project_path
+            .path
+            .parent()
+            .map(|parent| parent.display(path_style).into_owned())
+            .unwrap_or_default();
+        let url = request_file.http.url.clone();
+
+        let mut text = parent.clone();
+        if !text.is_empty() {
+            text.push_str(path_style.primary_separator());
+        }
+        let name_start = text.len();
+        text.push_str(&name);
+        text.push(' ');
+        let url_start = text.len();
+        text.push_str(&url);
+
+        Self {
+            project_path,
+            method: request_file.http.method.clone(),
+            name,
+            parent,
+            url,
+            text,
+            name_start,
+            url_start,
+        }
+    }
+
+    pub(crate) fn has_method(&self, method: Option<&str>) -> bool {
+        method.is_none_or(|method| self.method.trim().eq_ignore_ascii_case(method))
+    }
+
+    /// Splits the positions of a match in `text` into positions in the name, the parent path and
+    /// the URL.
+    pub(crate) fn highlights(&self, positions: &[usize]) -> RequestHighlights {
+        let mut highlights = RequestHighlights::default();
+        for &position in positions {
+            if position < self.parent.len() {
+                highlights.parent.push(position);
+            } else if (self.name_start..self.name_start + self.name.len()).contains(&position) {
+                highlights.name.push(position - self.name_start);
+            } else if position >= self.url_start {
+                highlights.url.push(position - self.url_start);
+            }
+        }
+        highlights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use path::RelPath;
+    use project::WorktreeId;
+
+    fn candidate(path: &str, method: &str, url: &str) -> RequestCandidate {
+        let mut request_file = RequestFile::default();
+        request_file.http.method = method.to_string();
+        request_file.http.url = url.to_string();
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_usize(0),
+            path: RelPath::unix(path).unwrap().into_arc(),
+        };
+        RequestCandidate::new(project_path, &request_file, PathStyle::Posix)
+    }
+
+    #[test]
+    fn test_parse_request_query() {
+        let query = |method, text: &str| RequestQuery {
+            method,
+            text: text.to_string(),
+        };
+
+        assert_eq!(
+            RequestQuery::parse("POST orders"),
+            query(Some("POST"), "orders")
+        );
+        assert_eq!(
+            RequestQuery::parse("  del  users/remove "),
+            query(Some("DELETE"), "users/remove")
+        );
+        assert_eq!(RequestQuery::parse("get "), query(Some("GET"), ""));
+        assert_eq!(RequestQuery::parse("post"), query(None, "post"));
+        assert_eq!(
+            RequestQuery::parse("postman export"),
+            query(None, "postman export")
+        );
+        assert_eq!(RequestQuery::parse(""), query(None, ""));
+    }
+
+    #[test]
+    fn test_request_candidate() {
+        let nested = candidate(
+            "orders/v2/create.toml",
+            "post",
+            "https://api.zaku.dev/orders",
+        );
+        assert_eq!(nested.name, "create");
+        assert_eq!(nested.parent, "orders/v2");
+        assert_eq!(nested.text, "orders/v2/create https://api.zaku.dev/orders");
+        assert!(nested.has_method(Some("POST")));
+        assert!(nested.has_method(None));
+        assert!(!nested.has_method(Some("GET")));
+        assert_eq!(
+            nested.highlights(&[0, 10, 15, 17, 35]),
+            RequestHighlights {
+                name: vec![0, 5],
+                parent: vec![0],
+                url: vec![0, 18],
+            }
+        );
+
+        let root = candidate("health.toml", "GET", "{{base_url}}/health");
+        assert_eq!(root.parent, "");
+        assert_eq!(root.text, "health {{base_url}}/health");
+        assert_eq!(
+            root.highlights(&[0, 1, 7]),
+            RequestHighlights {
+                name: vec![0, 1],
+                parent: Vec::new(),
+                url: vec![0],
+            }
+        );
+    }
+}
@@ -0,0 +1,432 @@
+mod request_candidates;
+
+use fuzzy_nucleo::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    ParentElement, Render, SharedString, Styled, Task, TaskExt, WeakEntity, Window, prelude::*,
+};
+use smol::channel::Receiver;
+use std::{
+    cmp::{self, Ordering},
+    collections::VecDeque,
+    sync::{Arc, atomic::AtomicBool},
+    time::Duration,
+};
+
+use path::PathStyle;
+use picker::{Picker, PickerDelegate};
+use project::{
+    Project, ProjectEntryId, ProjectEvent, ProjectPath, RequestFile, request_method_short_name,
+};
+use ui::{
+    Color, HighlightedText, ListItem, ListItemSpacing, Text, TextCommon, TextSize, Toggleable,
+};
+use workspace::{ModalView, Workspace};
+
+use crate::request_candidates::{RequestCandidate, RequestQuery};
+
+/// How many recently opened requests are remembered for ordering the finder.
+const MAX_RECENT_REQUESTS: usize = 50;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(RequestFinder::register).detach();
+}
+
+/// The entries most recently opened in a workspace, most recent first.
+#[derive(Default)]
+struct RecentRequests {
+    entry_ids: VecDeque<ProjectEntryId>,
+}
+
+impl RecentRequests {
+    fn push(&mut self, entry_id: ProjectEntryId) {
+        self.entry_ids
+            .retain(|recent_entry_id| *recent_entry_id != entry_id);
+        self.entry_ids.push_front(entry_id);
+        self.entry_ids.truncate(MAX_RECENT_REQUESTS);
+    }
+
+    fn project_paths(&self, project: &Project, cx: &App) -> Vec<ProjectPath> {
+        self.entry_ids
+            .iter()
+            .filter_map(|entry_id| project.path_for_entry(*entry_id, cx))
+            .collect()
+    }
+}
+
+pub struct RequestFinder {
+    picker: Entity<Picker<RequestFinderDelegate>>,
+    _load_candidates: Task<()>,
+}
+
+impl RequestFinder {
+    fn register(workspace: &mut Workspace, _: Option<&mut Window>, cx: &mut Context<Workspace>) {
+        let recent_requests = cx.new(|_| RecentRequests::default());
+        cx.subscribe(workspace.project(), {
+            let recent_requests = recent_requests.clone();
+            move |_, _, event: &ProjectEvent, cx| {
+                if let ProjectEvent::ActiveEntryChanged(Some(entry_id)) = event {
+                    recent_requests.update(cx, |recent_requests, _| {
+                        recent_requests.push(*entry_id);
+                    });
+                }
+            }
+        })
+        .detach();
+
+        workspace.register_action(
+            move |workspace, _: &actions::request_finder::Toggle, window, cx| {
+                let recent_requests = recent_requests.clone();
+                let weak_workspace = workspace.weak_handle();
+                let project = workspace.project().clone();
+                workspace.toggle_modal(window, cx, move |window, cx| {
+                    RequestFinder::new(weak_workspace, project, &recent_requests, window, cx)
+                });
+            },
+        );
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        recent_requests: &Entity<RecentRequests>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let recent_paths = recent_requests.read(cx).project_paths(project.read(cx), cx);
+        let path_style = project.read(cx).path_style(cx);
+        let load_request_files = project.read(cx).load_request_files(cx);
+
+        let delegate = RequestFinderDelegate::new(cx.entity().downgrade(), workspace);
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(delegate, window, cx)
+                .initial_width(gpui::rems(34.0))
+                .minimum_results_width(gpui::rems(30.0))
+                .height(gpui::rems(24.0))
+                .no_vertical_padding()
+        });
+
+        let load_candidates = cx.spawn_in(window, async move |this, cx| {
+            let request_files = load_request_files.await;
+            if let Err(error) = this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker
+                        .delegate
+                        .set_candidates(request_files, &recent_paths, path_style);
+                    picker.refresh(window, cx);
+                });
+            }) {
+                log::debug!("Failed to load request finder candidates: {error:?}");
+            }
+        });
+
+        Self {
+            picker,
+            _load_candidates: load_candidates,
+        }
+    }
+}
+
+impl ModalView for RequestFinder {}
+
+impl EventEmitter<DismissEvent> for RequestFinder {}
+
+impl Focusable for RequestFinder {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RequestFinder {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        gpui::div()
+            .flex()
+            .flex_col()
+            .key_context("RequestFinder")
+            .child(self.picker.clone())
+    }
+}
+
+pub struct RequestFinderDelegate {
+    request_finder: WeakEntity<RequestFinder>,
+    workspace: WeakEntity<Workspace>,
+    /// Every request in the project, ordered by path.
+    candidates: Arc<[RequestCandidate]>,
+    /// For each candidate, how recently it was opened, with 0 being the most recent.
+    recency: Arc<[Option<usize>]>,
+    loaded: bool,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    updating_matches: Option<(Task<()>, Receiver<Vec<StringMatch>>)>,
+}
+
+impl RequestFinderDelegate {
+    fn new(request_finder: WeakEntity<RequestFinder>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            request_finder,
+            workspace,
+            candidates: Arc::from([]),
+            recency: Arc::from([]),
+            loaded: false,
+            matches: Vec::new(),
+            selected_index: 0,
+            updating_matches: None,
+        }
+    }
+
+    fn set_candidates(
+        &mut self,
+        request_files: Vec<(ProjectPath, RequestFile)>,
+        recent_paths: &[ProjectPath],
+        path_style: PathStyle,
+    ) {
+        let mut candidates = request_files
+            .into_iter()
+            .map(|(project_path, request_file)| {
+                RequestCandidate::new(project_path, &request_file, path_style)
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+        self.recency = candidates
+            .iter()
+            .map(|candidate| {
+                recent_paths
+                    .iter()
+                    .position(|project_path| *project_path == candidate.project_path)
+            })
+            .collect();
+        self.candidates = candidates.into();
+        self.loaded = true;
+    }
+
+    fn matches_updated(&mut self, matches: Vec<StringMatch>) {
+        drop(self.updating_matches.take());
+        self.matches = matches;
+        if self.matches.is_empty() {
+            self.selected_index = 0;
+        } else {
+            self.selected_index = cmp::min(self.selected_index, self.matches.len() - 1);
+        }
+    }
+}
+
+impl PickerDelegate for RequestFinderDelegate {
+    type ListItem = ListItem;
+
+    fn name() -> &'static str {
+        "request finder"
+    }
+
+    fn placeholder_text(&self, _: &mut Window, _: &mut App) -> Arc<str> {
+        "Go to request...".into()
+    }
+
+    fn no_matches_text(&self, _: &mut Window, _: &mut App) -> Option<SharedString> {
+        if !self.loaded {
+            None
+        } else if self.candidates.is_empty() {
+            Some("This project has no requests.".into())
+        } else {
+            Some("No requests match your search.".into())
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, index: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = index;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let (tx, rx) = smol::channel::bounded(1);
+        let query = RequestQuery::parse(&query);
+
+        let task = cx.background_spawn({
+            let candidates = self.candidates.clone();
+            let recency = self.recency.clone();
+            let executor = cx.background_executor().clone();
+            async move {
+                let match_candidates = candidates
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, candidate)| candidate.has_method(query.method))
+                    .map(|(index, candidate)| StringMatchCandidate::new(index, &candidate.text))
+                    .collect::<Vec<_>>();
+
+                let mut matches = fuzzy_nucleo::match_strings_async(
+                    &match_candidates,
+                    &query.text,
+                    fuzzy_nucleo::Case::Smart,
+                    fuzzy_nucleo::LengthPenalty::On,
+                    10000,
+                    &AtomicBool::default(),
+                    executor,
+                )
+                .await;
+
+                // Recently opened requests come first, then the best matches, then by path.
+                let recency_of = |string_match: &StringMatch| {
+                    recency
+                        .get(string_match.candidate_id)
+                        .copied()
+                        .flatten()
+                        .unwrap_or(usize::MAX)
+                };
+                matches.sort_by(|a, b| {
+                    recency_of(a)
+                        .cmp(&recency_of(b))
+                        .then_with(|| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal))
+                        .then_with(|| a.candidate_id.cmp(&b.candidate_id))
+                });
+
+                if tx.send(matches).await.is_err() {
+                    log::debug!("Failed to send request finder matches");
+                }
+            }
+        });
+
+        self.updating_matches = Some((task, rx.clone()));
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let Ok(matches) = rx.recv().await else {
+                return;
+            };
+
+            if let Err(error) = picker.update(cx, |picker, _| {
+                picker.delegate.matches_updated(matches);
+            }) {
+                log::debug!("Failed to update request finder matches: {error:?}");
+            }
+        })
+    }
+
+    fn finalize_update_matches(
+        &mut self,
+        _: String,
+        duration: Duration,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> bool {
+        let Some((task, rx)) = self.updating_matches.take() else {
+            return true;
+        };
+
+        match cx
+            .foreground_executor()
+            .block_with_timeout(duration, rx.clone().recv())
+        {
+            Ok(Ok(matches)) => {
+                self.matches_updated(matches);
+                true
+            }
+            Ok(Err(_)) => true,
+            Err(_) => {
+                self.updating_matches = Some((task, rx));
+                false
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Err(error) = self.request_finder.update(cx, |_, cx| {
+            cx.emit(DismissEvent);
+        }) {
+            log::debug!("Failed to dismiss request finder: {error:?}");
+        }
+    }
+
+    /// Opens the selected request, or with the secondary confirm, opens it in a split to the right
+    /// of the active pane.
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(candidate) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|string_match| self.candidates.get(string_match.candidate_id))
+        else {
+            self.dismissed(window, cx);
+            return;
+        };
+
+        let project_path = candidate.project_path.clone();
+        if let Some(workspace) = self.workspace.upgrade() {
+            workspace.update(cx, |workspace, cx| {
+                if secondary {
+                    workspace.open_path_in_split(project_path, window, cx)
+                } else {
+                    workspace.open_path_preview(project_path, None, true, false, true, window, cx)
+                }
+                .detach_and_log_err(cx);
+            });
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn render_match(
+        &self,
+        index: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let string_match = self.matches.get(index)?;
+        let candidate = self.candidates.get(string_match.candidate_id)?;
+        let highlights = candidate.highlights(&string_match.positions);
+
+        Some(
+            ListItem::new(index)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    gpui::div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .w_full()
+                        .min_w_0()
+                        .py_px()
+                        .child(
+                            gpui::div().flex_none().w(gpui::rems(2.5)).child(
+                                Text::new(request_method_short_name(&candidate.method))
+                                    .size(TextSize::Small)
+                                    .weight(FontWeight::MEDIUM)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            ),
+                        )
+                        .child(
+                            HighlightedText::new(candidate.name.clone(), highlights.name)
+                                .single_line(),
+                        )
+                        .when(!candidate.parent.is_empty(), |this| {
+                            this.child(
+                                HighlightedText::new(candidate.parent.clone(), highlights.parent)
+                                    .size(TextSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            )
+                        })
+                        .child(
+                            gpui::div().flex_1().min_w_0().flex().justify_end().child(
+                                HighlightedText::new(candidate.url.clone(), highlights.url)
+                                    .size(TextSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                        ),
+                ),
+        )
+    }
+}
@@ -4,7 +4,9 @@ use futures::{FutureExt, TryStreamExt};
 use reqwest::redirect;
 use std::{io, mem, pin::Pin, sync::OnceLock, task, time::Duration};
 
-use http_client::{AsyncBody, HttpClient, Inner, RedirectPolicy, Url, http};
+use http_client::{
+    AsyncBody, HttpClient, Inner, RedirectPolicy, SendError, SendErrorKind, Url, http,
+};
 
 const DEFAULT_CAPACITY: usize = 4096;
 static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
@@ -87,7 +89,7 @@ impl HttpClient for ReqwestClient {
             let mut response = handle
                 .spawn(async { request.send().await })
                 .await?
-                .map_err(|error| anyhow!(error))?;
+                .map_err(|error| anyhow!(SendError::new(send_error_kind(&error), error)))?;
 
             let headers = mem::take(response.headers_mut());
             let mut builder = http::Response::builder()
@@ -117,6 +119,16 @@ impl HttpClient for ReqwestClient {
     }
 }
 
+fn send_error_kind(error: &reqwest::Error) -> SendErrorKind {
+    if error.is_connect() {
+        SendErrorKind::Connect
+    } else if error.is_timeout() {
+        SendErrorKind::Timeout
+    } else {
+        SendErrorKind::Other
+    }
+}
+
 pub fn runtime() -> &'static tokio::runtime::Runtime {
     RUNTIME.get_or_init(|| {
         tokio::runtime::Builder::new_multi_thread()
@@ -0,0 +1,375 @@
+use num_traits::ToPrimitive;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write as _, time::Duration};
+
+const HISTOGRAM_BUCKET_COUNT: u32 = 10;
+
+/// The outcome of one of the requests sent by a benchmark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkSample {
+    /// When the request was sent, relative to the start of the benchmark.
+    pub started_at: Duration,
+    /// The time until the whole response body was received.
+    pub latency: Duration,
+    pub outcome: Result<u16, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LatencySummary {
+    pub(crate) min: Duration,
+    pub(crate) p50: Duration,
+    pub(crate) p90: Duration,
+    pub(crate) p99: Duration,
+    pub(crate) max: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HistogramBucket {
+    pub(crate) start: Duration,
+    pub(crate) end: Duration,
+    pub(crate) count: usize,
+}
+
+/// Statistics over the samples a benchmark has collected so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    samples: Vec<BenchmarkSample>,
+    pub(crate) elapsed: Duration,
+    /// Only responses count towards the latencies, since failed requests often fail fast.
+    pub(crate) latency: Option<LatencySummary>,
+    pub(crate) statuses: BTreeMap<u16, usize>,
+    pub(crate) errors: BTreeMap<String, usize>,
+    pub(crate) histogram: Vec<HistogramBucket>,
+}
+
+impl BenchmarkReport {
+    pub fn new(samples: Vec<BenchmarkSample>, elapsed: Duration) -> Self {
+        let mut statuses = BTreeMap::new();
+        let mut errors = BTreeMap::new();
+        let mut latencies = Vec::new();
+        for sample in &samples {
+            match &sample.outcome {
+                Ok(status) => {
+                    *statuses.entry(*status).or_default() += 1;
+                    latencies.push(sample.latency);
+                }
+                Err(error) => *errors.entry(error.clone()).or_default() += 1,
+            }
+        }
+        latencies.sort_unstable();
+
+        Self {
+            latency: latency_summary(&latencies),
+            histogram: histogram(&latencies),
+            samples,
+            elapsed,
+            statuses,
+            errors,
+        }
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.errors.values().sum()
+    }
+
+    pub fn status_count(&self, status: u16) -> usize {
+        self.statuses.get(&status).copied().unwrap_or_default()
+    }
+
+    /// Requests completed per second.
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.request_count()
+                .to_f64()
+                .expect("request count should fit in f64")
+                / seconds
+        } else {
+            0.0
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        let latency = self.latency.map(|latency| SerializedLatency {
+            min_ms: milliseconds(latency.min),
+            p50_ms: milliseconds(latency.p50),
+            p90_ms: milliseconds(latency.p90),
+            p99_ms: milliseconds(latency.p99),
+            max_ms: milliseconds(latency.max),
+        });
+        let report = SerializedReport {
+            requests: self.request_count(),
+            errors: self.error_count(),
+            elapsed_ms: milliseconds(self.elapsed),
+            throughput: self.throughput(),
+            latency,
+            statuses: self
+                .statuses
+                .iter()
+                .map(|(status, count)| (status.to_string(), *count))
+                .collect(),
+            error_kinds: self.errors.clone(),
+            histogram: self
+                .histogram
+                .iter()
+                .map(|bucket| SerializedBucket {
+                    start_ms: milliseconds(bucket.start),
+                    end_ms: milliseconds(bucket.end),
+                    count: bucket.count,
+                })
+                .collect(),
+            samples: self
+                .samples
+                .iter()
+                .map(|sample| SerializedSample {
+                    started_at_ms: milliseconds(sample.started_at),
+                    latency_ms: milliseconds(sample.latency),
+                    status: sample.outcome.as_ref().ok().copied(),
+                    error: sample.outcome.as_ref().err().cloned(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&report)
+    }
+
+    /// One row per request, in the order they completed.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("started_at_ms,latency_ms,status,error\n");
+        for sample in &self.samples {
+            let (status, error) = match &sample.outcome {
+                Ok(status) => (status.to_string(), String::new()),
+                Err(error) => (String::new(), csv_field(error)),
+            };
+            writeln!(
+                csv,
+                "{:.3},{:.3},{status},{error}",
+                milliseconds(sample.started_at),
+                milliseconds(sample.latency),
+            )
+            .expect("writing to a string should not fail");
+        }
+        csv
+    }
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// The nearest-rank percentile of sorted latencies.
+fn percentile(latencies: &[Duration], percent: usize) -> Option<Duration> {
+    let rank = (percent * latencies.len()).div_ceil(100);
+    latencies.get(rank.saturating_sub(1)).copied()
+}
+
+fn latency_summary(latencies: &[Duration]) -> Option<LatencySummary> {
+    Some(LatencySummary {
+        min: *latencies.first()?,
+        p50: percentile(latencies, 50)?,
+        p90: percentile(latencies, 90)?,
+        p99: percentile(latencies, 99)?,
+        max: *latencies.last()?,
+    })
+}
+
+/// Splits the range of sorted latencies into equal buckets.
+fn histogram(latencies: &[Duration]) -> Vec<HistogramBucket> {
+    let (Some(min), Some(max)) = (latencies.first(), latencies.last()) else {
+        return Vec::new();
+    };
+    let range = max.saturating_sub(*min);
+    if range.is_zero() {
+        return vec![HistogramBucket {
+            start: *min,
+            end: *max,
+            count: latencies.len(),
+        }];
+    }
+
+    let width = range / HISTOGRAM_BUCKET_COUNT;
+    let mut buckets = (0..HISTOGRAM_BUCKET_COUNT)
+        .map(|index| HistogramBucket {
+            start: *min + width * index,
+            end: if index + 1 == HISTOGRAM_BUCKET_COUNT {
+                *max
+            } else {
+                *min + width * (index + 1)
+            },
+            count: 0,
+        })
+        .collect::<Vec<_>>();
+    for latency in latencies {
+        let offset = latency.saturating_sub(*min).as_nanos();
+        let index = (offset * u128::from(HISTOGRAM_BUCKET_COUNT) / range.as_nanos().max(1))
+            .min(u128::from(HISTOGRAM_BUCKET_COUNT - 1));
+        if let Some(bucket) = usize::try_from(index)
+            .ok()
+            .and_then(|index| buckets.get_mut(index))
+        {
+            bucket.count += 1;
+        }
+    }
+    buckets
+}
+
+#[derive(Serialize)]
+struct SerializedReport {
+    requests: usize,
+    errors: usize,
+    elapsed_ms: f64,
+    throughput: f64,
+    latency: Option<SerializedLatency>,
+    statuses: BTreeMap<String, usize>,
+    error_kinds: BTreeMap<String, usize>,
+    histogram: Vec<SerializedBucket>,
+    samples: Vec<SerializedSample>,
+}
+
+#[derive(Serialize)]
+struct SerializedLatency {
+    min_ms: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+}
+
+#[derive(Serialize)]
+struct SerializedBucket {
+    start_ms: f64,
+    end_ms: f64,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct SerializedSample {
+    started_at_ms: f64,
+    latency_ms: f64,
+    status: Option<u16>,
+    error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn sample(latency_ms: u64, outcome: Result<u16, &str>) -> BenchmarkSample {
+        BenchmarkSample {
+            started_at: Duration::ZERO,
+            latency: Duration::from_millis(latency_ms),
+            outcome: outcome.map_err(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let mut samples = (1..=100)
+            .map(|latency_ms| sample(latency_ms, Ok(if latency_ms % 10 == 0 { 500 } else { 200 })))
+            .collect::<Vec<_>>();
+        samples.push(sample(1, Err("connection refused")));
+        samples.push(sample(2, Err("connection refused")));
+        let report = BenchmarkReport::new(samples, Duration::from_secs(2));
+
+        assert_eq!(report.request_count(), 102);
+        assert_eq!(report.error_count(), 2);
+        assert_eq!(report.status_count(200), 90);
+        assert_eq!(report.status_count(500), 10);
+        assert!((report.throughput() - 51.0).abs() < f64::EPSILON);
+        assert_eq!(
+            report.latency,
+            Some(LatencySummary {
+                min: Duration::from_millis(1),
+                p50: Duration::from_millis(50),
+                p90: Duration::from_millis(90),
+                p99: Duration::from_millis(99),
+                max: Duration::from_millis(100),
+            })
+        );
+        assert_eq!(report.histogram.len(), 10);
+        assert_eq!(
+            report.histogram.first(),
+            Some(&HistogramBucket {
+                start: Duration::from_millis(1),
+                end: Duration::from_micros(10_900),
+                count: 10,
+            })
+        );
+        assert_eq!(
+            report
+                .histogram
+                .iter()
+                .map(|bucket| bucket.count)
+                .sum::<usize>(),
+            100
+        );
+        assert_eq!(
+            report.histogram.last().map(|bucket| bucket.end),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = BenchmarkReport::new(Vec::new(), Duration::ZERO);
+
+        assert_eq!(report.latency, None);
+        assert_eq!(report.histogram, Vec::new());
+        assert!(report.throughput().abs() < f64::EPSILON);
+
+        let report = BenchmarkReport::new(vec![sample(5, Ok(204))], Duration::ZERO);
+        assert_eq!(
+            report.histogram,
+            vec![HistogramBucket {
+                start: Duration::from_millis(5),
+                end: Duration::from_millis(5),
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let report = BenchmarkReport::new(
+            vec![
+                BenchmarkSample {
+                    started_at: Duration::from_millis(3),
+                    latency: Duration::from_micros(12_500),
+                    outcome: Ok(200),
+                },
+                sample(7, Err("error sending request, \"timed out\"")),
+            ],
+            Duration::from_secs(1),
+        );
+
+        assert_eq!(
+            report.to_csv(),
+            "started_at_ms,latency_ms,status,error\n\
+             3.000,12.500,200,\n\
+             0.000,7.000,,\"error sending request, \"\"timed out\"\"\"\n"
+        );
+
+        let json = serde_json::from_str::<serde_json::Value>(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["requests"], 2);
+        assert_eq!(json["errors"], 1);
+        assert_eq!(json["statuses"]["200"], 1);
+        assert_eq!(json["latency"]["p99_ms"], 12.5);
+        assert_eq!(
+            json["samples"][1]["error"],
+            "error sending request, \"timed out\""
+        );
+    }
+}
@@ -0,0 +1,1123 @@
+use anyhow::{Context as _, anyhow, bail};
+use std::cmp::Ordering;
+
+/// How deeply arrays and objects can be nested in a filtered body, as in `serde_json`.
+const MAX_JSON_DEPTH: usize = 128;
+
+/// A parsed response body filter.
+///
+/// Expressions starting with `$` are evaluated as JSONPath and produce an array of every matched
+/// value. Anything else is evaluated as a jq pipeline and produces one document per output value.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ResponseFilter(FilterExpression);
+
+#[derive(Clone, Debug, PartialEq)]
+enum FilterExpression {
+    JsonPath(Vec<Segment>),
+    Jq(Vec<JqStage>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Segment {
+    recursive: bool,
+    selector: Selector,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+    },
+    Union(Vec<Selector>),
+    Filter(Predicate),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Predicate {
+    path: Vec<Segment>,
+    comparison: Option<(Comparison, Value)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum JqStage {
+    Path(Vec<Segment>),
+    Keys { sorted: bool },
+    Length,
+    Select(Predicate),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    JsonPath,
+    Jq,
+}
+
+impl ResponseFilter {
+    pub(crate) fn parse(expression: &str) -> anyhow::Result<Self> {
+        let expression = expression.trim();
+        if expression.starts_with('$') {
+            let mut parser = Parser::new(expression);
+            parser.expect('$')?;
+            let segments = parser.parse_segments(Mode::JsonPath)?;
+            parser.expect_end()?;
+            Ok(Self(FilterExpression::JsonPath(segments)))
+        } else {
+            let stages = split_pipeline(expression)
+                .into_iter()
+                .map(parse_jq_stage)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(Self(FilterExpression::Jq(stages)))
+        }
+    }
+
+    /// Applies the filter to a JSON document and returns the pretty printed result. Numbers are
+    /// printed as the document wrote them, so large integers keep their precision.
+    pub(crate) fn apply(&self, payload: &str) -> anyhow::Result<String> {
+        let document = Value::parse(payload).context("Response body is not valid JSON")?;
+
+        match &self.0 {
+            FilterExpression::JsonPath(segments) => {
+                let values = select_path(vec![document], segments, Mode::JsonPath)?;
+                Ok(Value::Array(values).to_pretty_string())
+            }
+            FilterExpression::Jq(stages) => {
+                let mut values = vec![document];
+                for stage in stages {
+                    values = apply_jq_stage(values, stage)?;
+                }
+                let documents = values
+                    .iter()
+                    .map(Value::to_pretty_string)
+                    .collect::<Vec<_>>();
+                Ok(documents.join("\n"))
+            }
+        }
+    }
+}
+
+fn split_pipeline(expression: &str) -> Vec<&str> {
+    let mut stages = Vec::new();
+    let mut depth = 0_usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut stage_start = 0;
+
+    for (index, character) in expression.char_indices() {
+        if let Some(quote_character) = quote {
+            if escaped {
+                escaped = false;
+            } else if character == '\\' {
+                escaped = true;
+            } else if character == quote_character {
+                quote = None;
+            }
+            continue;
+        }
+
+        match character {
+            '"' | '\'' => quote = Some(character),
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '|' if depth == 0 => {
+                stages.push(expression.get(stage_start..index).unwrap_or_default());
+                stage_start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    stages.push(expression.get(stage_start..).unwrap_or_default());
+    stages
+}
+
+fn parse_jq_stage(stage: &str) -> anyhow::Result<JqStage> {
+    let stage = stage.trim();
+    match stage {
+        "" => bail!("Expected a filter"),
+        "keys" => return Ok(JqStage::Keys { sorted: true }),
+        "keys_unsorted" => return Ok(JqStage::Keys { sorted: false }),
+        "length" => return Ok(JqStage::Length),
+        _ => {}
+    }
+
+    let mut parser = Parser::new(stage);
+    if parser.consume_keyword("select") {
+        parser.skip_whitespace();
+        parser.expect('(')?;
+        let predicate = parser.parse_predicate(Mode::Jq)?;
+        parser.expect(')')?;
+        parser.expect_end()?;
+        return Ok(JqStage::Select(predicate));
+    }
+
+    let segments = parser.parse_jq_path()?;
+    parser.expect_end()?;
+    Ok(JqStage::Path(segments))
+}
+
+struct Parser {
+    characters: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self {
+            characters: text.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.characters.get(self.position).copied()
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        self.characters.get(self.position + 1).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += 1;
+        Some(character)
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        let end = self.position + keyword.chars().count();
+        let matches = self
+            .characters
+            .get(self.position..end)
+            .is_some_and(|characters| characters.iter().copied().eq(keyword.chars()));
+        if matches {
+            self.position = end;
+        }
+        matches
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> anyhow::Result<()> {
+        self.skip_whitespace();
+        match self.advance() {
+            Some(character) if character == expected => Ok(()),
+            Some(character) => bail!(
+                "Expected '{expected}' but found '{character}' at position {}",
+                self.position
+            ),
+            None => bail!("Expected '{expected}' but the expression ended"),
+        }
+    }
+
+    fn expect_end(&mut self) -> anyhow::Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(character) => bail!("Unexpected '{character}' at position {}", self.position + 1),
+            None => Ok(()),
+        }
+    }
+
+    fn unexpected(&self) -> anyhow::Error {
+        match self.peek() {
+            Some(character) => {
+                anyhow::anyhow!("Unexpected '{character}' at position {}", self.position + 1)
+            }
+            None => anyhow::anyhow!("Unexpected end of expression"),
+        }
+    }
+
+    /// Parses a jq path such as `.`, `.items[0].name` or `.["key"][]`.
+    fn parse_jq_path(&mut self) -> anyhow::Result<Vec<Segment>> {
+        self.skip_whitespace();
+        if self.peek() != Some('.') {
+            return Err(self.unexpected());
+        }
+        if self.peek_next().is_none_or(|character| {
+            !(character == '.' || character == '[' || character == '"' || is_name_start(character))
+        }) {
+            self.position += 1;
+        }
+        self.parse_segments(Mode::Jq)
+    }
+
+    fn parse_segments(&mut self, mode: Mode) -> anyhow::Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') => {
+                    self.position += 1;
+                    let recursive = mode == Mode::JsonPath && self.consume('.');
+                    let selector = match self.peek() {
+                        Some('[') => self.parse_bracket(mode)?,
+                        Some('*') if mode == Mode::JsonPath => {
+                            self.position += 1;
+                            Selector::Wildcard
+                        }
+                        Some('"') if mode == Mode::Jq => Selector::Name(self.parse_string('"')?),
+                        Some(character) if is_name_start(character) => {
+                            Selector::Name(self.parse_name())
+                        }
+                        _ => return Err(self.unexpected()),
+                    };
+                    segments.push(Segment {
+                        recursive,
+                        selector,
+                    });
+                }
+                Some('[') => {
+                    let selector = self.parse_bracket(mode)?;
+                    segments.push(Segment {
+                        recursive: false,
+                        selector,
+                    });
+                }
+                Some('?') if mode == Mode::Jq => self.position += 1,
+                _ => return Ok(segments),
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(character) = self.peek() {
+            if !is_name_continue(character) {
+                break;
+            }
+            name.push(character);
+            self.position += 1;
+        }
+        name
+    }
+
+    fn parse_bracket(&mut self, mode: Mode) -> anyhow::Result<Selector> {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        if self.consume(']') {
+            return if mode == Mode::Jq {
+                Ok(Selector::Wildcard)
+            } else {
+                bail!("Empty brackets at position {}", self.position)
+            };
+        }
+
+        if mode == Mode::JsonPath && self.consume('?') {
+            self.expect('(')?;
+            let predicate = self.parse_predicate(mode)?;
+            self.expect(')')?;
+            self.expect(']')?;
+            return Ok(Selector::Filter(predicate));
+        }
+
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let selector = match self.peek() {
+                Some('*') => {
+                    self.position += 1;
+                    Selector::Wildcard
+                }
+                Some(quote @ ('"' | '\'')) => Selector::Name(self.parse_string(quote)?),
+                Some(character)
+                    if character == '-' || character == ':' || character.is_ascii_digit() =>
+                {
+                    self.parse_index_or_slice()?
+                }
+                _ => return Err(self.unexpected()),
+            };
+            selectors.push(selector);
+            self.skip_whitespace();
+            if !self.consume(',') {
+                break;
+            }
+        }
+        self.expect(']')?;
+
+        Ok(if selectors.len() == 1 {
+            selectors.remove(0)
+        } else {
+            Selector::Union(selectors)
+        })
+    }
+
+    fn parse_index_or_slice(&mut self) -> anyhow::Result<Selector> {
+        let start = self.parse_integer()?;
+        self.skip_whitespace();
+        if !self.consume(':') {
+            return start.map(Selector::Index).ok_or_else(|| self.unexpected());
+        }
+        self.skip_whitespace();
+        let end = self.parse_integer()?;
+        Ok(Selector::Slice { start, end })
+    }
+
+    fn parse_integer(&mut self) -> anyhow::Result<Option<i64>> {
+        let mut digits = String::new();
+        if self.peek() == Some('-') {
+            digits.push('-');
+            self.position += 1;
+        }
+        while let Some(character) = self.peek().filter(char::is_ascii_digit) {
+            digits.push(character);
+            self.position += 1;
+        }
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        digits
+            .parse()
+            .map(Some)
+            .with_context(|| format!("Invalid index '{digits}'"))
+    }
+
+    fn parse_string(&mut self, quote: char) -> anyhow::Result<String> {
+        self.expect(quote)?;
+        let mut value = String::new();
+        loop {
+            match self.advance() {
+                Some('\\') => match self.advance() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(character) => value.push(character),
+                    None => bail!("Unterminated string"),
+                },
+                Some(character) if character == quote => return Ok(value),
+                Some(character) => value.push(character),
+                None => bail!("Unterminated string"),
+            }
+        }
+    }
+
+    fn parse_predicate(&mut self, mode: Mode) -> anyhow::Result<Predicate> {
+        self.skip_whitespace();
+        let path = match mode {
+            Mode::JsonPath => {
+                self.expect('@')?;
+                self.parse_segments(mode)?
+            }
+            Mode::Jq => self.parse_jq_path()?,
+        };
+
+        self.skip_whitespace();
+        let comparison = if self.consume_keyword("==") {
+            Comparison::Equal
+        } else if self.consume_keyword("!=") {
+            Comparison::NotEqual
+        } else if self.consume_keyword("<=") {
+            Comparison::LessOrEqual
+        } else if self.consume_keyword(">=") {
+            Comparison::GreaterOrEqual
+        } else if self.consume('<') {
+            Comparison::Less
+        } else if self.consume('>') {
+            Comparison::Greater
+        } else {
+            return Ok(Predicate {
+                path,
+                comparison: None,
+            });
+        };
+
+        self.skip_whitespace();
+        let literal = self.parse_literal()?;
+        Ok(Predicate {
+            path,
+            comparison: Some((comparison, literal)),
+        })
+    }
+
+    fn parse_literal(&mut self) -> anyhow::Result<Value> {
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => Ok(Value::String(self.parse_string(quote)?)),
+            Some(_) if self.consume_keyword("true") => Ok(Value::Bool(true)),
+            Some(_) if self.consume_keyword("false") => Ok(Value::Bool(false)),
+            Some(_) if self.consume_keyword("null") => Ok(Value::Null),
+            Some(character) if character == '-' || character.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(character) = self.peek().filter(|character| {
+                    character.is_ascii_digit() || matches!(character, '-' | '+' | '.' | 'e' | 'E')
+                }) {
+                    number.push(character);
+                    self.position += 1;
+                }
+                match Value::parse(&number) {
+                    Ok(value @ Value::Number(_)) => Ok(value),
+                    _ => bail!("Invalid number '{number}'"),
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+}
+
+fn is_name_start(character: char) -> bool {
+    character.is_alphabetic() || character == '_' || character == '$'
+}
+
+fn is_name_continue(character: char) -> bool {
+    character.is_alphanumeric() || character == '_' || character == '-' || character == '$'
+}
+
+fn select_path(values: Vec<Value>, segments: &[Segment], mode: Mode) -> anyhow::Result<Vec<Value>> {
+    let mut values = values;
+    for segment in segments {
+        let mut selected = Vec::new();
+        for value in values {
+            if segment.recursive {
+                let mut descendants = Vec::new();
+                collect_descendants(value, &mut descendants);
+                for descendant in descendants {
+                    select(descendant, &segment.selector, mode, &mut selected)?;
+                }
+            } else {
+                select(value, &segment.selector, mode, &mut selected)?;
+            }
+        }
+        values = selected;
+    }
+    Ok(values)
+}
+
+fn collect_descendants(value: Value, descendants: &mut Vec<Value>) {
+    descendants.push(value.clone());
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_descendants(item, descendants);
+            }
+        }
+        Value::Object(entries) => {
+            for (_, item) in entries {
+                collect_descendants(item, descendants);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn select(
+    value: Value,
+    selector: &Selector,
+    mode: Mode,
+    selected: &mut Vec<Value>,
+) -> anyhow::Result<()> {
+    match (selector, value) {
+        (Selector::Name(name), Value::Object(entries)) => {
+            match entries.into_iter().find(|(key, _)| key == name) {
+                Some((_, item)) => selected.push(item),
+                None if mode == Mode::Jq => selected.push(Value::Null),
+                None => {}
+            }
+        }
+        (Selector::Name(_), Value::Null) if mode == Mode::Jq => selected.push(Value::Null),
+        (Selector::Name(name), value) if mode == Mode::Jq => {
+            bail!("Cannot index {} with \"{name}\"", type_name(&value))
+        }
+        (Selector::Index(index), Value::Array(items)) => {
+            match resolve_index(*index, items.len()).and_then(|index| items.into_iter().nth(index))
+            {
+                Some(item) => selected.push(item),
+                None if mode == Mode::Jq => selected.push(Value::Null),
+                None => {}
+            }
+        }
+        (Selector::Index(_), Value::Null) if mode == Mode::Jq => selected.push(Value::Null),
+        (Selector::Index(index), value) if mode == Mode::Jq => {
+            bail!("Cannot index {} with number {index}", type_name(&value))
+        }
+        (Selector::Slice { start, end }, Value::Array(items)) => {
+            let length = items.len();
+            let start = start.map_or(0, |start| clamp_index(start, length));
+            let end = end.map_or(length, |end| clamp_index(end, length));
+            let items = items
+                .into_iter()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect::<Vec<_>>();
+            match mode {
+                Mode::JsonPath => selected.extend(items),
+                Mode::Jq => selected.push(Value::Array(items)),
+            }
+        }
+        (Selector::Wildcard, Value::Array(items)) => selected.extend(items),
+        (Selector::Wildcard, Value::Object(entries)) => {
+            selected.extend(entries.into_iter().map(|(_, item)| item));
+        }
+        (Selector::Wildcard, value) if mode == Mode::Jq => {
+            bail!("Cannot iterate over {}", type_name(&value))
+        }
+        (Selector::Union(selectors), value) => {
+            for selector in selectors {
+                select(value.clone(), selector, mode, selected)?;
+            }
+        }
+        (Selector::Filter(predicate), Value::Array(items)) => {
+            for item in items {
+                if matches_predicate(&item, predicate, mode)? {
+                    selected.push(item);
+                }
+            }
+        }
+        (Selector::Filter(predicate), Value::Object(entries)) => {
+            for (_, item) in entries {
+                if matches_predicate(&item, predicate, mode)? {
+                    selected.push(item);
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn resolve_index(index: i64, length: usize) -> Option<usize> {
+    if index < 0 {
+        let offset = usize::try_from(index.unsigned_abs()).ok()?;
+        length.checked_sub(offset)
+    } else {
+        usize::try_from(index).ok()
+    }
+}
+
+fn clamp_index(index: i64, length: usize) -> usize {
+    if index < 0 {
+        usize::try_from(index.unsigned_abs()).map_or(0, |offset| length.saturating_sub(offset))
+    } else {
+        usize::try_from(index).map_or(length, |index| index.min(length))
+    }
+}
+
+fn matches_predicate(value: &Value, predicate: &Predicate, mode: Mode) -> anyhow::Result<bool> {
+    let selected = select_path(vec![value.clone()], &predicate.path, mode)?;
+    let Some((comparison, literal)) = &predicate.comparison else {
+        return Ok(match mode {
+            Mode::JsonPath => !selected.is_empty(),
+            Mode::Jq => selected
+                .iter()
+                .any(|value| !matches!(value, Value::Null | Value::Bool(false))),
+        });
+    };
+
+    Ok(selected.iter().any(|value| {
+        let ordering = compare_values(value, literal);
+        match comparison {
+            Comparison::Equal => ordering == Some(Ordering::Equal),
+            Comparison::NotEqual => ordering != Some(Ordering::Equal),
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessOrEqual => {
+                matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+            }
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterOrEqual => {
+                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+            }
+        }
+    }))
+}
+
+fn compare_values(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => compare_numbers(left, right),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        (left, right) if left == right => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+/// Compares two JSON numbers, exactly when both are integers that fit in an `i128`.
+fn compare_numbers(left: &str, right: &str) -> Option<Ordering> {
+    if let (Ok(left), Ok(right)) = (left.parse::<i128>(), right.parse::<i128>()) {
+        return Some(left.cmp(&right));
+    }
+    left.parse::<f64>()
+        .ok()?
+        .partial_cmp(&right.parse::<f64>().ok()?)
+}
+
+fn apply_jq_stage(values: Vec<Value>, stage: &JqStage) -> anyhow::Result<Vec<Value>> {
+    match stage {
+        JqStage::Path(segments) => select_path(values, segments, Mode::Jq),
+        JqStage::Keys { sorted } => values
+            .into_iter()
+            .map(|value| match value {
+                Value::Object(entries) => {
+                    let mut keys = entries.into_iter().map(|(key, _)| key).collect::<Vec<_>>();
+                    if *sorted {
+                        keys.sort();
+                    }
+                    Ok(Value::Array(keys.into_iter().map(Value::String).collect()))
+                }
+                Value::Array(items) => Ok(Value::Array(
+                    (0..items.len()).map(Value::from_count).collect(),
+                )),
+                value => bail!("{} has no keys", type_name(&value)),
+            })
+            .collect(),
+        JqStage::Length => values
+            .into_iter()
+            .map(|value| match value {
+                Value::Array(items) => Ok(Value::from_count(items.len())),
+                Value::Object(entries) => Ok(Value::from_count(entries.len())),
+                Value::String(text) => Ok(Value::from_count(text.chars().count())),
+                Value::Null => Ok(Value::from_count(0)),
+                Value::Number(number) => Ok(Value::Number(
+                    number.strip_prefix('-').unwrap_or(&number).to_owned(),
+                )),
+                value => bail!("{} has no length", type_name(&value)),
+            })
+            .collect(),
+        JqStage::Select(predicate) => {
+            let mut selected = Vec::new();
+            for value in values {
+                if matches_predicate(&value, predicate, Mode::Jq)? {
+                    selected.push(value);
+                }
+            }
+            Ok(selected)
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A JSON value whose numbers keep the text they were written with, so filtering never rounds
+/// them the way parsing them into floating point numbers would.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    /// The entries in document order. A key that appears twice keeps its last value.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut parser = JsonParser { text, position: 0 };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        if parser.position < text.len() {
+            return Err(parser.error("Trailing characters"));
+        }
+        Ok(value)
+    }
+
+    fn from_count(count: usize) -> Self {
+        Self::Number(count.to_string())
+    }
+
+    /// Prints the value like `serde_json::to_string_pretty`, with numbers as they were written.
+    fn to_pretty_string(&self) -> String {
+        let mut text = String::new();
+        self.write_pretty(0, &mut text);
+        text
+    }
+
+    fn write_pretty(&self, indent: usize, text: &mut String) {
+        fn write_indent(indent: usize, text: &mut String) {
+            text.extend(std::iter::repeat_n("  ", indent));
+        }
+
+        match self {
+            Self::Null => text.push_str("null"),
+            Self::Bool(value) => text.push_str(if *value { "true" } else { "false" }),
+            Self::Number(number) => text.push_str(number),
+            Self::String(value) => write_string(value, text),
+            Self::Array(items) if items.is_empty() => text.push_str("[]"),
+            Self::Array(items) => {
+                text.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    text.push_str(if index == 0 { "\n" } else { ",\n" });
+                    write_indent(indent + 1, text);
+                    item.write_pretty(indent + 1, text);
+                }
+                text.push('\n');
+                write_indent(indent, text);
+                text.push(']');
+            }
+            Self::Object(entries) if entries.is_empty() => text.push_str("{}"),
+            Self::Object(entries) => {
+                text.push('{');
+                for (index, (key, item)) in entries.iter().enumerate() {
+                    text.push_str(if index == 0 { "\n" } else { ",\n" });
+                    write_indent(indent + 1, text);
+                    write_string(key, text);
+                    text.push_str(": ");
+                    item.write_pretty(indent + 1, text);
+                }
+                text.push('\n');
+                write_indent(indent, text);
+                text.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(value: &str, text: &mut String) {
+    text.push_str(&serde_json::to_string(value).expect("strings always serialize"));
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn consume(&mut self, byte: u8) -> bool {
+        let consumed = self.peek() == Some(byte);
+        if consumed {
+            self.position += 1;
+        }
+        consumed
+    }
+
+    fn expect(&mut self, byte: u8) -> anyhow::Result<()> {
+        if self.consume(byte) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", char::from(byte))))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow!("{message} at position {}", self.position)
+    }
+
+    fn parse_value(&mut self, depth: usize) -> anyhow::Result<Value> {
+        if depth > MAX_JSON_DEPTH {
+            return Err(self.error("Nested too deeply"));
+        }
+
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.parse_keyword("null", Value::Null),
+            Some(b't') => self.parse_keyword("true", Value::Bool(true)),
+            Some(b'f') => self.parse_keyword("false", Value::Bool(false)),
+            Some(b'"') => Ok(Value::String(self.parse_string()?)),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.consume(b']') {
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.parse_value(depth + 1)?);
+                    self.skip_whitespace();
+                    if !self.consume(b',') {
+                        self.expect(b']')?;
+                        return Ok(Value::Array(items));
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut entries = Vec::<(String, Value)>::new();
+                self.skip_whitespace();
+                if self.consume(b'}') {
+                    return Ok(Value::Object(entries));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error("Expected a key"));
+                    }
+                    let key = self.parse_string()?;
+                    self.skip_whitespace();
+                    self.expect(b':')?;
+                    let item = self.parse_value(depth + 1)?;
+                    match entries.iter_mut().find(|(entry_key, _)| *entry_key == key) {
+                        Some(entry) => entry.1 = item,
+                        None => entries.push((key, item)),
+                    }
+                    self.skip_whitespace();
+                    if !self.consume(b',') {
+                        self.expect(b'}')?;
+                        return Ok(Value::Object(entries));
+                    }
+                }
+            }
+            _ => Err(self.error("Expected a value")),
+        }
+    }
+
+    fn parse_keyword(&mut self, keyword: &str, value: Value) -> anyhow::Result<Value> {
+        if self
+            .text
+            .get(self.position..)
+            .is_some_and(|rest| rest.starts_with(keyword))
+        {
+            self.position += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error("Expected a value"))
+        }
+    }
+
+    /// Parses a string, leaving its escapes to `serde_json`.
+    fn parse_string(&mut self) -> anyhow::Result<String> {
+        let start = self.position;
+        self.position += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => self.position += 2,
+                Some(_) => self.position += 1,
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+        self.position += 1;
+        let string = self
+            .text
+            .get(start..self.position)
+            .ok_or_else(|| self.error("Invalid string"))?;
+        serde_json::from_str(string).with_context(|| format!("Invalid string at position {start}"))
+    }
+
+    fn parse_number(&mut self) -> anyhow::Result<Value> {
+        let start = self.position;
+        self.consume(b'-');
+        if !self.consume(b'0') && self.skip_digits() == 0 {
+            return Err(self.error("Expected a digit"));
+        }
+        if self.consume(b'.') && self.skip_digits() == 0 {
+            return Err(self.error("Expected a digit"));
+        }
+        if self.consume(b'e') || self.consume(b'E') {
+            if !self.consume(b'+') {
+                self.consume(b'-');
+            }
+            if self.skip_digits() == 0 {
+                return Err(self.error("Expected a digit"));
+            }
+        }
+        let number = self
+            .text
+            .get(start..self.position)
+            .ok_or_else(|| self.error("Invalid number"))?;
+        Ok(Value::Number(number.to_owned()))
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.position;
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.position - start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const PAYLOAD: &str = r#"{
+        "store": {
+            "books": [
+                { "title": "Sayings", "price": 8.95, "tags": ["classic"] },
+                { "title": "Sword", "price": 12.99, "isbn": "0-553" },
+                { "title": "Moby Dick", "price": 8.99, "isbn": "0-395" }
+            ],
+            "bicycle": { "color": "red", "price": 19.95 }
+        }
+    }"#;
+
+    fn apply(expression: &str) -> String {
+        ResponseFilter::parse(expression)
+            .unwrap()
+            .apply(PAYLOAD)
+            .unwrap()
+    }
+
+    fn compact(expression: &str) -> String {
+        let value: serde_json::Value = serde_json::from_str(&apply(expression)).unwrap();
+        value.to_string()
+    }
+
+    #[test]
+    fn test_json_path() {
+        assert_eq!(compact("$.store.bicycle.color"), r#"["red"]"#);
+        assert_eq!(compact("$['store']['bicycle']['color']"), r#"["red"]"#);
+        assert_eq!(compact("$.store.books[0].title"), r#"["Sayings"]"#);
+        assert_eq!(compact("$.store.books[-1].title"), r#"["Moby Dick"]"#);
+        assert_eq!(
+            compact("$.store.books[*].title"),
+            r#"["Sayings","Sword","Moby Dick"]"#
+        );
+        assert_eq!(compact("$.store.books[0:2].price"), "[8.95,12.99]");
+        assert_eq!(compact("$.store.books[0,2].price"), "[8.95,8.99]");
+        assert_eq!(compact("$..price"), "[8.95,12.99,8.99,19.95]");
+        assert_eq!(compact("$.store.bicycle.*"), r#"["red",19.95]"#);
+        assert_eq!(compact("$.store.missing"), "[]");
+    }
+
+    #[test]
+    fn test_json_path_filters() {
+        assert_eq!(
+            compact("$.store.books[?(@.price < 9)].title"),
+            r#"["Sayings","Moby Dick"]"#
+        );
+        assert_eq!(
+            compact("$.store.books[?(@.isbn)].title"),
+            r#"["Sword","Moby Dick"]"#
+        );
+        assert_eq!(compact("$..books[?(@.title == 'Sword')].price"), "[12.99]");
+    }
+
+    #[test]
+    fn test_jq() {
+        assert_eq!(apply(".store.bicycle.color"), r#""red""#);
+        assert_eq!(apply(".store.books[1].isbn"), r#""0-553""#);
+        assert_eq!(apply(".store.books[0].isbn"), "null");
+        assert_eq!(
+            apply(".store.books[].title"),
+            "\"Sayings\"\n\"Sword\"\n\"Moby Dick\""
+        );
+        assert_eq!(apply(".store.books | length"), "3");
+        assert_eq!(
+            apply(".store.bicycle | keys"),
+            "[\n  \"color\",\n  \"price\"\n]"
+        );
+        assert_eq!(
+            apply(".store.books[] | select(.price > 10) | .title"),
+            r#""Sword""#
+        );
+        assert_eq!(apply(r#".["store"].bicycle.price"#), "19.95");
+        assert_eq!(
+            compact("."),
+            serde_json::from_str::<serde_json::Value>(PAYLOAD)
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        ResponseFilter::parse("$.store[").unwrap_err();
+        ResponseFilter::parse("$.store]").unwrap_err();
+        ResponseFilter::parse("store").unwrap_err();
+        ResponseFilter::parse(".store |").unwrap_err();
+        ResponseFilter::parse(".store.bicycle.color[0]")
+            .unwrap()
+            .apply(PAYLOAD)
+            .unwrap_err();
+        ResponseFilter::parse("$.store")
+            .unwrap()
+            .apply("not json")
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let payload = r#"{
+            "id": 123456789012345678901234567890,
+            "ids": [9007199254740993, 9007199254740992, 1.5e300],
+            "balance": -18446744073709551617
+        }"#;
+        let apply = |expression: &str| {
+            ResponseFilter::parse(expression)
+                .unwrap()
+                .apply(payload)
+                .unwrap()
+        };
+
+        assert_eq!(apply(".id"), "123456789012345678901234567890");
+        assert_eq!(apply("$.ids[0]"), "[\n  9007199254740993\n]");
+        assert_eq!(
+            apply(".ids[] | select(. > 9007199254740992)"),
+            "9007199254740993\n1.5e300"
+        );
+        assert_eq!(apply(".balance | length"), "18446744073709551617");
+        assert_eq!(
+            apply("."),
+            concat!(
+                "{\n",
+                "  \"id\": 123456789012345678901234567890,\n",
+                "  \"ids\": [\n",
+                "    9007199254740993,\n",
+                "    9007199254740992,\n",
+                "    1.5e300\n",
+                "  ],\n",
+                "  \"balance\": -18446744073709551617\n",
+                "}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        for payload in [
+            "",
+            "{",
+            "[1,]",
+            "01",
+            "1.",
+            "-",
+            "{\"a\" 1}",
+            "\"\\x\"",
+            "[] []",
+        ] {
+            ResponseFilter::parse(".")
+                .unwrap()
+                .apply(payload)
+                .expect_err(payload);
+        }
+        assert_eq!(
+            ResponseFilter::parse(".a")
+                .unwrap()
+                .apply(r#"{ "a": 1, "b": "\u00e9\n", "a": 2 }"#)
+                .unwrap(),
+            "2"
+        );
+    }
+}
@@ -0,0 +1,750 @@
+use anyhow::Context as _;
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+use gpui::SharedString;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use db::{
+    Bind, Column, Row, Statement, StaticColumnCount, ThreadSafeConnection, query,
+    sql::domain::Domain, sql_macros::sql,
+};
+use http_client::StatusCode;
+use settings::{RegisterSetting, Settings, SettingsContent};
+
+use crate::{ResponseCookie, ResponseHeader, ResponseState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RegisterSetting)]
+pub(crate) struct ResponseHistorySettings {
+    pub(crate) enabled: bool,
+    pub(crate) max_entries: u32,
+}
+
+impl Settings for ResponseHistorySettings {
+    fn from_settings(content: &SettingsContent) -> Self {
+        let response_history = content.response_history.as_ref();
+
+        Self {
+            enabled: response_history
+                .and_then(|response_history| response_history.enabled)
+                .expect("response history enabled should be defaulted"),
+            max_entries: response_history
+                .and_then(|response_history| response_history.max_entries)
+                .expect("response history max entries should be defaulted"),
+        }
+    }
+}
+
+/// The request exactly as it went over the wire, after parameters were
+/// appended to the URL and disabled rows were dropped, with the values of
+/// secrets masked.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// The dynamic variables the request used, like `$uuid`, and the values they had.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<(String, String)>,
+}
+
+/// What the history tab lists for a response. The rest of it, like its body, is loaded when the
+/// response is shown or compared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseHistorySummary {
+    pub(crate) id: i64,
+    pub(crate) sent_at: Timestamp,
+    pub(crate) request: SentRequest,
+    pub(crate) status_code: Option<StatusCode>,
+    pub(crate) bytes_received: u64,
+    pub(crate) elapsed_duration: Duration,
+}
+
+impl ResponseHistorySummary {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn sent_at(&self) -> Timestamp {
+        self.sent_at
+    }
+
+    pub fn request(&self) -> &SentRequest {
+        &self.request
+    }
+
+    pub fn status_code(&self) -> Option<StatusCode> {
+        self.status_code
+    }
+
+    pub fn elapsed_duration(&self) -> Duration {
+        self.elapsed_duration
+    }
+
+    pub(crate) fn state(&self) -> ResponseState {
+        response_state(self.status_code, self.bytes_received, self.elapsed_duration)
+    }
+
+    pub fn status_text(&self) -> SharedString {
+        status_text(self.status_code)
+    }
+}
+
+impl Column for ResponseHistorySummary {
+    fn column(row: &mut Row<'_, '_>, start_index: i32) -> anyhow::Result<(Self, i32)> {
+        let (id, next_index) = i64::column(row, start_index)?;
+        let (sent_at, next_index) = i64::column(row, next_index)?;
+        let (request, next_index) = String::column(row, next_index)?;
+        let (status_code, next_index) = Option::<u16>::column(row, next_index)?;
+        let (bytes_received, next_index) = u64::column(row, next_index)?;
+        let (elapsed_millis, next_index) = u64::column(row, next_index)?;
+
+        Ok((
+            Self {
+                id,
+                sent_at: Timestamp::from_second(sent_at)?,
+                request: serde_json::from_str(&request)?,
+                status_code: status_code
+                    .map(StatusCode::from_u16)
+                    .transpose()
+                    .context("invalid status code in response history")?,
+                bytes_received,
+                elapsed_duration: Duration::from_millis(elapsed_millis),
+            },
+            next_index,
+        ))
+    }
+}
+
+#[derive(Clone)]
+pub struct ResponseHistoryEntry {
+    pub(crate) id: i64,
+    pub(crate) sent_at: Timestamp,
+    pub(crate) request: SentRequest,
+    pub(crate) status_code: Option<StatusCode>,
+    pub(crate) bytes_received: u64,
+    pub(crate) elapsed_duration: Duration,
+    pub(crate) headers: Vec<ResponseHeader>,
+    pub(crate) cookies: Vec<ResponseCookie>,
+    pub(crate) body: String,
+    pub(crate) language_name: Option<SharedString>,
+}
+
+impl ResponseHistoryEntry {
+    pub fn sent_at(&self) -> Timestamp {
+        self.sent_at
+    }
+
+    pub fn request(&self) -> &SentRequest {
+        &self.request
+    }
+
+    pub fn status_code(&self) -> Option<StatusCode> {
+        self.status_code
+    }
+
+    pub fn elapsed_duration(&self) -> Duration {
+        self.elapsed_duration
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub(crate) fn state(&self) -> ResponseState {
+        response_state(self.status_code, self.bytes_received, self.elapsed_duration)
+    }
+
+    pub fn status_text(&self) -> SharedString {
+        status_text(self.status_code)
+    }
+
+    /// Renders the request line, status, headers and body as plain text so
+    /// two entries can be compared line by line.
+    pub(crate) fn diff_text(&self) -> String {
+        let headers = self
+            .headers
+            .iter()
+            .map(|header| format!("{}: {}\n", header.name, header.value))
+            .collect::<String>();
+        let body_line_ending = if self.body.ends_with('\n') { "" } else { "\n" };
+
+        format!(
+            "{} {}\n\n{}\n{headers}\n{}{body_line_ending}",
+            self.request.method,
+            self.request.url,
+            self.status_text(),
+            self.body,
+        )
+    }
+}
+
+fn response_state(
+    status_code: Option<StatusCode>,
+    bytes_received: u64,
+    elapsed_duration: Duration,
+) -> ResponseState {
+    match status_code {
+        Some(status_code) => ResponseState::Completed {
+            status_code,
+            bytes_received,
+            elapsed_duration,
+        },
+        None => ResponseState::Error {
+            bytes_received,
+            elapsed_duration,
+        },
+    }
+}
+
+fn status_text(status_code: Option<StatusCode>) -> SharedString {
+    match status_code {
+        Some(status_code) => match status_code.canonical_reason() {
+            Some(reason_phrase) => format!("{} {reason_phrase}", status_code.as_u16()).into(),
+            None => status_code.as_u16().to_string().into(),
+        },
+        None => "Error".into(),
+    }
+}
+
+#[cfg(any(test, feature = "test"))]
+impl ResponseHistoryEntry {
+    pub fn test(
+        request: SentRequest,
+        status_code: Option<StatusCode>,
+        elapsed_duration: Duration,
+        body: &str,
+    ) -> Self {
+        Self {
+            id: 0,
+            sent_at: Timestamp::UNIX_EPOCH,
+            request,
+            status_code,
+            bytes_received: u64::try_from(body.len()).unwrap_or(u64::MAX),
+            elapsed_duration,
+            headers: Vec::new(),
+            cookies: Vec::new(),
+            body: body.to_string(),
+            language_name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffLineKind {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffLine {
+    pub(crate) kind: DiffLineKind,
+    pub(crate) text: SharedString,
+}
+
+pub(crate) fn diff_lines(old_text: &str, new_text: &str) -> Vec<DiffLine> {
+    fn push_lines(lines: &mut Vec<DiffLine>, kind: DiffLineKind, text: &str) {
+        lines.extend(text.split_inclusive('\n').map(|line| DiffLine {
+            kind,
+            text: line.strip_suffix('\n').unwrap_or(line).to_string().into(),
+        }));
+    }
+
+    let mut lines = Vec::new();
+    let mut old_offset = 0;
+    for (old_range, new_text) in language::text_diff(old_text, new_text) {
+        push_lines(
+            &mut lines,
+            DiffLineKind::Unchanged,
+            old_text
+                .get(old_offset..old_range.start)
+                .expect("diff range should be valid"),
+        );
+        push_lines(
+            &mut lines,
+            DiffLineKind::Removed,
+            old_text
+                .get(old_range.clone())
+                .expect("diff range should be valid"),
+        );
+        push_lines(&mut lines, DiffLineKind::Added, &new_text);
+        old_offset = old_range.end;
+    }
+    push_lines(
+        &mut lines,
+        DiffLineKind::Unchanged,
+        old_text
+            .get(old_offset..)
+            .expect("diff range should be valid"),
+    );
+
+    lines
+}
+
+fn compress_body(body: &str) -> anyhow::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(body.as_bytes())
+        .context("failed to compress response body")?;
+    encoder.finish().context("failed to compress response body")
+}
+
+fn decompress_body(compressed: &[u8]) -> anyhow::Result<String> {
+    let mut body = String::new();
+    DeflateDecoder::new(compressed)
+        .read_to_string(&mut body)
+        .context("failed to decompress response body")?;
+    Ok(body)
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedResponseCookie {
+    name: String,
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_age: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secure: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    http_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    same_site: Option<String>,
+}
+
+impl From<&ResponseCookie> for SerializedResponseCookie {
+    fn from(cookie: &ResponseCookie) -> Self {
+        Self {
+            name: cookie.name.to_string(),
+            value: cookie.value.to_string(),
+            domain: cookie.domain.as_ref().map(ToString::to_string),
+            path: cookie.path.as_ref().map(ToString::to_string),
+            expires: cookie.expires.as_ref().map(ToString::to_string),
+            max_age: cookie.max_age.as_ref().map(ToString::to_string),
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+            same_site: cookie.same_site.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+impl From<SerializedResponseCookie> for ResponseCookie {
+    fn from(cookie: SerializedResponseCookie) -> Self {
+        ResponseCookie::new(cookie.name, cookie.value)
+            .domain(cookie.domain)
+            .path(cookie.path)
+            .expires(cookie.expires)
+            .max_age(cookie.max_age)
+            .secure(cookie.secure)
+            .http_only(cookie.http_only)
+            .same_site(cookie.same_site)
+    }
+}
+
+/// A history entry as it is stored in the database, with its body compressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerializedResponseHistoryEntry {
+    id: i64,
+    sent_at: i64,
+    request: String,
+    status_code: Option<u16>,
+    bytes_received: u64,
+    elapsed_millis: u64,
+    headers: String,
+    cookies: String,
+    body: Vec<u8>,
+    language_name: Option<String>,
+}
+
+impl SerializedResponseHistoryEntry {
+    pub fn from_entry(entry: &ResponseHistoryEntry) -> anyhow::Result<Self> {
+        let headers = entry
+            .headers
+            .iter()
+            .map(|header| (header.name.to_string(), header.value.to_string()))
+            .collect::<Vec<_>>();
+        let cookies = entry
+            .cookies
+            .iter()
+            .map(SerializedResponseCookie::from)
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            id: entry.id,
+            sent_at: entry.sent_at.as_second(),
+            request: serde_json::to_string(&entry.request)?,
+            status_code: entry.status_code.map(|status_code| status_code.as_u16()),
+            bytes_received: entry.bytes_received,
+            elapsed_millis: u64::try_from(entry.elapsed_duration.as_millis()).unwrap_or(u64::MAX),
+            headers: serde_json::to_string(&headers)?,
+            cookies: serde_json::to_string(&cookies)?,
+            body: compress_body(&entry.body)?,
+            language_name: entry.language_name.as_ref().map(ToString::to_string),
+        })
+    }
+
+    pub fn into_entry(self) -> anyhow::Result<ResponseHistoryEntry> {
+        let headers: Vec<(String, String)> = serde_json::from_str(&self.headers)?;
+        let cookies: Vec<SerializedResponseCookie> = serde_json::from_str(&self.cookies)?;
+        let status_code = self
+            .status_code
+            .map(StatusCode::from_u16)
+            .transpose()
+            .context("invalid status code in response history")?;
+
+        Ok(ResponseHistoryEntry {
+            id: self.id,
+            sent_at: Timestamp::from_second(self.sent_at)?,
+            request: serde_json::from_str(&self.request)?,
+            status_code,
+            bytes_received: self.bytes_received,
+            elapsed_duration: Duration::from_millis(self.elapsed_millis),
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| ResponseHeader::new(name, value))
+                .collect(),
+            cookies: cookies.into_iter().map(ResponseCookie::from).collect(),
+            body: decompress_body(&self.body)?,
+            language_name: self.language_name.map(SharedString::from),
+        })
+    }
+}
+
+impl StaticColumnCount for SerializedResponseHistoryEntry {
+    fn column_count() -> usize {
+        9
+    }
+}
+
+impl Bind for SerializedResponseHistoryEntry {
+    fn bind(&self, statement: &Statement<'_>, start_index: i32) -> anyhow::Result<i32> {
+        let next_index = statement.bind(&self.sent_at, start_index)?;
+        let next_index = statement.bind(&self.request, next_index)?;
+        let next_index = statement.bind(&self.status_code, next_index)?;
+        let next_index = statement.bind(&self.bytes_received, next_index)?;
+        let next_index = statement.bind(&self.elapsed_millis, next_index)?;
+        let next_index = statement.bind(&self.headers, next_index)?;
+        let next_index = statement.bind(&self.cookies, next_index)?;
+        let next_index = statement.bind(&self.body, next_index)?;
+        statement.bind(&self.language_name, next_index)
+    }
+}
+
+impl Column for SerializedResponseHistoryEntry {
+    fn column(row: &mut Row<'_, '_>, start_index: i32) -> anyhow::Result<(Self, i32)> {
+        let (id, next_index) = i64::column(row, start_index)?;
+        let (sent_at, next_index) = i64::column(row, next_index)?;
+        let (request, next_index) = String::column(row, next_index)?;
+        let (status_code, next_index) = Option::<u16>::column(row, next_index)?;
+        let (bytes_received, next_index) = u64::column(row, next_index)?;
+        let (elapsed_millis, next_index) = u64::column(row, next_index)?;
+        let (headers, next_index) = String::column(row, next_index)?;
+        let (cookies, next_index) = String::column(row, next_index)?;
+        let (body, next_index) = Vec::<u8>::column(row, next_index)?;
+        let (language_name, next_index) = Option::<String>::column(row, next_index)?;
+
+        Ok((
+            Self {
+                id,
+                sent_at,
+                request,
+                status_code,
+                bytes_received,
+                elapsed_millis,
+                headers,
+                cookies,
+                body,
+                language_name,
+            },
+            next_index,
+        ))
+    }
+}
+
+pub(crate) struct ResponseHistoryDb(ThreadSafeConnection);
+
+impl ResponseHistoryDb {
+    pub(crate) fn load_response_history_entry(
+        &self,
+        id: i64,
+    ) -> anyhow::Result<Option<ResponseHistoryEntry>> {
+        self.response_history_entry(id)?
+            .map(SerializedResponseHistoryEntry::into_entry)
+            .transpose()
+    }
+
+    query! {
+        pub(crate) fn load_response_history(
+            path: &Path,
+        ) -> anyhow::Result<Vec<ResponseHistorySummary>> {
+            SELECT id, sent_at, request, status_code, bytes_received, elapsed_millis
+            FROM response_history
+            WHERE path = ?
+            ORDER BY id DESC
+        }
+    }
+
+    query! {
+        fn response_history_entry(
+            id: i64,
+        ) -> anyhow::Result<Option<SerializedResponseHistoryEntry>> {
+            SELECT
+                id,
+                sent_at,
+                request,
+                status_code,
+                bytes_received,
+                elapsed_millis,
+                headers,
+                cookies,
+                body,
+                language_name
+            FROM response_history
+            WHERE id = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_response_history_entry(
+            path: PathBuf,
+            entry: SerializedResponseHistoryEntry,
+            max_entries: u32,
+        ) -> anyhow::Result<()> {
+            INSERT INTO response_history(
+                path,
+                sent_at,
+                request,
+                status_code,
+                bytes_received,
+                elapsed_millis,
+                headers,
+                cookies,
+                body,
+                language_name
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10);
+            DELETE FROM response_history
+            WHERE path = ?1 AND id NOT IN (
+                SELECT id
+                FROM response_history
+                WHERE path = ?1
+                ORDER BY id DESC
+                LIMIT ?11
+            )
+        }
+    }
+
+    query! {
+        pub(crate) async fn move_response_history(
+            old_path: PathBuf,
+            new_path: PathBuf,
+        ) -> anyhow::Result<()> {
+            UPDATE response_history
+            SET path = ?2
+            WHERE path = ?1
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_response_history(path: PathBuf) -> anyhow::Result<()> {
+            DELETE FROM response_history
+            WHERE path = ?
+        }
+    }
+}
+
+impl Domain for ResponseHistoryDb {
+    const NAME: &str = stringify!(ResponseHistoryDb);
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE IF NOT EXISTS response_history(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path BLOB NOT NULL,
+            sent_at INTEGER NOT NULL,
+            request TEXT NOT NULL,
+            status_code INTEGER,
+            bytes_received INTEGER NOT NULL,
+            elapsed_millis INTEGER NOT NULL,
+            headers TEXT NOT NULL,
+            cookies TEXT NOT NULL,
+            body BLOB NOT NULL,
+            language_name TEXT
+        ) STRICT;
+
+        CREATE INDEX IF NOT EXISTS response_history_path ON response_history(path);
+    )];
+}
+
+db::static_connection!(ResponseHistoryDb, []);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn entry(status_code: u16, body: &str) -> ResponseHistoryEntry {
+        ResponseHistoryEntry {
+            id: 0,
+            sent_at: Timestamp::from_second(1_700_000_000).unwrap(),
+            request: SentRequest {
+                method: "GET".to_string(),
+                url: "https://example.com/users?page=1".to_string(),
+                headers: vec![("Accept".to_string(), "application/json".to_string())],
+                body: None,
+                variables: Vec::new(),
+            },
+            status_code: Some(StatusCode::from_u16(status_code).unwrap()),
+            bytes_received: u64::try_from(body.len()).unwrap(),
+            elapsed_duration: Duration::from_millis(120),
+            headers: vec![ResponseHeader::new("content-type", "application/json")],
+            cookies: vec![
+                ResponseCookie::new("session", "abc")
+                    .path(Some("/"))
+                    .http_only(Some(true)),
+            ],
+            body: body.to_string(),
+            language_name: Some("JSON".into()),
+        }
+    }
+
+    #[test]
+    fn test_body_compression_round_trip() {
+        let body = "{\"id\": 1}\n".repeat(1000);
+        let compressed = compress_body(&body).unwrap();
+
+        assert!(compressed.len() < body.len());
+        assert_eq!(decompress_body(&compressed).unwrap(), body);
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = diff_lines("a\nb\nc\n", "a\nB\nc\nd\n");
+
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| (line.kind, line.text.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (DiffLineKind::Unchanged, "a".to_string()),
+                (DiffLineKind::Removed, "b".to_string()),
+                (DiffLineKind::Added, "B".to_string()),
+                (DiffLineKind::Unchanged, "c".to_string()),
+                (DiffLineKind::Added, "d".to_string()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_response_history_is_recorded() {
+        let db = ResponseHistoryDb::test_open("test_response_history_is_recorded").await;
+        let path = PathBuf::from("/project/users.toml");
+
+        assert!(db.load_response_history(&path).unwrap().is_empty());
+
+        let first = entry(200, "{\"id\": 1}");
+        db.save_response_history_entry(
+            path.clone(),
+            SerializedResponseHistoryEntry::from_entry(&first).unwrap(),
+            10,
+        )
+        .await
+        .unwrap();
+        db.save_response_history_entry(
+            path.clone(),
+            SerializedResponseHistoryEntry::from_entry(&entry(404, "not found")).unwrap(),
+            10,
+        )
+        .await
+        .unwrap();
+
+        let history = db.load_response_history(&path).unwrap();
+        assert_eq!(history.len(), 2);
+
+        let [newest, oldest] = history.as_slice() else {
+            panic!("expected two history entries");
+        };
+        assert_eq!(newest.status_code, Some(StatusCode::NOT_FOUND));
+        assert_eq!(oldest.status_code, Some(StatusCode::OK));
+        assert_eq!(oldest.request, first.request);
+        assert_eq!(oldest.bytes_received, first.bytes_received);
+
+        let newest = db.load_response_history_entry(newest.id).unwrap().unwrap();
+        assert_eq!(newest.body, "not found");
+        let oldest = db.load_response_history_entry(oldest.id).unwrap().unwrap();
+        assert_eq!(oldest.status_code, Some(StatusCode::OK));
+        assert_eq!(oldest.body, first.body);
+        assert_eq!(oldest.request, first.request);
+        assert_eq!(oldest.sent_at, first.sent_at);
+        assert_eq!(oldest.elapsed_duration, first.elapsed_duration);
+        assert_eq!(oldest.headers.len(), 1);
+        assert_eq!(oldest.cookies.len(), 1);
+        assert_eq!(oldest.cookies[0].http_only, Some(true));
+        assert_eq!(oldest.language_name, Some("JSON".into()));
+    }
+
+    #[gpui::test]
+    async fn test_response_history_retention() {
+        let db = ResponseHistoryDb::test_open("test_response_history_retention").await;
+        let path = PathBuf::from("/project/users.toml");
+        let other_path = PathBuf::from("/project/orders.toml");
+
+        db.save_response_history_entry(
+            other_path.clone(),
+            SerializedResponseHistoryEntry::from_entry(&entry(200, "orders")).unwrap(),
+            3,
+        )
+        .await
+        .unwrap();
+        for index in 0..5 {
+            db.save_response_history_entry(
+                path.clone(),
+                SerializedResponseHistoryEntry::from_entry(&entry(200, &index.to_string()))
+                    .unwrap(),
+                3,
+            )
+            .await
+            .unwrap();
+        }
+
+        let bodies = db
+            .load_response_history(&path)
+            .unwrap()
+            .iter()
+            .map(|entry| {
+                db.load_response_history_entry(entry.id)
+                    .unwrap()
+                    .unwrap()
+                    .body
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(bodies, vec!["4", "3", "2"]);
+        assert!(db.load_response_history_entry(i64::MAX).unwrap().is_none());
+        assert_eq!(db.load_response_history(&other_path).unwrap().len(), 1);
+
+        let renamed_path = PathBuf::from("/project/people.toml");
+        db.move_response_history(path.clone(), renamed_path.clone())
+            .await
+            .unwrap();
+        assert!(db.load_response_history(&path).unwrap().is_empty());
+        assert_eq!(db.load_response_history(&renamed_path).unwrap().len(), 3);
+
+        db.delete_response_history(renamed_path.clone())
+            .await
+            .unwrap();
+        assert!(db.load_response_history(&renamed_path).unwrap().is_empty());
+    }
+}
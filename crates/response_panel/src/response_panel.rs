@@ -1,25 +1,52 @@
+mod benchmark;
+mod filter;
+mod history;
+
 use gpui::{
     Action, AnyElement, App, Context, DefiniteLength, ElementId, Entity, FocusHandle, Focusable,
-    FontWeight, ListAlignment, ListState, Pixels, Render, SharedString, Subscription, Window,
-    prelude::*,
+    FontWeight, ListAlignment, ListState, Pixels, Render, SharedString, Subscription, Task,
+    UniformListScrollHandle, Window, prelude::*,
 };
+use jiff::{Timestamp, tz::TimeZone};
 use num_traits::ToPrimitive;
-use std::{rc::Rc, sync::Arc, time::Duration};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
 
-use editor::Editor;
+use db::kv::KeyValueStore;
+use editor::{Diagnostic, DiagnosticSeverity, Editor};
+use fs::Fs as _;
 use http_client::StatusCode;
-use language::{Buffer, Language, PLAIN_TEXT};
-use multi_buffer::MultiBuffer;
+use input::{ErasedEditorEvent, InputField};
+use json_schema::{JsonSchema, SourceError};
+use language::{Buffer, Language, PLAIN_TEXT, TextFormat};
+use multi_buffer::{MultiBuffer, MultiBufferOffset};
+use settings::Settings;
 use theme::ActiveTheme;
 use ui::{
-    Color, ColumnWidthConfig, DynamicSpacing, IconAsset, Indicator, KeyBinding, LineHeightStyle,
-    ScrollAxes, Scrollbars, SelectableText, SelectableTextGroup, Table, TableCell,
-    TableInteractionState, Text, TextCommon, TextInteractionState, TextSize,
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, ColumnWidthConfig,
+    Disableable, DynamicSpacing, IconAsset, Indicator, KeyBinding, LineHeightStyle, ScrollAxes,
+    Scrollbars, SelectableText, SelectableTextGroup, Table, TableCell, TableInteractionState, Text,
+    TextCommon, TextInteractionState, TextSize, ToggleState, WithScrollbar,
+};
+use workspace::{AppState, Panel, Workspace};
+
+use benchmark::LatencySummary;
+use filter::ResponseFilter;
+use history::{DiffLine, DiffLineKind, ResponseHistoryDb, ResponseHistorySettings};
+
+pub use benchmark::{BenchmarkReport, BenchmarkSample};
+pub use history::{
+    ResponseHistoryEntry, ResponseHistorySummary, SentRequest, SerializedResponseHistoryEntry,
 };
-use workspace::{Panel, Workspace};
 
 const NAME_COLUMN_INDEX: usize = 0;
 const VALUE_COLUMN_INDEX: usize = 1;
+const RESPONSE_FILTER_NAMESPACE: &str = "response_filter";
 
 pub fn init(cx: &mut App) {
     cx.observe_new(
@@ -81,11 +108,37 @@ fn format_elapsed_duration(elapsed_duration: Duration) -> SharedString {
     .into()
 }
 
+/// The color a response with `status_code` is shown in.
+pub fn status_color(status_code: StatusCode) -> Color {
+    if status_code.is_informational() {
+        Color::Info
+    } else if status_code.is_success() {
+        Color::Success
+    } else if status_code.is_redirection() {
+        Color::Warning
+    } else if status_code.is_client_error() || status_code.is_server_error() {
+        Color::Error
+    } else {
+        Color::Muted
+    }
+}
+
+fn status_text(status_code: StatusCode) -> SharedString {
+    if let Some(reason_phrase) = status_code.canonical_reason() {
+        format!("{} {reason_phrase}", status_code.as_u16()).into()
+    } else {
+        status_code.as_u16().to_string().into()
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ResponsePanelTab {
     Body,
     Headers,
     Cookies,
+    History,
+    Benchmark,
+    Console,
 }
 
 #[derive(Clone)]
@@ -117,6 +170,14 @@ impl ResponseHeader {
             value: value.into(),
         }
     }
+
+    pub fn name(&self) -> &SharedString {
+        &self.name
+    }
+
+    pub fn value(&self) -> &SharedString {
+        &self.value
+    }
 }
 
 #[derive(Clone)]
@@ -196,6 +257,78 @@ struct CookieTableRow {
     value: SharedString,
 }
 
+/// One of the attempts at sending a request that was retried.
+#[derive(Clone)]
+pub struct ResponseAttempt {
+    outcome: Result<StatusCode, SharedString>,
+    elapsed_duration: Duration,
+    retry_delay: Option<Duration>,
+}
+
+impl ResponseAttempt {
+    pub fn completed(status_code: StatusCode, elapsed_duration: Duration) -> Self {
+        Self {
+            outcome: Ok(status_code),
+            elapsed_duration,
+            retry_delay: None,
+        }
+    }
+
+    pub fn failed(error: impl Into<SharedString>, elapsed_duration: Duration) -> Self {
+        Self {
+            outcome: Err(error.into()),
+            elapsed_duration,
+            retry_delay: None,
+        }
+    }
+
+    /// How long the request waited after this attempt before being sent again.
+    pub fn retry_delay(mut self, retry_delay: Option<Duration>) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    pub fn status_code(&self) -> Option<StatusCode> {
+        self.outcome.as_ref().ok().copied()
+    }
+}
+
+/// A line written by the scripts of the request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResponseConsoleEntry {
+    Log(SharedString),
+    Assertion { message: SharedString, passed: bool },
+    Error(SharedString),
+}
+
+#[derive(Clone)]
+struct Benchmark {
+    report: BenchmarkReport,
+    running: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BenchmarkExportFormat {
+    Json,
+    Csv,
+}
+
+impl BenchmarkExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// The outcome of validating a response body against the JSON Schema configured for its status.
+#[derive(Clone)]
+struct SchemaValidation {
+    path: SharedString,
+    result: Result<Vec<SourceError>, SharedString>,
+}
+
 #[derive(Clone, Default)]
 pub enum ResponseState {
     #[default]
@@ -204,6 +337,12 @@ pub enum ResponseState {
         bytes_received: u64,
         elapsed_duration: Duration,
     },
+    /// Counting down to sending the request again after a failed attempt.
+    Waiting {
+        attempt: u32,
+        remaining_duration: Duration,
+        elapsed_duration: Duration,
+    },
     Completed {
         status_code: StatusCode,
         bytes_received: u64,
@@ -217,20 +356,6 @@ pub enum ResponseState {
 
 impl ResponseState {
     fn summary(&self) -> Option<ResponseSummary> {
-        let status_color = |status_code: StatusCode| {
-            if status_code.is_informational() {
-                Color::Info
-            } else if status_code.is_success() {
-                Color::Success
-            } else if status_code.is_redirection() {
-                Color::Warning
-            } else if status_code.is_client_error() || status_code.is_server_error() {
-                Color::Error
-            } else {
-                Color::Muted
-            }
-        };
-
         match self {
             ResponseState::Idle => None,
             ResponseState::Fetching {
@@ -243,25 +368,32 @@ impl ResponseState {
                 elapsed_duration: format_elapsed_duration(*elapsed_duration),
                 bytes_received: format_bytes_received(*bytes_received),
             }),
+            ResponseState::Waiting {
+                attempt,
+                remaining_duration,
+                elapsed_duration,
+            } => Some(ResponseSummary {
+                text: format!(
+                    "Attempt {attempt} in {} s",
+                    remaining_duration.as_secs() + u64::from(remaining_duration.subsec_nanos() > 0)
+                )
+                .into(),
+                color: Color::Warning,
+                selectable: false,
+                elapsed_duration: format_elapsed_duration(*elapsed_duration),
+                bytes_received: format_bytes_received(0),
+            }),
             ResponseState::Completed {
                 status_code,
                 bytes_received,
                 elapsed_duration,
-            } => {
-                let text = if let Some(reason_phrase) = status_code.canonical_reason() {
-                    format!("{} {reason_phrase}", status_code.as_u16()).into()
-                } else {
-                    status_code.as_u16().to_string().into()
-                };
-
-                Some(ResponseSummary {
-                    text,
-                    color: status_color(*status_code),
-                    selectable: true,
-                    elapsed_duration: format_elapsed_duration(*elapsed_duration),
-                    bytes_received: format_bytes_received(*bytes_received),
-                })
-            }
+            } => Some(ResponseSummary {
+                text: status_text(*status_code),
+                color: status_color(*status_code),
+                selectable: true,
+                elapsed_duration: format_elapsed_duration(*elapsed_duration),
+                bytes_received: format_bytes_received(*bytes_received),
+            }),
             ResponseState::Error {
                 bytes_received,
                 elapsed_duration,
@@ -281,6 +413,15 @@ pub struct Response {
     state: ResponseState,
     editor: Entity<Editor>,
     payload: Entity<MultiBuffer>,
+    filter_field: Entity<InputField>,
+    formatted_editor: Entity<Editor>,
+    formatted_payload: Entity<MultiBuffer>,
+    show_formatted: bool,
+    pretty: bool,
+    filter_error: Option<SharedString>,
+    schema_validation: Option<SchemaValidation>,
+    attempts: Vec<ResponseAttempt>,
+    console: Vec<ResponseConsoleEntry>,
     summary_text: Entity<TextInteractionState<ResponseSummaryTextId>>,
     headers: Vec<ResponseHeader>,
     headers_table: Entity<TableInteractionState>,
@@ -288,11 +429,45 @@ pub struct Response {
     cookies: Vec<ResponseCookie>,
     cookies_table: Entity<TableInteractionState>,
     cookies_list_state: ListState,
+    history_path: Option<PathBuf>,
+    history: Arc<[ResponseHistorySummary]>,
+    shown_history_entry: Option<i64>,
+    compared_history_entries: Vec<i64>,
+    history_diff: Option<Rc<[DiffLine]>>,
+    history_scroll_handle: UniformListScrollHandle,
+    history_entry_task: Task<()>,
+    history_diff_task: Task<()>,
+    benchmark: Option<Benchmark>,
+    _filter_subscription: Subscription,
 }
 
 impl Response {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         let (editor, payload) = Self::new_editor(window, cx);
+        let (formatted_editor, formatted_payload) = Self::new_editor(window, cx);
+        let filter_field = cx.new(|cx| {
+            InputField::new(
+                window,
+                cx,
+                "Filter with JSONPath ($.items[0]) or jq (.items[0])",
+            )
+            .start_icon(IconAsset::ListSearch)
+        });
+        let response = cx.weak_entity();
+        let filter_subscription = filter_field.read(cx).editor().clone().subscribe(
+            Box::new(move |event, _window, cx| {
+                if event == ErasedEditorEvent::BufferEdited
+                    && let Err(error) = response.update(cx, |response, cx| {
+                        response.refresh_formatted_payload(cx);
+                        response.save_filter(cx);
+                    })
+                {
+                    log::debug!("Failed to update response filter: {error:?}");
+                }
+            }),
+            window,
+            cx,
+        );
         let response_id = cx.entity_id();
         let summary_text: Entity<TextInteractionState<ResponseSummaryTextId>> =
             cx.new(|cx| TextInteractionState::new(cx));
@@ -316,6 +491,15 @@ impl Response {
             state: ResponseState::default(),
             editor,
             payload,
+            filter_field,
+            formatted_editor,
+            formatted_payload,
+            show_formatted: false,
+            pretty: true,
+            filter_error: None,
+            schema_validation: None,
+            attempts: Vec::new(),
+            console: Vec::new(),
             summary_text,
             headers: Vec::new(),
             headers_table,
@@ -323,6 +507,16 @@ impl Response {
             cookies: Vec::new(),
             cookies_table,
             cookies_list_state,
+            history_path: None,
+            history: Arc::from([]),
+            shown_history_entry: None,
+            compared_history_entries: Vec::new(),
+            history_diff: None,
+            history_scroll_handle: UniformListScrollHandle::new(),
+            history_entry_task: Task::ready(()),
+            history_diff_task: Task::ready(()),
+            benchmark: None,
+            _filter_subscription: filter_subscription,
         }
     }
 
@@ -352,10 +546,247 @@ impl Response {
     }
 
     fn editor(&self) -> Entity<Editor> {
-        self.editor.clone()
+        if self.show_formatted {
+            self.formatted_editor.clone()
+        } else {
+            self.editor.clone()
+        }
+    }
+
+    fn filter_field(&self) -> Entity<InputField> {
+        self.filter_field.clone()
+    }
+
+    pub fn filter(&self, cx: &App) -> String {
+        self.filter_field.read(cx).value(cx)
+    }
+
+    pub fn set_filter(&mut self, filter: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_field.update(cx, |field, cx| {
+            field.set_value(filter, window, cx);
+        });
+        self.refresh_formatted_payload(cx);
+    }
+
+    pub fn filter_error(&self) -> Option<&SharedString> {
+        self.filter_error.as_ref()
+    }
+
+    /// Returns the text shown in the body tab, after filtering and pretty printing.
+    pub fn displayed_text(&self, cx: &App) -> String {
+        if self.show_formatted {
+            self.formatted_payload.read(cx).snapshot(cx).text()
+        } else {
+            self.text(cx)
+        }
+    }
+
+    pub fn pretty(&self) -> bool {
+        self.pretty
+    }
+
+    pub fn set_pretty(&mut self, pretty: bool, cx: &mut Context<Self>) {
+        if self.pretty != pretty {
+            self.pretty = pretty;
+            self.refresh_formatted_payload(cx);
+        }
+    }
+
+    fn can_pretty_print(&self, cx: &App) -> bool {
+        self.language_name(cx)
+            .is_some_and(|language_name| language::supports_text_format(&language_name))
+    }
+
+    /// Rebuilds the formatted payload from the received one, applying the filter and the pretty
+    /// print setting. The received payload is never modified.
+    fn refresh_formatted_payload(&mut self, cx: &mut Context<Self>) {
+        let expression = self.filter(cx);
+        let payload = self.text(cx);
+        let format = if self.pretty {
+            TextFormat::Pretty
+        } else {
+            TextFormat::Minified
+        };
+        self.filter_error = None;
+
+        let formatted = if payload.is_empty() {
+            None
+        } else if !expression.trim().is_empty() {
+            match ResponseFilter::parse(&expression).and_then(|filter| filter.apply(&payload)) {
+                Ok(filtered) => {
+                    Some(language::format_text("JSON", &filtered, format).unwrap_or(filtered))
+                }
+                Err(error) => {
+                    self.filter_error = Some(error.to_string().into());
+                    None
+                }
+            }
+        } else if self.pretty {
+            self.language_name(cx).and_then(|language_name| {
+                language::format_text(&language_name, &payload, format)
+                    .inspect_err(|error| {
+                        log::debug!("Failed to pretty print response body: {error:?}");
+                    })
+                    .ok()
+            })
+        } else {
+            None
+        };
+
+        self.show_formatted = formatted.is_some();
+        if let Some(formatted) = formatted {
+            let language = self
+                .payload
+                .read(cx)
+                .as_singleton()
+                .and_then(|buffer| buffer.read(cx).language().cloned());
+            self.formatted_payload.update(cx, |formatted_payload, cx| {
+                if let Some(buffer) = formatted_payload.as_singleton() {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_language(language, cx);
+                    });
+                }
+                formatted_payload.set_text(formatted, cx);
+            });
+        }
+        self.refresh_schema_diagnostics(cx);
+        cx.notify();
+    }
+
+    pub fn schema_errors(&self) -> &[SourceError] {
+        match self
+            .schema_validation
+            .as_ref()
+            .map(|validation| &validation.result)
+        {
+            Some(Ok(errors)) => errors,
+            Some(Err(_)) | None => &[],
+        }
+    }
+
+    /// Validates the received payload against `schema`, which was loaded from `path`. A schema
+    /// that failed to load is reported in place of the validation errors.
+    pub fn validate_schema(
+        &mut self,
+        request_id: usize,
+        path: impl Into<SharedString>,
+        schema: anyhow::Result<Arc<JsonSchema>>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.request_id != request_id {
+            return false;
+        }
+
+        let path = path.into();
+        let result = match schema {
+            Ok(schema) => Ok(json_schema::validate_source(&schema, &self.text(cx))),
+            Err(error) => Err(format!("Failed to load schema {path}: {error:#}").into()),
+        };
+        self.schema_validation = Some(SchemaValidation { path, result });
+        self.refresh_schema_diagnostics(cx);
+        cx.notify();
+        true
+    }
+
+    /// Underlines schema errors in the raw payload, and in the formatted one unless it is
+    /// filtered, in which case the errors' pointers no longer apply.
+    fn refresh_schema_diagnostics(&self, cx: &mut Context<Self>) {
+        let errors = self.schema_errors();
+        let diagnostic = |error: &SourceError, range: Range<usize>| Diagnostic {
+            range: MultiBufferOffset(range.start)..MultiBufferOffset(range.end),
+            severity: DiagnosticSeverity::Error,
+            message: error.message.clone().into(),
+        };
+
+        let raw_diagnostics = errors
+            .iter()
+            .map(|error| diagnostic(error, error.range.clone()))
+            .collect::<Vec<_>>();
+        let formatted_diagnostics = if self.show_formatted && self.filter(cx).trim().is_empty() {
+            let formatted = self.formatted_payload.read(cx).snapshot(cx).text();
+            json_schema::locate_pointers(
+                &formatted,
+                errors.iter().map(|error| error.pointer.as_str()),
+            )
+            .map(|ranges| {
+                errors
+                    .iter()
+                    .zip(ranges)
+                    .map(|(error, range)| diagnostic(error, range))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        self.editor.update(cx, |editor, cx| {
+            editor.set_diagnostics(raw_diagnostics, cx);
+        });
+        self.formatted_editor.update(cx, |editor, cx| {
+            editor.set_diagnostics(formatted_diagnostics, cx);
+        });
+    }
+
+    /// Jumps to the schema error at `index` in the body editor, clearing the filter first so
+    /// that the error's location is visible.
+    fn select_schema_error(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.filter(cx).trim().is_empty() {
+            self.set_filter("", window, cx);
+        }
+        self.editor().update(cx, |editor, cx| {
+            editor.select_diagnostic(index, window, cx);
+        });
+    }
+
+    fn load_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let filter = match &self.history_path {
+            Some(path) => KeyValueStore::global(cx)
+                .scoped(RESPONSE_FILTER_NAMESPACE)
+                .read(&path.to_string_lossy())
+                .unwrap_or_else(|error| {
+                    log::error!("Failed to load response filter: {error:?}");
+                    None
+                }),
+            None => None,
+        };
+        self.set_filter(filter.as_deref().unwrap_or_default(), window, cx);
+    }
+
+    fn save_filter(&self, cx: &App) {
+        let Some(path) = self.history_path.as_ref() else {
+            return;
+        };
+
+        let kv_store = KeyValueStore::global(cx);
+        let key = path.to_string_lossy().into_owned();
+        let filter = self.filter(cx);
+        cx.background_spawn(async move {
+            let scope = kv_store.scoped(RESPONSE_FILTER_NAMESPACE);
+            let result = if filter.trim().is_empty() {
+                scope.delete(key).await
+            } else {
+                scope.write(key, filter).await
+            };
+            if let Err(error) = result {
+                log::error!("Failed to save response filter: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    fn delete_filter(path: &Path, cx: &App) {
+        let kv_store = KeyValueStore::global(cx);
+        let key = path.to_string_lossy().into_owned();
+        cx.background_spawn(async move {
+            if let Err(error) = kv_store.scoped(RESPONSE_FILTER_NAMESPACE).delete(key).await {
+                log::error!("Failed to delete response filter: {error:?}");
+            }
+        })
+        .detach();
     }
 
-    fn headers(&self) -> &[ResponseHeader] {
+    pub fn headers(&self) -> &[ResponseHeader] {
         &self.headers
     }
 
@@ -458,6 +889,12 @@ impl Response {
 
         self.request_id = request_id;
         self.state = ResponseState::default();
+        self.shown_history_entry = None;
+        self.show_formatted = false;
+        self.filter_error = None;
+        self.schema_validation = None;
+        self.attempts.clear();
+        self.console.clear();
         self.headers.clear();
         self.cookies.clear();
         self.headers_list_state.reset(0);
@@ -495,6 +932,44 @@ impl Response {
         true
     }
 
+    pub fn attempts(&self) -> &[ResponseAttempt] {
+        &self.attempts
+    }
+
+    pub fn push_attempt(
+        &mut self,
+        request_id: usize,
+        attempt: ResponseAttempt,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.request_id != request_id {
+            return false;
+        }
+
+        self.attempts.push(attempt);
+        cx.notify();
+        true
+    }
+
+    pub fn console(&self) -> &[ResponseConsoleEntry] {
+        &self.console
+    }
+
+    pub fn push_console_entries(
+        &mut self,
+        request_id: usize,
+        entries: impl IntoIterator<Item = ResponseConsoleEntry>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.request_id != request_id {
+            return false;
+        }
+
+        self.console.extend(entries);
+        cx.notify();
+        true
+    }
+
     pub fn set_payload<T: Into<String>>(
         &mut self,
         request_id: usize,
@@ -517,81 +992,456 @@ impl Response {
             }
             payload_buffer.set_text(payload, cx);
         });
-        cx.notify();
+        self.refresh_formatted_payload(cx);
         true
     }
-}
-
-pub struct ResponsePanel {
-    focus_handle: FocusHandle,
-    response: Option<Entity<Response>>,
-    active_tab: ResponsePanelTab,
-    on_active_tab_change: Option<Rc<dyn Fn(ResponsePanelTab, &mut Context<ResponsePanel>)>>,
-    has_response_context: bool,
-    auto_hidden: bool,
-    response_subscription: Option<Subscription>,
-    _focus_subscription: Subscription,
-}
-
-impl ResponsePanel {
-    const PANEL_KEY: &str = "ResponsePanel";
-    const DEFAULT_SIZE: Pixels = gpui::px(440.0);
-
-    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
-        let focus_handle = cx.focus_handle();
-        let focus_subscription = cx.on_focus(&focus_handle, window, |_, window, cx| {
-            cx.on_next_frame(window, |response_panel, window, cx| {
-                let editor = response_panel.response.as_ref().and_then(|response| {
-                    let response = response.read(cx);
-                    match response.state() {
-                        ResponseState::Idle => None,
-                        ResponseState::Fetching { .. }
-                        | ResponseState::Completed { .. }
-                        | ResponseState::Error { .. } => Some(response.editor()),
-                    }
-                });
-
-                if response_panel.focus_handle.is_focused(window)
-                    && response_panel.active_tab() == ResponsePanelTab::Body
-                    && let Some(editor) = editor
-                {
-                    editor.focus_handle(cx).focus(window, cx);
-                }
-            });
-        });
 
-        Self {
-            focus_handle,
-            response: None,
-            active_tab: ResponsePanelTab::Body,
-            on_active_tab_change: None,
-            has_response_context: false,
-            auto_hidden: true,
-            response_subscription: None,
-            _focus_subscription: focus_subscription,
-        }
+    fn language_name(&self, cx: &App) -> Option<SharedString> {
+        let buffer = self.payload.read(cx).as_singleton()?;
+        let language = buffer.read(cx).language()?;
+        (!Arc::ptr_eq(language, &PLAIN_TEXT)).then(|| language.name().into())
     }
 
-    #[cfg(any(test, feature = "test"))]
-    pub fn headers_list_state(&self, cx: &App) -> Option<ListState> {
-        self.response
-            .as_ref()
-            .map(|response| response.read(cx).headers_list_state.clone())
+    pub fn benchmark(&self) -> Option<&BenchmarkReport> {
+        self.benchmark.as_ref().map(|benchmark| &benchmark.report)
     }
 
-    #[cfg(any(test, feature = "test"))]
-    pub fn cookies_list_state(&self, cx: &App) -> Option<ListState> {
-        self.response
+    pub fn is_benchmark_running(&self) -> bool {
+        self.benchmark
             .as_ref()
-            .map(|response| response.read(cx).cookies_list_state.clone())
+            .is_some_and(|benchmark| benchmark.running)
     }
 
-    #[cfg(any(test, feature = "test"))]
-    pub fn has_response_context(&self) -> bool {
-        self.has_response_context
+    /// Shows the statistics of a benchmark, which is still sending requests while `running`.
+    pub fn set_benchmark(
+        &mut self,
+        report: BenchmarkReport,
+        running: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.benchmark = Some(Benchmark { report, running });
+        cx.notify();
     }
 
-    fn active_tab(&self) -> ResponsePanelTab {
+    /// Asks where to save the benchmark report, next to the request file by default.
+    fn export_benchmark(&self, format: BenchmarkExportFormat, cx: &mut Context<Self>) {
+        let Some(benchmark) = self.benchmark.as_ref() else {
+            return;
+        };
+        let contents = match format {
+            BenchmarkExportFormat::Json => match benchmark.report.to_json() {
+                Ok(json) => json,
+                Err(error) => {
+                    log::error!("Failed to serialize benchmark report: {error:?}");
+                    return;
+                }
+            },
+            BenchmarkExportFormat::Csv => benchmark.report.to_csv(),
+        };
+
+        let directory = self
+            .history_path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| path::home_dir().as_path());
+        let file_stem = self
+            .history_path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .map_or_else(
+                || "benchmark".to_owned(),
+                |file_stem| format!("{}-benchmark", file_stem.to_string_lossy()),
+            );
+        let suggested_name = format!("{file_stem}.{}", format.extension());
+        let path_prompt = cx.prompt_for_new_path(directory, Some(&suggested_name));
+        let fs = AppState::global(cx).fs.clone();
+        cx.spawn(async move |_, _| {
+            let path = match path_prompt.await {
+                Ok(Ok(Some(path))) => path,
+                Ok(Ok(None)) | Err(_) => return,
+                Ok(Err(error)) => {
+                    log::error!("Failed to prompt for benchmark report path: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = fs.write(&path, contents.as_bytes()).await {
+                log::error!("Failed to export benchmark report: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    pub fn history(&self) -> &[ResponseHistorySummary] {
+        &self.history
+    }
+
+    pub fn set_history_path(
+        &mut self,
+        path: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.history_path == path {
+            return;
+        }
+
+        if let Some(old_path) = self.history_path.take()
+            && let Some(new_path) = path.clone()
+        {
+            let db = ResponseHistoryDb::global(cx);
+            self.history_path = path;
+            Self::delete_filter(&old_path, cx);
+            self.save_filter(cx);
+            cx.spawn(async move |response, cx| {
+                if let Err(error) = db.move_response_history(old_path, new_path).await {
+                    log::error!("Failed to move response history: {error:?}");
+                }
+                if let Err(error) = response.update(cx, |response, cx| {
+                    response.reload_history(cx);
+                }) {
+                    log::debug!("Failed to reload response history: {error:?}");
+                }
+            })
+            .detach();
+            return;
+        }
+
+        self.history_path = path;
+        self.load_filter(window, cx);
+        self.reload_history(cx);
+    }
+
+    fn reload_history(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.history_path.clone() else {
+            self.set_history(Vec::new(), cx);
+            return;
+        };
+
+        let db = ResponseHistoryDb::global(cx);
+        let load_history = cx.background_spawn(async move { db.load_response_history(&path) });
+        cx.spawn(async move |response, cx| {
+            let history = match load_history.await {
+                Ok(history) => history,
+                Err(error) => {
+                    log::error!("Failed to load response history: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = response.update(cx, |response, cx| {
+                response.set_history(history, cx);
+            }) {
+                log::debug!("Failed to update response history: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    fn set_history(&mut self, history: Vec<ResponseHistorySummary>, cx: &mut Context<Self>) {
+        self.history = history.into();
+        self.compared_history_entries
+            .retain(|entry_id| self.history.iter().any(|entry| entry.id == *entry_id));
+        if self
+            .shown_history_entry
+            .is_some_and(|entry_id| !self.history.iter().any(|entry| entry.id == entry_id))
+        {
+            self.shown_history_entry = None;
+        }
+        self.update_history_diff(cx);
+        cx.notify();
+    }
+
+    /// Records the finished response to `request` in the history of the request file. Returns
+    /// the recorded entry, or `None` if the response is no longer the current one or hasn't
+    /// finished.
+    pub fn record_history(
+        &mut self,
+        request_id: usize,
+        request: SentRequest,
+        cx: &mut Context<Self>,
+    ) -> Option<ResponseHistoryEntry> {
+        if self.request_id != request_id {
+            return None;
+        }
+
+        let (status_code, bytes_received, elapsed_duration) = match self.state {
+            ResponseState::Completed {
+                status_code,
+                bytes_received,
+                elapsed_duration,
+            } => (Some(status_code), bytes_received, elapsed_duration),
+            ResponseState::Error {
+                bytes_received,
+                elapsed_duration,
+            } => (None, bytes_received, elapsed_duration),
+            ResponseState::Idle
+            | ResponseState::Fetching { .. }
+            | ResponseState::Waiting { .. } => return None,
+        };
+        let entry = ResponseHistoryEntry {
+            id: 0,
+            sent_at: Timestamp::now(),
+            request,
+            status_code,
+            bytes_received,
+            elapsed_duration,
+            headers: self.headers.clone(),
+            cookies: self.cookies.clone(),
+            body: self.text(cx),
+            language_name: self.language_name(cx),
+        };
+
+        let settings = *ResponseHistorySettings::get_global(cx);
+        if !settings.enabled || settings.max_entries == 0 {
+            return Some(entry);
+        }
+        let Some(path) = self.history_path.clone() else {
+            return Some(entry);
+        };
+        let db = ResponseHistoryDb::global(cx);
+        let serialize_entry = cx.background_spawn({
+            let entry = entry.clone();
+            async move { SerializedResponseHistoryEntry::from_entry(&entry) }
+        });
+        cx.spawn(async move |response, cx| {
+            let entry = match serialize_entry.await {
+                Ok(entry) => entry,
+                Err(error) => {
+                    log::error!("Failed to serialize response history entry: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = db
+                .save_response_history_entry(path, entry, settings.max_entries)
+                .await
+            {
+                log::error!("Failed to save response history entry: {error:?}");
+                return;
+            }
+            if let Err(error) = response.update(cx, |response, cx| {
+                response.reload_history(cx);
+            }) {
+                log::debug!("Failed to reload response history: {error:?}");
+            }
+        })
+        .detach();
+        Some(entry)
+    }
+
+    fn clear_history(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.history_path.clone() else {
+            return;
+        };
+
+        let db = ResponseHistoryDb::global(cx);
+        self.set_history(Vec::new(), cx);
+        cx.background_spawn(async move {
+            if let Err(error) = db.delete_response_history(path).await {
+                log::error!("Failed to clear response history: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    /// Loads a response from the history, with its headers and body.
+    pub fn load_history_entry(
+        &self,
+        entry_id: i64,
+        cx: &App,
+    ) -> Task<anyhow::Result<Option<ResponseHistoryEntry>>> {
+        let db = ResponseHistoryDb::global(cx);
+        cx.background_spawn(async move { db.load_response_history_entry(entry_id) })
+    }
+
+    /// Loads a response from the history and shows it in place of the current one.
+    fn show_history_entry(&mut self, entry_id: i64, window: &mut Window, cx: &mut Context<Self>) {
+        let load_entry = self.load_history_entry(entry_id, cx);
+        self.history_entry_task = cx.spawn_in(window, async move |response, cx| {
+            let entry = match load_entry.await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => return,
+                Err(error) => {
+                    log::error!("Failed to load response history entry: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = response.update_in(cx, |response, window, cx| {
+                response.show_entry(entry, window, cx);
+                response.shown_history_entry = Some(entry_id);
+            }) {
+                log::debug!("Failed to show response history entry: {error:?}");
+            }
+        });
+    }
+
+    /// Replaces the current response with one that was received earlier.
+    pub fn show_entry(
+        &mut self,
+        entry: ResponseHistoryEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let request_id = self.begin_response(window, cx);
+        self.set_state(request_id, entry.state(), cx);
+        self.set_headers(request_id, entry.headers, cx);
+        self.set_cookies(request_id, entry.cookies, cx);
+        self.set_payload(request_id, entry.body, None, cx);
+
+        let Some(language_name) = entry.language_name else {
+            return;
+        };
+        let languages = AppState::global(cx).languages.clone();
+        cx.spawn(async move |response, cx| {
+            let language = match languages.language_for_name(&language_name).await {
+                Ok(language) => language,
+                Err(error) => {
+                    log::error!("Failed to load {language_name} language: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = response.update(cx, |response, cx| {
+                if let Some(buffer) = response.payload.read(cx).as_singleton() {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_language(Some(language), cx);
+                    });
+                }
+                response.refresh_formatted_payload(cx);
+            }) {
+                log::debug!("Failed to update response language: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    fn toggle_compared_history_entry(&mut self, entry_id: i64, cx: &mut Context<Self>) {
+        if let Some(index) = self
+            .compared_history_entries
+            .iter()
+            .position(|compared_entry_id| *compared_entry_id == entry_id)
+        {
+            self.compared_history_entries.remove(index);
+        } else {
+            if self.compared_history_entries.len() == 2 {
+                self.compared_history_entries.remove(0);
+            }
+            self.compared_history_entries.push(entry_id);
+        }
+        self.update_history_diff(cx);
+        cx.notify();
+    }
+
+    /// Loads the two compared responses and diffs them in the background.
+    fn update_history_diff(&mut self, cx: &mut Context<Self>) {
+        let mut compared_entries = self
+            .history
+            .iter()
+            .map(|entry| entry.id)
+            .filter(|entry_id| self.compared_history_entries.contains(entry_id))
+            .collect::<Vec<_>>();
+        compared_entries.sort_unstable();
+
+        self.history_diff = None;
+        let &[older, newer] = compared_entries.as_slice() else {
+            self.history_diff_task = Task::ready(());
+            return;
+        };
+        let db = ResponseHistoryDb::global(cx);
+        let diff = cx.background_spawn(async move {
+            let load_entry = |entry_id| {
+                db.load_response_history_entry(entry_id)?
+                    .ok_or_else(|| anyhow::anyhow!("response history entry {entry_id} not found"))
+            };
+            let (older, newer) = (load_entry(older)?, load_entry(newer)?);
+            anyhow::Ok(history::diff_lines(&older.diff_text(), &newer.diff_text()))
+        });
+        self.history_diff_task = cx.spawn(async move |response, cx| {
+            let diff = match diff.await {
+                Ok(diff) => diff,
+                Err(error) => {
+                    log::error!("Failed to compare response history entries: {error:?}");
+                    return;
+                }
+            };
+            if let Err(error) = response.update(cx, |response, cx| {
+                response.history_diff = Some(diff.into());
+                cx.notify();
+            }) {
+                log::debug!("Failed to show response history diff: {error:?}");
+            }
+        });
+    }
+}
+
+pub struct ResponsePanel {
+    focus_handle: FocusHandle,
+    response: Option<Entity<Response>>,
+    active_tab: ResponsePanelTab,
+    on_active_tab_change: Option<Rc<dyn Fn(ResponsePanelTab, &mut Context<ResponsePanel>)>>,
+    has_response_context: bool,
+    auto_hidden: bool,
+    response_subscription: Option<Subscription>,
+    _focus_subscription: Subscription,
+}
+
+impl ResponsePanel {
+    const PANEL_KEY: &str = "ResponsePanel";
+    const DEFAULT_SIZE: Pixels = gpui::px(440.0);
+
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let focus_handle = cx.focus_handle();
+        let focus_subscription = cx.on_focus(&focus_handle, window, |_, window, cx| {
+            cx.on_next_frame(window, |response_panel, window, cx| {
+                let editor = response_panel.response.as_ref().and_then(|response| {
+                    let response = response.read(cx);
+                    match response.state() {
+                        ResponseState::Idle => None,
+                        ResponseState::Fetching { .. }
+                        | ResponseState::Waiting { .. }
+                        | ResponseState::Completed { .. }
+                        | ResponseState::Error { .. } => Some(response.editor()),
+                    }
+                });
+
+                if response_panel.focus_handle.is_focused(window)
+                    && response_panel.active_tab() == ResponsePanelTab::Body
+                    && let Some(editor) = editor
+                {
+                    editor.focus_handle(cx).focus(window, cx);
+                }
+            });
+        });
+
+        Self {
+            focus_handle,
+            response: None,
+            active_tab: ResponsePanelTab::Body,
+            on_active_tab_change: None,
+            has_response_context: false,
+            auto_hidden: true,
+            response_subscription: None,
+            _focus_subscription: focus_subscription,
+        }
+    }
+
+    #[cfg(any(test, feature = "test"))]
+    pub fn headers_list_state(&self, cx: &App) -> Option<ListState> {
+        self.response
+            .as_ref()
+            .map(|response| response.read(cx).headers_list_state.clone())
+    }
+
+    #[cfg(any(test, feature = "test"))]
+    pub fn cookies_list_state(&self, cx: &App) -> Option<ListState> {
+        self.response
+            .as_ref()
+            .map(|response| response.read(cx).cookies_list_state.clone())
+    }
+
+    #[cfg(any(test, feature = "test"))]
+    pub fn has_response_context(&self) -> bool {
+        self.has_response_context
+    }
+
+    fn active_tab(&self) -> ResponsePanelTab {
         self.active_tab
     }
 
@@ -742,21 +1592,232 @@ impl ResponsePanel {
         };
 
         let colors = cx.theme().colors();
-        let editor = {
+        let (editor, filter_field, filter_error, pretty, schema_validation, attempts) = {
             let response = response.read(cx);
             match response.state() {
                 ResponseState::Idle => return self.render_send_request_hint(cx),
                 ResponseState::Fetching { .. }
+                | ResponseState::Waiting { .. }
                 | ResponseState::Completed { .. }
-                | ResponseState::Error { .. } => response.editor(),
+                | ResponseState::Error { .. } => (
+                    response.editor(),
+                    response.filter_field(),
+                    response.filter_error().cloned(),
+                    response.can_pretty_print(cx).then(|| response.pretty()),
+                    response.schema_validation.clone(),
+                    response.attempts.clone(),
+                ),
             }
         };
+        let format_button = |id: &'static str, label: &'static str, pretty: bool, toggled: bool| {
+            let response = response.clone();
+            Button::new(id, label)
+                .variant(ButtonVariant::Ghost)
+                .size(ButtonSize::Compact)
+                .toggle_state(toggled)
+                .on_click(move |_, _, cx| {
+                    response.update(cx, |response, cx| {
+                        response.set_pretty(pretty, cx);
+                    });
+                })
+        };
 
         gpui::div()
             .flex_1()
             .min_h_0()
+            .flex()
+            .flex_col()
             .bg(colors.panel_background)
-            .child(editor)
+            .child(
+                gpui::div()
+                    .flex()
+                    .flex_col()
+                    .gap(DynamicSpacing::Base04.px(cx))
+                    .p(DynamicSpacing::Base08.px(cx))
+                    .border_b_1()
+                    .border_color(colors.border)
+                    .child(
+                        gpui::div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .child(gpui::div().flex_1().min_w_0().child(filter_field))
+                            .when_some(pretty, |this, pretty| {
+                                this.child(format_button("response-pretty", "Pretty", true, pretty))
+                                    .child(format_button("response-raw", "Raw", false, !pretty))
+                            }),
+                    )
+                    .when_some(filter_error, |this, filter_error| {
+                        this.child(
+                            Text::new(filter_error)
+                                .size(TextSize::Small)
+                                .color(Color::Error)
+                                .single_line()
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when(attempts.len() > 1, |this| {
+                this.child(Self::render_attempts(&attempts, cx))
+            })
+            .child(gpui::div().flex_1().min_h_0().child(editor))
+            .children(
+                schema_validation.map(|validation| Self::render_schema_validation(validation, cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_attempts(attempts: &[ResponseAttempt], cx: &App) -> AnyElement {
+        let colors = cx.theme().colors();
+        let rows = attempts.iter().enumerate().map(|(index, attempt)| {
+            let (outcome, color) = match &attempt.outcome {
+                Ok(status_code) => (status_text(*status_code), status_color(*status_code)),
+                Err(error) => (error.clone(), Color::Error),
+            };
+
+            gpui::div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .w_full()
+                .px_3()
+                .py_0p5()
+                .child(
+                    Text::new(format!("#{}", index + 1))
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                )
+                .child(
+                    gpui::div().flex_1().min_w_0().child(
+                        Text::new(outcome)
+                            .size(TextSize::Small)
+                            .color(color)
+                            .single_line()
+                            .truncate(),
+                    ),
+                )
+                .child(
+                    Text::new(format_elapsed_duration(attempt.elapsed_duration))
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                )
+                .when_some(attempt.retry_delay, |this, retry_delay| {
+                    this.child(
+                        Text::new(format!(
+                            "retried after {}",
+                            format_elapsed_duration(retry_delay)
+                        ))
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                    )
+                })
+        });
+
+        gpui::div()
+            .id("response-attempts")
+            .flex()
+            .flex_col()
+            .flex_none()
+            .max_h(gpui::rems(8.0))
+            .py_1()
+            .overflow_y_scroll()
+            .border_b_1()
+            .border_color(colors.border)
+            .children(rows)
+            .into_any_element()
+    }
+
+    fn render_schema_validation(
+        schema_validation: SchemaValidation,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let (summary, color, errors) = match schema_validation.result {
+            Ok(errors) if errors.is_empty() => (
+                format!("Matches {}", schema_validation.path),
+                Color::Success,
+                errors,
+            ),
+            Ok(errors) => (
+                format!(
+                    "{} schema {} against {}",
+                    errors.len(),
+                    if errors.len() == 1 { "error" } else { "errors" },
+                    schema_validation.path
+                ),
+                Color::Error,
+                errors,
+            ),
+            Err(error) => (error.to_string(), Color::Error, Vec::new()),
+        };
+
+        let rows = errors.into_iter().enumerate().map(|(index, error)| {
+            let pointer = if error.pointer.is_empty() {
+                "(root)".to_string()
+            } else {
+                error.pointer
+            };
+
+            gpui::div()
+                .id(("response-schema-error", index))
+                .flex()
+                .items_center()
+                .gap_2()
+                .w_full()
+                .px_3()
+                .py_0p5()
+                .cursor_pointer()
+                .hover(|this| this.bg(colors.ghost_element_hover))
+                .on_click(cx.listener(move |response_panel, _, window, cx| {
+                    if let Some(response) = response_panel.response.clone() {
+                        response.update(cx, |response, cx| {
+                            response.select_schema_error(index, window, cx);
+                        });
+                    }
+                }))
+                .child(
+                    Text::new(pointer)
+                        .size(TextSize::Small)
+                        .font_buffer(cx)
+                        .single_line(),
+                )
+                .child(
+                    gpui::div().flex_1().min_w_0().child(
+                        Text::new(error.message)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+                )
+        });
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .flex_none()
+            .border_t_1()
+            .border_color(colors.border)
+            .child(
+                gpui::div().px_3().py_1().child(
+                    Text::new(summary)
+                        .size(TextSize::Small)
+                        .color(color)
+                        .single_line()
+                        .truncate(),
+                ),
+            )
+            .child(
+                gpui::div()
+                    .id("response-schema-errors")
+                    .flex()
+                    .flex_col()
+                    .max_h(gpui::rems(8.0))
+                    .overflow_y_scroll()
+                    .children(rows),
+            )
             .into_any_element()
     }
 
@@ -769,7 +1830,7 @@ impl ResponsePanel {
             let response = response.read(cx);
             let is_fetching = match response.state() {
                 ResponseState::Idle => return self.render_send_request_hint(cx),
-                ResponseState::Fetching { .. } => true,
+                ResponseState::Fetching { .. } | ResponseState::Waiting { .. } => true,
                 ResponseState::Completed { .. } | ResponseState::Error { .. } => false,
             };
 
@@ -857,7 +1918,7 @@ impl ResponsePanel {
             let response = response.read(cx);
             let is_fetching = match response.state() {
                 ResponseState::Idle => return self.render_send_request_hint(cx),
-                ResponseState::Fetching { .. } => true,
+                ResponseState::Fetching { .. } | ResponseState::Waiting { .. } => true,
                 ResponseState::Completed { .. } | ResponseState::Error { .. } => false,
             };
 
@@ -953,6 +2014,560 @@ impl ResponsePanel {
             .into_any_element()
     }
 
+    fn render_history(&self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let Some(response) = self.response.as_ref() else {
+            return self.render_send_request_hint(cx);
+        };
+
+        let (history_len, has_history_diff, scroll_handle) = {
+            let response = response.read(cx);
+            (
+                response.history.len(),
+                response.history_diff.is_some(),
+                response.history_scroll_handle.clone(),
+            )
+        };
+
+        if history_len == 0 {
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Text::new("No responses recorded yet.")
+                        .size(TextSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element();
+        }
+
+        let colors = cx.theme().colors();
+        let clear_button = Button::new("response-history-clear", "Clear History")
+            .variant(ButtonVariant::OutlinedGhost)
+            .size(ButtonSize::Compact)
+            .on_click(cx.listener(|response_panel, _, _, cx| {
+                if let Some(response) = response_panel.response.clone() {
+                    response.update(cx, |response, cx| {
+                        response.clear_history(cx);
+                    });
+                }
+            }));
+        let hint = if has_history_diff {
+            "Comparing the selected responses."
+        } else {
+            "Select two responses to compare them."
+        };
+        let diff = has_history_diff.then(|| {
+            let line_count = response
+                .read(cx)
+                .history_diff
+                .as_ref()
+                .map_or(0, |history_diff| history_diff.len());
+            gpui::div()
+                .flex_1()
+                .min_h_0()
+                .border_t_1()
+                .border_color(colors.border)
+                .py_1()
+                .child(
+                    gpui::uniform_list(
+                        "response-history-diff",
+                        line_count,
+                        cx.processor(|this, range: Range<usize>, _window, cx| {
+                            let Some(history_diff) = this
+                                .response
+                                .as_ref()
+                                .and_then(|response| response.read(cx).history_diff.clone())
+                            else {
+                                return Vec::new();
+                            };
+                            range
+                                .filter_map(|index| {
+                                    Some(Self::render_history_diff_line(
+                                        history_diff.get(index)?,
+                                        cx,
+                                    ))
+                                })
+                                .collect()
+                        }),
+                    )
+                    .size_full(),
+                )
+        });
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .px_3()
+                    .py_1p5()
+                    .child(Text::new(hint).size(TextSize::Small).color(Color::Muted))
+                    .child(clear_button),
+            )
+            .child(
+                gpui::div()
+                    .flex()
+                    .flex_col()
+                    .min_h_0()
+                    .map(|this| {
+                        if diff.is_some() {
+                            this.h(gpui::rems(12.0))
+                        } else {
+                            this.flex_1()
+                        }
+                    })
+                    .child(
+                        gpui::uniform_list(
+                            "response-history",
+                            history_len,
+                            cx.processor(|this, range: Range<usize>, _window, cx| {
+                                let Some(response) = this.response.clone() else {
+                                    return Vec::new();
+                                };
+                                let (history, shown_history_entry, compared_history_entries) = {
+                                    let response = response.read(cx);
+                                    (
+                                        response.history.clone(),
+                                        response.shown_history_entry,
+                                        response.compared_history_entries.clone(),
+                                    )
+                                };
+                                let time_zone = TimeZone::system();
+                                range
+                                    .filter_map(|index| {
+                                        let entry = history.get(index)?;
+                                        Some(this.render_history_entry(
+                                            index,
+                                            entry,
+                                            shown_history_entry == Some(entry.id),
+                                            compared_history_entries.contains(&entry.id),
+                                            &time_zone,
+                                            cx,
+                                        ))
+                                    })
+                                    .collect()
+                            }),
+                        )
+                        .size_full()
+                        .track_scroll(&scroll_handle),
+                    )
+                    .custom_scrollbars(
+                        Scrollbars::new(ScrollAxes::Vertical)
+                            .id("response-history-scrollbar")
+                            .tracked_scroll_handle(&scroll_handle),
+                        window,
+                        cx,
+                    ),
+            )
+            .children(diff)
+            .into_any_element()
+    }
+
+    fn render_history_entry(
+        &self,
+        index: usize,
+        entry: &ResponseHistorySummary,
+        is_shown: bool,
+        is_compared: bool,
+        time_zone: &TimeZone,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let entry_id = entry.id;
+        let summary = entry.state().summary();
+        let status_color = summary
+            .as_ref()
+            .map_or(Color::Muted, |summary| summary.color);
+        let sent_at = entry
+            .sent_at
+            .to_zoned(time_zone.clone())
+            .strftime("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let compare_checkbox = ui::checkbox(
+            ("response-history-compare", index),
+            ToggleState::from(is_compared),
+        )
+        .on_click(cx.listener(move |response_panel, _: &ToggleState, _, cx| {
+            if let Some(response) = response_panel.response.clone() {
+                response.update(cx, |response, cx| {
+                    response.toggle_compared_history_entry(entry_id, cx);
+                });
+            }
+        }));
+
+        gpui::div()
+            .id(("response-history-entry", index))
+            .flex()
+            .items_center()
+            .gap_2()
+            .w_full()
+            .px_3()
+            .py_1()
+            .cursor_pointer()
+            .hover(|this| this.bg(colors.ghost_element_hover))
+            .when(is_shown, |this| this.bg(colors.ghost_element_selected))
+            .on_click(cx.listener(move |response_panel, _, window, cx| {
+                if let Some(response) = response_panel.response.clone() {
+                    response.update(cx, |response, cx| {
+                        response.show_history_entry(entry_id, window, cx);
+                    });
+                }
+                response_panel.set_active_tab(ResponsePanelTab::Body, cx);
+            }))
+            .child(compare_checkbox)
+            .child(
+                Text::new(entry.status_text())
+                    .size(TextSize::Small)
+                    .color(status_color)
+                    .single_line(),
+            )
+            .child(
+                gpui::div().flex_1().min_w_0().child(
+                    Text::new(format!("{} {}", entry.request.method, entry.request.url))
+                        .size(TextSize::Small)
+                        .truncate(),
+                ),
+            )
+            .children(summary.map(|summary| {
+                Text::new(format!(
+                    "{} · {}",
+                    summary.elapsed_duration, summary.bytes_received
+                ))
+                .size(TextSize::Small)
+                .color(Color::Muted)
+                .single_line()
+            }))
+            .child(
+                Text::new(sent_at)
+                    .size(TextSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .into_any_element()
+    }
+
+    fn render_history_diff_line(line: &DiffLine, cx: &App) -> AnyElement {
+        let status_colors = cx.theme().status();
+        let (prefix, background) = match line.kind {
+            DiffLineKind::Unchanged => (" ", None),
+            DiffLineKind::Removed => ("-", Some(status_colors.deleted_background)),
+            DiffLineKind::Added => ("+", Some(status_colors.created_background)),
+        };
+
+        gpui::div()
+            .px_3()
+            .when_some(background, |this, background| this.bg(background))
+            .child(
+                Text::new(format!("{prefix} {}", line.text))
+                    .size(TextSize::Small)
+                    .font_buffer(cx)
+                    .single_line(),
+            )
+            .into_any_element()
+    }
+
+    fn render_console(&self, cx: &mut Context<Self>) -> AnyElement {
+        let Some(response) = self.response.as_ref() else {
+            return self.render_send_request_hint(cx);
+        };
+
+        let console = {
+            let response = response.read(cx);
+            if matches!(response.state(), ResponseState::Idle) {
+                return self.render_send_request_hint(cx);
+            }
+            response.console.clone()
+        };
+        if console.is_empty() {
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Text::new("Scripts of the request haven't written anything.")
+                        .size(TextSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element();
+        }
+
+        let rows = console.into_iter().map(|entry| {
+            let (marker, text, color) = match entry {
+                ResponseConsoleEntry::Log(text) => (None, text, Color::Default),
+                ResponseConsoleEntry::Assertion {
+                    message,
+                    passed: true,
+                } => (Some("✓"), message, Color::Success),
+                ResponseConsoleEntry::Assertion {
+                    message,
+                    passed: false,
+                } => (Some("✗"), message, Color::Error),
+                ResponseConsoleEntry::Error(text) => (Some("Error:"), text, Color::Error),
+            };
+
+            gpui::div()
+                .flex()
+                .items_start()
+                .gap_2()
+                .w_full()
+                .px_3()
+                .py_0p5()
+                .when_some(marker, |this, marker| {
+                    this.child(
+                        Text::new(marker)
+                            .size(TextSize::Small)
+                            .color(color)
+                            .single_line(),
+                    )
+                })
+                .child(
+                    gpui::div().flex_1().min_w_0().child(
+                        Text::new(text)
+                            .size(TextSize::Small)
+                            .font_buffer(cx)
+                            .color(color),
+                    ),
+                )
+        });
+
+        gpui::div()
+            .id("response-console")
+            .flex_1()
+            .min_h_0()
+            .flex()
+            .flex_col()
+            .py_1()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background)
+            .children(rows)
+            .into_any_element()
+    }
+
+    fn render_benchmark(&self, cx: &mut Context<Self>) -> AnyElement {
+        let benchmark = self
+            .response
+            .as_ref()
+            .and_then(|response| response.read(cx).benchmark.clone());
+        let Some(Benchmark { report, running }) = benchmark else {
+            return gpui::div()
+                .flex_1()
+                .min_h_0()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Text::new("Run a benchmark from the request's Benchmark tab.")
+                        .size(TextSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element();
+        };
+
+        let colors = cx.theme().colors();
+        let section = |title: &'static str| {
+            gpui::div().px_3().pt_2().pb_1().child(
+                Text::new(title)
+                    .size(TextSize::Small)
+                    .weight(FontWeight::MEDIUM)
+                    .single_line(),
+            )
+        };
+        let row = |name: SharedString, name_color: Color, value: SharedString| {
+            gpui::div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .px_3()
+                .py_0p5()
+                .child(
+                    gpui::div().flex_1().min_w_0().child(
+                        Text::new(name)
+                            .size(TextSize::Small)
+                            .color(name_color)
+                            .truncate(),
+                    ),
+                )
+                .child(
+                    Text::new(value)
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                )
+        };
+        let export_button = |id: &'static str, label: &'static str, format| {
+            Button::new(id, label)
+                .variant(ButtonVariant::OutlinedGhost)
+                .size(ButtonSize::Compact)
+                .disabled(running || report.request_count() == 0)
+                .on_click(cx.listener(move |response_panel, _, _, cx| {
+                    if let Some(response) = response_panel.response.clone() {
+                        response.update(cx, |response, cx| {
+                            response.export_benchmark(format, cx);
+                        });
+                    }
+                }))
+        };
+
+        let summary = format!(
+            "{}{} requests in {} · {:.1} req/s · {} errors",
+            if running { "Running · " } else { "" },
+            report.request_count(),
+            format_elapsed_duration(report.elapsed),
+            report.throughput(),
+            report.error_count(),
+        );
+        let latencies = report.latency.map(
+            |LatencySummary {
+                 min,
+                 p50,
+                 p90,
+                 p99,
+                 max,
+             }| {
+                [
+                    ("min", min),
+                    ("p50", p50),
+                    ("p90", p90),
+                    ("p99", p99),
+                    ("max", max),
+                ]
+                .map(|(name, latency)| {
+                    row(
+                        name.into(),
+                        Color::Default,
+                        format_elapsed_duration(latency),
+                    )
+                })
+            },
+        );
+        let statuses = report.statuses.iter().map(|(status, count)| {
+            let (text, color) = StatusCode::from_u16(*status).map_or_else(
+                |_| (status.to_string().into(), Color::Muted),
+                |status_code| (status_text(status_code), status_color(status_code)),
+            );
+            row(text, color, count.to_string().into())
+        });
+        let errors = report.errors.iter().map(|(error, count)| {
+            row(error.clone().into(), Color::Error, count.to_string().into())
+        });
+        let largest_bucket = report
+            .histogram
+            .iter()
+            .map(|bucket| bucket.count)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let histogram = report.histogram.iter().map(|bucket| {
+            let fraction =
+                bucket.count.to_f32().unwrap_or_default() / largest_bucket.to_f32().unwrap_or(1.0);
+            gpui::div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .px_3()
+                .py_0p5()
+                .child(
+                    gpui::div().w(gpui::rems(10.0)).flex_none().child(
+                        Text::new(format!(
+                            "{} – {}",
+                            format_elapsed_duration(bucket.start),
+                            format_elapsed_duration(bucket.end)
+                        ))
+                        .size(TextSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                    ),
+                )
+                .child(
+                    gpui::div().flex_1().h(gpui::rems(0.75)).child(
+                        gpui::div()
+                            .h_full()
+                            .w(gpui::relative(fraction))
+                            .rounded_sm()
+                            .bg(colors.text_accent),
+                    ),
+                )
+                .child(
+                    gpui::div().w(gpui::rems(3.0)).flex_none().child(
+                        Text::new(bucket.count.to_string())
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+                )
+        });
+
+        gpui::div()
+            .flex_1()
+            .min_h_0()
+            .flex()
+            .flex_col()
+            .child(
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .p(DynamicSpacing::Base08.px(cx))
+                    .border_b_1()
+                    .border_color(colors.border)
+                    .child(
+                        gpui::div().flex_1().min_w_0().child(
+                            Text::new(summary)
+                                .size(TextSize::Small)
+                                .color(if running {
+                                    Color::Muted
+                                } else {
+                                    Color::Default
+                                })
+                                .truncate(),
+                        ),
+                    )
+                    .child(export_button(
+                        "benchmark-export-json",
+                        "Export JSON",
+                        BenchmarkExportFormat::Json,
+                    ))
+                    .child(export_button(
+                        "benchmark-export-csv",
+                        "Export CSV",
+                        BenchmarkExportFormat::Csv,
+                    )),
+            )
+            .child(
+                gpui::div()
+                    .id("response-benchmark")
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .min_h_0()
+                    .pb_2()
+                    .overflow_y_scroll()
+                    .when_some(latencies, |this, latencies| {
+                        this.child(section("Latency")).children(latencies)
+                    })
+                    .when(!report.statuses.is_empty(), |this| {
+                        this.child(section("Status Codes")).children(statuses)
+                    })
+                    .when(!report.errors.is_empty(), |this| {
+                        this.child(section("Errors")).children(errors)
+                    })
+                    .when(!report.histogram.is_empty(), |this| {
+                        this.child(section("Latency Histogram")).children(histogram)
+                    }),
+            )
+            .into_any_element()
+    }
+
     fn render_response_summary(
         response_summary: ResponseSummary,
         summary_text: &Entity<TextInteractionState<ResponseSummaryTextId>>,
@@ -1146,6 +2761,24 @@ impl ResponsePanel {
                         active_tab == ResponsePanelTab::Cookies,
                         "Cookies".into(),
                         ResponsePanelTab::Cookies,
+                    ))
+                    .child(render_tab(
+                        ElementId::Name("response-history-tab".into()),
+                        active_tab == ResponsePanelTab::History,
+                        "History".into(),
+                        ResponsePanelTab::History,
+                    ))
+                    .child(render_tab(
+                        ElementId::Name("response-benchmark-tab".into()),
+                        active_tab == ResponsePanelTab::Benchmark,
+                        "Benchmark".into(),
+                        ResponsePanelTab::Benchmark,
+                    ))
+                    .child(render_tab(
+                        ElementId::Name("response-console-tab".into()),
+                        active_tab == ResponsePanelTab::Console,
+                        "Console".into(),
+                        ResponsePanelTab::Console,
                     )),
             )
             .when_some(
@@ -1209,7 +2842,7 @@ impl Panel for ResponsePanel {
 }
 
 impl Render for ResponsePanel {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let focus_handle = self.focus_handle(cx);
         let tab_bar = self.has_response_context.then(|| self.render_tab_bar(cx));
         let tab_content = if self.has_response_context {
@@ -1217,6 +2850,9 @@ impl Render for ResponsePanel {
                 ResponsePanelTab::Body => self.render_body(cx),
                 ResponsePanelTab::Headers => self.render_headers(cx),
                 ResponsePanelTab::Cookies => self.render_cookies(cx),
+                ResponsePanelTab::History => self.render_history(window, cx),
+                ResponsePanelTab::Benchmark => self.render_benchmark(cx),
+                ResponsePanelTab::Console => self.render_console(cx),
             }
         } else {
             gpui::div()
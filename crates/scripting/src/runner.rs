@@ -0,0 +1,461 @@
+use anyhow::{Context as _, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use hmac::{Hmac, KeyInit as _, Mac as _};
+use jiff::Timestamp;
+use parking_lot::Mutex;
+use rhai::{
+    Dynamic, Engine, EvalAltResult, Map, OptimizationLevel, Scope,
+    module_resolvers::DummyModuleResolver,
+};
+use sha2::{Digest as _, Sha256};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// How many operations a script may run before it is stopped, so a runaway loop can't hold up the
+/// request forever.
+const MAX_OPERATIONS: u64 = 10_000_000;
+const MAX_CALL_LEVELS: usize = 64;
+const MAX_STRING_SIZE: usize = 64 * 1024 * 1024;
+const MAX_COLLECTION_SIZE: usize = 1_000_000;
+
+/// The request as scripts see it, before the variables and secrets it references are resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub elapsed_duration: Duration,
+}
+
+/// A line of what the scripts of a request printed, asserted or failed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleEntry {
+    Log(String),
+    Assertion { message: String, passed: bool },
+    Error(String),
+}
+
+/// What the scripts run for one send share: the variables they can change, the secrets they can
+/// read and what they write to the console.
+///
+/// Scripts are written in [Rhai](https://rhai.rs). They can't load modules, and the only things
+/// they can reach outside the script are the request, its response and this context.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptContext {
+    pub variables: BTreeMap<String, String>,
+    pub secrets: BTreeMap<String, String>,
+    pub console: Vec<ConsoleEntry>,
+}
+
+impl ScriptContext {
+    /// Runs a script that can change `request` before it is sent, through the `request` map.
+    pub fn run_pre_request(
+        &mut self,
+        script: &str,
+        request: &mut ScriptRequest,
+    ) -> anyhow::Result<()> {
+        let mut scope = Scope::new();
+        scope.push("request", request_map(request));
+        let scope = self.run(script, scope)?;
+        let result = scope
+            .get_value::<Map>("request")
+            .context("`request` must stay an object map")
+            .and_then(|map| updated_request(request, map))
+            .and_then(|updated| Ok((updated, script_variables(&scope)?)));
+        let (updated, variables) = self.record_error(result)?;
+        *request = updated;
+        self.variables = variables;
+        Ok(())
+    }
+
+    /// Runs a script that inspects the `response` to `request`. Neither can be changed.
+    pub fn run_post_response(
+        &mut self,
+        script: &str,
+        request: &ScriptRequest,
+        response: &ScriptResponse,
+    ) -> anyhow::Result<()> {
+        let mut scope = Scope::new();
+        scope.push_constant("request", request_map(request));
+        scope.push_constant("response", response_map(response));
+        let scope = self.run(script, scope)?;
+        self.variables = self.record_error(script_variables(&scope))?;
+        Ok(())
+    }
+
+    fn run<'a>(&mut self, script: &str, mut scope: Scope<'a>) -> anyhow::Result<Scope<'a>> {
+        let console = Arc::new(Mutex::new(Vec::new()));
+        let engine = engine(&console);
+        scope.push("variables", string_map(&self.variables));
+        scope.push_constant("secrets", string_map(&self.secrets));
+
+        let result = engine.run_with_scope(&mut scope, script);
+        self.console.append(&mut console.lock());
+        self.record_error(result.map_err(|error| anyhow!("{error}")))?;
+        Ok(scope)
+    }
+
+    /// Writes the error of a failed script to the console. Nothing the script changed is kept.
+    fn record_error<T>(&mut self, result: anyhow::Result<T>) -> anyhow::Result<T> {
+        result.inspect_err(|error| self.console.push(ConsoleEntry::Error(format!("{error:#}"))))
+    }
+}
+
+fn script_variables(scope: &Scope) -> anyhow::Result<BTreeMap<String, String>> {
+    let variables = scope
+        .get_value::<Map>("variables")
+        .context("`variables` must stay an object map")?;
+    Ok(variables
+        .into_iter()
+        .filter(|(_, value)| !value.is_unit())
+        .map(|(name, value)| (name.to_string(), dynamic_to_string(value)))
+        .collect())
+}
+
+fn engine(console: &Arc<Mutex<Vec<ConsoleEntry>>>) -> Engine {
+    let mut engine = Engine::new();
+    // The optimizer inlines the constants of the scope, after which assigning to one of their
+    // properties panics instead of failing the script.
+    engine
+        .set_optimization_level(OptimizationLevel::None)
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE);
+
+    engine.on_print({
+        let console = console.clone();
+        move |text| console.lock().push(ConsoleEntry::Log(text.to_owned()))
+    });
+    engine.on_debug({
+        let console = console.clone();
+        move |text, _, _| console.lock().push(ConsoleEntry::Log(text.to_owned()))
+    });
+    engine.register_fn("assert", {
+        let console = console.clone();
+        move |passed: bool, message: &str| {
+            console.lock().push(ConsoleEntry::Assertion {
+                message: message.to_owned(),
+                passed,
+            });
+        }
+    });
+
+    engine
+        .register_fn("uuid", || Uuid::new_v4().to_string())
+        .register_fn("unix_timestamp", || Timestamp::now().as_second())
+        .register_fn("base64_encode", |text: &str| STANDARD.encode(text))
+        .register_fn(
+            "base64_decode",
+            |text: &str| -> Result<String, Box<EvalAltResult>> {
+                let bytes = STANDARD
+                    .decode(text)
+                    .map_err(|error| format!("invalid Base64: {error}"))?;
+                String::from_utf8(bytes)
+                    .map_err(|error| format!("decoded Base64 isn't UTF-8: {error}").into())
+            },
+        )
+        .register_fn("sha256", |text: &str| hex::encode(Sha256::digest(text)))
+        .register_fn("hmac_sha256", |key: &str, data: &str| {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(data.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
+        })
+        .register_fn(
+            "parse_json",
+            |text: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+                serde_json::from_str(text).map_err(|error| format!("invalid JSON: {error}").into())
+            },
+        )
+        .register_fn(
+            "to_json",
+            |value: Dynamic| -> Result<String, Box<EvalAltResult>> {
+                serde_json::to_string(&value).map_err(|error| error.to_string().into())
+            },
+        );
+    engine
+}
+
+fn string_map(values: &BTreeMap<String, String>) -> Map {
+    values
+        .iter()
+        .map(|(name, value)| (name.as_str().into(), Dynamic::from(value.clone())))
+        .collect()
+}
+
+/// The headers by name. Only the first of several headers with the same name is kept.
+fn headers_map(headers: &[(String, String)]) -> Map {
+    let mut map = Map::new();
+    for (name, value) in headers {
+        map.entry(name.as_str().into())
+            .or_insert_with(|| Dynamic::from(value.clone()));
+    }
+    map
+}
+
+fn request_map(request: &ScriptRequest) -> Map {
+    let mut map = Map::new();
+    map.insert("method".into(), Dynamic::from(request.method.clone()));
+    map.insert("url".into(), Dynamic::from(request.url.clone()));
+    map.insert(
+        "headers".into(),
+        Dynamic::from(headers_map(&request.headers)),
+    );
+    map.insert(
+        "body".into(),
+        request.body.clone().map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map
+}
+
+fn response_map(response: &ScriptResponse) -> Map {
+    let mut map = Map::new();
+    map.insert("status".into(), Dynamic::from(i64::from(response.status)));
+    map.insert(
+        "headers".into(),
+        Dynamic::from(headers_map(&response.headers)),
+    );
+    map.insert("body".into(), Dynamic::from(response.body.clone()));
+    map.insert(
+        "elapsed_ms".into(),
+        Dynamic::from(i64::try_from(response.elapsed_duration.as_millis()).unwrap_or(i64::MAX)),
+    );
+    map
+}
+
+/// The request with the changes a script made to its map. Headers keep their order, and those
+/// the script added come after them.
+fn updated_request(request: &ScriptRequest, mut map: Map) -> anyhow::Result<ScriptRequest> {
+    let mut string_field = |name: &str| {
+        map.remove(name)
+            .filter(Dynamic::is_string)
+            .map(dynamic_to_string)
+            .with_context(|| format!("`request.{name}` must be a string"))
+    };
+    let method = string_field("method")?;
+    let url = string_field("url")?;
+    let mut headers = map
+        .remove("headers")
+        .and_then(|headers| headers.try_cast::<Map>())
+        .context("`request.headers` must be an object map")?;
+    let body = map
+        .remove("body")
+        .filter(|body| !body.is_unit())
+        .map(dynamic_to_string);
+
+    let mut updated_headers = request
+        .headers
+        .iter()
+        .filter_map(|(name, _)| {
+            let value = headers.remove(name.as_str())?;
+            Some((name.clone(), dynamic_to_string(value)))
+        })
+        .collect::<Vec<_>>();
+    updated_headers.extend(
+        headers
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), dynamic_to_string(value))),
+    );
+
+    Ok(ScriptRequest {
+        method,
+        url,
+        headers: updated_headers,
+        body,
+    })
+}
+
+fn dynamic_to_string(value: Dynamic) -> String {
+    if value.is_string() {
+        value.into_string().unwrap_or_default()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn request() -> ScriptRequest {
+        ScriptRequest {
+            method: "POST".to_string(),
+            url: "https://api.zaku.dev/users".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Accept".to_string(), "*/*".to_string()),
+                ("X-Trace".to_string(), "1".to_string()),
+            ],
+            body: Some(r#"{"name":"zaku"}"#.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_pre_request_script() {
+        let mut context = ScriptContext {
+            variables: BTreeMap::from([("page".to_string(), "1".to_string())]),
+            secrets: BTreeMap::from([("key".to_string(), "key".to_string())]),
+            console: Vec::new(),
+        };
+        let mut request = request();
+
+        context
+            .run_pre_request(
+                indoc! {r#"
+                    let body = parse_json(request.body);
+                    body.id = 7;
+                    request.body = to_json(body);
+                    request.method = "PUT";
+                    request.url += "/7?page=" + variables.page;
+                    request.headers["Accept"] = "application/json";
+                    request.headers.remove("X-Trace");
+                    request.headers["X-Signature"] = hmac_sha256(secrets.key, "The quick brown fox jumps over the lazy dog");
+                    variables.page = 2;
+                    variables.user = ();
+                    variables.encoded = base64_encode("user:pass");
+                    print(`signed ${request.url}`);
+                "#},
+                &mut request,
+            )
+            .unwrap();
+
+        assert_eq!(
+            request,
+            ScriptRequest {
+                method: "PUT".to_string(),
+                url: "https://api.zaku.dev/users/7?page=1".to_string(),
+                headers: vec![
+                    ("Content-Type".to_string(), "application/json".to_string()),
+                    ("Accept".to_string(), "application/json".to_string()),
+                    (
+                        "X-Signature".to_string(),
+                        "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+                            .to_string()
+                    ),
+                ],
+                body: Some(r#"{"id":7,"name":"zaku"}"#.to_string()),
+            }
+        );
+        assert_eq!(
+            context.variables,
+            BTreeMap::from([
+                ("encoded".to_string(), "dXNlcjpwYXNz".to_string()),
+                ("page".to_string(), "2".to_string()),
+            ])
+        );
+        assert_eq!(
+            context.console,
+            vec![ConsoleEntry::Log(
+                "signed https://api.zaku.dev/users/7?page=1".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_post_response_script() {
+        let mut context = ScriptContext::default();
+        let response = ScriptResponse {
+            status: 201,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: r#"{"token":"t0ken","items":[1,2]}"#.to_string(),
+            elapsed_duration: Duration::from_millis(42),
+        };
+
+        context
+            .run_post_response(
+                indoc! {r#"
+                    let body = parse_json(response.body);
+                    assert(response.status == 201, "created");
+                    assert(body.items.len() == 3, "three items");
+                    assert(response.headers["content-type"] == "application/json", "is JSON");
+                    variables.token = body.token;
+                    debug(response.elapsed_ms);
+                "#},
+                &request(),
+                &response,
+            )
+            .unwrap();
+
+        assert_eq!(
+            context.console,
+            vec![
+                ConsoleEntry::Assertion {
+                    message: "created".to_string(),
+                    passed: true,
+                },
+                ConsoleEntry::Assertion {
+                    message: "three items".to_string(),
+                    passed: false,
+                },
+                ConsoleEntry::Assertion {
+                    message: "is JSON".to_string(),
+                    passed: true,
+                },
+                ConsoleEntry::Log("42".to_string()),
+            ]
+        );
+        assert_eq!(
+            context.variables,
+            BTreeMap::from([("token".to_string(), "t0ken".to_string())])
+        );
+
+        let error = context
+            .run_post_response("response.status = 500;", &request(), &response)
+            .unwrap_err();
+        assert_eq!(
+            context.console.last(),
+            Some(&ConsoleEntry::Error(format!("{error:#}")))
+        );
+    }
+
+    #[test]
+    fn test_scripts_are_sandboxed() {
+        let mut context = ScriptContext {
+            secrets: BTreeMap::from([("key".to_string(), "key".to_string())]),
+            ..ScriptContext::default()
+        };
+        let mut request = request();
+
+        context
+            .run_pre_request(r#"import "secrets" as secrets;"#, &mut request)
+            .unwrap_err();
+        context
+            .run_pre_request("loop {}", &mut request)
+            .unwrap_err();
+        context
+            .run_pre_request(r#"request = "GET";"#, &mut request)
+            .unwrap_err();
+        context
+            .run_pre_request(r#"secrets.key = "value";"#, &mut request)
+            .unwrap_err();
+        // Changes of a script that failed are discarded.
+        context
+            .run_pre_request(r#"variables.token = "a"; throw "failed";"#, &mut request)
+            .unwrap_err();
+
+        assert_eq!(request, self::request());
+        assert!(context.variables.is_empty());
+        assert_eq!(
+            context
+                .console
+                .iter()
+                .filter(|entry| matches!(entry, ConsoleEntry::Error(_)))
+                .count(),
+            5
+        );
+    }
+}
@@ -0,0 +1,11 @@
+mod runner;
+mod variables;
+
+use gpui::App;
+
+pub use runner::{ConsoleEntry, ScriptContext, ScriptRequest, ScriptResponse};
+pub use variables::{ScriptVariables, resolve_variables};
+
+pub fn init(cx: &mut App) {
+    variables::init(cx);
+}
@@ -0,0 +1,181 @@
+use anyhow::anyhow;
+use gpui::{App, AppContext as _, Context, Entity, Global};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use collections::HashMap;
+
+const VARIABLE_REFERENCE_PREFIX: &str = "var.";
+
+struct GlobalScriptVariables(Entity<ScriptVariables>);
+
+impl Global for GlobalScriptVariables {}
+
+pub(crate) fn init(cx: &mut App) {
+    let variables = cx.new(|_| ScriptVariables::default());
+    cx.set_global(GlobalScriptVariables(variables));
+}
+
+/// The variables set by scripts, which requests reference as `{{var.name}}`. They are kept in
+/// memory for the session, for each project by the path of its root.
+#[derive(Default)]
+pub struct ScriptVariables {
+    projects: HashMap<PathBuf, BTreeMap<String, String>>,
+}
+
+impl ScriptVariables {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalScriptVariables>().0.clone()
+    }
+
+    pub fn try_global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalScriptVariables>()
+            .map(|variables| variables.0.clone())
+    }
+
+    /// The variables of the project at `project_path`, which are empty until a script sets one.
+    pub fn project_variables(project_path: &Path, cx: &App) -> BTreeMap<String, String> {
+        Self::try_global(cx)
+            .and_then(|variables| variables.read(cx).projects.get(project_path).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Applies the changes a script made to the variables it was given, so requests sent at the
+    /// same time don't undo each other's changes.
+    pub fn apply_changes(
+        &mut self,
+        project_path: PathBuf,
+        before: &BTreeMap<String, String>,
+        after: &BTreeMap<String, String>,
+        cx: &mut Context<Self>,
+    ) {
+        if before == after {
+            return;
+        }
+
+        let variables = self.projects.entry(project_path).or_default();
+        for name in before.keys() {
+            if !after.contains_key(name) {
+                variables.remove(name);
+            }
+        }
+        for (name, value) in after {
+            if before.get(name) != Some(value) {
+                variables.insert(name.clone(), value.clone());
+            }
+        }
+        cx.notify();
+    }
+}
+
+/// Replaces every `{{var.name}}` reference in `text` with the value of the variable. Other `{{…}}`
+/// sequences are left as they are.
+pub fn resolve_variables(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+) -> anyhow::Result<String> {
+    let mut resolved = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((before, after_open)) = rest.split_once("{{") {
+        resolved.push_str(before);
+        let reference = after_open
+            .split_once("}}")
+            .and_then(|(reference, after_close)| {
+                let name = reference
+                    .trim()
+                    .strip_prefix(VARIABLE_REFERENCE_PREFIX)
+                    .filter(|name| !name.is_empty())?;
+                Some((name, after_close))
+            });
+        if let Some((name, after_close)) = reference {
+            let value = variables
+                .get(name)
+                .ok_or_else(|| anyhow!("unknown variable \"{name}\""))?;
+            resolved.push_str(value);
+            rest = after_close;
+        } else {
+            resolved.push_str("{{");
+            rest = after_open;
+        }
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn variables(variables: &[(&str, &str)]) -> BTreeMap<String, String> {
+        variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_variables() {
+        let variables = variables(&[("token", "abc123"), ("user.id", "7")]);
+
+        assert_eq!(
+            resolve_variables("/users/{{var.user.id}}?token={{ var.token }}", &variables).unwrap(),
+            "/users/7?token=abc123"
+        );
+        assert_eq!(
+            resolve_variables(
+                "{{secret.token}} {{$uuid}} {{var.}} {{var.token",
+                &variables
+            )
+            .unwrap(),
+            "{{secret.token}} {{$uuid}} {{var.}} {{var.token"
+        );
+        assert_eq!(
+            resolve_variables("{{var.missing}}", &variables)
+                .unwrap_err()
+                .to_string(),
+            "unknown variable \"missing\""
+        );
+    }
+
+    #[gpui::test]
+    fn test_apply_variable_changes(cx: &mut App) {
+        init(cx);
+        let project_path = PathBuf::from("/project");
+        let store = ScriptVariables::global(cx);
+
+        store.update(cx, |store, cx| {
+            store.apply_changes(
+                project_path.clone(),
+                &BTreeMap::new(),
+                &variables(&[("token", "a"), ("user", "1")]),
+                cx,
+            );
+            // Another script that started from the same variables only changes what it changed.
+            store.apply_changes(
+                project_path.clone(),
+                &BTreeMap::new(),
+                &variables(&[("page", "2")]),
+                cx,
+            );
+            store.apply_changes(
+                project_path.clone(),
+                &variables(&[("token", "a"), ("user", "1")]),
+                &variables(&[("token", "b")]),
+                cx,
+            );
+        });
+
+        assert_eq!(
+            ScriptVariables::project_variables(&project_path, cx),
+            variables(&[("page", "2"), ("token", "b")])
+        );
+        assert_eq!(
+            ScriptVariables::project_variables(Path::new("/other"), cx),
+            BTreeMap::new()
+        );
+    }
+}
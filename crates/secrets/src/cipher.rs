@@ -0,0 +1,118 @@
+use anyhow::{Context as _, anyhow};
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, OsRng},
+};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// The local key secrets are encrypted with at rest.
+pub(crate) struct SecretKey(ChaCha20Poly1305);
+
+impl SecretKey {
+    /// Reads the key from `path`, creating a new random key there if the file does not exist
+    /// yet.
+    pub(crate) fn load_or_create(path: &Path) -> anyhow::Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Self::from_bytes(&bytes)
+                .with_context(|| format!("reading secrets key {}", path.display())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+                match write_key_file(path, &key) {
+                    Ok(()) => Ok(Self(ChaCha20Poly1305::new(&key))),
+                    // Another instance created the key first.
+                    Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                        Self::from_bytes(&fs::read(path)?)
+                    }
+                    Err(error) => Err(error)
+                        .with_context(|| format!("creating secrets key {}", path.display())),
+                }
+            }
+            Err(error) => {
+                Err(error).with_context(|| format!("reading secrets key {}", path.display()))
+            }
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let key = <[u8; KEY_LEN]>::try_from(bytes)
+            .with_context(|| format!("expected a {KEY_LEN} byte key, found {}", bytes.len()))?;
+        Ok(Self(ChaCha20Poly1305::new(&Key::from(key))))
+    }
+
+    /// Encrypts `value` under a fresh nonce, which is stored in front of the ciphertext.
+    pub(crate) fn encrypt(&self, value: &str) -> anyhow::Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(|error| anyhow!("failed to encrypt secret: {error}"))?;
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(ciphertext);
+        Ok(encrypted)
+    }
+
+    pub(crate) fn decrypt(&self, encrypted: &[u8]) -> anyhow::Result<String> {
+        let (nonce, ciphertext) = encrypted
+            .split_at_checked(NONCE_LEN)
+            .context("encrypted secret is too short")?;
+        let nonce = <[u8; NONCE_LEN]>::try_from(nonce)?;
+        let value = self
+            .0
+            .decrypt(&Nonce::from(nonce), ciphertext)
+            .map_err(|error| anyhow!("failed to decrypt secret: {error}"))?;
+        Ok(String::from_utf8(value)?)
+    }
+}
+
+/// Writes a new key file that only the current user can read.
+fn write_key_file(path: &Path, key: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(key)?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_key_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("secrets.key");
+
+        let key = SecretKey::load_or_create(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap().len(), KEY_LEN);
+
+        let encrypted = key.encrypt("hunter2").unwrap();
+        assert!(!encrypted.windows(7).any(|window| window == b"hunter2"));
+        assert_ne!(key.encrypt("hunter2").unwrap(), encrypted);
+        assert_eq!(key.decrypt(&encrypted).unwrap(), "hunter2");
+
+        let reloaded_key = SecretKey::load_or_create(&path).unwrap();
+        assert_eq!(reloaded_key.decrypt(&encrypted).unwrap(), "hunter2");
+
+        let other_key = SecretKey::load_or_create(&dir.path().join("other.key")).unwrap();
+        other_key.decrypt(&encrypted).unwrap_err();
+        key.decrypt(&encrypted[..4]).unwrap_err();
+    }
+}
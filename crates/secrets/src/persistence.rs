@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use db::{ThreadSafeConnection, query, sql::domain::Domain, sql_macros::sql};
+
+pub(crate) struct SecretsDb(ThreadSafeConnection);
+
+impl SecretsDb {
+    query! {
+        pub(crate) fn secrets() -> anyhow::Result<Vec<(PathBuf, String, String, Vec<u8>)>> {
+            SELECT project_path, environment, name, value
+            FROM secrets
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_secret(
+            project_path: PathBuf,
+            environment: String,
+            name: String,
+            value: Vec<u8>,
+        ) -> anyhow::Result<()> {
+            INSERT OR REPLACE INTO secrets(project_path, environment, name, value)
+            VALUES (?1, ?2, ?3, ?4)
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_secret(
+            project_path: PathBuf,
+            environment: String,
+            name: String,
+        ) -> anyhow::Result<()> {
+            DELETE FROM secrets
+            WHERE project_path = ?1 AND environment = ?2 AND name = ?3
+        }
+    }
+
+    query! {
+        pub(crate) fn active_environments() -> anyhow::Result<Vec<(PathBuf, String)>> {
+            SELECT project_path, environment
+            FROM active_secret_environments
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_active_environment(
+            project_path: PathBuf,
+            environment: String,
+        ) -> anyhow::Result<()> {
+            INSERT OR REPLACE INTO active_secret_environments(project_path, environment)
+            VALUES (?1, ?2)
+        }
+    }
+}
+
+impl Domain for SecretsDb {
+    const NAME: &str = stringify!(SecretsDb);
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE IF NOT EXISTS secrets(
+            project_path BLOB NOT NULL,
+            environment TEXT NOT NULL,
+            name TEXT NOT NULL,
+            value BLOB NOT NULL,
+            PRIMARY KEY(project_path, environment, name)
+        ) STRICT;
+        CREATE TABLE IF NOT EXISTS active_secret_environments(
+            project_path BLOB PRIMARY KEY,
+            environment TEXT NOT NULL
+        ) STRICT;
+    )];
+}
+
+db::static_connection!(SecretsDb, []);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[gpui::test]
+    async fn test_secrets_are_saved() {
+        let db = SecretsDb::test_open("test_secrets_are_saved").await;
+        let project_path = PathBuf::from("/projects/api");
+
+        assert!(db.secrets().unwrap().is_empty());
+
+        db.save_secret(
+            project_path.clone(),
+            "staging".to_string(),
+            "token".to_string(),
+            vec![1, 2, 3],
+        )
+        .await
+        .unwrap();
+        db.save_secret(
+            project_path.clone(),
+            "staging".to_string(),
+            "token".to_string(),
+            vec![4, 5, 6],
+        )
+        .await
+        .unwrap();
+        db.save_secret(
+            project_path.clone(),
+            "production".to_string(),
+            "token".to_string(),
+            vec![7, 8, 9],
+        )
+        .await
+        .unwrap();
+
+        let mut secrets = db.secrets().unwrap();
+        secrets.sort();
+        assert_eq!(
+            secrets,
+            vec![
+                (
+                    project_path.clone(),
+                    "production".to_string(),
+                    "token".to_string(),
+                    vec![7, 8, 9]
+                ),
+                (
+                    project_path.clone(),
+                    "staging".to_string(),
+                    "token".to_string(),
+                    vec![4, 5, 6]
+                ),
+            ]
+        );
+
+        db.delete_secret(
+            project_path.clone(),
+            "production".to_string(),
+            "token".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(db.secrets().unwrap().len(), 1);
+
+        assert!(db.active_environments().unwrap().is_empty());
+        db.save_active_environment(project_path.clone(), "staging".to_string())
+            .await
+            .unwrap();
+        db.save_active_environment(project_path.clone(), "production".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.active_environments().unwrap(),
+            vec![(project_path, "production".to_string())]
+        );
+    }
+}
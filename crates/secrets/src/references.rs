@@ -0,0 +1,205 @@
+use anyhow::anyhow;
+use std::collections::BTreeMap;
+
+/// What a secret value is replaced with wherever it would be shown.
+pub const SECRET_MASK: &str = "••••••••";
+
+const SECRET_REFERENCE_PREFIX: &str = "secret.";
+
+/// The secrets of one project environment, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Secrets(BTreeMap<String, String>);
+
+impl Secrets {
+    pub fn new(secrets: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self(secrets.into_iter().collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub(crate) fn insert(&mut self, name: String, value: String) {
+        self.0.insert(name, value);
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.0.remove(name);
+    }
+
+    /// Replaces every `{{secret.name}}` reference in `text` with the value of the secret. Other
+    /// `{{…}}` sequences are left as they are.
+    pub fn resolve(&self, text: &str) -> anyhow::Result<String> {
+        let mut resolved = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some((before, after_open)) = rest.split_once("{{") {
+            resolved.push_str(before);
+            let reference = after_open
+                .split_once("}}")
+                .and_then(|(reference, after_close)| {
+                    Some((secret_reference_name(reference)?, after_close))
+                });
+            if let Some((name, after_close)) = reference {
+                let value = self
+                    .get(name)
+                    .ok_or_else(|| anyhow!("unknown secret \"{name}\""))?;
+                resolved.push_str(value);
+                rest = after_close;
+            } else {
+                resolved.push_str("{{");
+                rest = after_open;
+            }
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+
+    /// Replaces every secret value that appears in `text` with [`SECRET_MASK`], including its
+    /// encoded forms. See [`mask_value`].
+    pub fn mask(&self, text: &str) -> String {
+        let mut values = self
+            .0
+            .values()
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>();
+        // Longer values first, so a secret that contains another is masked whole.
+        values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+
+        values
+            .into_iter()
+            .fold(text.to_string(), |masked, value| mask_value(&masked, value))
+    }
+}
+
+/// Replaces `value` in `text` with [`SECRET_MASK`], along with the forms it takes once encoded in
+/// a URL or a form body, where characters like `+`, `/` and `=` are percent-encoded.
+pub fn mask_value(text: &str, value: &str) -> String {
+    if value.is_empty() {
+        return text.to_string();
+    }
+
+    let mut masked = text.to_string();
+    for form in [
+        value.to_string(),
+        percent_encode(value, "%20"),
+        percent_encode(value, "+"),
+    ] {
+        if masked.contains(form.as_str()) {
+            masked = masked.replace(form.as_str(), SECRET_MASK);
+        }
+    }
+    masked
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986, writing spaces as
+/// `space`.
+fn percent_encode(value: &str, space: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else if byte == b' ' {
+            encoded.push_str(space);
+        } else {
+            encoded.push('%');
+            for digit in [byte >> 4, byte & 0xF] {
+                if let Some(digit) = char::from_digit(u32::from(digit), 16) {
+                    encoded.push(digit.to_ascii_uppercase());
+                }
+            }
+        }
+    }
+    encoded
+}
+
+/// Whether `name` can be referenced as `{{secret.name}}`.
+pub fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || matches!(char, '_' | '-' | '.'))
+}
+
+fn secret_reference_name(reference: &str) -> Option<&str> {
+    reference
+        .trim()
+        .strip_prefix(SECRET_REFERENCE_PREFIX)
+        .filter(|name| is_valid_secret_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets() -> Secrets {
+        Secrets::new([
+            ("token".to_string(), "abc123".to_string()),
+            ("api.key".to_string(), "abc123-long".to_string()),
+            ("empty".to_string(), String::new()),
+        ])
+    }
+
+    #[test]
+    fn test_resolve_secret_references() {
+        let secrets = secrets();
+
+        assert_eq!(
+            secrets.resolve("Bearer {{secret.token}}").unwrap(),
+            "Bearer abc123"
+        );
+        assert_eq!(
+            secrets
+                .resolve("https://example.com/?key={{ secret.api.key }}&t={{secret.token}}")
+                .unwrap(),
+            "https://example.com/?key=abc123-long&t=abc123"
+        );
+        assert_eq!(
+            secrets.resolve("{{secret.empty}}-{{name}}").unwrap(),
+            "-{{name}}"
+        );
+        assert_eq!(
+            secrets.resolve("{{ {{secret.token}} }}").unwrap(),
+            "{{ abc123 }}"
+        );
+        assert_eq!(secrets.resolve("{{secret.token").unwrap(), "{{secret.token");
+        assert_eq!(
+            secrets.resolve("{{secret.}} {{secret.a b}}").unwrap(),
+            "{{secret.}} {{secret.a b}}"
+        );
+        assert_eq!(
+            secrets
+                .resolve("{{secret.missing}}")
+                .unwrap_err()
+                .to_string(),
+            "unknown secret \"missing\""
+        );
+    }
+
+    #[test]
+    fn test_mask_secret_values() {
+        let secrets = secrets();
+
+        assert_eq!(
+            secrets.mask("key=abc123-long&t=abc123"),
+            format!("key={SECRET_MASK}&t={SECRET_MASK}")
+        );
+        assert_eq!(secrets.mask("nothing to hide"), "nothing to hide");
+
+        let secrets = Secrets::new([("key".to_string(), "a+b/c= d".to_string())]);
+        assert_eq!(
+            secrets.mask("?raw=a+b/c= d&url=a%2Bb%2Fc%3D%20d&form=a%2Bb%2Fc%3D+d"),
+            format!("?raw={SECRET_MASK}&url={SECRET_MASK}&form={SECRET_MASK}")
+        );
+        assert_eq!(Secrets::default().mask("abc123"), "abc123");
+    }
+}
@@ -0,0 +1,339 @@
+mod cipher;
+mod persistence;
+mod references;
+mod secrets_modal;
+
+use anyhow::Context as _;
+use gpui::{App, AppContext as _, Context, Entity, Global, Task};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use collections::HashMap;
+use workspace::Workspace;
+
+use cipher::SecretKey;
+use persistence::SecretsDb;
+use secrets_modal::SecretsModal;
+
+pub use references::{SECRET_MASK, Secrets, is_valid_secret_name, mask_value};
+
+/// The environment of a project until another one is chosen.
+pub const DEFAULT_ENVIRONMENT: &str = "default";
+
+const SECRETS_KEY_FILE_NAME: &str = "secrets.key";
+
+pub fn init(cx: &mut App) {
+    init_store(path::data_dir().join(SECRETS_KEY_FILE_NAME), cx);
+
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace.register_action(
+                |workspace, _: &actions::secrets::ManageSecrets, window, cx| {
+                    let Some(project_path) = workspace.project().read(cx).root(cx) else {
+                        return;
+                    };
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        SecretsModal::new(project_path, window, cx)
+                    });
+                },
+            );
+        },
+    )
+    .detach();
+}
+
+/// Sets up the secret store with its key at `key_path` instead of the app data directory.
+#[cfg(any(test, feature = "test"))]
+pub fn init_test(key_path: PathBuf, cx: &mut App) {
+    init_store(key_path, cx);
+}
+
+fn init_store(key_path: PathBuf, cx: &mut App) {
+    let store = cx.new(|cx| SecretStore::new(key_path, cx));
+    cx.set_global(GlobalSecretStore(store));
+}
+
+struct GlobalSecretStore(Entity<SecretStore>);
+
+impl Global for GlobalSecretStore {}
+
+#[derive(Default)]
+struct ProjectSecrets {
+    active_environment: Option<String>,
+    environments: BTreeMap<String, Secrets>,
+}
+
+/// The secrets of every project, kept in the app database rather than in the project, and
+/// encrypted with a key that never leaves this machine.
+pub struct SecretStore {
+    /// Set once the key has been read and the stored secrets decrypted.
+    key: Option<Arc<SecretKey>>,
+    /// The secrets of each project, by the path of its root.
+    projects: HashMap<PathBuf, ProjectSecrets>,
+    _load_task: Task<()>,
+}
+
+impl SecretStore {
+    fn new(key_path: PathBuf, cx: &mut Context<Self>) -> Self {
+        let db = SecretsDb::global(cx);
+        let load_secrets = cx.background_spawn(async move {
+            let key = SecretKey::load_or_create(&key_path)?;
+            let mut projects = HashMap::<PathBuf, ProjectSecrets>::default();
+            for (project_path, environment) in db.active_environments()? {
+                projects.entry(project_path).or_default().active_environment = Some(environment);
+            }
+            for (project_path, environment, name, value) in db.secrets()? {
+                match key.decrypt(&value) {
+                    Ok(value) => projects
+                        .entry(project_path)
+                        .or_default()
+                        .environments
+                        .entry(environment)
+                        .or_default()
+                        .insert(name, value),
+                    Err(error) => log::error!("Failed to decrypt secret {name}: {error:?}"),
+                }
+            }
+            anyhow::Ok((key, projects))
+        });
+
+        Self {
+            key: None,
+            projects: HashMap::default(),
+            _load_task: cx.spawn(async move |store, cx| {
+                let (key, projects) = match load_secrets.await {
+                    Ok(loaded) => loaded,
+                    Err(error) => {
+                        log::error!("Failed to load secrets: {error:?}");
+                        return;
+                    }
+                };
+                if let Err(error) = store.update(cx, |store, cx| {
+                    store.key = Some(Arc::new(key));
+                    store.projects = projects;
+                    cx.notify();
+                }) {
+                    log::debug!("Failed to update secret store: {error:?}");
+                }
+            }),
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalSecretStore>().0.clone()
+    }
+
+    /// The secrets a request sent from the project at `project_path` can reference, or none if
+    /// the store has not been set up.
+    pub fn active_secrets(project_path: &Path, cx: &App) -> Secrets {
+        cx.try_global::<GlobalSecretStore>()
+            .map(|store| {
+                let store = store.0.read(cx);
+                store.secrets(project_path, store.active_environment(project_path))
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub fn active_environment(&self, project_path: &Path) -> &str {
+        self.projects
+            .get(project_path)
+            .and_then(|project| project.active_environment.as_deref())
+            .unwrap_or(DEFAULT_ENVIRONMENT)
+    }
+
+    /// The environments of the project that have secrets, along with the default and active
+    /// ones, in name order.
+    pub fn environments(&self, project_path: &Path) -> Vec<String> {
+        let mut environments = BTreeSet::from([
+            DEFAULT_ENVIRONMENT.to_string(),
+            self.active_environment(project_path).to_string(),
+        ]);
+        if let Some(project) = self.projects.get(project_path) {
+            environments.extend(
+                project
+                    .environments
+                    .iter()
+                    .filter(|(_, secrets)| !secrets.is_empty())
+                    .map(|(environment, _)| environment.clone()),
+            );
+        }
+        environments.into_iter().collect()
+    }
+
+    pub fn secrets(&self, project_path: &Path, environment: &str) -> Secrets {
+        self.projects
+            .get(project_path)
+            .and_then(|project| project.environments.get(environment))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_active_environment(
+        &mut self,
+        project_path: &Path,
+        environment: &str,
+        cx: &mut Context<Self>,
+    ) {
+        self.projects
+            .entry(project_path.to_path_buf())
+            .or_default()
+            .active_environment = Some(environment.to_string());
+        cx.notify();
+
+        let db = SecretsDb::global(cx);
+        let project_path = project_path.to_path_buf();
+        let environment = environment.to_string();
+        cx.background_spawn(async move {
+            if let Err(error) = db.save_active_environment(project_path, environment).await {
+                log::error!("Failed to save the active secrets environment: {error:?}");
+            }
+        })
+        .detach();
+    }
+
+    pub fn set_secret(
+        &mut self,
+        project_path: &Path,
+        environment: &str,
+        name: &str,
+        value: &str,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            is_valid_secret_name(name),
+            "Secret names may only contain letters, digits, '_', '-' and '.'."
+        );
+        let key = self.key.as_ref().context("Secrets are still loading.")?;
+        let encrypted_value = key.encrypt(value)?;
+
+        self.projects
+            .entry(project_path.to_path_buf())
+            .or_default()
+            .environments
+            .entry(environment.to_string())
+            .or_default()
+            .insert(name.to_string(), value.to_string());
+        cx.notify();
+
+        let db = SecretsDb::global(cx);
+        let project_path = project_path.to_path_buf();
+        let environment = environment.to_string();
+        let name = name.to_string();
+        cx.background_spawn(async move {
+            if let Err(error) = db
+                .save_secret(project_path, environment, name, encrypted_value)
+                .await
+            {
+                log::error!("Failed to save secret: {error:?}");
+            }
+        })
+        .detach();
+        Ok(())
+    }
+
+    pub fn delete_secret(
+        &mut self,
+        project_path: &Path,
+        environment: &str,
+        name: &str,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(secrets) = self
+            .projects
+            .get_mut(project_path)
+            .and_then(|project| project.environments.get_mut(environment))
+        {
+            secrets.remove(name);
+            cx.notify();
+        }
+
+        let db = SecretsDb::global(cx);
+        let project_path = project_path.to_path_buf();
+        let environment = environment.to_string();
+        let name = name.to_string();
+        cx.background_spawn(async move {
+            if let Err(error) = db.delete_secret(project_path, environment, name).await {
+                log::error!("Failed to delete secret: {error:?}");
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+
+    #[gpui::test]
+    async fn test_secret_store(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join(SECRETS_KEY_FILE_NAME);
+        let project_path = PathBuf::from("/projects/test_secret_store");
+        cx.update(|cx| init_test(key_path.clone(), cx));
+        cx.run_until_parked();
+
+        let store = cx.update(SecretStore::global);
+        store.update(cx, |store, cx| {
+            assert!(store.is_loaded());
+            assert_eq!(store.active_environment(&project_path), DEFAULT_ENVIRONMENT);
+            assert_eq!(store.environments(&project_path), vec!["default"]);
+
+            store
+                .set_secret(&project_path, "default", "token", "dev-token", cx)
+                .unwrap();
+            store
+                .set_secret(&project_path, "production", "token", "prod-token", cx)
+                .unwrap();
+            store
+                .set_secret(&project_path, "production", "not a name", "value", cx)
+                .unwrap_err();
+            store.set_active_environment(&project_path, "production", cx);
+        });
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            assert_eq!(
+                SecretStore::active_secrets(&project_path, cx),
+                Secrets::new([("token".to_string(), "prod-token".to_string())])
+            );
+        });
+
+        // Reloading decrypts what was saved.
+        let reloaded_store = cx.new(|cx| SecretStore::new(key_path, cx));
+        cx.run_until_parked();
+        reloaded_store.update(cx, |store, cx| {
+            assert_eq!(store.active_environment(&project_path), "production");
+            assert_eq!(
+                store.environments(&project_path),
+                vec!["default", "production"]
+            );
+            assert_eq!(
+                store.secrets(&project_path, "default").get("token"),
+                Some("dev-token")
+            );
+
+            store.delete_secret(&project_path, "default", "token", cx);
+        });
+        cx.run_until_parked();
+
+        let reloaded_store = cx.new(|cx| SecretStore::new(dir.path().join("other.key"), cx));
+        cx.run_until_parked();
+        reloaded_store.read_with(cx, |store, _| {
+            assert!(store.secrets(&project_path, "default").is_empty());
+            // Secrets encrypted with another key cannot be read.
+            assert!(store.secrets(&project_path, "production").is_empty());
+        });
+    }
+}
@@ -0,0 +1,398 @@
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, MouseButton, Render,
+    SharedString, Subscription, Window, prelude::*,
+};
+use std::{collections::BTreeSet, path::PathBuf};
+
+use input::{ErasedEditorEvent, InputField};
+use theme::ActiveTheme;
+use ui::{
+    Button, ButtonCommon, ButtonSize, ButtonVariant, Clickable, Color, Headline, HeadlineSize,
+    IconAsset, IconButton, IconButtonShape, StyledExt, Text, TextCommon, TextSize, Tooltip,
+};
+use workspace::{DismissDecision, ModalView};
+
+use crate::{SECRET_MASK, SecretStore, is_valid_secret_name};
+
+/// Adds, reveals and deletes the secrets of a project, and switches between its environments.
+pub(crate) struct SecretsModal {
+    focus_handle: FocusHandle,
+    store: Entity<SecretStore>,
+    project_path: PathBuf,
+    environment_name: Entity<InputField>,
+    secret_name: Entity<InputField>,
+    secret_value: Entity<InputField>,
+    /// The names of the secrets whose values are shown.
+    revealed: BTreeSet<String>,
+    error: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SecretsModal {
+    pub(crate) fn new(project_path: PathBuf, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let store = SecretStore::global(cx);
+        let environment_name = cx.new(|cx| InputField::new(window, cx, "staging").tab_index(0));
+        let secret_name = cx.new(|cx| {
+            InputField::new(window, cx, "token")
+                .label("Name")
+                .tab_index(1)
+        });
+        let secret_value = cx.new(|cx| {
+            InputField::new(window, cx, "Value")
+                .label("Value")
+                .tab_index(2)
+        });
+        let value_editor = secret_value.read(cx).editor().clone();
+        value_editor.set_masked(true, window, cx);
+
+        let mut subscriptions = vec![cx.observe(&store, |_, _, cx| cx.notify())];
+        for input in [&environment_name, &secret_name, &secret_value] {
+            let secrets_modal = cx.weak_entity();
+            let editor = input.read(cx).editor().clone();
+            subscriptions.push(editor.subscribe(
+                Box::new(move |event, _, cx| {
+                    if event == ErasedEditorEvent::BufferEdited
+                        && let Err(error) = secrets_modal.update(cx, |modal, cx| {
+                            modal.error = None;
+                            cx.notify();
+                        })
+                    {
+                        log::debug!("Failed to update secrets modal input state: {error:?}");
+                    }
+                }),
+                window,
+                cx,
+            ));
+        }
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            store,
+            project_path,
+            environment_name,
+            secret_name,
+            secret_value,
+            revealed: BTreeSet::new(),
+            error: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn cancel(&mut self, _: &actions::menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &actions::menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.environment_name.focus_handle(cx).is_focused(window) {
+            self.add_environment(window, cx);
+        } else {
+            self.add_secret(window, cx);
+        }
+    }
+
+    fn add_secret(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.secret_name.read(cx).value(cx).trim().to_string();
+        let value = self.secret_value.read(cx).value(cx);
+        if name.is_empty() {
+            self.error = Some("Enter a secret name.".into());
+            cx.notify();
+            return;
+        }
+
+        let project_path = self.project_path.clone();
+        let result = self.store.update(cx, |store, cx| {
+            let environment = store.active_environment(&project_path).to_string();
+            store.set_secret(&project_path, &environment, &name, &value, cx)
+        });
+        match result {
+            Ok(()) => {
+                self.error = None;
+                self.secret_name
+                    .update(cx, |field, cx| field.clear(window, cx));
+                self.secret_value
+                    .update(cx, |field, cx| field.clear(window, cx));
+                window.focus(&self.secret_name.focus_handle(cx), cx);
+            }
+            Err(error) => self.error = Some(error.to_string().into()),
+        }
+        cx.notify();
+    }
+
+    fn add_environment(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let environment = self.environment_name.read(cx).value(cx).trim().to_string();
+        if !is_valid_secret_name(&environment) {
+            self.error = Some(
+                "Environment names may only contain letters, digits, '_', '-' and '.'.".into(),
+            );
+            cx.notify();
+            return;
+        }
+
+        self.select_environment(&environment, cx);
+        self.environment_name
+            .update(cx, |field, cx| field.clear(window, cx));
+    }
+
+    fn select_environment(&mut self, environment: &str, cx: &mut Context<Self>) {
+        let project_path = self.project_path.clone();
+        self.store.update(cx, |store, cx| {
+            store.set_active_environment(&project_path, environment, cx);
+        });
+        self.revealed.clear();
+        self.error = None;
+        cx.notify();
+    }
+
+    fn render_environments(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let store = self.store.read(cx);
+        let active_environment = store.active_environment(&self.project_path).to_string();
+        let environments = store.environments(&self.project_path);
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .gap_1()
+            .child(Text::new("Environment").size(TextSize::Small))
+            .child(
+                gpui::div()
+                    .flex()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_1()
+                    .children(environments.into_iter().map(|environment| {
+                        let is_active = environment == active_environment;
+                        Button::new(
+                            SharedString::from(format!("secrets-environment-{environment}")),
+                            environment.clone(),
+                        )
+                        .variant(if is_active {
+                            ButtonVariant::Solid
+                        } else {
+                            ButtonVariant::Ghost
+                        })
+                        .size(ButtonSize::Medium)
+                        .on_click(cx.listener(move |modal, _, _, cx| {
+                            modal.select_environment(&environment, cx);
+                        }))
+                    }))
+                    .child(
+                        gpui::div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .w(gpui::rems(12.0))
+                            .child(gpui::div().flex_1().child(self.environment_name.clone()))
+                            .child(
+                                IconButton::new("secrets-add-environment", IconAsset::Plus)
+                                    .shape(IconButtonShape::Square)
+                                    .variant(ButtonVariant::Outline)
+                                    .icon_color(Color::Muted)
+                                    .tooltip(Tooltip::text("Add Environment"))
+                                    .on_click(cx.listener(|modal, _, window, cx| {
+                                        modal.add_environment(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+
+    fn render_secrets(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let store = self.store.read(cx);
+        let is_loaded = store.is_loaded();
+        let active_environment = store.active_environment(&self.project_path).to_string();
+        let secrets = store.secrets(&self.project_path, &active_environment);
+        let colors = cx.theme().colors();
+
+        let rows = secrets
+            .iter()
+            .map(|(name, value)| {
+                let name = name.to_string();
+                let is_revealed = self.revealed.contains(&name);
+                let shown_value = if is_revealed { value } else { SECRET_MASK };
+
+                gpui::div()
+                    .flex()
+                    .items_center()
+                    .w_full()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(
+                        gpui::div()
+                            .w(gpui::rems(10.0))
+                            .min_w_0()
+                            .child(Text::new(name.clone()).truncate()),
+                    )
+                    .child(
+                        gpui::div().flex_1().min_w_0().child(
+                            Text::new(shown_value.to_string())
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                    )
+                    .child(
+                        Button::new(
+                            SharedString::from(format!("secret-reveal-{name}")),
+                            if is_revealed { "Hide" } else { "Show" },
+                        )
+                        .variant(ButtonVariant::Ghost)
+                        .size(ButtonSize::Medium)
+                        .on_click(cx.listener({
+                            let name = name.clone();
+                            move |modal, _, _, cx| {
+                                if !modal.revealed.remove(&name) {
+                                    modal.revealed.insert(name.clone());
+                                }
+                                cx.notify();
+                            }
+                        })),
+                    )
+                    .child(
+                        IconButton::new(
+                            SharedString::from(format!("secret-delete-{name}")),
+                            IconAsset::Trash,
+                        )
+                        .shape(IconButtonShape::Square)
+                        .variant(ButtonVariant::Outline)
+                        .icon_color(Color::Muted)
+                        .tooltip(Tooltip::text("Delete"))
+                        .on_click(cx.listener({
+                            let active_environment = active_environment.clone();
+                            move |modal, _, _, cx| {
+                                let project_path = modal.project_path.clone();
+                                modal.store.update(cx, |store, cx| {
+                                    store.delete_secret(
+                                        &project_path,
+                                        &active_environment,
+                                        &name,
+                                        cx,
+                                    );
+                                });
+                                modal.revealed.remove(&name);
+                            }
+                        })),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        let empty_message = if is_loaded {
+            format!("No secrets in the {active_environment} environment.")
+        } else {
+            "Loading secrets…".to_string()
+        };
+
+        gpui::div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .max_h(gpui::rems(16.0))
+            .overflow_hidden()
+            .rounded_md()
+            .border_1()
+            .border_color(colors.border_variant)
+            .when(rows.is_empty(), |this| {
+                this.child(
+                    gpui::div().p_2().child(
+                        Text::new(empty_message)
+                            .color(Color::Muted)
+                            .size(TextSize::Small),
+                    ),
+                )
+            })
+            .children(rows)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SecretsModal {}
+
+impl Focusable for SecretsModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.secret_name.focus_handle(cx)
+    }
+}
+
+impl ModalView for SecretsModal {
+    fn on_before_dismiss(&mut self, _: &mut Window, _: &mut Context<Self>) -> DismissDecision {
+        DismissDecision::Dismiss(true)
+    }
+}
+
+impl Render for SecretsModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let project_path = self.project_path.to_string_lossy().into_owned();
+
+        gpui::div()
+            .key_context("SecretsModal")
+            .track_focus(&self.focus_handle)
+            .on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                window.focus(&focus_handle, cx);
+            })
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .flex()
+            .flex_col()
+            .w(gpui::rems(40.0))
+            .overflow_hidden()
+            .elevation_3(cx)
+            .child(
+                gpui::div()
+                    .flex()
+                    .flex_col()
+                    .w_full()
+                    .gap_0p5()
+                    .px_3()
+                    .pt_3()
+                    .pb_2()
+                    .child(Headline::new("Secrets").size(HeadlineSize::Small))
+                    .child(
+                        Text::new(project_path)
+                            .size(TextSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .child(
+                gpui::div()
+                    .flex()
+                    .flex_col()
+                    .w_full()
+                    .gap_3()
+                    .px_3()
+                    .pb_3()
+                    .child(self.render_environments(cx))
+                    .child(self.render_secrets(cx))
+                    .child(
+                        gpui::div()
+                            .flex()
+                            .items_end()
+                            .w_full()
+                            .gap_2()
+                            .child(gpui::div().w(gpui::rems(12.0)).child(self.secret_name.clone()))
+                            .child(gpui::div().flex_1().child(self.secret_value.clone()))
+                            .child(
+                                Button::new("secrets-add", "Add")
+                                    .tab_index(3)
+                                    .variant(ButtonVariant::Solid)
+                                    .size(ButtonSize::Medium)
+                                    .on_click(cx.listener(|modal, _, window, cx| {
+                                        modal.add_secret(window, cx);
+                                    })),
+                            ),
+                    )
+                    .child(
+                        Text::new(
+                            "Secrets are stored encrypted outside the project. Reference one in a request as {{secret.name}}.",
+                        )
+                        .size(TextSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(Text::new(error).size(TextSize::Small).color(Color::Error))
+                    }),
+            )
+    }
+}
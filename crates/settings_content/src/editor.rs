@@ -54,4 +54,5 @@ pub enum CurrentLineHighlight {
 pub struct GutterContent {
     pub line_numbers: Option<bool>,
     pub min_line_number_digits: Option<usize>,
+    pub folds: Option<bool>,
 }
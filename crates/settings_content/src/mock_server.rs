@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use settings_macros::{MergeFrom, with_fallible_options};
+
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, MergeFrom)]
+pub struct MockServerSettingsContent {
+    pub port: Option<u16>,
+    pub delay_ms: Option<u64>,
+    /// Whether to answer CORS preflight requests and allow any origin to read the responses.
+    pub cors: Option<bool>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use settings_macros::{MergeFrom, with_fallible_options};
+
+/// How the project panel orders the entries of a folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectPanelSort {
+    /// Folders first, then requests, alphabetically.
+    #[default]
+    Alphabetical,
+    /// In the order set by dragging entries, kept in the folder's `_folder.toml` file, with the
+    /// other entries after them alphabetically.
+    Manual,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, MergeFrom)]
+pub struct ProjectPanelSettingsContent {
+    pub sort: Option<ProjectPanelSort>,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+use settings_macros::{MergeFrom, with_fallible_options};
+
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, MergeFrom)]
+pub struct ResponseHistorySettingsContent {
+    pub enabled: Option<bool>,
+    pub max_entries: Option<u32>,
+}
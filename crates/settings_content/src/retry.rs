@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use settings_macros::{MergeFrom, with_fallible_options};
+
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, MergeFrom)]
+pub struct RetrySettingsContent {
+    pub max_attempts: Option<u32>,
+    pub statuses: Option<Vec<u16>>,
+    pub errors: Option<Vec<RetryErrorKind>>,
+    pub initial_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
+    pub retry_non_idempotent: Option<bool>,
+}
+
+/// Failures to send a request that are worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryErrorKind {
+    Connect,
+    Timeout,
+}
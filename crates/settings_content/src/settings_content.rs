@@ -2,6 +2,11 @@ mod editor;
 mod fallible_options;
 mod git;
 pub mod merge_from;
+mod mock_server;
+mod project_panel;
+mod request_history;
+mod response_history;
+mod retry;
 mod theme;
 mod ui;
 mod update;
@@ -10,6 +15,11 @@ pub use editor::*;
 pub use fallible_options::*;
 pub use git::*;
 pub use merge_from::MergeFrom as MergeFromTrait;
+pub use mock_server::*;
+pub use project_panel::*;
+pub use request_history::*;
+pub use response_history::*;
+pub use retry::*;
 pub use theme::*;
 pub use ui::*;
 pub use update::*;
@@ -34,6 +44,11 @@ pub struct SettingsContent {
     pub ui: Option<UiSettingsContent>,
     pub editor: Option<EditorSettingsContent>,
     pub git: Option<GitSettingsContent>,
+    pub project_panel: Option<ProjectPanelSettingsContent>,
     pub update: Option<UpdateSettingsContent>,
+    pub response_history: Option<ResponseHistorySettingsContent>,
+    pub retry: Option<RetrySettingsContent>,
+    pub mock_server: Option<MockServerSettingsContent>,
+    pub request_history: Option<RequestHistorySettingsContent>,
     pub log: Option<HashMap<String, String>>,
 }
@@ -53,6 +53,7 @@ pub enum IconAsset {
     FolderOpen,
     FolderPlus,
     GitBranch,
+    History,
     Info,
     LinuxClose,
     LinuxMaximize,
@@ -65,6 +66,7 @@ pub enum IconAsset {
     Option,
     Plus,
     Return,
+    Server,
     Shift,
     SquareDot,
     SquareMinus,
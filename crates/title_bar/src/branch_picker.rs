@@ -0,0 +1,374 @@
+use fuzzy_nucleo::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    PromptLevel, Render, SharedString, Styled, Task, WeakEntity, Window, prelude::*,
+};
+use std::{
+    cmp::{self, Reverse},
+    sync::{Arc, atomic::AtomicBool},
+};
+
+use git::repository::Branch;
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use ui::{
+    Color, HighlightedText, Icon, IconAsset, IconSize, ListItem, ListItemSpacing, Text, TextCommon,
+    TextSize, Toggleable,
+};
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace.register_action(
+                |workspace, _: &actions::branch_picker::Toggle, window, cx| {
+                    BranchPicker::toggle(workspace, window, cx);
+                },
+            );
+        },
+    )
+    .detach();
+}
+
+/// Lists the local and remote branches of the active repository and checks out the chosen one.
+pub(crate) struct BranchPicker {
+    picker: Entity<Picker<BranchPickerDelegate>>,
+}
+
+impl BranchPicker {
+    fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(repository) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .active_repository()
+        else {
+            return;
+        };
+        let weak_workspace = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            BranchPicker::new(weak_workspace, repository, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        repository: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let snapshot = repository.read(cx).snapshot();
+        let delegate = BranchPickerDelegate {
+            branch_picker: cx.entity().downgrade(),
+            workspace,
+            repository,
+            branches: branch_candidates(&snapshot.branch_list).into(),
+            branch_list_error: snapshot.branch_list_error,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(delegate, window, cx)
+                .initial_width(gpui::rems(28.0))
+                .height(gpui::rems(20.0))
+                .no_vertical_padding()
+        });
+
+        Self { picker }
+    }
+}
+
+impl ModalView for BranchPicker {}
+
+impl EventEmitter<DismissEvent> for BranchPicker {}
+
+impl Focusable for BranchPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for BranchPicker {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        gpui::div()
+            .flex()
+            .flex_col()
+            .key_context("BranchPicker")
+            .child(self.picker.clone())
+    }
+}
+
+/// The branches that can be checked out: the current branch, then the other local branches and
+/// then the remote ones, each with the most recently committed first. The symbolic `HEAD` of a
+/// remote is left out, as it only points at one of its other branches.
+fn branch_candidates(branch_list: &[Branch]) -> Vec<Branch> {
+    let mut branches = branch_list
+        .iter()
+        .filter(|branch| !(branch.is_remote() && branch.name().ends_with("/HEAD")))
+        .cloned()
+        .collect::<Vec<_>>();
+    branches.sort_by_key(|branch| {
+        (
+            branch.is_remote(),
+            !branch.is_head,
+            Reverse(
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| commit.commit_timestamp),
+            ),
+            branch.name().to_string(),
+        )
+    });
+    branches
+}
+
+/// Checks out `branch`, asking first if open requests have unsaved changes, as the requests are
+/// reloaded from the checked out files afterwards.
+fn checkout_branch(
+    workspace: &Workspace,
+    repository: Entity<Repository>,
+    branch: Branch,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let project = workspace.project().clone();
+    let dirty_paths = project.read(cx).dirty_request_paths(cx);
+    let prompt = (!dirty_paths.is_empty()).then(|| {
+        let path_style = project.read(cx).path_style(cx);
+        let detail = dirty_paths
+            .iter()
+            .map(|project_path| project_path.path.display(path_style).into_owned())
+            .collect::<Vec<_>>()
+            .join("\n");
+        window.prompt(
+            PromptLevel::Warning,
+            &format!("Discard unsaved changes to check out {}?", branch.name()),
+            Some(&detail),
+            &["Discard Changes", "Cancel"],
+            cx,
+        )
+    });
+
+    let task = window.spawn(cx, async move |cx| {
+        if let Some(prompt) = prompt
+            && !matches!(prompt.await, Ok(0))
+        {
+            return anyhow::Ok(());
+        }
+
+        cx.update(|_, cx| {
+            repository.update(cx, |repository, cx| repository.checkout_branch(branch, cx))
+        })?
+        .await?;
+        cx.update(|_, cx| project.update(cx, |project, cx| project.reload_request_buffers(cx)))?
+            .await;
+        anyhow::Ok(())
+    });
+    task.detach_and_prompt_err("Failed to check out the branch", window, cx, |_, _, _| None);
+}
+
+pub(crate) struct BranchPickerDelegate {
+    branch_picker: WeakEntity<BranchPicker>,
+    workspace: WeakEntity<Workspace>,
+    repository: Entity<Repository>,
+    branches: Arc<[Branch]>,
+    branch_list_error: Option<SharedString>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for BranchPickerDelegate {
+    type ListItem = ListItem;
+
+    fn name() -> &'static str {
+        "branch picker"
+    }
+
+    fn placeholder_text(&self, _: &mut Window, _: &mut App) -> Arc<str> {
+        "Check out a branch...".into()
+    }
+
+    fn no_matches_text(&self, _: &mut Window, _: &mut App) -> Option<SharedString> {
+        if let Some(error) = &self.branch_list_error {
+            Some(error.clone())
+        } else if self.branches.is_empty() {
+            Some("This repository has no branches.".into())
+        } else {
+            Some("No branches match your search.".into())
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, index: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = index;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let branches = self.branches.clone();
+        let executor = cx.background_executor().clone();
+        cx.spawn_in(window, async move |picker, cx| {
+            let match_candidates = branches
+                .iter()
+                .enumerate()
+                .map(|(index, branch)| StringMatchCandidate::new(index, branch.name()))
+                .collect::<Vec<_>>();
+            let mut matches = fuzzy_nucleo::match_strings_async(
+                &match_candidates,
+                &query,
+                fuzzy_nucleo::Case::Smart,
+                fuzzy_nucleo::LengthPenalty::On,
+                10000,
+                &AtomicBool::default(),
+                executor,
+            )
+            .await;
+            // Without a query, the branches keep their order instead of the order of the scores.
+            if query.is_empty() {
+                matches.sort_by_key(|string_match| string_match.candidate_id);
+            }
+
+            if let Err(error) = picker.update(cx, |picker, _| {
+                let delegate = &mut picker.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = cmp::min(
+                    delegate.selected_index,
+                    delegate.matches.len().saturating_sub(1),
+                );
+            }) {
+                log::debug!("Failed to update branch picker matches: {error:?}");
+            }
+        })
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Err(error) = self.branch_picker.update(cx, |_, cx| {
+            cx.emit(DismissEvent);
+        }) {
+            log::debug!("Failed to dismiss branch picker: {error:?}");
+        }
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let branch = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|string_match| self.branches.get(string_match.candidate_id))
+            .filter(|branch| !branch.is_head)
+            .cloned();
+        if let Some(branch) = branch
+            && let Some(workspace) = self.workspace.upgrade()
+        {
+            let repository = self.repository.clone();
+            workspace.update(cx, |workspace, cx| {
+                checkout_branch(workspace, repository, branch, window, cx);
+            });
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn render_match(
+        &self,
+        index: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let string_match = self.matches.get(index)?;
+        let branch = self.branches.get(string_match.candidate_id)?;
+
+        Some(
+            ListItem::new(index)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    gpui::div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .w_full()
+                        .min_w_0()
+                        .py_px()
+                        .child(
+                            gpui::div().flex_1().min_w_0().child(
+                                HighlightedText::new(
+                                    branch.name().to_string(),
+                                    string_match.positions.clone(),
+                                )
+                                .single_line()
+                                .truncate(),
+                            ),
+                        )
+                        .when(branch.is_head, |this| {
+                            this.child(
+                                Icon::new(IconAsset::Check)
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(branch.is_remote(), |this| {
+                            this.child(
+                                Text::new("remote")
+                                    .size(TextSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            )
+                        }),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use git::repository::CommitSummary;
+
+    fn branch(ref_name: &'static str, is_head: bool, commit_timestamp: i64) -> Branch {
+        Branch {
+            is_head,
+            ref_name: ref_name.into(),
+            upstream: None,
+            most_recent_commit: Some(CommitSummary {
+                sha: "0000000".into(),
+                subject: "Commit".into(),
+                commit_timestamp,
+                author_name: "Author".into(),
+                has_parent: false,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_branch_candidates() {
+        let branches = branch_candidates(&[
+            branch("refs/remotes/origin/HEAD", false, 3),
+            branch("refs/remotes/origin/main", false, 3),
+            branch("refs/heads/feature", false, 1),
+            branch("refs/heads/release", false, 2),
+            branch("refs/heads/main", true, 0),
+        ]);
+
+        assert_eq!(
+            branches.iter().map(Branch::name).collect::<Vec<_>>(),
+            ["main", "release", "feature", "origin/main"]
+        );
+    }
+}
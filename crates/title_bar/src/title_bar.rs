@@ -1,9 +1,10 @@
 mod application_menu;
+mod branch_picker;
 
 pub use platform_title_bar::{self, PlatformTitleBar};
 
 use gpui::{
-    AnyElement, App, Context, ElementId, Entity, MouseButton, SharedString, Subscription,
+    Action, AnyElement, App, Context, ElementId, Entity, MouseButton, SharedString, Subscription,
     WeakEntity, Window, WindowButton, prelude::*,
 };
 use smallvec::SmallVec;
@@ -16,7 +17,7 @@ use project::{
 };
 use ui::{
     ActiveTheme, Color, DynamicSpacing, Icon, IconAsset, IconSize, PlatformStyle, Svg, SvgAsset,
-    Text, TextCommon, TextSize,
+    Text, TextCommon, TextSize, Tooltip,
 };
 use workspace::Workspace;
 
@@ -27,6 +28,7 @@ const MAX_BRANCH_NAME_LENGTH: usize = 40;
 const MAX_SHORT_SHA_LENGTH: usize = 8;
 
 pub fn init(cx: &mut App) {
+    branch_picker::init(cx);
     cx.observe_new(|workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
@@ -136,9 +138,18 @@ impl TitleBar {
 
         Some(
             gpui::div()
+                .id("title-bar-branch")
                 .flex()
                 .items_center()
                 .gap_1()
+                .px_1()
+                .rounded_sm()
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().colors().ghost_element_hover))
+                .tooltip(Tooltip::text("Switch Branch"))
+                .on_click(|_, window, cx| {
+                    window.dispatch_action(actions::branch_picker::Toggle.boxed_clone(), cx);
+                })
                 .child(
                     Icon::new(IconAsset::GitBranch)
                         .size(IconSize::XSmall)
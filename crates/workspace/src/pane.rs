@@ -36,6 +36,8 @@ pub enum PaneEvent {
         item: Box<dyn ItemHandle>,
     },
     ChangeItemTitle,
+    /// The pane, or an item in it, was focused.
+    Focus,
     UserSavedItem {
         item: Box<dyn WeakItemHandle>,
         save_intent: actions::pane::SaveIntent,
@@ -1091,6 +1093,7 @@ impl Pane {
             if !self.suppress_scroll && self.items.get(self.active_item_index).is_some() {
                 self.update_active_tab(self.active_item_index);
             }
+            cx.emit(PaneEvent::Focus);
             cx.notify();
         }
 
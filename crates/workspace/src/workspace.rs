@@ -1170,7 +1170,11 @@ pub struct Workspace {
     project: Entity<Project>,
     left_dock: Entity<Dock>,
     bottom_dock: Entity<Dock>,
+    /// The active pane, which is one of `panes`.
     pane: Entity<Pane>,
+    /// The panes of the center, from left to right. There is more than one once the center is
+    /// split.
+    panes: Vec<Entity<Pane>>,
     panes_by_item: HashMap<EntityId, WeakEntity<Pane>>,
     status_bar: Entity<StatusBar>,
     pub(crate) modal_layer: Entity<ModalLayer>,
@@ -1547,7 +1551,7 @@ impl Workspace {
 
             let cleanup_tasks = workspace.update_in(cx, |workspace, window, cx| {
                 if let Some(pane) = pane {
-                    workspace.set_active_pane(&pane, window, cx);
+                    workspace.replace_center_pane(&pane, window, cx);
                     cx.emit(WorkspaceEvent::PaneRestored(pane.clone()));
                     cx.focus_self(window);
                 }
@@ -1591,7 +1595,10 @@ impl Workspace {
         let current_workspace = cx.entity();
 
         let has_worktree = self.project.read(cx).root_worktree(cx).is_some();
-        let has_dirty_items = self.pane.read(cx).items().any(|item| item.is_dirty(cx));
+        let has_dirty_items = self
+            .panes
+            .iter()
+            .any(|pane| pane.read(cx).items().any(|item| item.is_dirty(cx)));
         let is_empty_workspace = !has_worktree && !has_dirty_items;
         if is_empty_workspace {
             open_mode = OpenMode::Activate;
@@ -1727,10 +1734,63 @@ impl Workspace {
         self.titlebar_item.clone()
     }
 
+    /// The active pane.
     pub fn pane(&self) -> &Entity<Pane> {
         &self.pane
     }
 
+    /// The panes of the center, from left to right.
+    pub fn panes(&self) -> &[Entity<Pane>] {
+        &self.panes
+    }
+
+    /// Splits the active pane, adding an empty pane to its right, which becomes active.
+    pub fn split_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Pane> {
+        let pane = self.add_pane(window, cx);
+        let index = self
+            .panes
+            .iter()
+            .position(|existing| existing == &self.pane)
+            .map_or(self.panes.len(), |index| index + 1);
+        self.panes.insert(index, pane.clone());
+        self.activate_pane(&pane, window, cx);
+        cx.notify();
+        pane
+    }
+
+    /// Opens `path` in a new pane to the right of the active one.
+    pub fn open_path_in_split(
+        &mut self,
+        path: ProjectPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Box<dyn ItemHandle>>> {
+        let pane = self.split_pane(window, cx);
+        self.open_path(path, Some(pane), true, window, cx)
+    }
+
+    /// Removes a pane of a split center, activating its neighbor if it was active.
+    fn remove_pane(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
+        if self.panes.len() < 2 {
+            return;
+        }
+        let Some(index) = self.panes.iter().position(|existing| existing == pane) else {
+            return;
+        };
+        self.panes.remove(index);
+        if &self.pane == pane
+            && let Some(neighbor) = self
+                .panes
+                .get(index.saturating_sub(1))
+                .or(self.panes.first())
+                .cloned()
+        {
+            self.activate_pane(&neighbor, window, cx);
+            window.focus(&neighbor.read(cx).focus_handle(cx), cx);
+        }
+        cx.notify();
+    }
+
     fn add_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Pane> {
         let workspace = self.weak_self.clone();
         let project = self.project.clone();
@@ -1748,7 +1808,8 @@ impl Workspace {
         pane
     }
 
-    fn set_active_pane(
+    /// Replaces the panes of the center with `pane`, like when the workspace is restored.
+    fn replace_center_pane(
         &mut self,
         pane: &Entity<Pane>,
         window: &mut Window,
@@ -1773,7 +1834,12 @@ impl Workspace {
             }
             self.pane = pane.clone();
         }
+        self.panes = vec![pane.clone()];
+        self.activate_pane(pane, window, cx);
+    }
 
+    fn activate_pane(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
+        self.pane = pane.clone();
         self.status_bar.update(cx, |status_bar, cx| {
             status_bar.set_active_pane(pane, window, cx);
         });
@@ -1824,8 +1890,15 @@ impl Workspace {
             self.active_item_path_changed(cx);
         }
 
+        if matches!(event, PaneEvent::Focus) && &self.pane != pane && self.panes.contains(pane) {
+            self.activate_pane(pane, window, cx);
+        }
+
         if matches!(event, PaneEvent::RemovedItem { .. }) {
             cx.emit(WorkspaceEvent::ActiveItemChanged);
+            if pane.read(cx).items_len() == 0 {
+                self.remove_pane(pane, window, cx);
+            }
         }
 
         self.serialize_workspace(window, cx);
@@ -2110,7 +2183,8 @@ impl Workspace {
         };
 
         if let Some(root_path) = self.root(cx) {
-            let pane = self.pane.clone();
+            // Only the leftmost pane is restored, without the panes it was split into.
+            let pane = self.panes.first().unwrap_or(&self.pane).clone();
             let center_pane = serialize_pane_handle(&pane, window, cx);
             let serialized_workspace = SerializedWorkspace {
                 id: database_id,
@@ -2371,6 +2445,7 @@ impl Workspace {
             project,
             left_dock,
             bottom_dock,
+            panes: vec![pane.clone()],
             pane,
             panes_by_item: HashMap::default(),
             status_bar,
@@ -2813,9 +2888,24 @@ impl Render for Workspace {
                             .overflow_hidden()
                             .child(
                                 gpui::div()
+                                    .flex()
+                                    .flex_row()
                                     .flex_1()
                                     .overflow_hidden()
-                                    .child(self.pane.clone()),
+                                    .children(self.panes.iter().enumerate().map(
+                                        |(index, pane)| {
+                                            gpui::div()
+                                                .flex_1()
+                                                .h_full()
+                                                .min_w_0()
+                                                .overflow_hidden()
+                                                .when(index > 0, |this| {
+                                                    this.border_l_1()
+                                                        .border_color(cx.theme().colors().border)
+                                                })
+                                                .child(pane.clone())
+                                        },
+                                    )),
                             )
                             .child(self.bottom_dock.clone()),
                     )
@@ -3519,6 +3609,57 @@ mod tests {
         assert_item_labels(&pane, ["Second*^"], cx);
     }
 
+    #[gpui::test]
+    async fn test_split_pane(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let temp_fs = TempFs::new(cx.executor());
+        let app_state = cx.update(|cx| AppState::test_new(temp_fs.clone(), None, cx));
+        init_test(app_state, cx);
+
+        temp_fs.insert_tree(path!("project"), Value::default());
+
+        let project_path = temp_fs.path().join(path!("project"));
+        let project = Project::test_new(temp_fs.clone(), &project_path, cx).await;
+        let (workspace, cx) = build_workspace(&project, cx);
+        let pane = workspace.update_in(cx, |workspace, _, _| workspace.pane().clone());
+        add_labeled_item(&pane, "First", false, cx);
+
+        let split =
+            workspace.update_in(cx, |workspace, window, cx| workspace.split_pane(window, cx));
+        add_labeled_item(&split, "Second", false, cx);
+        cx.run_until_parked();
+        workspace.read_with(cx, |workspace, _| {
+            assert_eq!(workspace.panes(), [pane.clone(), split.clone()]);
+            assert_eq!(workspace.pane(), &split);
+        });
+        assert_item_labels(&pane, ["First*"], cx);
+        assert_item_labels(&split, ["Second*"], cx);
+
+        pane.update_in(cx, |pane, window, cx| {
+            window.focus(&pane.focus_handle(cx), cx);
+        });
+        cx.run_until_parked();
+        workspace.read_with(cx, |workspace, _| assert_eq!(workspace.pane(), &pane));
+
+        split
+            .update_in(cx, |pane, window, cx| {
+                pane.close_all_items(
+                    &actions::pane::CloseAllItems { save_intent: None },
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        workspace.read_with(cx, |workspace, _| {
+            assert_eq!(workspace.panes(), [pane.clone()]);
+            assert_eq!(workspace.pane(), &pane);
+        });
+        assert_item_labels(&pane, ["First*"], cx);
+    }
+
     #[gpui::test]
     async fn test_discard_all_reloads_from_disk(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -1,10 +1,15 @@
 use anyhow::{Context, anyhow};
 use serde::{Deserialize, Serialize};
-use std::mem;
-use toml_edit::{Item, Table};
+use std::{collections::BTreeMap, mem, ops::Range, path::Path};
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+use settings::RetryErrorKind;
 
 pub const REQUEST_FILE_VERSION: u32 = 1;
 
+/// The name of the file in a folder with the defaults of the requests in it and its subfolders.
+pub const FOLDER_DEFAULTS_FILE_NAME: &str = "_folder.toml";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RequestFileState {
     Parsed(RequestFile),
@@ -15,6 +20,8 @@ pub enum RequestFileState {
 pub struct RequestFile {
     pub meta: RequestFileMeta,
     pub http: RequestFileHttp,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<RequestFileExample>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -37,9 +44,51 @@ pub struct RequestFileHttp {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub params: Vec<RequestFileParam>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_variables: Vec<RequestFilePathVariable>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub headers: Vec<RequestFileHeader>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub body: Option<RequestFileBody>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<RequestFileSchema>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RequestFileRetry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<RequestFileAuth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scripts: Option<RequestFileScripts>,
+}
+
+impl RequestFileHttp {
+    /// The URL with the enabled parameters as its query string.
+    pub fn url_with_params(&self) -> String {
+        join_url_query(
+            &self.url,
+            self.params
+                .iter()
+                .filter(|param| param.is_in_url())
+                .map(|param| (param.name.as_str(), param.value.as_str())),
+        )
+    }
+
+    /// Files written before the URL and the parameters were kept in sync may have a query string
+    /// in `url`. It was sent ahead of the parameters, so it becomes the first parameters.
+    fn move_url_query_to_params(&mut self) {
+        let (url, Some(query)) = split_url_query(&self.url) else {
+            return;
+        };
+        let mut params = parse_query(query)
+            .into_iter()
+            .map(|(name, value)| RequestFileParam {
+                name,
+                value,
+                disabled: false,
+            })
+            .collect::<Vec<_>>();
+        params.append(&mut self.params);
+        self.params = params;
+        self.url = url;
+    }
 }
 
 impl Default for RequestFileHttp {
@@ -48,12 +97,19 @@ impl Default for RequestFileHttp {
             method: "GET".to_string(),
             url: String::new(),
             params: Vec::new(),
+            path_variables: Vec::new(),
             headers: Vec::new(),
             body: None,
+            schema: None,
+            retry: None,
+            auth: None,
+            scripts: None,
         }
     }
 }
 
+/// A query parameter, with its name and value written as they appear in the URL. Characters that
+/// can't appear there, like spaces, are encoded when the URL is built.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RequestFileParam {
     pub name: String,
@@ -62,6 +118,21 @@ pub struct RequestFileParam {
     pub disabled: bool,
 }
 
+impl RequestFileParam {
+    /// Whether the parameter is part of the URL's query string. Blank rows are left out.
+    pub fn is_in_url(&self) -> bool {
+        !self.disabled && (!self.name.is_empty() || !self.value.is_empty())
+    }
+}
+
+/// The value of a `:name` or `{name}` segment in the path of the URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestFilePathVariable {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RequestFileHeader {
     pub name: String,
@@ -97,12 +168,204 @@ impl RequestFileBodyType {
     }
 }
 
+/// JSON Schema files, relative to the worktree root, that the request and response bodies are
+/// validated against.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestFileSchema {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Keyed by status code (`"200"`), status class (`"2XX"`), or `"default"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub responses: BTreeMap<String, String>,
+}
+
+impl RequestFileSchema {
+    /// Returns the schema path for a response status, preferring an exact status code over its
+    /// class and the class over `"default"`.
+    pub fn response_schema(&self, status_code: u16) -> Option<&str> {
+        let class = status_code / 100;
+        self.responses
+            .get(&status_code.to_string())
+            .or_else(|| self.responses.get(&format!("{class}XX")))
+            .or_else(|| self.responses.get(&format!("{class}xx")))
+            .or_else(|| self.responses.get("default"))
+            .map(String::as_str)
+    }
+}
+
+/// Overrides of the global retry settings for this request. Unset fields use the settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestFileRetry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<u16>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<RetryErrorKind>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_non_idempotent: Option<bool>,
+}
+
+/// How the request is authorized. Values can reference secrets as `{{secret.name}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RequestFileAuth {
+    #[serde(rename = "oauth2")]
+    OAuth2(RequestFileOAuth2),
+    #[serde(rename = "aws_sigv4")]
+    AwsSigV4(RequestFileAwsSigV4),
+}
+
+/// An OAuth 2.0 configuration, whose access token is sent as the `Authorization` header.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RequestFileOAuth2 {
+    pub grant_type: OAuth2GrantType,
+    pub token_url: String,
+    /// Where the user signs in, for the authorization code grant.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub authorization_url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub client_secret: String,
+    /// Space-separated scopes to request.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scope: String,
+    /// The resource owner's credentials, for the password grant.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub username: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+    /// The port the authorization code grant's redirect listener binds to, for servers that only
+    /// accept registered redirect URIs. Any free port is used when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuth2GrantType {
+    #[default]
+    ClientCredentials,
+    Password,
+    AuthorizationCode,
+}
+
+impl OAuth2GrantType {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::ClientCredentials => "Client Credentials",
+            Self::Password => "Password",
+            Self::AuthorizationCode => "Authorization Code (PKCE)",
+        }
+    }
+}
+
+/// AWS Signature Version 4 credentials, which each request is signed with right before it is
+/// sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RequestFileAwsSigV4 {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// The session token of temporary credentials.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub session_token: String,
+    pub region: String,
+    pub service: String,
+    #[serde(default, skip_serializing_if = "util::serde::is_default")]
+    pub payload: AwsPayloadSigning,
+}
+
+/// How the request body is covered by the signature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AwsPayloadSigning {
+    /// The hash of the whole body is signed.
+    #[default]
+    Signed,
+    /// The body isn't signed, which saves hashing large uploads.
+    Unsigned,
+    /// The body is sent `aws-chunked`, with each chunk signed in turn.
+    Streaming,
+}
+
+impl AwsPayloadSigning {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Signed => "Signed",
+            Self::Unsigned => "Unsigned",
+            Self::Streaming => "Streaming (aws-chunked)",
+        }
+    }
+}
+
+/// Scripts run around sending the request, in addition to those of the folders it is in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestFileScripts {
+    /// Runs before the request is sent, and can change it.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pre_request: String,
+    /// Runs once the response has been received.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub post_response: String,
+}
+
+impl RequestFileScripts {
+    pub fn is_empty(&self) -> bool {
+        self.pre_request.trim().is_empty() && self.post_response.trim().is_empty()
+    }
+}
+
+/// The defaults a folder's [`FOLDER_DEFAULTS_FILE_NAME`] file sets for the requests in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FolderDefaults {
+    /// Scripts run for every request in the folder, before those of the request itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scripts: Option<RequestFileScripts>,
+    /// The file names of the folder's requests and subfolders, in the order the project panel
+    /// shows them in. Those left out come after, alphabetically.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+}
+
+/// A saved response, which the mock server returns for requests matching this one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestFileExample {
+    pub name: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<RequestFileHeader>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+    /// How long the mock server waits before responding, instead of the configured delay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+impl Default for RequestFileExample {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            status: 200,
+            headers: Vec::new(),
+            body: String::new(),
+            delay_ms: None,
+        }
+    }
+}
+
 pub fn serialize_request_file(request_file: &RequestFile) -> anyhow::Result<String> {
     let mut document = toml_edit::ser::to_document(request_file)?;
     promote_to_table(document.as_table_mut(), "meta")
         .context("Failed to serialize request meta")?;
     promote_to_table(document.as_table_mut(), "http")
         .context("Failed to serialize request http")?;
+    promote_to_array_of_tables(document.as_table_mut(), "examples")
+        .context("Failed to serialize request examples")?;
     Ok(document.to_string())
 }
 
@@ -127,13 +390,318 @@ fn promote_to_table(parent: &mut Table, key: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn promote_to_array_of_tables(parent: &mut Table, key: &str) -> anyhow::Result<()> {
+    let Some(item) = parent.get_mut(key) else {
+        return Ok(());
+    };
+    if item.is_array_of_tables() {
+        return Ok(());
+    }
+
+    let original_item = mem::take(item);
+    let array_of_tables = match original_item.into_array_of_tables() {
+        Ok(array_of_tables) => array_of_tables,
+        Err(original_item) => {
+            let item_type = original_item.type_name();
+            *item = original_item;
+            return Err(anyhow!(
+                "expected {key} to be array of tables, got {item_type}"
+            ));
+        }
+    };
+    *item = Item::ArrayOfTables(array_of_tables);
+    Ok(())
+}
+
 pub fn parse_request_file(contents: &str) -> RequestFileState {
     match toml::from_str::<RequestFile>(contents) {
-        Ok(request_file) => RequestFileState::Parsed(request_file),
+        Ok(mut request_file) => {
+            request_file.http.move_url_query_to_params();
+            RequestFileState::Parsed(request_file)
+        }
         Err(error) => RequestFileState::Invalid(error.to_string()),
     }
 }
 
+pub fn parse_folder_defaults(contents: &str) -> anyhow::Result<FolderDefaults> {
+    Ok(toml::from_str(contents)?)
+}
+
+/// Sets the order of a folder's entries in the `contents` of its [`FOLDER_DEFAULTS_FILE_NAME`]
+/// file, keeping the rest of the file as it is.
+pub fn set_folder_order(contents: &str, order: &[String]) -> anyhow::Result<String> {
+    let mut document = contents
+        .parse::<DocumentMut>()
+        .context("Failed to parse folder defaults")?;
+    if order.is_empty() {
+        document.remove("order");
+    } else {
+        let order = order.iter().map(String::as_str).collect::<Array>();
+        document.insert("order", Item::Value(Value::Array(order)));
+    }
+    Ok(document.to_string())
+}
+
+/// Whether the file at `path` is a request, rather than a folder's defaults or another file.
+pub fn is_request_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+        && path
+            .file_name()
+            .is_none_or(|file_name| file_name != FOLDER_DEFAULTS_FILE_NAME)
+}
+
+/// The byte range of the path of `url`, between the authority and the query string or fragment.
+fn url_path_range(url: &str) -> Range<usize> {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    let authority_start = url.find("://").map_or(0, |index| index + 3);
+    let start = url
+        .get(authority_start..end)
+        .and_then(|authority_and_path| authority_and_path.find('/'))
+        .map_or(end, |index| authority_start + index);
+    start..end
+}
+
+/// The path of `url`, between the authority and the query string or fragment.
+pub fn url_path(url: &str) -> &str {
+    url.get(url_path_range(url)).unwrap_or_default()
+}
+
+/// The name of a path segment written as `:name` or `{name}`. `{{name}}` is left alone.
+pub fn path_variable_name(segment: &str) -> Option<&str> {
+    let name = match segment.strip_prefix(':') {
+        Some(name) => name,
+        None => segment
+            .strip_prefix('{')
+            .and_then(|segment| segment.strip_suffix('}'))
+            .filter(|name| !name.starts_with('{'))?,
+    };
+    (!name.is_empty() && !name.contains(char::is_whitespace)).then_some(name)
+}
+
+/// The names of the path variables in `url`, in the order they first appear.
+pub fn path_variable_names(url: &str) -> Vec<String> {
+    let mut names = Vec::<String>::new();
+    for name in url_path(url).split('/').filter_map(path_variable_name) {
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_owned());
+        }
+    }
+    names
+}
+
+/// Replaces the path variables in `url` with their percent-encoded values. Fails if one of them
+/// has no value.
+pub fn substitute_path_variables(
+    url: &str,
+    path_variables: &[RequestFilePathVariable],
+) -> anyhow::Result<String> {
+    let path_range = url_path_range(url);
+    let (Some(before_path), Some(path), Some(after_path)) = (
+        url.get(..path_range.start),
+        url.get(path_range.clone()),
+        url.get(path_range.end..),
+    ) else {
+        return Ok(url.to_owned());
+    };
+
+    let segments = path
+        .split('/')
+        .map(|segment| {
+            let Some(name) = path_variable_name(segment) else {
+                return Ok(segment.to_owned());
+            };
+            let value = path_variables
+                .iter()
+                .find(|variable| variable.name == name)
+                .map(|variable| variable.value.as_str())
+                .filter(|value| !value.is_empty())
+                .with_context(|| format!("path variable `{name}` has no value"))?;
+            Ok(percent_encode_path_segment(value))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(format!("{before_path}{}{after_path}", segments.join("/")))
+}
+
+/// Splits `text` into the parts outside its `{{…}}` references, which may be nested, and the
+/// references. Each part is returned with whether it is a reference.
+fn split_references(text: &str) -> Vec<(&str, bool)> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{")
+        && let Some((before, reference)) = rest.split_at_checked(start)
+        && let Some(reference_len) = reference_len(reference)
+        && let Some((reference, after)) = reference.split_at_checked(reference_len)
+    {
+        parts.push((before, false));
+        parts.push((reference, true));
+        rest = after;
+    }
+    parts.push((rest, false));
+    parts
+}
+
+/// The length of the `{{…}}` reference `text` starts with, if it is closed.
+fn reference_len(text: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut index = 0;
+    while let Some(rest) = text.get(index..) {
+        if rest.starts_with("{{") {
+            depth += 1;
+            index += 2;
+        } else if rest.starts_with("}}") {
+            depth = depth.checked_sub(1)?;
+            index += 2;
+            if depth == 0 {
+                return Some(index);
+            }
+        } else {
+            index += rest.chars().next()?.len_utf8();
+        }
+    }
+    None
+}
+
+/// Percent-encodes the characters of a query parameter name or value that can't appear in the
+/// query string as they are: spaces, control characters, `&` and `#`, plus `=` in names. The rest
+/// is kept as it was written, including escapes, so encoding is idempotent. `{{…}}` references are
+/// kept as they are, so they can still be resolved once the URL is sent.
+fn escape_query_text(text: &str, escape_equals: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (part, is_reference) in split_references(text) {
+        if is_reference {
+            escaped.push_str(part);
+            continue;
+        }
+        for character in part.chars() {
+            if character == ' '
+                || character.is_ascii_control()
+                || matches!(character, '&' | '#')
+                || (escape_equals && character == '=')
+            {
+                escaped.push_str(&percent_encode_path_segment(
+                    character.encode_utf8(&mut [0; 4]),
+                ));
+            } else {
+                escaped.push(character);
+            }
+        }
+    }
+    escaped
+}
+
+/// Encodes a query parameter name for [`join_url_query`].
+pub fn escape_query_name(name: &str) -> String {
+    escape_query_text(name, true)
+}
+
+/// Encodes a query parameter value for [`join_url_query`].
+pub fn escape_query_value(value: &str) -> String {
+    escape_query_text(value, false)
+}
+
+/// Resolves the `{{…}}` references in a query parameter name or value with `resolve`, and
+/// percent-encodes the values they resolve to. The text around them is already written as it
+/// appears in the URL, so it is kept as it is. A reference that resolves to another reference, like
+/// `{{$base64 {{secret.name}}}}` once its secret is resolved, is kept for a later `resolve`.
+pub fn resolve_query_text(
+    text: &str,
+    mut resolve: impl FnMut(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let mut resolved = String::with_capacity(text.len());
+    for (part, is_reference) in split_references(text) {
+        if !is_reference {
+            resolved.push_str(part);
+            continue;
+        }
+        let value = resolve(part)?;
+        if reference_len(&value) == Some(value.len()) {
+            resolved.push_str(&value);
+        } else {
+            resolved.push_str(&percent_encode_path_segment(&value));
+        }
+    }
+    Ok(resolved)
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+fn percent_encode_path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push('%');
+            for digit in [byte >> 4, byte & 0xF] {
+                if let Some(digit) = char::from_digit(u32::from(digit), 16) {
+                    encoded.push(digit.to_ascii_uppercase());
+                }
+            }
+        }
+    }
+    encoded
+}
+
+/// Splits `url` into the URL without its query string, keeping any fragment, and the query string.
+pub fn split_url_query(url: &str) -> (String, Option<&str>) {
+    let (before_fragment, fragment) = match url.split_once('#') {
+        Some((before_fragment, fragment)) => (before_fragment, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = before_fragment.split_once('?') else {
+        return (url.to_owned(), None);
+    };
+
+    let mut url = base.to_owned();
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    (url, Some(query))
+}
+
+/// Splits a query string into names and values without decoding them, so joining them again with
+/// [`join_url_query`] gives back the same text.
+pub fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) => (name.to_owned(), value.to_owned()),
+            None => (pair.to_owned(), String::new()),
+        })
+        .collect()
+}
+
+/// Sets the query string of `url`, which must not have one, to `params`, encoded with
+/// [`escape_query_name`] and [`escape_query_value`]. Parameters without a value are written
+/// without `=`.
+pub fn join_url_query<'a>(
+    url: &str,
+    params: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let query = params
+        .into_iter()
+        .map(|(name, value)| {
+            let name = escape_query_name(name);
+            if value.is_empty() {
+                name
+            } else {
+                format!("{name}={}", escape_query_value(value))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    if query.is_empty() {
+        return url.to_owned();
+    }
+
+    match url.split_once('#') {
+        Some((base, fragment)) => format!("{base}?{query}#{fragment}"),
+        None => format!("{url}?{query}"),
+    }
+}
+
 pub fn request_method_short_name(method: &str) -> String {
     let method = method.trim().to_ascii_uppercase();
     match method.as_str() {
@@ -205,6 +773,7 @@ mod tests {
                             disabled: false,
                         },
                     ],
+                    path_variables: Vec::new(),
                     headers: vec![
                         RequestFileHeader {
                             name: "Content-Type".to_string(),
@@ -225,7 +794,12 @@ mod tests {
                             }"#}
                         .to_string(),
                     }),
+                    schema: None,
+                    retry: None,
+                    auth: None,
+                    scripts: None,
                 },
+                examples: Vec::new(),
             })
         );
     }
@@ -256,6 +830,7 @@ mod tests {
                         disabled: false,
                     },
                 ],
+                path_variables: Vec::new(),
                 headers: vec![
                     RequestFileHeader {
                         name: "Content-Type".to_string(),
@@ -276,7 +851,12 @@ mod tests {
                         }"#}
                     .to_string(),
                 }),
+                schema: None,
+                retry: None,
+                auth: None,
+                scripts: None,
             },
+            examples: Vec::new(),
         };
 
         let serialized = serialize_request_file(&request_file).unwrap();
@@ -301,4 +881,422 @@ mod tests {
             RequestFileState::Parsed(request_file)
         );
     }
+
+    #[test]
+    fn test_request_file_examples() {
+        let request_file = RequestFile {
+            http: RequestFileHttp {
+                url: "https://api.zaku.dev/users/:id".to_string(),
+                ..RequestFileHttp::default()
+            },
+            examples: vec![
+                RequestFileExample {
+                    name: "Found".to_string(),
+                    status: 200,
+                    headers: vec![RequestFileHeader {
+                        name: "Content-Type".to_string(),
+                        value: "application/json".to_string(),
+                        disabled: false,
+                    }],
+                    body: "{\n  \"id\": 7\n}".to_string(),
+                    delay_ms: None,
+                },
+                RequestFileExample {
+                    name: "Missing".to_string(),
+                    status: 404,
+                    delay_ms: Some(250),
+                    ..RequestFileExample::default()
+                },
+            ],
+            ..RequestFile::default()
+        };
+
+        let serialized = serialize_request_file(&request_file).unwrap();
+        let expected = indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "GET"
+            url = "https://api.zaku.dev/users/:id"
+
+            [[examples]]
+            name = "Found"
+            status = 200
+            headers = [{ name = "Content-Type", value = "application/json" }]
+            body = """
+            {
+              "id": 7
+            }"""
+
+            [[examples]]
+            name = "Missing"
+            status = 404
+            delay_ms = 250
+        "#};
+
+        assert_eq!(serialized, expected);
+        assert_eq!(
+            parse_request_file(&serialized),
+            RequestFileState::Parsed(request_file)
+        );
+    }
+
+    #[test]
+    fn test_request_file_schema() {
+        let source = indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "POST"
+            url = "https://api.zaku.dev/users"
+            schema = { body = "schemas/user.json", responses = { 201 = "schemas/user.json", 4XX = "schemas/error.json", default = "schemas/any.json" } }
+        "#};
+        let RequestFileState::Parsed(request_file) = parse_request_file(source) else {
+            panic!("expected request file to parse");
+        };
+        let schema = request_file.http.schema.clone().unwrap();
+
+        assert_eq!(schema.body.as_deref(), Some("schemas/user.json"));
+        assert_eq!(schema.response_schema(201), Some("schemas/user.json"));
+        assert_eq!(schema.response_schema(404), Some("schemas/error.json"));
+        assert_eq!(schema.response_schema(500), Some("schemas/any.json"));
+        assert_eq!(RequestFileSchema::default().response_schema(200), None);
+        assert_eq!(serialize_request_file(&request_file).unwrap(), source);
+    }
+
+    #[test]
+    fn test_request_file_auth() {
+        let source = indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "GET"
+            url = "https://api.zaku.dev/me"
+            auth = { type = "oauth2", grant_type = "authorization_code", token_url = "https://auth.zaku.dev/token", authorization_url = "https://auth.zaku.dev/authorize", client_id = "zaku", scope = "read write", redirect_port = 8765 }
+        "#};
+        let RequestFileState::Parsed(request_file) = parse_request_file(source) else {
+            panic!("expected request file to parse");
+        };
+
+        assert_eq!(
+            request_file.http.auth,
+            Some(RequestFileAuth::OAuth2(RequestFileOAuth2 {
+                grant_type: OAuth2GrantType::AuthorizationCode,
+                token_url: "https://auth.zaku.dev/token".to_string(),
+                authorization_url: "https://auth.zaku.dev/authorize".to_string(),
+                client_id: "zaku".to_string(),
+                scope: "read write".to_string(),
+                redirect_port: Some(8765),
+                ..RequestFileOAuth2::default()
+            }))
+        );
+        assert_eq!(serialize_request_file(&request_file).unwrap(), source);
+
+        let RequestFileState::Invalid(error) = parse_request_file(indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "GET"
+            url = "https://api.zaku.dev/me"
+            auth = { type = "oauth2", grant_type = "implicit", token_url = "https://auth.zaku.dev/token" }
+        "#}) else {
+            panic!("expected an unknown grant type to be rejected");
+        };
+        assert!(error.contains("unknown variant `implicit`"), "{error}");
+
+        let source = indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "PUT"
+            url = "https://zaku.s3.amazonaws.com/upload.bin"
+            auth = { type = "aws_sigv4", access_key_id = "{{secret.aws_access_key_id}}", secret_access_key = "{{secret.aws_secret_access_key}}", region = "us-east-1", service = "s3", payload = "streaming" }
+        "#};
+        let RequestFileState::Parsed(request_file) = parse_request_file(source) else {
+            panic!("expected request file to parse");
+        };
+
+        assert_eq!(
+            request_file.http.auth,
+            Some(RequestFileAuth::AwsSigV4(RequestFileAwsSigV4 {
+                access_key_id: "{{secret.aws_access_key_id}}".to_string(),
+                secret_access_key: "{{secret.aws_secret_access_key}}".to_string(),
+                session_token: String::new(),
+                region: "us-east-1".to_string(),
+                service: "s3".to_string(),
+                payload: AwsPayloadSigning::Streaming,
+            }))
+        );
+        assert_eq!(serialize_request_file(&request_file).unwrap(), source);
+    }
+
+    #[test]
+    fn test_request_file_scripts() {
+        let RequestFileState::Parsed(request_file) = parse_request_file(indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "GET"
+            url = "https://api.zaku.dev/me"
+            scripts = { pre_request = """
+            request.headers["X-Signature"] = hmac_sha256(secrets.key, request.url);
+            """ }
+        "#}) else {
+            panic!("expected request file to parse");
+        };
+
+        let scripts = RequestFileScripts {
+            pre_request:
+                "request.headers[\"X-Signature\"] = hmac_sha256(secrets.key, request.url);\n"
+                    .to_string(),
+            post_response: String::new(),
+        };
+        assert_eq!(request_file.http.scripts, Some(scripts.clone()));
+        assert_eq!(
+            parse_request_file(&serialize_request_file(&request_file).unwrap()),
+            RequestFileState::Parsed(request_file)
+        );
+        assert!(!scripts.is_empty());
+        assert!(
+            RequestFileScripts {
+                pre_request: "\n  ".to_string(),
+                post_response: String::new(),
+            }
+            .is_empty()
+        );
+
+        let folder_defaults = parse_folder_defaults(indoc! {r#"
+            [scripts]
+            post_response = "assert(response.status < 400, \"request succeeded\");"
+        "#})
+        .unwrap();
+        assert_eq!(
+            folder_defaults.scripts,
+            Some(RequestFileScripts {
+                pre_request: String::new(),
+                post_response: "assert(response.status < 400, \"request succeeded\");".to_string(),
+            })
+        );
+        parse_folder_defaults("scripts = 1").unwrap_err();
+        assert_eq!(folder_defaults.order, Vec::<String>::new());
+
+        assert!(is_request_path(Path::new("users/list.toml")));
+        assert!(is_request_path(Path::new("users/Create.TOML")));
+        assert!(!is_request_path(Path::new("users/_folder.toml")));
+        assert!(!is_request_path(Path::new("users/notes.md")));
+    }
+
+    #[test]
+    fn test_folder_order() {
+        let contents = indoc! {r#"
+            # Runs after every request of the flow.
+            [scripts]
+            post_response = "assert(response.status < 400);"
+        "#};
+        let order = ["login.toml", "orders", "logout.toml"].map(String::from);
+
+        let ordered = set_folder_order(contents, &order).unwrap();
+        assert_eq!(
+            ordered,
+            indoc! {r#"
+                order = ["login.toml", "orders", "logout.toml"]
+                # Runs after every request of the flow.
+                [scripts]
+                post_response = "assert(response.status < 400);"
+            "#}
+        );
+        let folder_defaults = parse_folder_defaults(&ordered).unwrap();
+        assert_eq!(folder_defaults.order, order);
+        assert!(folder_defaults.scripts.is_some());
+
+        assert_eq!(set_folder_order(&ordered, &[]).unwrap(), contents);
+        assert_eq!(
+            set_folder_order("", &order[..1]).unwrap(),
+            "order = [\"login.toml\"]\n"
+        );
+        set_folder_order("order = [", &order).unwrap_err();
+    }
+
+    #[test]
+    fn test_url_query() {
+        let url = "https://api.zaku.dev/search?q=a%20b+c&flag&empty=&=x#results";
+        let (base, query) = split_url_query(url);
+        assert_eq!(base, "https://api.zaku.dev/search#results");
+        assert_eq!(query, Some("q=a%20b+c&flag&empty=&=x"));
+
+        let params = parse_query(query.unwrap());
+        assert_eq!(
+            params,
+            vec![
+                ("q".to_string(), "a%20b+c".to_string()),
+                ("flag".to_string(), String::new()),
+                ("empty".to_string(), String::new()),
+                (String::new(), "x".to_string()),
+            ]
+        );
+        assert_eq!(
+            join_url_query(
+                &base,
+                params
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+            ),
+            "https://api.zaku.dev/search?q=a%20b+c&flag&empty&=x#results"
+        );
+        assert_eq!(
+            split_url_query("https://api.zaku.dev/users"),
+            ("https://api.zaku.dev/users".to_string(), None)
+        );
+        assert_eq!(
+            join_url_query("https://api.zaku.dev/users", []),
+            "https://api.zaku.dev/users"
+        );
+    }
+
+    #[test]
+    fn test_query_round_trip() {
+        let url = "https://api.zaku.dev/search?q=a%20b&sort=name,desc&redirect=https://zaku.dev/a?b=c\
+            &tag=c%2Bd+e&ü=é#results";
+        let (base, query) = split_url_query(url);
+        let params = parse_query(query.unwrap());
+        assert_eq!(
+            join_url_query(
+                &base,
+                params
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+            ),
+            url
+        );
+
+        let params = [
+            ("q", "a b"),
+            ("filter", "x&y=z"),
+            ("tag", "#1 + 2%"),
+            ("a=b", "c=d"),
+            ("key", "{{secret.api_key}}"),
+            ("page", "{{$randomInt 1 {{var.pages}}}} of 10"),
+        ];
+        let url = join_url_query("https://api.zaku.dev/search#results", params);
+        assert_eq!(
+            url,
+            "https://api.zaku.dev/search?q=a%20b&filter=x%26y=z&tag=%231%20+%202%&a%3Db=c=d\
+                &key={{secret.api_key}}&page={{$randomInt 1 {{var.pages}}}}%20of%2010#results"
+        );
+
+        let (base, query) = split_url_query(&url);
+        assert_eq!(base, "https://api.zaku.dev/search#results");
+        let parsed = parse_query(query.unwrap());
+        assert_eq!(
+            parsed
+                .iter()
+                .map(|(name, value)| (escape_query_name(name), escape_query_value(value)))
+                .collect::<Vec<_>>(),
+            params.map(|(name, value)| (escape_query_name(name), escape_query_value(value)))
+        );
+        assert_eq!(
+            join_url_query(
+                &base,
+                parsed
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+            ),
+            url
+        );
+    }
+
+    #[test]
+    fn test_resolve_query_text() {
+        let resolve = |reference: &str| {
+            Ok(match reference {
+                "{{secret.api_key}}" => "a+b/c= d".to_string(),
+                "{{$base64 {{secret.api_key}}}}" => "{{$base64 a+b/c= d}}".to_string(),
+                _ => reference.to_string(),
+            })
+        };
+        assert_eq!(
+            resolve_query_text("key-{{secret.api_key}}+{{unknown}}%20", resolve).unwrap(),
+            "key-a%2Bb%2Fc%3D%20d+{{unknown}}%20"
+        );
+        assert_eq!(
+            resolve_query_text("{{$base64 {{secret.api_key}}}}", resolve).unwrap(),
+            "{{$base64 a+b/c= d}}"
+        );
+        resolve_query_text("{{secret.api_key}}", |_| Err(anyhow!("missing"))).unwrap_err();
+    }
+
+    #[test]
+    fn test_path_variables() {
+        let url =
+            "https://api.zaku.dev:8443/orgs/{org}/users/:id/{{version}}/:id?fields=:name#{frag}";
+        assert_eq!(path_variable_names(url), vec!["org", "id"]);
+        assert_eq!(path_variable_names("localhost:3000/users/:id"), vec!["id"]);
+        assert_eq!(
+            path_variable_names("https://api.zaku.dev"),
+            Vec::<String>::new()
+        );
+
+        let path_variables = [
+            RequestFilePathVariable {
+                name: "org".to_string(),
+                value: "zaku dev/ü".to_string(),
+            },
+            RequestFilePathVariable {
+                name: "id".to_string(),
+                value: "7".to_string(),
+            },
+        ];
+        assert_eq!(
+            substitute_path_variables(url, &path_variables).unwrap(),
+            "https://api.zaku.dev:8443/orgs/zaku%20dev%2F%C3%BC/users/7/{{version}}/7?fields=:name#{frag}"
+        );
+
+        let error = substitute_path_variables(url, &path_variables[..1]).unwrap_err();
+        assert_eq!(error.to_string(), "path variable `id` has no value");
+    }
+
+    #[test]
+    fn test_upgrade_url_query_to_params() {
+        let source = indoc! {r#"
+            [meta]
+            version = 1
+
+            [http]
+            method = "GET"
+            url = "https://api.zaku.dev/users?page=2&sort=name%2Cdesc"
+            params = [
+                { name = "limit", value = "10" },
+                { name = "debug", value = "1", disabled = true },
+            ]
+        "#};
+        let RequestFileState::Parsed(request_file) = parse_request_file(source) else {
+            panic!("expected request file to parse");
+        };
+
+        assert_eq!(request_file.http.url, "https://api.zaku.dev/users");
+        assert_eq!(
+            request_file
+                .http
+                .params
+                .iter()
+                .map(|param| (param.name.as_str(), param.value.as_str(), param.disabled))
+                .collect::<Vec<_>>(),
+            vec![
+                ("page", "2", false),
+                ("sort", "name%2Cdesc", false),
+                ("limit", "10", false),
+                ("debug", "1", true),
+            ]
+        );
+        assert_eq!(
+            request_file.http.url_with_params(),
+            "https://api.zaku.dev/users?page=2&sort=name%2Cdesc&limit=10"
+        );
+    }
 }
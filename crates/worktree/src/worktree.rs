@@ -3,9 +3,14 @@ mod request;
 
 pub use language::DiskState;
 pub use request::{
-    REQUEST_FILE_VERSION, RequestFile, RequestFileBody, RequestFileBodyType, RequestFileHeader,
-    RequestFileHttp, RequestFileMeta, RequestFileParam, RequestFileState, parse_request_file,
-    request_method_short_name, serialize_request_file,
+    AwsPayloadSigning, FOLDER_DEFAULTS_FILE_NAME, FolderDefaults, OAuth2GrantType,
+    REQUEST_FILE_VERSION, RequestFile, RequestFileAuth, RequestFileAwsSigV4, RequestFileBody,
+    RequestFileBodyType, RequestFileExample, RequestFileHeader, RequestFileHttp, RequestFileMeta,
+    RequestFileOAuth2, RequestFileParam, RequestFilePathVariable, RequestFileRetry,
+    RequestFileSchema, RequestFileScripts, RequestFileState, escape_query_name, escape_query_value,
+    is_request_path, join_url_query, parse_folder_defaults, parse_query, parse_request_file,
+    path_variable_name, path_variable_names, request_method_short_name, resolve_query_text,
+    serialize_request_file, set_folder_order, split_url_query, substitute_path_variables, url_path,
 };
 pub use settings::WorktreeId;
 
@@ -73,6 +78,8 @@ pub struct Worktree {
     visible: bool,
     next_entry_id: Arc<AtomicUsize>,
     scanning_enabled: bool,
+    /// Held while a [`FOLDER_DEFAULTS_FILE_NAME`] file's order is read and written.
+    folder_order_lock: Arc<Mutex<()>>,
 }
 
 impl Worktree {
@@ -162,6 +169,7 @@ impl Worktree {
                 visible,
                 next_entry_id,
                 scanning_enabled,
+                folder_order_lock: Arc::default(),
             };
             worktree.start_background_scanner(scan_requests_rx, path_prefixes_to_scan_rx, cx);
             worktree
@@ -213,6 +221,100 @@ impl Worktree {
         })
     }
 
+    /// Writes the order of the entries of `folder` to its [`FOLDER_DEFAULTS_FILE_NAME`] file,
+    /// creating the file if the folder doesn't have one.
+    pub fn write_folder_order(
+        &self,
+        folder: &RelPath,
+        order: Vec<String>,
+        cx: &Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.update_folder_order(
+            folder,
+            move |current| {
+                *current = order;
+                true
+            },
+            cx,
+        )
+    }
+
+    /// Renames the entry named `old_name` in the order of the entries of `folder` to `new_name`,
+    /// or removes it when `new_name` is `None`, as when the entry is moved to another folder.
+    pub fn rename_in_folder_order(
+        &self,
+        folder: &RelPath,
+        old_name: String,
+        new_name: Option<String>,
+        cx: &Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.update_folder_order(
+            folder,
+            move |order| {
+                let Some(index) = order.iter().position(|name| *name == old_name) else {
+                    return false;
+                };
+                match new_name {
+                    Some(new_name) => {
+                        if let Some(name) = order.get_mut(index) {
+                            *name = new_name;
+                        }
+                    }
+                    None => {
+                        order.remove(index);
+                    }
+                }
+                true
+            },
+            cx,
+        )
+    }
+
+    /// Updates the order in the [`FOLDER_DEFAULTS_FILE_NAME`] file of `folder`, writing the file
+    /// when `update` returns whether it changed the order. Updates of the same worktree run one
+    /// at a time, so that they don't overwrite each other.
+    fn update_folder_order(
+        &self,
+        folder: &RelPath,
+        update: impl FnOnce(&mut Vec<String>) -> bool + Send + 'static,
+        cx: &Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let path = match RelPath::unix(FOLDER_DEFAULTS_FILE_NAME) {
+            Ok(file_name) => folder.join(file_name),
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let fs = self.fs().clone();
+        let abs_path = self.absolutize(&path);
+        let folder_order_lock = self.folder_order_lock.clone();
+        let write_task = cx.background_spawn(async move {
+            let _guard = folder_order_lock.lock().await;
+            let exists = fs
+                .metadata(&abs_path)
+                .await?
+                .is_some_and(|metadata| !metadata.is_dir);
+            let contents = if exists {
+                fs.load(&abs_path).await?
+            } else {
+                String::new()
+            };
+            let mut order = request::parse_folder_defaults(&contents)?.order;
+            if !update(&mut order) {
+                return anyhow::Ok(false);
+            }
+            let contents = request::set_folder_order(&contents, &order)?;
+            fs.write(&abs_path, contents.as_bytes()).await?;
+            Ok(true)
+        });
+
+        cx.spawn(async move |this, cx| {
+            if write_task.await? {
+                this.update(cx, |worktree, cx| worktree.refresh_entry(path, None, cx))?
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
     fn descendant_entry_ids(&self, path: &RelPath) -> Vec<ProjectEntryId> {
         fn inner(worktree: &Worktree, path: &RelPath, entry_ids: &mut Vec<ProjectEntryId>) {
             for entry in worktree.child_entries(path) {
@@ -2565,9 +2667,7 @@ impl BackgroundScanner {
                 }
 
                 child_entry.is_ignored = ignore_stack.is_abs_path_ignored(&child_abs_path, false);
-                child_entry.is_request = child_abs_path
-                    .extension()
-                    .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+                child_entry.is_request = is_request_path(&child_abs_path);
             }
 
             new_entries.push(child_entry);
@@ -2692,9 +2792,7 @@ impl BackgroundScanner {
                     fs_entry.is_ignored = ignore_stack.is_abs_path_ignored(&abs_path, is_dir);
                     fs_entry.is_external = is_external;
                     if !is_dir {
-                        fs_entry.is_request = abs_path
-                            .extension()
-                            .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+                        fs_entry.is_request = is_request_path(&abs_path);
                     }
 
                     if let (Some(scan_queue_tx), true) = (&scan_queue_tx, is_dir) {
@@ -50,6 +50,12 @@ pub fn app_menu(cx: &mut App) -> Vec<Menu> {
                 MenuItem::action("New Window", actions::workspace::NewWindow),
                 MenuItem::separator(),
                 MenuItem::action("Open…", actions::workspace::Open::default()),
+                MenuItem::action("Go to Request…", actions::request_finder::Toggle),
+                MenuItem::action("Compare with HEAD", actions::request_diff::CompareWithHead),
+                MenuItem::action("File History", actions::request_diff::ShowFileHistory),
+                MenuItem::action("Switch Branch…", actions::branch_picker::Toggle),
+                MenuItem::separator(),
+                MenuItem::action("Manage Secrets…", actions::secrets::ManageSecrets),
                 MenuItem::separator(),
                 MenuItem::action("Close Project", actions::workspace::CloseProject),
                 MenuItem::action("Close Window", actions::workspace::CloseWindow),
@@ -63,6 +69,9 @@ pub fn app_menu(cx: &mut App) -> Vec<Menu> {
                 MenuItem::action("Toggle Bottom Dock", actions::workspace::ToggleBottomDock),
                 MenuItem::separator(),
                 MenuItem::action("Project Panel", actions::project_panel::ToggleFocus),
+                MenuItem::action("Project Search", actions::project_search::ToggleFocus),
+                MenuItem::action("Git Panel", actions::git_panel::ToggleFocus),
+                MenuItem::action("History Panel", actions::history_panel::ToggleFocus),
                 MenuItem::action("Response Panel", actions::response_panel::ToggleFocus),
             ],
         },
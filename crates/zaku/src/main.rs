@@ -110,12 +110,21 @@ fn main() {
         updater::init(http_client, path::cache_dir().clone(), cx);
         workspace::init(app_state.clone(), cx);
         project_panel::init(cx);
+        mock_server::init(cx);
+        history_panel::init(cx);
+        project_search::init(cx);
+        git_panel::init(cx);
+        secrets::init(cx);
+        auth::init(cx);
+        scripting::init(cx);
         editor::init(cx);
         request_editor::init(cx);
         response_panel::init(cx);
         title_bar::init(cx);
         zaku::init(cx);
         command_palette::init(cx);
+        request_finder::init(cx);
+        request_diff::init(cx);
         let menus = zaku::app_menu(cx);
         cx.set_menus(menus);
 
@@ -12,7 +12,11 @@ use gpui::{
 use std::{borrow::Cow, io::IsTerminal, path::Path, sync::Arc};
 
 use ::settings::{initial_user_keymap, initial_user_settings};
+use git_panel::GitPanel;
+use history_panel::HistoryPanel;
+use mock_server::MockServerPanel;
 use project_panel::ProjectPanel;
+use project_search::ProjectSearchPanel;
 use response_panel::ResponsePanel;
 use system_specs::SystemSpecs;
 use theme::ActiveTheme;
@@ -113,9 +117,21 @@ pub fn init(cx: &mut App) {
             });
         }
 
+        let history_panel = HistoryPanel::new(workspace, window, cx);
+        workspace.add_panel(history_panel, DockPosition::Left, window, cx);
+
+        let project_search_panel = ProjectSearchPanel::new(workspace, window, cx);
+        workspace.add_panel(project_search_panel, DockPosition::Left, window, cx);
+
+        let git_panel = GitPanel::new(workspace, window, cx);
+        workspace.add_panel(git_panel, DockPosition::Left, window, cx);
+
         let response_panel = cx.new(|cx| ResponsePanel::new(window, cx));
         workspace.add_panel(response_panel, DockPosition::Bottom, window, cx);
 
+        let mock_server_panel = MockServerPanel::new(workspace, window, cx);
+        workspace.add_panel(mock_server_panel, DockPosition::Bottom, window, cx);
+
         workspace.register_action(
             |_, _: &actions::zaku::CopySystemSpecsIntoClipboard, window, cx| {
                 let specs = SystemSpecs::new(